@@ -1,4 +1,4 @@
-use agentic_browser::AgenticBrowser;
+use agentic_browser::{AgenticBrowser, Blocking};
 use std::time::Duration;
 
 #[tokio::main]
@@ -79,14 +79,13 @@ async fn main() -> agentic_browser::Result<()> {
     page.screenshot_to_file("google_btc.png").await?;
     println!("Saved google_btc.png");
 
-    if result_url.contains("/sorry") {
-        println!("\nGoogle /sorry/ page - checking for CAPTCHA...");
-        let html = page.html().await?;
-        if html.contains("recaptcha") || html.contains("captcha") {
-            println!("CAPTCHA detected. This IP is rate-limited by Google.");
+    let blocking = page.detect_blocking().await?;
+    if blocking.is_blocked() {
+        if blocking == Blocking::GoogleSorry {
+            println!("\nGoogle /sorry/ page. This IP is rate-limited by Google.");
             println!("Options: use a residential proxy, or wait for IP cooldown.");
         } else {
-            println!("Blocked without CAPTCHA - hard IP block.");
+            println!("\nBlocked: {blocking:?}");
         }
 
         // Show what's on the page
@@ -0,0 +1,54 @@
+/* C API of agentic-browser, built with the `ffi` feature:
+ *
+ *     cargo build --release --features ffi
+ *
+ * Functions returning int give 0 on success and -1 on failure; functions
+ * returning pointers give NULL on failure, panics included. After a failure,
+ * ab_last_error() describes it. Calls block until the browser answers. Free pages before the
+ * browser that opened them.
+ */
+#ifndef AGENTIC_BROWSER_H
+#define AGENTIC_BROWSER_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct AbBrowser AbBrowser;
+typedef struct AbPage AbPage;
+
+/* Message of the last failure on this thread, or NULL. Do not free. */
+const char *ab_last_error(void);
+
+/* Launch a browser configured from a TOML file, or from AGENTIC_BROWSER_*
+ * environment variables if config_path is NULL. */
+AbBrowser *ab_browser_new(const char *config_path);
+void ab_browser_free(AbBrowser *browser);
+
+/* Open a page at url, or a blank page if url is NULL. */
+AbPage *ab_page_new(AbBrowser *browser, const char *url);
+/* Close the page's tab and free the handle. */
+void ab_page_free(AbPage *page);
+
+int ab_page_goto(AbPage *page, const char *url);
+int ab_page_click(AbPage *page, const char *selector);
+int ab_page_type(AbPage *page, const char *selector, const char *text);
+
+/* PNG screenshot of the viewport. Free *out_data with ab_buffer_free. */
+int ab_page_screenshot(AbPage *page, uint8_t **out_data, size_t *out_len);
+
+/* JSON with "observation", "links", "form_fields" and "accessibility_tree".
+ * Free with ab_string_free. */
+char *ab_page_snapshot(AbPage *page);
+
+void ab_string_free(char *s);
+void ab_buffer_free(uint8_t *data, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* AGENTIC_BROWSER_H */
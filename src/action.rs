@@ -0,0 +1,62 @@
+/// A high-level page command, serializable for planners, recorders and replay
+/// tooling (`{"action": "click", "selector": "#submit"}`). Run with
+/// `Page::execute_action`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Action {
+    Goto { url: String },
+    /// Click an element once it is visible and enabled.
+    Click { selector: String },
+    /// Replace the field's value with `text`.
+    Type { selector: String, text: String },
+    /// Press a key such as "Enter", "Tab" or "Escape".
+    Press { key: String },
+    /// Choose a `<select>` option by value.
+    Select { selector: String, value: String },
+    /// Scroll the element into view, or else the page by `pixels`
+    /// (negative scrolls up).
+    Scroll {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        selector: Option<String>,
+        #[serde(default)]
+        pixels: i64,
+    },
+    /// Wait until `selector` is visible, or else sleep for `ms`.
+    Wait {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        selector: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        ms: Option<u64>,
+    },
+    /// Read an element's text, or one of its attributes.
+    Extract {
+        selector: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        attribute: Option<String>,
+    },
+}
+
+impl Action {
+    /// The `action` tag, e.g. "click".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Goto { .. } => "goto",
+            Action::Click { .. } => "click",
+            Action::Type { .. } => "type",
+            Action::Press { .. } => "press",
+            Action::Select { .. } => "select",
+            Action::Scroll { .. } => "scroll",
+            Action::Wait { .. } => "wait",
+            Action::Extract { .. } => "extract",
+        }
+    }
+}
+
+/// Outcome of `Page::execute_action`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "result", content = "value", rename_all = "snake_case")]
+pub enum ActionResult {
+    Done,
+    /// Value read by `Action::Extract`; empty if the attribute is missing.
+    Extracted(String),
+}
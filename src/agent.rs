@@ -0,0 +1,232 @@
+use std::future::Future;
+
+use crate::action::{Action, ActionResult};
+use crate::approval::{Approval, ApprovalPolicy, ApprovalRequest, ApproveAll, RiskRules};
+use crate::error::{Error, Result};
+use crate::page::Page;
+use crate::snapshot::TreeOptions;
+
+/// What the policy sees before each step.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct AgentObservation {
+    /// 1-based step number.
+    pub step: usize,
+    pub url: String,
+    pub title: String,
+    /// Compact accessibility tree of the page (see `Page::accessibility_tree_with`).
+    pub tree: String,
+}
+
+/// A policy's choice for the next step.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum AgentDecision {
+    Act { action: Action },
+    /// The goal is reached (or given up on); `answer` is returned to the caller.
+    Done { answer: String },
+}
+
+/// Decides the next action from the current observation, typically by prompting
+/// an LLM. `history` holds the steps taken so far, including failed actions, so
+/// the model can correct itself.
+pub trait LlmPolicy {
+    fn decide(
+        &self,
+        observation: &AgentObservation,
+        history: &[TranscriptEntry],
+    ) -> impl Future<Output = Result<AgentDecision>> + Send;
+}
+
+/// One step of an agent run.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct TranscriptEntry {
+    pub step: usize,
+    pub url: String,
+    pub title: String,
+    /// The action, with text typed into password and other sensitive fields
+    /// masked (see `Page::redact_action`).
+    pub action: Action,
+    /// The action's result, or its error message.
+    pub result: std::result::Result<ActionResult, String>,
+}
+
+/// Why an agent run ended.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "reason", content = "value", rename_all = "snake_case")]
+pub enum StopReason {
+    /// The policy returned `AgentDecision::Done` with this answer.
+    Done(String),
+    /// `AgentRunner::stop_when` matched.
+    Condition,
+    MaxSteps,
+    /// Too many actions failed in a row.
+    TooManyErrors,
+}
+
+/// Result of `AgentRunner::run`.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct AgentOutcome {
+    pub stop_reason: StopReason,
+    pub transcript: Vec<TranscriptEntry>,
+}
+
+type StopCondition = Box<dyn Fn(&AgentObservation) -> bool + Send + Sync>;
+
+/// Runs observe → decide → act on a page until the policy is done, a stop
+/// condition matches, or the step limit is reached.
+pub struct AgentRunner<P, A = ApproveAll> {
+    policy: P,
+    max_steps: usize,
+    max_consecutive_errors: usize,
+    stop_conditions: Vec<StopCondition>,
+    tree_options: TreeOptions,
+    approval: Option<A>,
+    risk_rules: RiskRules,
+}
+
+impl<P: LlmPolicy> AgentRunner<P> {
+    /// A runner with a limit of 20 steps and 3 consecutive failed actions.
+    pub fn new(policy: P) -> Self {
+        Self {
+            policy,
+            max_steps: 20,
+            max_consecutive_errors: 3,
+            stop_conditions: Vec::new(),
+            tree_options: TreeOptions::default(),
+            approval: None,
+            risk_rules: RiskRules::default(),
+        }
+    }
+}
+
+impl<P: LlmPolicy, A: ApprovalPolicy> AgentRunner<P, A> {
+    /// Ask `approval` before each risky action (see `risk_rules`) and wait for
+    /// its answer. Denied actions are skipped and recorded as failed.
+    pub fn approval<B: ApprovalPolicy>(self, approval: B) -> AgentRunner<P, B> {
+        AgentRunner {
+            policy: self.policy,
+            max_steps: self.max_steps,
+            max_consecutive_errors: self.max_consecutive_errors,
+            stop_conditions: self.stop_conditions,
+            tree_options: self.tree_options,
+            approval: Some(approval),
+            risk_rules: self.risk_rules,
+        }
+    }
+
+    /// Which actions need approval (default: `RiskRules::new()`).
+    pub fn risk_rules(mut self, rules: RiskRules) -> Self {
+        self.risk_rules = rules;
+        self
+    }
+
+    pub fn max_steps(mut self, n: usize) -> Self {
+        self.max_steps = n;
+        self
+    }
+
+    /// Stop after `n` failed actions in a row (0: never). Failed actions are
+    /// otherwise recorded in the transcript and the loop continues.
+    pub fn max_consecutive_errors(mut self, n: usize) -> Self {
+        self.max_consecutive_errors = n;
+        self
+    }
+
+    /// What the observed tree includes besides the main document, e.g.
+    /// `TreeOptions::all()` for sites that log in or check out in an iframe.
+    pub fn tree_options(mut self, options: TreeOptions) -> Self {
+        self.tree_options = options;
+        self
+    }
+
+    /// Stop before deciding when `condition` matches the observation,
+    /// e.g. `|o| o.url.contains("/checkout/done")`.
+    pub fn stop_when(
+        mut self,
+        condition: impl Fn(&AgentObservation) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.stop_conditions.push(Box::new(condition));
+        self
+    }
+
+    /// Run the loop on `page`. Errors from observing the page or from the policy
+    /// end the run; errors from actions are fed back to the policy.
+    pub async fn run(&self, page: &Page) -> Result<AgentOutcome> {
+        let mut transcript: Vec<TranscriptEntry> = Vec::new();
+        let mut consecutive_errors = 0;
+
+        for step in 1..=self.max_steps {
+            let snapshot = page.observe().await?;
+            let observation = AgentObservation {
+                step,
+                url: snapshot.url,
+                title: snapshot.title,
+                tree: page.accessibility_tree_with(self.tree_options).await?,
+            };
+            if self.stop_conditions.iter().any(|c| c(&observation)) {
+                return Ok(AgentOutcome { stop_reason: StopReason::Condition, transcript });
+            }
+
+            let action = match self.policy.decide(&observation, &transcript).await? {
+                AgentDecision::Done { answer } => {
+                    return Ok(AgentOutcome { stop_reason: StopReason::Done(answer), transcript });
+                }
+                AgentDecision::Act { action } => action,
+            };
+
+            // Looked up before acting, while the field is still on the page.
+            let logged = page.redact_action(&action).await;
+            let result = match self.review(page, &observation, &logged, &transcript).await? {
+                Approval::Approve => page.execute_action(&action).await.map_err(|e| e.to_string()),
+                Approval::Deny(reason) => {
+                    Err(Error::ActionRejected(format!("denied by approver: {reason}")).to_string())
+                }
+            };
+            consecutive_errors = if result.is_err() { consecutive_errors + 1 } else { 0 };
+            transcript.push(TranscriptEntry {
+                step,
+                url: observation.url,
+                title: observation.title,
+                action: logged,
+                result,
+            });
+            if self.max_consecutive_errors > 0 && consecutive_errors >= self.max_consecutive_errors {
+                return Ok(AgentOutcome { stop_reason: StopReason::TooManyErrors, transcript });
+            }
+        }
+        Ok(AgentOutcome { stop_reason: StopReason::MaxSteps, transcript })
+    }
+
+    /// Ask the approval policy, if any, about `action` if it is risky.
+    async fn review(
+        &self,
+        page: &Page,
+        observation: &AgentObservation,
+        action: &Action,
+        history: &[TranscriptEntry],
+    ) -> Result<Approval> {
+        let Some(ref approval) = self.approval else {
+            return Ok(Approval::Approve);
+        };
+        let Some(reason) = self.risk_rules.assess(page, action).await else {
+            return Ok(Approval::Approve);
+        };
+        let target = match action {
+            Action::Click { selector } => page.highlight(selector, "red").await.is_ok(),
+            _ => false,
+        };
+        let screenshot = page.screenshot_redacted().await;
+        if target {
+            page.clear_highlights().await?;
+        }
+        let request = ApprovalRequest {
+            step: observation.step,
+            url: observation.url.clone(),
+            title: observation.title.clone(),
+            action: action.clone(),
+            reason,
+            screenshot: screenshot?,
+        };
+        approval.review(&request, history).await
+    }
+}
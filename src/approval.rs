@@ -0,0 +1,152 @@
+use std::future::Future;
+
+use crate::action::Action;
+use crate::agent::TranscriptEntry;
+use crate::error::Result;
+use crate::locator::{ElementState, WaitOptions};
+use crate::page::Page;
+
+/// Which actions `AgentRunner` asks its `ApprovalPolicy` about: clicks on, and
+/// Enter in forms with, elements whose text or label names one of `keywords`
+/// or that match one of `selectors`.
+#[derive(Debug, Clone)]
+pub struct RiskRules {
+    /// Whole words or phrases, case-insensitive: payments, deletes and
+    /// messages by default.
+    pub keywords: Vec<String>,
+    /// CSS selectors of risky elements or of containers around them.
+    pub selectors: Vec<String>,
+}
+
+impl Default for RiskRules {
+    fn default() -> Self {
+        let keywords = [
+            "pay", "purchase", "buy", "checkout", "place order", "subscribe", "transfer", "withdraw",
+            "delete", "remove", "close account", "send", "post", "publish", "reply",
+        ];
+        Self {
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            selectors: Vec::new(),
+        }
+    }
+}
+
+impl RiskRules {
+    /// Payments, deletes and messages.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only `selectors`, no keywords.
+    pub fn selectors_only(selectors: &[&str]) -> Self {
+        Self {
+            keywords: Vec::new(),
+            selectors: selectors.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    pub fn keyword(mut self, keyword: impl Into<String>) -> Self {
+        self.keywords.push(keyword.into());
+        self
+    }
+
+    pub fn selector(mut self, selector: impl Into<String>) -> Self {
+        self.selectors.push(selector.into());
+        self
+    }
+
+    /// Why `action` on `page` needs approval, or `None` if it doesn't. An action
+    /// that can't be assessed needs approval too.
+    pub(crate) async fn assess(&self, page: &Page, action: &Action) -> Option<String> {
+        let target = match action {
+            Action::Click { selector } => {
+                // Clicks wait for their element; look at the one that will be clicked
+                let found = page
+                    .wait_for_selector_with(selector, WaitOptions::new(ElementState::Attached))
+                    .await;
+                if let Err(e) = found {
+                    return Some(format!("could not assess: {e}"));
+                }
+                Some(selector)
+            }
+            Action::Press { key } if key.eq_ignore_ascii_case("enter") => None,
+            _ => return None,
+        };
+        page.evaluate_with_args(RISK_JS, (target, &self.keywords, &self.selectors))
+            .await
+            .unwrap_or_else(|e| Some(format!("could not assess: {e}")))
+    }
+}
+
+/// What an `ApprovalPolicy` is shown before a risky action.
+#[derive(Debug, Clone)]
+pub struct ApprovalRequest {
+    /// 1-based step number.
+    pub step: usize,
+    pub url: String,
+    pub title: String,
+    /// The action, with sensitive text masked as in the transcript.
+    pub action: Action,
+    /// Which rule matched, e.g. `"delete" in "Delete account"`.
+    pub reason: String,
+    /// PNG of the viewport with the clicked element outlined and sensitive
+    /// fields blurred (see `Page::screenshot_redacted`).
+    pub screenshot: Vec<u8>,
+}
+
+/// An `ApprovalPolicy`'s answer.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "approval", content = "reason", rename_all = "snake_case")]
+pub enum Approval {
+    Approve,
+    /// Skip the action; the reason is recorded as its error, for the agent to
+    /// see.
+    Deny(String),
+}
+
+/// Decides whether a risky action may run, typically by asking a person. The
+/// agent waits for the answer, however long it takes.
+pub trait ApprovalPolicy {
+    fn review(
+        &self,
+        request: &ApprovalRequest,
+        history: &[TranscriptEntry],
+    ) -> impl Future<Output = Result<Approval>> + Send;
+}
+
+/// Approves everything; what `AgentRunner` uses until given an approval policy.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ApproveAll;
+
+impl ApprovalPolicy for ApproveAll {
+    async fn review(&self, _: &ApprovalRequest, _: &[TranscriptEntry]) -> Result<Approval> {
+        Ok(Approval::Approve)
+    }
+}
+
+/// `(selector, keywords, selectors) => string | null`: why the element matching
+/// `selector` (or, for `null`, the form of the focused element) is risky.
+const RISK_JS: &str = r#"(selector, keywords, selectors) => {
+    const el = selector === null ? document.activeElement : document.querySelector(selector);
+    if (!el || el === document.body) return null;
+    for (const sel of selectors) {
+        try { if (el.closest(sel)) return 'matches ' + sel; } catch (e) {}
+    }
+    const control = el.closest('a, button, input, select, textarea, [role], [onclick]') || el;
+    const texts = [control.innerText, control.getAttribute('aria-label'), control.title];
+    if (control.tagName === 'INPUT' && ['submit', 'button', 'image'].includes(control.type)) texts.push(control.value);
+    const form = control.form || control.closest('form');
+    if (form && (selector === null || control.type === 'submit')) {
+        texts.push(form.getAttribute('aria-label'));
+        for (const button of form.querySelectorAll('button[type=submit], button:not([type]), input[type=submit]')) {
+            texts.push(button.innerText || button.value);
+        }
+    }
+    const text = texts.filter(Boolean).join(' ').replace(/\s+/g, ' ').trim();
+    for (const keyword of keywords) {
+        const words = keyword.trim().split(/\s+/).map(w => w.replace(/[.*+?^${}()|[\]\\]/g, '\\$&'));
+        const pattern = new RegExp('(^|[^\\p{L}\\p{N}])' + words.join('[\\s_-]+') + '($|[^\\p{L}\\p{N}])', 'iu');
+        if (pattern.test(text)) return JSON.stringify(keyword) + ' in ' + JSON.stringify(text.substring(0, 80));
+    }
+    return null;
+}"#;
@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+
+use base64::Engine;
+use chromiumoxide::cdp::browser_protocol::network::ResourceType;
+use chromiumoxide::cdp::browser_protocol::page::{
+    FrameResourceTree, GetResourceContentParams, GetResourceTreeParams,
+};
+use chromiumoxide::page::Page as CrPage;
+
+use crate::error::{Error, Result};
+
+/// Directory, inside the archive, that resources are written to.
+pub(crate) const RESOURCE_DIR: &str = "resources";
+
+/// A subresource of the main frame, as loaded by the browser.
+pub(crate) struct Resource {
+    pub url: String,
+    pub file_name: String,
+    pub is_stylesheet: bool,
+    pub body: Vec<u8>,
+}
+
+/// File extension for a MIME type, for URLs whose path has none.
+fn extension(mime_type: &str) -> &'static str {
+    match mime_type.split(';').next().unwrap_or_default().trim() {
+        "text/css" => "css",
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/avif" => "avif",
+        "image/svg+xml" => "svg",
+        "image/x-icon" | "image/vnd.microsoft.icon" => "ico",
+        "font/woff2" => "woff2",
+        "font/woff" | "application/font-woff" => "woff",
+        "font/ttf" | "font/sfnt" => "ttf",
+        "font/otf" => "otf",
+        _ => "bin",
+    }
+}
+
+/// `{index}-{last path segment}`, limited to safe characters, with an extension.
+fn file_name(index: usize, url: &str, mime_type: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let segment = path.rsplit('/').next().unwrap_or_default();
+    let mut name: String = segment
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+        .take(60)
+        .collect();
+    if name.is_empty() || !name.contains('.') {
+        if name.is_empty() {
+            name.push_str("resource");
+        }
+        name.push('.');
+        name.push_str(extension(mime_type));
+    }
+    format!("{index:03}-{name}")
+}
+
+/// The main frame's images, stylesheets and fonts, read from the browser's
+/// resource cache (Page.getResourceContent), so nothing is downloaded again.
+/// Resources that failed or can no longer be read are left out.
+pub(crate) async fn collect(page: &CrPage) -> Result<Vec<Resource>> {
+    let tree: FrameResourceTree = page
+        .execute(GetResourceTreeParams::default())
+        .await
+        .map_err(|e| Error::JsError(format!("Failed to get resource tree: {e}")))?
+        .result
+        .frame_tree;
+
+    let mut resources = Vec::new();
+    let mut seen = HashSet::new();
+    for resource in &tree.resources {
+        let wanted = matches!(
+            resource.r#type,
+            ResourceType::Image | ResourceType::Stylesheet | ResourceType::Font
+        );
+        if !wanted
+            || resource.failed == Some(true)
+            || resource.canceled == Some(true)
+            || !resource.url.starts_with("http")
+            || !seen.insert(resource.url.as_str())
+        {
+            continue;
+        }
+        let Ok(content) = page
+            .execute(GetResourceContentParams::new(tree.frame.id.clone(), resource.url.clone()))
+            .await
+        else {
+            continue;
+        };
+        let body = if content.result.base64_encoded {
+            match base64::engine::general_purpose::STANDARD.decode(&content.result.content) {
+                Ok(body) => body,
+                Err(_) => continue,
+            }
+        } else {
+            content.result.content.clone().into_bytes()
+        };
+        resources.push(Resource {
+            url: resource.url.clone(),
+            file_name: file_name(resources.len(), &resource.url, &resource.mime_type),
+            is_stylesheet: resource.r#type == ResourceType::Stylesheet,
+            body,
+        });
+    }
+    Ok(resources)
+}
+
+/// Serializes the current DOM with resource URLs in `src`, `srcset`, `href`,
+/// `poster`, inline styles and `<style>` blocks pointed at the local files in
+/// `files` (absolute URL → file name), and rewrites the `stylesheets` texts the
+/// same way, relative to the resource directory. Scripts are dropped so the copy
+/// shows what was rendered instead of re-running; other links are made absolute.
+pub(crate) const REWRITE_JS: &str = r#"(files, stylesheets, dir) => {
+    const local = (ref, base, prefix) => {
+        if (!ref || /^(data|blob|javascript|mailto|tel):/i.test(ref.trim())) return null;
+        let abs;
+        try { abs = new URL(ref.trim(), base).href; } catch (e) { return null; }
+        return files[abs] !== undefined ? prefix + files[abs] : abs;
+    };
+    const rewriteCss = (css, base, prefix) => css
+        .replace(/url\(\s*(['"]?)([^'")]+)\1\s*\)/gi, (m, q, ref) => {
+            const to = local(ref, base, prefix);
+            return to ? 'url("' + to + '")' : m;
+        })
+        .replace(/@import\s+(['"])([^'"]+)\1/gi, (m, q, ref) => {
+            const to = local(ref, base, prefix);
+            return to ? '@import "' + to + '"' : m;
+        });
+
+    const root = document.documentElement.cloneNode(true);
+    const base = document.baseURI;
+    root.querySelectorAll('script, base, noscript, link[rel=preload], link[rel=modulepreload], link[rel=prefetch]')
+        .forEach(el => el.remove());
+    for (const el of root.querySelectorAll('[src], [href], [poster], [action]')) {
+        for (const attr of ['src', 'href', 'poster', 'action']) {
+            const value = el.getAttribute(attr);
+            if (value === null || value.startsWith('#')) continue;
+            const to = local(value, base, dir + '/');
+            if (to) el.setAttribute(attr, to);
+        }
+    }
+    for (const el of root.querySelectorAll('[srcset]')) {
+        el.setAttribute('srcset', el.getAttribute('srcset').split(',').map(part => {
+            const [ref, ...size] = part.trim().split(/\s+/);
+            return [local(ref, base, dir + '/') || ref, ...size].join(' ');
+        }).join(', '));
+    }
+    for (const el of root.querySelectorAll('[style]')) {
+        el.setAttribute('style', rewriteCss(el.getAttribute('style'), base, dir + '/'));
+    }
+    for (const el of root.querySelectorAll('style')) {
+        el.textContent = rewriteCss(el.textContent, base, dir + '/');
+    }
+    for (const el of root.querySelectorAll('*')) {
+        for (const attr of [...el.attributes]) {
+            if (attr.name.startsWith('on')) el.removeAttribute(attr.name);
+        }
+    }
+    // The copy is written as UTF-8 whatever the original encoding was.
+    root.querySelectorAll('meta[charset], meta[http-equiv="content-type" i]').forEach(el => el.remove());
+    const head = root.querySelector('head');
+    if (head) head.insertAdjacentHTML('afterbegin', '<meta charset="utf-8">');
+
+    const doctype = document.doctype ? '<!DOCTYPE ' + document.doctype.name + '>\n' : '';
+    return {
+        html: doctype + root.outerHTML,
+        stylesheets: stylesheets.map(s => rewriteCss(s.text, s.url, '')),
+    };
+}"#;
+
+/// Output of `REWRITE_JS`.
+#[derive(serde::Deserialize)]
+pub(crate) struct Rewritten {
+    pub html: String,
+    pub stylesheets: Vec<String>,
+}
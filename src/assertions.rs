@@ -0,0 +1,303 @@
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::capture::wildcard_match;
+use crate::config::BrowserConfig;
+use crate::element::Element;
+use crate::error::{Error, Result};
+use crate::locator::Locator;
+use crate::page::Page;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Start an assertion on a `Page`, `Locator` or `Element`, e.g.
+/// `expect(&page).to_have_title("Example Domain").await?`. Assertions retry until
+/// they pass or the timeout elapses, then fail with `Error::AssertionFailed`.
+pub fn expect<T: Expect>(target: T) -> T::Assertions {
+    target.expect()
+}
+
+/// Something `expect` accepts.
+pub trait Expect {
+    type Assertions;
+    fn expect(self) -> Self::Assertions;
+}
+
+impl<'a> Expect for &'a Page {
+    type Assertions = PageAssertions<'a>;
+
+    fn expect(self) -> PageAssertions<'a> {
+        PageAssertions {
+            page: self,
+            timeout: self.default_timeout(),
+        }
+    }
+}
+
+impl<'a> Expect for &'a Locator {
+    type Assertions = ElementAssertions<'a>;
+
+    fn expect(self) -> ElementAssertions<'a> {
+        ElementAssertions {
+            target: Target::Locator(self),
+            timeout: self.timeout,
+        }
+    }
+}
+
+impl<'a> Expect for &'a Element {
+    type Assertions = ElementAssertions<'a>;
+
+    /// Elements don't know their page's timeout, so this uses the browser
+    /// default (30s); override it with `ElementAssertions::timeout`.
+    fn expect(self) -> ElementAssertions<'a> {
+        ElementAssertions {
+            target: Target::Element(self),
+            timeout: BrowserConfig::default().default_timeout,
+        }
+    }
+}
+
+/// Run `check` until it reports success or `timeout` elapses. `check` returns
+/// whether it passed and what it saw; errors count as not passing yet.
+async fn poll<F, Fut>(timeout: Duration, expected: &str, check: F) -> Result<()>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<(bool, String)>>,
+{
+    let deadline = Instant::now() + timeout;
+    loop {
+        let seen = match check().await {
+            Ok((true, _)) => return Ok(()),
+            Ok((false, seen)) => seen,
+            Err(e) => e.to_string(),
+        };
+        if Instant::now() >= deadline {
+            return Err(Error::AssertionFailed(format!(
+                "expected {expected}, got {seen} (after {timeout:?})"
+            )));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Assertions on a page's title, URL and text. See `expect`.
+pub struct PageAssertions<'a> {
+    page: &'a Page,
+    timeout: Duration,
+}
+
+impl PageAssertions<'_> {
+    /// Wait this long instead of the page's default timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub async fn to_have_title(self, title: &str) -> Result<()> {
+        poll(self.timeout, &format!("title {title:?}"), || async {
+            let seen = self.page.title().await?;
+            Ok((seen == title, format!("{seen:?}")))
+        })
+        .await
+    }
+
+    pub async fn to_have_title_containing(self, text: &str) -> Result<()> {
+        poll(self.timeout, &format!("title containing {text:?}"), || async {
+            let seen = self.page.title().await?;
+            Ok((seen.contains(text), format!("{seen:?}")))
+        })
+        .await
+    }
+
+    pub async fn to_have_url(self, url: &str) -> Result<()> {
+        poll(self.timeout, &format!("URL {url:?}"), || async {
+            let seen = self.page.url().await?;
+            Ok((seen == url, format!("{seen:?}")))
+        })
+        .await
+    }
+
+    /// The whole URL matches `pattern`, where `*` matches any run of characters,
+    /// e.g. `"https://example.com/orders/*"`.
+    pub async fn to_have_url_matching(self, pattern: &str) -> Result<()> {
+        poll(self.timeout, &format!("URL matching {pattern:?}"), || async {
+            let seen = self.page.url().await?;
+            Ok((wildcard_match(pattern, &seen), format!("{seen:?}")))
+        })
+        .await
+    }
+
+    /// The page's visible text contains `text`.
+    pub async fn to_contain_text(self, text: &str) -> Result<()> {
+        poll(self.timeout, &format!("page text containing {text:?}"), || async {
+            let body: String = self
+                .page
+                .evaluate_typed("document.body ? document.body.innerText : ''")
+                .await?;
+            Ok((body.contains(text), format!("{} characters without it", body.len())))
+        })
+        .await
+    }
+}
+
+#[derive(Clone, Copy)]
+enum State {
+    Visible,
+    Enabled,
+    Disabled,
+    Checked,
+    Editable,
+}
+
+impl State {
+    fn name(self) -> &'static str {
+        match self {
+            State::Visible => "visible",
+            State::Enabled => "enabled",
+            State::Disabled => "disabled",
+            State::Checked => "checked",
+            State::Editable => "editable",
+        }
+    }
+}
+
+enum Held<'a> {
+    Borrowed(&'a Element),
+    Owned(Element),
+}
+
+impl std::ops::Deref for Held<'_> {
+    type Target = Element;
+
+    fn deref(&self) -> &Element {
+        match self {
+            Held::Borrowed(el) => el,
+            Held::Owned(el) => el,
+        }
+    }
+}
+
+enum Target<'a> {
+    Element(&'a Element),
+    Locator(&'a Locator),
+}
+
+/// Assertions on an element's state and content. For a `Locator` the element is
+/// looked up again on every try, so it may appear, change or be replaced while
+/// the assertion waits. See `expect`.
+pub struct ElementAssertions<'a> {
+    target: Target<'a>,
+    timeout: Duration,
+}
+
+impl ElementAssertions<'_> {
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The element now: for a locator, its current match.
+    async fn current(&self) -> Result<Held<'_>> {
+        match self.target {
+            Target::Element(el) => Ok(Held::Borrowed(el)),
+            Target::Locator(loc) => Ok(Held::Owned(loc.current().await?)),
+        }
+    }
+
+    async fn wait_for_state(self, state: State) -> Result<()> {
+        poll(self.timeout, state.name(), || async {
+            let el = self.current().await?;
+            let ok = match state {
+                State::Visible => el.is_visible().await?,
+                State::Enabled => el.is_enabled().await?,
+                State::Disabled => !el.is_enabled().await?,
+                State::Checked => el.is_checked().await?,
+                State::Editable => el.is_editable().await?,
+            };
+            Ok((ok, format!("not {}", state.name())))
+        })
+        .await
+    }
+
+    pub async fn to_be_visible(self) -> Result<()> {
+        self.wait_for_state(State::Visible).await
+    }
+
+    /// Not visible, or no longer in the page.
+    pub async fn to_be_hidden(self) -> Result<()> {
+        poll(self.timeout, "hidden", || async {
+            let visible = match self.current().await {
+                Ok(el) => el.is_visible().await?,
+                Err(Error::ElementNotFound(_)) => false,
+                Err(e) => return Err(e),
+            };
+            Ok((!visible, "visible".to_string()))
+        })
+        .await
+    }
+
+    pub async fn to_be_enabled(self) -> Result<()> {
+        self.wait_for_state(State::Enabled).await
+    }
+
+    pub async fn to_be_disabled(self) -> Result<()> {
+        self.wait_for_state(State::Disabled).await
+    }
+
+    pub async fn to_be_checked(self) -> Result<()> {
+        self.wait_for_state(State::Checked).await
+    }
+
+    pub async fn to_be_editable(self) -> Result<()> {
+        self.wait_for_state(State::Editable).await
+    }
+
+    /// The element's inner text, trimmed, equals `text`.
+    pub async fn to_have_text(self, text: &str) -> Result<()> {
+        poll(self.timeout, &format!("text {text:?}"), || async {
+            let seen = self.current().await?.inner_text().await?;
+            Ok((seen.trim() == text, format!("{:?}", seen.trim())))
+        })
+        .await
+    }
+
+    pub async fn to_contain_text(self, text: &str) -> Result<()> {
+        poll(self.timeout, &format!("text containing {text:?}"), || async {
+            let seen = self.current().await?.inner_text().await?;
+            Ok((seen.contains(text), format!("{seen:?}")))
+        })
+        .await
+    }
+
+    /// The value of an input, textarea or select.
+    pub async fn to_have_value(self, value: &str) -> Result<()> {
+        poll(self.timeout, &format!("value {value:?}"), || async {
+            let seen: String = self.current().await?.get_property("value").await?;
+            Ok((seen == value, format!("{seen:?}")))
+        })
+        .await
+    }
+
+    pub async fn to_have_attribute(self, name: &str, value: &str) -> Result<()> {
+        poll(self.timeout, &format!("{name}={value:?}"), || async {
+            let seen = self.current().await?.get_attribute(name).await?;
+            Ok((seen.as_deref() == Some(value), format!("{name}={seen:?}")))
+        })
+        .await
+    }
+
+    /// Only for a `Locator`: the number of matching elements.
+    pub async fn to_have_count(self, count: usize) -> Result<()> {
+        let Target::Locator(loc) = self.target else {
+            return Err(Error::AssertionFailed("to_have_count needs a Locator".to_string()));
+        };
+        poll(self.timeout, &format!("{count} elements"), || async {
+            let seen = loc.count().await?;
+            Ok((seen == count, seen.to_string()))
+        })
+        .await
+    }
+}
@@ -0,0 +1,297 @@
+use crate::page::FormField;
+
+/// Personal details for `Page::autofill`. Leave unknown fields as `None`; the
+/// full name and its parts are derived from each other when only one is set.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct FillProfile {
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub full_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub company: Option<String>,
+    pub address_line1: Option<String>,
+    pub address_line2: Option<String>,
+    pub city: Option<String>,
+    /// State, province or region.
+    pub state: Option<String>,
+    pub postal_code: Option<String>,
+    /// Matched against `<select>` options by value or visible text.
+    pub country: Option<String>,
+}
+
+/// Which `FillProfile` value a form field was matched to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileField {
+    FirstName,
+    LastName,
+    FullName,
+    Email,
+    Phone,
+    Company,
+    AddressLine1,
+    AddressLine2,
+    City,
+    State,
+    PostalCode,
+    Country,
+}
+
+impl FillProfile {
+    pub fn value(&self, field: ProfileField) -> Option<String> {
+        let words = || self.full_name.as_deref().map(|n| n.split_whitespace().collect::<Vec<_>>());
+        match field {
+            ProfileField::FirstName => self
+                .first_name
+                .clone()
+                .or_else(|| words().and_then(|w| w.first().map(|s| s.to_string()))),
+            ProfileField::LastName => self.last_name.clone().or_else(|| {
+                words().filter(|w| w.len() > 1).and_then(|w| w.last().map(|s| s.to_string()))
+            }),
+            ProfileField::FullName => self.full_name.clone().or_else(|| {
+                let parts: Vec<&str> =
+                    [&self.first_name, &self.last_name].iter().filter_map(|p| p.as_deref()).collect();
+                (!parts.is_empty()).then(|| parts.join(" "))
+            }),
+            ProfileField::Email => self.email.clone(),
+            ProfileField::Phone => self.phone.clone(),
+            ProfileField::Company => self.company.clone(),
+            ProfileField::AddressLine1 => self.address_line1.clone(),
+            ProfileField::AddressLine2 => self.address_line2.clone(),
+            ProfileField::City => self.city.clone(),
+            ProfileField::State => self.state.clone(),
+            ProfileField::PostalCode => self.postal_code.clone(),
+            ProfileField::Country => self.country.clone(),
+        }
+    }
+}
+
+/// Why `Page::autofill` left a field alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// Nothing in the field's label, name, id or placeholder matched.
+    NoMatch,
+    /// Matched, but the profile has no value for it.
+    NoProfileValue,
+    /// Payment, password or identity-number field; never filled.
+    Sensitive,
+    /// Hidden, button, checkbox, radio or file input.
+    Unsupported,
+    /// Already has a value.
+    AlreadyFilled,
+    /// Has neither an id nor a name to find it by.
+    NoSelector,
+    /// A `<select>` with no option matching the value.
+    NoOption,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct FilledField {
+    pub field: FormField,
+    pub matched: ProfileField,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SkippedField {
+    pub field: FormField,
+    pub reason: SkipReason,
+}
+
+/// Result of `Page::autofill`.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct AutofillReport {
+    pub filled: Vec<FilledField>,
+    pub skipped: Vec<SkippedField>,
+}
+
+/// Lowercase alphanumeric words, splitting camelCase (`billingZip` → billing, zip).
+fn words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in text.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_numeric();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Words matched whole, since they also occur inside unrelated words.
+const SENSITIVE_WORDS: &[&str] = &["cc", "cvv", "cvc", "csc", "cvn", "ssn", "pin", "exp", "mm", "yy"];
+
+/// Matched anywhere in the joined words.
+const SENSITIVE_TEXT: &[&str] = &[
+    "card",
+    "creditcard",
+    "expir",
+    "securitycode",
+    "iban",
+    "routing",
+    "accountnumber",
+    "socialsecurity",
+    "taxid",
+    "password",
+    "passcode",
+];
+
+/// Keywords for each profile value, tried in order so that e.g. "email address"
+/// is an email and "billing address 2" a second address line.
+const RULES: &[(ProfileField, &[&str])] = &[
+    (ProfileField::Email, &["email", "mail"]),
+    (ProfileField::Phone, &["phone", "tel", "telephone", "mobile", "cell"]),
+    (ProfileField::FirstName, &["firstname", "givenname", "fname", "forename"]),
+    (ProfileField::LastName, &["lastname", "surname", "familyname", "lname"]),
+    (ProfileField::Company, &["company", "organization", "organisation", "employer", "business"]),
+    (ProfileField::AddressLine2, &["address2", "addressline2", "apartment", "suite", "apt"]),
+    (ProfileField::PostalCode, &["zip", "zipcode", "postal", "postcode"]),
+    (ProfileField::City, &["city", "town", "locality", "suburb"]),
+    (ProfileField::State, &["state", "province", "region", "county"]),
+    (ProfileField::Country, &["country"]),
+    (ProfileField::AddressLine1, &["address", "address1", "addressline1", "street"]),
+    (ProfileField::FullName, &["fullname", "yourname", "name"]),
+];
+
+/// Whether the field's description looks like payment or identity data, or its
+/// placeholder is card-shaped ("1234 5678 9012 3456", "MM / YY").
+fn is_sensitive(field: &FormField, parts: &[Vec<String>]) -> bool {
+    if field.r#type == "password" {
+        return true;
+    }
+    let digits = field.placeholder.chars().filter(char::is_ascii_digit).count();
+    if digits >= 12 {
+        return true;
+    }
+    parts.iter().any(|words| {
+        words.iter().any(|w| SENSITIVE_WORDS.contains(&w.as_str()))
+            || SENSITIVE_TEXT.iter().any(|k| words.concat().contains(k))
+    })
+}
+
+/// Match a field to a profile value, or say why it can't be.
+pub(crate) fn classify(field: &FormField) -> Result<ProfileField, SkipReason> {
+    let kind = field.r#type.to_lowercase();
+    if field.tag == "input"
+        && matches!(
+            kind.as_str(),
+            "hidden" | "submit" | "button" | "reset" | "image" | "file" | "checkbox" | "radio"
+        )
+    {
+        return Err(SkipReason::Unsupported);
+    }
+    let parts: Vec<Vec<String>> = [&field.label, &field.name, &field.id, &field.placeholder]
+        .iter()
+        .map(|text| words(text))
+        .filter(|w| !w.is_empty())
+        .collect();
+    if is_sensitive(field, &parts) {
+        return Err(SkipReason::Sensitive);
+    }
+    match kind.as_str() {
+        "email" => return Ok(ProfileField::Email),
+        "tel" => return Ok(ProfileField::Phone),
+        _ => {}
+    }
+    for (profile_field, keywords) in RULES {
+        let matched = parts.iter().any(|words| {
+            let joined = words.concat();
+            keywords.iter().any(|k| {
+                // "name" alone would match "username" or "company name"
+                if *k == "name" {
+                    joined == "name"
+                } else {
+                    words.iter().any(|w| w == k) || (k.len() > 4 && joined.contains(k))
+                }
+            })
+        });
+        if matched {
+            return Ok(*profile_field);
+        }
+    }
+    Err(SkipReason::NoMatch)
+}
+
+/// A matched field and the value to put in it.
+pub(crate) struct PlannedFill {
+    pub filled: FilledField,
+    pub value: String,
+}
+
+/// Match `fields` against `profile`, splitting them into fields to fill and
+/// fields to skip.
+pub(crate) fn plan(fields: Vec<FormField>, profile: &FillProfile) -> (Vec<PlannedFill>, Vec<SkippedField>) {
+    let mut fills = Vec::new();
+    let mut skipped = Vec::new();
+    for field in fields {
+        let planned = classify(&field).and_then(|matched| {
+            let value = profile.value(matched).ok_or(SkipReason::NoProfileValue)?;
+            if field.id.is_empty() && field.name.is_empty() {
+                return Err(SkipReason::NoSelector);
+            }
+            if field.tag != "select" && !field.value.is_empty() {
+                return Err(SkipReason::AlreadyFilled);
+            }
+            Ok((matched, value))
+        });
+        match planned {
+            Ok((matched, value)) => fills.push(PlannedFill {
+                filled: FilledField { field, matched },
+                value,
+            }),
+            Err(reason) => skipped.push(SkippedField { field, reason }),
+        }
+    }
+    (fills, skipped)
+}
+
+/// A fill that `AUTOFILL_JS` could not apply.
+#[derive(serde::Deserialize)]
+pub(crate) struct FillFailure {
+    pub index: usize,
+    pub reason: SkipReason,
+}
+
+/// Fills `fills` (`{id, name, tag, value}`) in one pass and returns the ones it
+/// could not apply as `FillFailure`s. Uses the native value setter and
+/// dispatches input/change/blur so framework-controlled inputs pick it up.
+pub(crate) const AUTOFILL_JS: &str = r#"(fills) => {
+    const failed = [];
+    fills.forEach((f, i) => {
+        let el = f.id ? document.getElementById(f.id) : null;
+        if (!el || el.tagName.toLowerCase() !== f.tag) {
+            el = [...document.getElementsByName(f.name)].find(e => e.tagName.toLowerCase() === f.tag);
+        }
+        if (!el) { failed.push({ index: i, reason: 'no_selector' }); return; }
+        if (f.tag === 'select') {
+            const want = f.value.trim().toLowerCase();
+            const options = [...el.options];
+            const option = options.find(o => o.value.toLowerCase() === want || o.text.trim().toLowerCase() === want)
+                || options.find(o => o.text.trim().toLowerCase().startsWith(want));
+            if (!option) { failed.push({ index: i, reason: 'no_option' }); return; }
+            el.value = option.value;
+        } else {
+            const proto = f.tag === 'textarea' ? HTMLTextAreaElement.prototype : HTMLInputElement.prototype;
+            el.focus();
+            Object.getOwnPropertyDescriptor(proto, 'value').set.call(el, f.value);
+        }
+        el.dispatchEvent(new Event('input', { bubbles: true }));
+        el.dispatchEvent(new Event('change', { bubbles: true }));
+        el.blur();
+    });
+    return failed;
+}"#;
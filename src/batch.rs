@@ -0,0 +1,112 @@
+use std::future::Future;
+use std::time::Duration;
+
+use futures::StreamExt;
+
+use crate::browser::AgenticBrowser;
+use crate::error::{Error, Result};
+use crate::page::Page;
+
+/// Settings for `AgenticBrowser::map_urls_with`.
+#[derive(Debug, Clone)]
+pub struct MapOptions {
+    /// Pages open at once.
+    pub concurrency: usize,
+    /// Limit for one attempt: navigation plus the closure.
+    pub timeout: Duration,
+    /// Extra attempts after a failure or timeout, each on a fresh page.
+    pub retries: u32,
+    pub retry_delay: Duration,
+}
+
+impl MapOptions {
+    /// `concurrency` pages at once, 60s per attempt, one retry after 1s.
+    pub fn new(concurrency: usize) -> Self {
+        Self {
+            concurrency: concurrency.max(1),
+            timeout: Duration::from_secs(60),
+            retries: 1,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+}
+
+/// Outcome for one URL of `AgenticBrowser::map_urls`.
+#[derive(Debug)]
+pub struct UrlResult<T> {
+    pub url: String,
+    /// Attempts made, including the successful one.
+    pub attempts: u32,
+    /// The closure's value, or the error of the last attempt.
+    pub result: Result<T>,
+}
+
+/// One attempt: open a page, navigate, run `f`, and close the page whatever
+/// happened, including a timeout.
+async fn attempt<F, Fut, T>(browser: &AgenticBrowser, url: &str, timeout: Duration, f: &F) -> Result<T>
+where
+    F: Fn(Page) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let page = browser.new_blank_page().await?;
+    let tab = page.inner().clone();
+    let result = tokio::time::timeout(timeout, async {
+        page.goto(url).await?;
+        f(page).await
+    })
+    .await
+    .unwrap_or_else(|_| Err(Error::Timeout(format!("{url} after {timeout:?}"))));
+    let _ = tab.close().await;
+    result
+}
+
+pub(crate) async fn map_urls<I, S, F, Fut, T>(
+    browser: &AgenticBrowser,
+    urls: I,
+    options: MapOptions,
+    f: F,
+) -> Vec<UrlResult<T>>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+    F: Fn(Page) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let options = &options;
+    let f = &f;
+    let mut results: Vec<(usize, UrlResult<T>)> = futures::stream::iter(urls.into_iter().enumerate())
+        .map(|(index, url)| async move {
+            let url: String = url.into();
+            let mut attempts = 0;
+            let result = loop {
+                attempts += 1;
+                match attempt(browser, &url, options.timeout, f).await {
+                    Err(_) if attempts <= options.retries => {
+                        tokio::time::sleep(options.retry_delay).await;
+                    }
+                    result => break result,
+                }
+            };
+            (index, UrlResult { url, attempts, result })
+        })
+        .buffer_unordered(options.concurrency.max(1))
+        .collect()
+        .await;
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
@@ -0,0 +1,68 @@
+/// Bot wall or CAPTCHA on a page, as reported by `Page::detect_blocking`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Blocking {
+    #[default]
+    None,
+    /// A visible Google reCAPTCHA v2 challenge (invisible v3 badges don't count).
+    Recaptcha,
+    Hcaptcha,
+    /// A Cloudflare Turnstile widget embedded in the site's own page.
+    Turnstile,
+    /// Cloudflare's interstitial "Just a moment..." / "Attention Required" page.
+    CloudflareChallenge,
+    /// Google's `/sorry/` rate-limit page.
+    GoogleSorry,
+    /// A 403/429 response or a block page ("Access Denied", "You have been blocked").
+    AccessDenied,
+}
+
+impl Blocking {
+    pub fn is_blocked(&self) -> bool {
+        *self != Blocking::None
+    }
+
+    /// A CAPTCHA a human (or solver) could pass, as opposed to a hard block.
+    pub fn is_captcha(&self) -> bool {
+        matches!(self, Blocking::Recaptcha | Blocking::Hcaptcha | Blocking::Turnstile)
+    }
+}
+
+/// Checks, most specific first: the URL, Cloudflare challenge markers, visible
+/// CAPTCHA widgets, then the document's response status and block-page wording.
+pub(crate) const DETECT_BLOCKING_JS: &str = r#"(() => {
+    const visible = (el) => {
+        if (!el) return false;
+        const rect = el.getBoundingClientRect();
+        if (rect.width < 2 || rect.height < 2) return false;
+        return typeof el.checkVisibility === 'function'
+            ? el.checkVisibility({ checkOpacity: true, checkVisibilityCSS: true })
+            : el.offsetParent !== null;
+    };
+    const any = (sel) => [...document.querySelectorAll(sel)].some(visible);
+    const title = document.title || '';
+
+    if (/^(www\.|ipv4\.|ipv6\.)?google\.[a-z.]+$/.test(location.hostname) && location.pathname.startsWith('/sorry/')) {
+        return 'google_sorry';
+    }
+    if (/^(just a moment|attention required!? \| cloudflare)/i.test(title)
+        || document.querySelector('#challenge-form, #cf-challenge-running, #challenge-stage, #cf-chl-widget')
+        || (typeof window._cf_chl_opt === 'object' && !document.querySelector('main, nav, article'))) {
+        return 'cloudflare_challenge';
+    }
+    if (any('.cf-turnstile, iframe[src*="challenges.cloudflare.com"]')) return 'turnstile';
+    if (any('.h-captcha, iframe[src*="hcaptcha.com"]')) return 'hcaptcha';
+    if (any('.g-recaptcha, iframe[src*="/recaptcha/"][src*="anchor"]:not([src*="size=invisible"]), iframe[src*="/recaptcha/"][src*="bframe"]')) {
+        return 'recaptcha';
+    }
+
+    const nav = performance.getEntriesByType('navigation')[0];
+    const status = nav && nav.responseStatus ? nav.responseStatus : 0;
+    const text = document.body ? document.body.innerText : '';
+    const blockPage = /access denied|you have been blocked|request blocked|request unsuccessful|unusual traffic|too many requests|are you a robot|403 forbidden/i;
+    if (status === 403 || status === 429
+        || (text.length < 3000 && (blockPage.test(title) || blockPage.test(text.substring(0, 1000))))) {
+        return 'access_denied';
+    }
+    return 'none';
+})()"#;
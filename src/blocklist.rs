@@ -0,0 +1,55 @@
+/// A small ("EasyList-lite") list of the most common ad, analytics and tracking
+/// hosts. Patterns use the `Network.setBlockedURLs` wildcard syntax (`*`).
+/// Not a replacement for a full EasyList, but covers the bulk of third-party
+/// requests on typical news, shopping and search pages.
+pub const ADS_AND_TRACKERS: &[&str] = &[
+    // Google ads & analytics
+    "*://*.doubleclick.net/*",
+    "*://*.googlesyndication.com/*",
+    "*://*.googleadservices.com/*",
+    "*://*.google-analytics.com/*",
+    "*://*.googletagmanager.com/*",
+    "*://*.googletagservices.com/*",
+    "*://adservice.google.com/*",
+    // Social trackers
+    "*://connect.facebook.net/*",
+    "*://*.facebook.com/tr*",
+    "*://analytics.twitter.com/*",
+    "*://static.ads-twitter.com/*",
+    "*://px.ads.linkedin.com/*",
+    "*://snap.licdn.com/*",
+    "*://*.tiktok.com/i18n/pixel/*",
+    // Ad exchanges & networks
+    "*://*.adnxs.com/*",
+    "*://*.adsrvr.org/*",
+    "*://*.amazon-adsystem.com/*",
+    "*://*.criteo.com/*",
+    "*://*.criteo.net/*",
+    "*://*.pubmatic.com/*",
+    "*://*.rubiconproject.com/*",
+    "*://*.openx.net/*",
+    "*://*.casalemedia.com/*",
+    "*://*.taboola.com/*",
+    "*://*.outbrain.com/*",
+    "*://*.moatads.com/*",
+    "*://*.media.net/*",
+    "*://*.smartadserver.com/*",
+    "*://*.adform.net/*",
+    // Analytics, session replay & tag managers
+    "*://*.scorecardresearch.com/*",
+    "*://*.quantserve.com/*",
+    "*://*.hotjar.com/*",
+    "*://*.mouseflow.com/*",
+    "*://*.fullstory.com/*",
+    "*://*.clarity.ms/*",
+    "*://*.segment.io/*",
+    "*://cdn.segment.com/*",
+    "*://*.mixpanel.com/*",
+    "*://*.amplitude.com/*",
+    "*://*.newrelic.com/*",
+    "*://*.nr-data.net/*",
+    "*://*.chartbeat.com/*",
+    "*://*.optimizely.com/*",
+    "*://*.branch.io/*",
+    "*://*.bat.bing.com/*",
+];
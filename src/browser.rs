@@ -1,19 +1,55 @@
-use std::sync::Arc;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use chromiumoxide::browser::{Browser as CrBrowser, BrowserConfig as CrBrowserConfig};
-use chromiumoxide::cdp::browser_protocol::fetch::{
-    self, AuthChallengeResponseResponse, ContinueWithAuthParams, EnableParams,
-    EventAuthRequired, EventRequestPaused,
+use chromiumoxide::browser::{
+    Browser as CrBrowser, BrowserConfig as CrBrowserConfig, HeadlessMode as CrHeadlessMode,
+};
+use chromiumoxide::cdp::browser_protocol::browser::{
+    Bounds, BrowserContextId, EventDownloadWillBegin, GetWindowForTargetParams, SetDownloadBehaviorBehavior,
+    SetDownloadBehaviorParams, SetWindowBoundsParams, WindowId,
+};
+use chromiumoxide::cdp::browser_protocol::page::NavigateParams;
+use chromiumoxide::cdp::browser_protocol::target::{
+    CreateTargetParams, EventTargetCrashed, EventTargetCreated, EventTargetDestroyed, GetTargetsParams, TargetId,
+    TargetInfo,
 };
 use chromiumoxide::handler::viewport::Viewport;
+use chromiumoxide::page::Page as CrPage;
 use futures::StreamExt;
 
-use crate::config::{BrowserBuilder, BrowserConfig};
+use crate::batch::{self, MapOptions, UrlResult};
+#[cfg(feature = "low-resource")]
+use crate::blocklist::ADS_AND_TRACKERS;
+#[cfg(feature = "cdp-log")]
+use crate::cdp_log;
+use crate::certs::{self, CertStore};
+use crate::config::{BrowserBuilder, BrowserConfig, HeadlessMode, NetworkThrottle, WindowBounds, WindowState};
+use crate::detect;
 use crate::error::{Error, Result};
+use crate::events::{self, BackgroundErrorPolicy, BrowserEvent, EventHandler, EventKind, SharedHandlers};
+use crate::features::{self, FeatureReport};
+use crate::identity::{Identity, RequestIdentity};
+use crate::intercept::{self, InterceptRules, Interceptor};
+use crate::metrics::{self, MemoryUsage};
 use crate::page::Page;
+use crate::profiles::ProfileContext;
+use crate::ratelimit::RateLimiter;
+use crate::readonly::ReadOnly;
+use crate::redact::Redaction;
+use crate::snapshot::ExtractionBackend;
+#[cfg(feature = "stealth")]
 use crate::stealth;
+use crate::telemetry;
+use crate::watchdog;
+use crate::xvfb::Xvfb;
 
 /// Chrome flags that improve performance without affecting functionality.
+#[cfg(feature = "low-resource")]
 const PERF_ARGS: &[&str] = &[
     "disable-gpu",
     "disable-extensions",
@@ -21,14 +57,51 @@ const PERF_ARGS: &[&str] = &[
     "mute-audio",
     "no-default-browser-check",
 ];
+#[cfg(not(feature = "low-resource"))]
+const PERF_ARGS: &[&str] = &[];
 
 /// The main entry point for controlling a browser instance.
 pub struct AgenticBrowser {
-    browser: CrBrowser,
+    /// Shared with the task guarding new tabs, which only holds it weakly.
+    browser: Arc<CrBrowser>,
+    headless: bool,
+    #[cfg(feature = "stealth")]
     stealth: bool,
-    proxy_auth: Option<(Arc<str>, Arc<str>)>,
-    default_timeout: std::time::Duration,
-    _handler_task: tokio::task::JoinHandle<()>,
+    intercept: InterceptRules,
+    blocked_urls: Vec<String>,
+    extra_headers: HashMap<String, String>,
+    network_throttle: Option<NetworkThrottle>,
+    default_timeout: Duration,
+    extraction_backend: ExtractionBackend,
+    rate_limiter: Option<RateLimiter>,
+    redaction: Option<Redaction>,
+    read_only: Option<ReadOnly>,
+    request_identity: Option<RequestIdentity>,
+    dialog_handlers: Arc<SharedHandlers>,
+    memory_handlers: Arc<SharedHandlers>,
+    background_handlers: Arc<SharedHandlers>,
+    background_error_policy: BackgroundErrorPolicy,
+    /// Whether a `DownloadStarted` handler is registered, so profile contexts
+    /// emit download events too.
+    download_events: AtomicBool,
+    /// Interceptors of pages already set up, by target id.
+    prepared: Arc<Mutex<HashMap<String, Arc<Interceptor>>>>,
+    /// Tabs in `prepared` whose interceptor was registered as they appeared
+    /// and whose remaining setup is still to do. Changed under `prepared`'s lock.
+    adopted: Arc<Mutex<HashSet<String>>>,
+    /// Request rules and identity of each open profile context, for the tabs
+    /// its pages open themselves.
+    contexts: Arc<ContextRules>,
+    process_id: Option<u32>,
+    handler_task: tokio::task::JoinHandle<()>,
+    /// Prewarmed about:blank pages handed out by `new_blank_page`/`new_page`.
+    pool: Mutex<Vec<Page>>,
+    /// The configuration this browser was launched with, for `restart`.
+    config: BrowserConfig,
+    /// Virtual display for headful mode; dropped after the browser.
+    _xvfb: Option<Xvfb>,
+    /// Home and profile holding the client certificates; removed after the browser.
+    _certs: Option<CertStore>,
 }
 
 impl AgenticBrowser {
@@ -39,12 +112,45 @@ impl AgenticBrowser {
 
     /// Launch a browser instance with the given configuration.
     pub async fn launch(config: BrowserConfig) -> Result<Self> {
+        check_features(&config)?;
+        #[cfg(feature = "cdp-log")]
+        if let Some(ref path) = config.cdp_log {
+            cdp_log::install(path)?;
+        }
+        let original = config.clone();
         let mut builder = CrBrowserConfig::builder();
 
-        if config.headless {
-            builder = builder.new_headless_mode().no_sandbox();
-        } else {
-            builder = builder.with_head().no_sandbox();
+        builder = match config.launch_mode() {
+            HeadlessMode::New => builder.new_headless_mode(),
+            HeadlessMode::Old => builder.headless_mode(CrHeadlessMode::True),
+            HeadlessMode::False => builder.with_head(),
+        }
+        .no_sandbox();
+
+        // Headful without a display: give Chrome a virtual one
+        let mut xvfb = None;
+        if config.xvfb && !config.launch_mode().is_headless() && std::env::var_os("DISPLAY").is_none() {
+            let display = Xvfb::start(config.viewport_width, config.viewport_height).await?;
+            builder = builder.env("DISPLAY", display.display());
+            xvfb = Some(display);
+        }
+
+        // Window placement only means something with a window; there is no
+        // switch to start minimized
+        if !config.launch_mode().is_headless() {
+            if let Some((x, y)) = config.window_position {
+                builder = builder.arg(("window-position", format!("{x},{y}")));
+            }
+            match config.window_state {
+                WindowState::Normal => {}
+                WindowState::Maximized => builder = builder.arg("start-maximized"),
+                WindowState::Fullscreen => builder = builder.arg("start-fullscreen"),
+                WindowState::Minimized => {
+                    return Err(Error::ConfigError(
+                        "windows can't start minimized; call set_window_state after launch".to_string(),
+                    ));
+                }
+            }
         }
 
         // Performance: add Chrome flags that reduce startup and load time
@@ -54,6 +160,7 @@ impl AgenticBrowser {
 
         // Stealth: add anti-detection Chrome flags
         // chromiumoxide adds `--` prefix automatically, so keys must NOT include `--`
+        #[cfg(feature = "stealth")]
         if config.stealth {
             for arg in stealth::stealth_key_args() {
                 builder = builder.arg(arg);
@@ -67,34 +174,91 @@ impl AgenticBrowser {
         // Use tuple format: ("key", "value") -> --key=value
         if let Some(ref proxy) = config.proxy {
             builder = builder.arg(("proxy-server", proxy.server.as_str()));
+            if !proxy.bypass.is_empty() {
+                builder = builder.arg(("proxy-bypass-list", proxy.bypass.join(";")));
+            }
         }
 
-        if let Some(ref path) = config.chrome_path {
-            builder = builder.chrome_executable(path);
+        // Host mappings: "MAP example.com 127.0.0.1, MAP *.test 127.0.0.1"
+        if !config.host_rules.is_empty() {
+            let rules: Vec<String> = config
+                .host_rules
+                .iter()
+                .map(|(host, target)| format!("MAP {host} {target}"))
+                .collect();
+            builder = builder.arg(("host-resolver-rules", rules.join(", ")));
         }
 
-        builder = builder.viewport(Viewport {
-            width: config.viewport_width,
-            height: config.viewport_height,
-            device_scale_factor: None,
-            emulating_mobile: false,
-            is_landscape: false,
-            has_touch: false,
-        });
+        // Fake camera/microphone: ("key", "value") -> --key=value
+        if let Some(ref media) = config.fake_media {
+            for (key, value) in media.args()? {
+                builder = match value {
+                    Some(value) => builder.arg((key, value)),
+                    None => builder.arg(key),
+                };
+            }
+        }
+
+        // Secure DNS is a feature flag: ("enable-features", "...,DnsOverHttps:...")
+        if let Some(ref dns) = config.secure_dns {
+            builder = builder.arg(dns.arg()?);
+        }
+
+        // User-supplied switches: "--key=value" -> ("key", "value"), "--key" -> "key"
+        for arg in &config.extra_args {
+            let arg = arg.trim_start_matches("--");
+            builder = match arg.split_once('=') {
+                Some((key, value)) => builder.arg((key, value)),
+                None => builder.arg(arg),
+            };
+        }
+
+        // Client certificates live in an NSS database under HOME, not in Chrome flags
+        let cert_store = certs::install(&config.client_certificates, &config.extra_args)?;
+        if let Some(ref store) = cert_store {
+            builder = builder
+                .env("HOME", store.home().to_string_lossy())
+                .user_data_dir(store.user_data_dir());
+        }
+
+        let chrome_path = match config.chrome_path {
+            Some(ref path) => PathBuf::from(path),
+            None => detect::find_chrome()?,
+        };
+        builder = builder.chrome_executable(chrome_path);
+
+        // A maximized or fullscreen window shows pages at its own size
+        let fills_screen = matches!(config.window_state, WindowState::Maximized | WindowState::Fullscreen);
+        if config.launch_mode().is_headless() || !fills_screen {
+            builder = builder.viewport(Viewport {
+                width: config.viewport_width,
+                height: config.viewport_height,
+                device_scale_factor: config.device_scale_factor,
+                emulating_mobile: false,
+                is_landscape: false,
+                has_touch: false,
+            });
+        } else {
+            builder = builder.viewport(None);
+        }
 
         let cr_config = builder
             .build()
             .map_err(|e| Error::LaunchError(e.to_string()))?;
 
-        let (browser, mut handler) = CrBrowser::launch(cr_config)
+        let (mut browser, mut handler) = CrBrowser::launch(cr_config)
             .await
             .map_err(|e| Error::LaunchError(e.to_string()))?;
+        let process_id = browser.get_mut_child().and_then(|c| c.as_mut_inner().id());
 
         let handler_task = tokio::spawn(async move {
             while let Some(_event) = handler.next().await {}
         });
+        #[cfg(feature = "telemetry")]
+        telemetry::watch_pages(&browser).await?;
 
         // Extract proxy auth credentials for later use with CDP (Arc to avoid per-event cloning)
+        #[cfg(feature = "proxy-auth")]
         let proxy_auth = config.proxy.as_ref().and_then(|p| {
             match (&p.username, &p.password) {
                 (Some(u), Some(p)) => Some((Arc::from(u.as_str()), Arc::from(p.as_str()))),
@@ -102,101 +266,708 @@ impl AgenticBrowser {
             }
         });
 
-        Ok(Self {
-            browser,
-            stealth: config.stealth,
+        let intercept = InterceptRules {
+            #[cfg(feature = "proxy-auth")]
             proxy_auth,
+            blocked_resources: config
+                .blocked_resources
+                .iter()
+                .filter_map(|rt| intercept::parse_resource_type(rt))
+                .collect(),
+            navigation_policy: config.navigation_policy.map(Arc::new),
+            block_writes: config.read_only.as_ref().is_some_and(ReadOnly::blocks_writes),
+            fixtures: config.network_fixtures.map(Arc::new),
+            ..InterceptRules::default()
+        };
+
+        #[cfg_attr(not(feature = "low-resource"), allow(unused_mut))]
+        let mut blocked_urls = config.blocked_urls;
+        #[cfg(feature = "low-resource")]
+        if config.block_ads {
+            blocked_urls.extend(ADS_AND_TRACKERS.iter().map(|p| p.to_string()));
+        }
+
+        let agentic = Self {
+            browser: Arc::new(browser),
+            headless: config.headless && config.headless_mode.is_headless(),
+            #[cfg(feature = "stealth")]
+            stealth: config.stealth,
+            intercept,
+            blocked_urls,
+            extra_headers: config.extra_headers,
+            network_throttle: config.network_throttle,
             default_timeout: config.default_timeout,
-            _handler_task: handler_task,
-        })
+            extraction_backend: config.extraction_backend,
+            rate_limiter: config.rate_limiter,
+            redaction: config.redaction,
+            read_only: config.read_only,
+            request_identity: config.request_identity,
+            dialog_handlers: Arc::default(),
+            memory_handlers: Arc::default(),
+            background_handlers: Arc::default(),
+            background_error_policy: config.background_error_policy,
+            download_events: AtomicBool::new(false),
+            prepared: Arc::default(),
+            adopted: Arc::default(),
+            contexts: Arc::default(),
+            process_id,
+            handler_task,
+            pool: Mutex::default(),
+            config: original,
+            _xvfb: xvfb,
+            _certs: cert_store,
+        };
+
+        if let Some(budget) = config.memory_budget {
+            watchdog::spawn(
+                budget,
+                process_id,
+                Arc::downgrade(&agentic.prepared),
+                Arc::clone(&agentic.memory_handlers),
+            );
+        }
+
+        if agentic.intercept.navigation_policy.is_some() {
+            agentic.guard_new_tabs().await?;
+        }
+
+        // Open the prewarmed tabs now so the first `new_page` calls skip tab creation
+        for _ in 0..config.prewarm_pages {
+            let page = agentic.open_blank_page().await?;
+            agentic.pool.lock().unwrap_or_else(|e| e.into_inner()).push(page);
+        }
+        Ok(agentic)
     }
 
     /// Open a new page (tab) navigated to the given URL.
     /// If stealth mode is enabled, anti-detection scripts are injected before navigation.
     /// If proxy auth is configured, it handles 407 challenges automatically.
+    /// Browser-level request rules (blocked resources and URLs, extra headers, throttling)
+    /// are applied before navigation as well.
     pub async fn new_page(&self, url: &str) -> Result<Page> {
+        let page = self.new_blank_page().await?;
+        page.goto(url).await?;
+        Ok(page)
+    }
+
+    /// Open a page for each URL, at most `concurrency` at a time, and run `f` on it
+    /// once it has loaded. Pages are closed afterwards; failed or timed-out URLs are
+    /// retried on a fresh page (see `MapOptions::new` for the defaults). Returns
+    /// one result per URL, in input order; one URL failing doesn't stop the others.
+    pub async fn map_urls<I, S, F, Fut, T>(&self, urls: I, concurrency: usize, f: F) -> Vec<UrlResult<T>>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+        F: Fn(Page) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        batch::map_urls(self, urls, MapOptions::new(concurrency), f).await
+    }
+
+    /// `map_urls` with explicit timeout and retry settings.
+    pub async fn map_urls_with<I, S, F, Fut, T>(
+        &self,
+        urls: I,
+        options: MapOptions,
+        f: F,
+    ) -> Vec<UrlResult<T>>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+        F: Fn(Page) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        batch::map_urls(self, urls, options, f).await
+    }
+
+    /// Open a new about:blank page with the same setup as `new_page`.
+    /// Pair with `Page::reset()` to reuse tabs instead of creating one per task.
+    /// Served from the prewarmed pool first (see `BrowserBuilder::prewarm_pages`).
+    pub async fn new_blank_page(&self) -> Result<Page> {
+        let pooled = self.pool.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        match pooled {
+            Some(page) => Ok(page),
+            None => self.open_blank_page().await,
+        }
+    }
+
+    /// Open the stored profile `name` (created with a fresh identity if it doesn't
+    /// exist) in its own browser context; needs `BrowserBuilder::profiles`. Pages
+    /// of the context share its cookies and storage but none of other profiles'.
+    pub async fn open_profile(&self, name: &str) -> Result<ProfileContext<'_>> {
+        let manager = self.config.profiles.as_ref().ok_or_else(|| {
+            Error::ConfigError("open_profile needs a profile directory (BrowserBuilder::profiles)".to_string())
+        })?;
+        let profile = manager.load_or_create(name)?;
+        ProfileContext::open(self, manager, profile).await
+    }
+
+    /// A new about:blank tab in browser context `context_id`, set up like
+    /// `new_page` but with the request rules and identity registered for the
+    /// context with `register_context`.
+    pub(crate) async fn open_context_page(&self, context_id: &BrowserContextId) -> Result<Page> {
+        let params = CreateTargetParams::builder()
+            .url("about:blank")
+            .browser_context_id(context_id.clone())
+            .build()
+            .map_err(Error::NavigationError)?;
+        let cr_page = self
+            .browser
+            .new_page(params)
+            .await
+            .map_err(|e| Error::NavigationError(e.to_string()))?;
+        let (intercept, identity) = self.context_settings(Some(context_id));
+        self.prepare_page(cr_page, intercept, identity).await
+    }
+
+    /// Give tabs of browser context `context_id`, including popups and tabs its
+    /// pages open, `intercept` and `identity` instead of the browser's.
+    pub(crate) fn register_context(&self, context_id: BrowserContextId, intercept: InterceptRules, identity: Identity) {
+        self.contexts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(context_id, (intercept, identity));
+    }
+
+    /// Undo `register_context` once the context is disposed.
+    pub(crate) fn forget_context(&self, context_id: &BrowserContextId) {
+        self.contexts.lock().unwrap_or_else(|e| e.into_inner()).remove(context_id);
+    }
+
+    /// The request rules and identity for a tab of `context_id`: the registered
+    /// ones, or the browser's for the default context.
+    fn context_settings(&self, context_id: Option<&BrowserContextId>) -> (InterceptRules, Option<Identity>) {
+        let contexts = self.contexts.lock().unwrap_or_else(|e| e.into_inner());
+        match context_id.and_then(|id| contexts.get(id)) {
+            Some((intercept, identity)) => (intercept.clone(), Some(identity.clone())),
+            None => (self.intercept.clone(), None),
+        }
+    }
+
+    /// Targets of the open tabs with their browser context, by target id.
+    async fn tab_contexts(&self) -> Result<HashMap<TargetId, Option<BrowserContextId>>> {
+        let targets = self.browser.execute(GetTargetsParams::default()).await?.result.target_infos;
+        Ok(targets
+            .into_iter()
+            .filter(|t| t.r#type == "page")
+            .map(|t| (t.target_id, t.browser_context_id))
+            .collect())
+    }
+
+    /// The open tabs of browser context `context_id`, whoever opened them.
+    pub(crate) async fn context_tabs(&self, context_id: &BrowserContextId) -> Result<Vec<CrPage>> {
+        let mut tabs = Vec::new();
+        for (target_id, context) in self.tab_contexts().await? {
+            if context.as_ref() == Some(context_id) {
+                // Closed since listing: nothing to read from it
+                if let Ok(tab) = self.browser.get_page(target_id).await {
+                    tabs.push(tab);
+                }
+            }
+        }
+        Ok(tabs)
+    }
+
+    pub(crate) fn cdp(&self) -> &CrBrowser {
+        &self.browser
+    }
+
+    /// The request rules every new page starts with.
+    pub(crate) fn intercept_rules(&self) -> &InterceptRules {
+        &self.intercept
+    }
+
+    /// Create and set up a new about:blank tab, bypassing the pool.
+    async fn open_blank_page(&self) -> Result<Page> {
         let cr_page = self
             .browser
             .new_page("about:blank")
             .await
             .map_err(|e| Error::NavigationError(e.to_string()))?;
+        self.prepare_page(cr_page, self.intercept.clone(), None).await
+    }
 
+    /// Apply stealth scripts, request interception and network settings to a
+    /// freshly created page, then wrap it. `identity` replaces one drawn from the
+    /// browser's `RequestIdentity` pool.
+    async fn prepare_page(&self, cr_page: CrPage, intercept: InterceptRules, identity: Option<Identity>) -> Result<Page> {
         // Inject stealth scripts BEFORE navigating to the target URL
+        #[cfg(feature = "stealth")]
         if self.stealth {
             stealth::apply_stealth(&cr_page).await?;
         }
+        self.setup_page(cr_page, intercept, identity).await
+    }
 
-        // Set up proxy authentication if credentials are provided
-        if let Some((ref username, ref password)) = self.proxy_auth {
-            // Set up event listeners BEFORE enabling fetch domain to avoid race condition
-            let mut auth_events = cr_page
-                .event_listener::<EventAuthRequired>()
-                .await
-                .map_err(|e| Error::LaunchError(format!("Failed to listen for auth events: {e}")))?;
+    /// Everything `prepare_page` does except stealth injection. Registers the page
+    /// so `pages()` hands out the same interceptor instead of setting it up again.
+    async fn setup_page(&self, cr_page: CrPage, intercept: InterceptRules, identity: Option<Identity>) -> Result<Page> {
+        match self.interceptor_for(&cr_page, intercept) {
+            (interceptor, true) => self.finish_setup(cr_page, interceptor, identity).await,
+            (interceptor, false) => self.wrap_page(cr_page, interceptor).await,
+        }
+    }
 
-            let mut pause_events = cr_page
-                .event_listener::<EventRequestPaused>()
-                .await
-                .map_err(|e| Error::LaunchError(format!("Failed to listen for request paused events: {e}")))?;
-
-            // Now enable fetch domain — listeners are already subscribed
-            let enable_params = EnableParams::builder()
-                .handle_auth_requests(true)
-                .build();
-            cr_page
-                .execute(enable_params)
-                .await
-                .map_err(|e| Error::LaunchError(format!("Failed to enable fetch for proxy auth: {e}")))?;
-
-            // Listen for auth challenges and respond with credentials
-            let username = Arc::clone(username);
-            let password = Arc::clone(password);
-            let page_clone = cr_page.clone();
-
-            tokio::spawn(async move {
-                while let Some(event) = auth_events.next().await {
-                    let auth_response = match fetch::AuthChallengeResponse::builder()
-                        .response(AuthChallengeResponseResponse::ProvideCredentials)
-                        .username(username.as_ref())
-                        .password(password.as_ref())
-                        .build()
-                    {
-                        Ok(r) => r,
-                        Err(e) => {
-                            eprintln!("Failed to build auth response: {e}");
-                            continue;
-                        }
-                    };
-                    let params = ContinueWithAuthParams::new(
-                        event.request_id.clone(),
-                        auth_response,
-                    );
-                    let _ = page_clone.execute(params).await;
-                }
-            });
+    /// The interceptor registered for `cr_page`, and whether it was registered
+    /// just now and still has to be set up. Registering under the lock keeps
+    /// concurrent callers from setting up a second interceptor for the same tab.
+    fn interceptor_for(&self, cr_page: &CrPage, intercept: InterceptRules) -> (Arc<Interceptor>, bool) {
+        let mut prepared = self.prepared.lock().unwrap_or_else(|e| e.into_inner());
+        match prepared.entry(cr_page.target_id().inner().clone()) {
+            Entry::Occupied(entry) => {
+                let adopted = self.adopted.lock().unwrap_or_else(|e| e.into_inner()).remove(entry.key());
+                (Arc::clone(entry.get()), adopted)
+            }
+            Entry::Vacant(entry) => {
+                // Proxy authentication and resource blocking share one Fetch.enable
+                let interceptor = Interceptor::new(
+                    cr_page.clone(),
+                    intercept,
+                    self.background_error_policy,
+                    Arc::clone(&self.background_handlers),
+                );
+                entry.insert(Arc::clone(&interceptor));
+                (interceptor, true)
+            }
+        }
+    }
 
-            // Also continue non-auth paused requests
-            let page_clone2 = cr_page.clone();
-            tokio::spawn(async move {
-                while let Some(event) = pause_events.next().await {
-                    let params = fetch::ContinueRequestParams::new(event.request_id.clone());
-                    let _ = page_clone2.execute(params).await;
+    /// Undo `interceptor_for` after a failed setup.
+    fn forget(&self, cr_page: &CrPage) {
+        let mut prepared = self.prepared.lock().unwrap_or_else(|e| e.into_inner());
+        prepared.remove(cr_page.target_id().inner());
+        self.adopted.lock().unwrap_or_else(|e| e.into_inner()).remove(cr_page.target_id().inner());
+    }
+
+    /// Register and enable the interceptor of every new tab as soon as Chrome
+    /// announces it, so the navigation policy covers popups and tabs opened by
+    /// scripts from their first requests rather than from the next `pages()`
+    /// call. The rest of their setup still happens on first sight.
+    async fn guard_new_tabs(&self) -> Result<()> {
+        let mut created = self
+            .browser
+            .event_listener::<EventTargetCreated>()
+            .await
+            .map_err(|e| Error::LaunchError(format!("Failed to listen for new tabs: {e}")))?;
+        let browser = Arc::downgrade(&self.browser);
+        let guard = TabGuard {
+            prepared: Arc::clone(&self.prepared),
+            adopted: Arc::clone(&self.adopted),
+            contexts: Arc::clone(&self.contexts),
+            intercept: self.intercept.clone(),
+            policy: self.background_error_policy,
+            handlers: Arc::clone(&self.background_handlers),
+        };
+        tokio::spawn(async move {
+            while let Some(event) = created.next().await {
+                if event.target_info.r#type != "page" {
+                    continue;
                 }
-            });
+                let Some(browser) = browser.upgrade() else {
+                    return;
+                };
+                let guard = guard.clone();
+                let target = event.target_info.clone();
+                tokio::spawn(async move { guard.adopt(&browser, target).await });
+            }
+        });
+        Ok(())
+    }
+
+    /// Enable a freshly registered interceptor and apply the network settings
+    /// and `identity`, or one from the browser's pool.
+    async fn finish_setup(
+        &self,
+        cr_page: CrPage,
+        interceptor: Arc<Interceptor>,
+        identity: Option<Identity>,
+    ) -> Result<Page> {
+        let enabled = async {
+            interceptor.apply().await?;
+            self.dialog_handlers.watch(&cr_page).await
+        }
+        .await;
+        if let Err(e) = enabled {
+            self.forget(&cr_page);
+            return Err(e);
         }
 
-        cr_page
-            .goto(url)
-            .await
-            .map_err(|e| Error::NavigationError(e.to_string()))?;
+        let page = self.wrap_page(cr_page, interceptor).await?;
+        if !self.blocked_urls.is_empty() {
+            let patterns: Vec<&str> = self.blocked_urls.iter().map(String::as_str).collect();
+            page.block_urls(&patterns).await?;
+        }
+        if !self.extra_headers.is_empty() {
+            page.set_extra_headers(&self.extra_headers).await?;
+        }
+        if let Some(ref throttle) = self.network_throttle {
+            page.set_network_throttle(throttle).await?;
+        }
+        if let Some(identity) = identity.or_else(|| self.request_identity.as_ref().map(RequestIdentity::next)) {
+            page.set_identity(&identity).await?;
+        }
+        Ok(page)
+    }
 
-        Ok(Page::new(cr_page, self.default_timeout))
+    /// Wrap a tab whose interceptor is set up, with the browser's page settings.
+    async fn wrap_page(&self, cr_page: CrPage, interceptor: Arc<Interceptor>) -> Result<Page> {
+        let mut page = Page::new(cr_page, self.default_timeout, interceptor);
+        page.set_extraction_backend(self.extraction_backend);
+        page.set_rate_limiter(self.rate_limiter.clone());
+        page.set_redaction(self.redaction.clone());
+        page.set_read_only(self.read_only.clone()).await?;
+        Ok(page)
     }
 
     /// Return all currently open pages (tabs).
+    /// Tabs this browser did not open itself (popups, tabs opened by scripts, tabs
+    /// that existed before attaching) are set up like `new_page` on first sight:
+    /// stealth (also applied to the already loaded document), proxy auth and
+    /// browser-level request rules. Tabs of a profile context get the profile's
+    /// request rules and identity instead.
     pub async fn pages(&self) -> Result<Vec<Page>> {
-        let timeout = self.default_timeout;
         let cr_pages = self.browser.pages().await.map_err(|e| Error::CdpError(e))?;
-        Ok(cr_pages.into_iter().map(|p| Page::new(p, timeout)).collect())
+
+        // Forget tabs that have been closed since the last call
+        {
+            let mut prepared = self.prepared.lock().unwrap_or_else(|e| e.into_inner());
+            prepared.retain(|id, _| cr_pages.iter().any(|p| p.target_id().inner() == id));
+            let mut adopted = self.adopted.lock().unwrap_or_else(|e| e.into_inner());
+            adopted.retain(|id| prepared.contains_key(id));
+        }
+
+        let has_contexts = !self.contexts.lock().unwrap_or_else(|e| e.into_inner()).is_empty();
+        let tab_contexts = if has_contexts { self.tab_contexts().await? } else { HashMap::new() };
+
+        let mut pages = Vec::with_capacity(cr_pages.len());
+        for cr_page in cr_pages {
+            let context = tab_contexts.get(cr_page.target_id()).and_then(Option::as_ref);
+            let (intercept, identity) = self.context_settings(context);
+            let page = match self.interceptor_for(&cr_page, intercept) {
+                (interceptor, false) => self.wrap_page(cr_page, interceptor).await?,
+                (interceptor, true) => {
+                    #[cfg(feature = "stealth")]
+                    if self.stealth {
+                        if let Err(e) = stealth::apply_stealth_to_existing(&cr_page).await {
+                            self.forget(&cr_page);
+                            return Err(e);
+                        }
+                    }
+                    self.finish_setup(cr_page, interceptor, identity).await?
+                }
+            };
+            pages.push(page);
+        }
+        Ok(pages)
+    }
+
+    /// Report which optional capabilities (GPU, screencast, PDF, heap profiler,
+    /// extensions) work in this environment, with a reason for each one that doesn't.
+    /// Probes on a temporary blank tab, which is closed afterwards.
+    pub async fn feature_report(&self) -> Result<FeatureReport> {
+        let cr_page = self
+            .browser
+            .new_page("about:blank")
+            .await
+            .map_err(|e| Error::NavigationError(e.to_string()))?;
+        let report = features::probe(&cr_page, self.headless, PERF_ARGS).await;
+        let _ = cr_page.close().await;
+        Ok(report)
+    }
+
+    /// Chrome product, protocol and V8 versions plus the default user agent
+    /// (`Browser.getVersion`). Useful to check the binary matches the stealth UA.
+    pub async fn version(&self) -> Result<BrowserVersion> {
+        let version = self.browser.version().await?;
+        Ok(BrowserVersion {
+            product: version.product,
+            revision: version.revision,
+            protocol_version: version.protocol_version,
+            js_version: version.js_version,
+            user_agent: version.user_agent,
+        })
+    }
+
+    /// Whether the CDP connection is up and Chrome answers within 5 seconds.
+    /// `false` means the browser crashed, was closed, or is wedged.
+    pub async fn is_alive(&self) -> bool {
+        if self.handler_task.is_finished() {
+            return false;
+        }
+        matches!(
+            tokio::time::timeout(Duration::from_secs(5), self.browser.version()).await,
+            Ok(Ok(_))
+        )
+    }
+
+    /// Call `handler` for every event of the given kind, for as long as the browser
+    /// runs. Dialog events cover pages opened with `new_page`.
+    pub async fn on(
+        &self,
+        kind: EventKind,
+        handler: impl Fn(BrowserEvent) + Send + Sync + 'static,
+    ) -> Result<()> {
+        let handler: EventHandler = Arc::new(handler);
+        match kind {
+            EventKind::TargetCreated => {
+                events::forward(&self.browser, handler, |e: &EventTargetCreated| {
+                    BrowserEvent::TargetCreated {
+                        target_id: e.target_info.target_id.inner().clone(),
+                        target_type: e.target_info.r#type.clone(),
+                        url: e.target_info.url.clone(),
+                    }
+                })
+                .await
+            }
+            EventKind::TargetDestroyed => {
+                events::forward(&self.browser, handler, |e: &EventTargetDestroyed| {
+                    BrowserEvent::TargetDestroyed {
+                        target_id: e.target_id.inner().clone(),
+                    }
+                })
+                .await
+            }
+            EventKind::Crashed => {
+                events::forward(&self.browser, handler, |e: &EventTargetCrashed| {
+                    BrowserEvent::Crashed {
+                        target_id: e.target_id.inner().clone(),
+                        status: e.status.clone(),
+                        error_code: e.error_code,
+                    }
+                })
+                .await
+            }
+            EventKind::DownloadStarted => {
+                events::forward(&self.browser, handler, |e: &EventDownloadWillBegin| {
+                    BrowserEvent::DownloadStarted {
+                        guid: e.guid.clone(),
+                        url: e.url.clone(),
+                        suggested_filename: e.suggested_filename.clone(),
+                    }
+                })
+                .await?;
+                if self.download_events.swap(true, Ordering::SeqCst) {
+                    return Ok(());
+                }
+                self.enable_download_events(None).await
+            }
+            EventKind::DialogOpened => {
+                self.dialog_handlers.push(handler);
+                Ok(())
+            }
+            EventKind::MemoryBudgetExceeded => {
+                self.memory_handlers.push(handler);
+                Ok(())
+            }
+            EventKind::BackgroundError => {
+                self.background_handlers.push(handler);
+                Ok(())
+            }
+        }
+    }
+
+    /// Have `context` (the default context for `None`) emit download events, if
+    /// a `DownloadStarted` handler is registered. Only that context's download
+    /// behavior is set, to Chrome's default.
+    pub(crate) async fn enable_download_events(&self, context: Option<BrowserContextId>) -> Result<()> {
+        if !self.download_events.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        let mut params = SetDownloadBehaviorParams::builder()
+            .behavior(SetDownloadBehaviorBehavior::Default)
+            .events_enabled(true)
+            .build()
+            .map_err(Error::LaunchError)?;
+        params.browser_context_id = context;
+        self.browser.execute(params).await?;
+        Ok(())
+    }
+
+    /// Move and resize the window showing `page` (headful only), e.g. to tile
+    /// several sessions across a monitor. A maximized, minimized or fullscreen
+    /// window is restored first. Pages keep the emulated viewport size (see
+    /// `BrowserBuilder::window_state` to have them fill the window instead).
+    pub async fn set_window_bounds(&self, page: &Page, bounds: WindowBounds) -> Result<()> {
+        let window_id = self.window_for(page).await?;
+        self.set_bounds(window_id, Bounds::builder().window_state(WindowState::Normal.to_cdp()).build())
+            .await?;
+        let bounds = Bounds::builder()
+            .left(bounds.left as i64)
+            .top(bounds.top as i64)
+            .width(bounds.width as i64)
+            .height(bounds.height as i64)
+            .build();
+        self.set_bounds(window_id, bounds).await
+    }
+
+    /// Maximize, minimize, fullscreen or restore the window showing `page`
+    /// (headful only).
+    pub async fn set_window_state(&self, page: &Page, state: WindowState) -> Result<()> {
+        let window_id = self.window_for(page).await?;
+        if state != WindowState::Normal {
+            // Chrome only switches between states through normal
+            self.set_bounds(window_id, Bounds::builder().window_state(WindowState::Normal.to_cdp()).build())
+                .await?;
+        }
+        self.set_bounds(window_id, Bounds::builder().window_state(state.to_cdp()).build())
+            .await
+    }
+
+    async fn window_for(&self, page: &Page) -> Result<WindowId> {
+        if self.headless {
+            return Err(Error::ConfigError("window bounds need a headful browser".to_string()));
+        }
+        let params = GetWindowForTargetParams::builder()
+            .target_id(page.inner().target_id().clone())
+            .build();
+        let window = self.browser.execute(params).await?;
+        Ok(window.result.window_id)
+    }
+
+    async fn set_bounds(&self, window_id: WindowId, bounds: Bounds) -> Result<()> {
+        self.browser.execute(SetWindowBoundsParams::new(window_id, bounds)).await?;
+        Ok(())
+    }
+
+    /// OS process id of the Chrome browser process, if this instance launched it.
+    pub fn process_id(&self) -> Option<u32> {
+        self.process_id
+    }
+
+    /// Resident memory of the Chrome process and all of its child processes
+    /// (renderers, GPU, utility). Linux only.
+    pub fn memory_usage(&self) -> Result<MemoryUsage> {
+        let pid = self
+            .process_id
+            .ok_or_else(|| Error::LaunchError("Chrome process id is unknown".to_string()))?;
+        metrics::process_tree_memory(pid)
+    }
+
+    /// Close Chrome and launch it again with the same configuration, e.g. after
+    /// the memory watchdog killed it (`BudgetAction::KillBrowser`).
+    /// Existing `Page`s stop working and handlers registered with `on` are dropped;
+    /// open new pages and register handlers again afterwards.
+    pub async fn restart(&mut self) -> Result<()> {
+        // While the tab guard holds the browser, dropping it kills Chrome instead
+        if let Some(browser) = Arc::get_mut(&mut self.browser) {
+            let _ = browser.close().await;
+        }
+        *self = Self::launch(self.config.clone()).await?;
+        telemetry::chrome_restarted();
+        Ok(())
+    }
+}
+
+/// Request rules and identity of the open profile contexts.
+type ContextRules = Mutex<HashMap<BrowserContextId, (InterceptRules, Identity)>>;
+
+/// What `AgenticBrowser::guard_new_tabs` needs to set up a tab's interceptor.
+#[derive(Clone)]
+struct TabGuard {
+    prepared: Arc<Mutex<HashMap<String, Arc<Interceptor>>>>,
+    adopted: Arc<Mutex<HashSet<String>>>,
+    contexts: Arc<ContextRules>,
+    intercept: InterceptRules,
+    policy: BackgroundErrorPolicy,
+    handlers: Arc<SharedHandlers>,
+}
+
+impl TabGuard {
+    /// Register and enable the interceptor of new tab `target`, unless someone
+    /// else set the tab up first.
+    async fn adopt(&self, browser: &CrBrowser, target: TargetInfo) {
+        // The handler attaches to the tab right after announcing it
+        let mut cr_page = None;
+        for _ in 0..40 {
+            match browser.get_page(target.target_id.clone()).await {
+                Ok(page) => {
+                    cr_page = Some(page);
+                    break;
+                }
+                Err(_) => tokio::time::sleep(Duration::from_millis(25)).await,
+            }
+        }
+        let Some(cr_page) = cr_page else {
+            return;
+        };
+
+        let rules = {
+            let contexts = self.contexts.lock().unwrap_or_else(|e| e.into_inner());
+            match target.browser_context_id.as_ref().and_then(|id| contexts.get(id)) {
+                Some((rules, _)) => rules.clone(),
+                None => self.intercept.clone(),
+            }
+        };
+        let policy = rules.navigation_policy.clone();
+        let interceptor = {
+            let mut prepared = self.prepared.lock().unwrap_or_else(|e| e.into_inner());
+            let Entry::Vacant(entry) = prepared.entry(target.target_id.inner().clone()) else {
+                return;
+            };
+            let interceptor =
+                Interceptor::new(cr_page.clone(), rules, self.policy, Arc::clone(&self.handlers));
+            self.adopted
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(entry.key().clone());
+            entry.insert(Arc::clone(&interceptor));
+            interceptor
+        };
+        // Failures are retried when the tab is set up on first sight
+        if interceptor.apply().await.is_err() {
+            return;
+        }
+
+        // A navigation committed before the interceptor was up got past it
+        let Some(policy) = policy else {
+            return;
+        };
+        if let Ok(Some(url)) = cr_page.url().await {
+            if !policy.is_allowed(&url) {
+                let _ = cr_page.execute(NavigateParams::new("about:blank")).await;
+            }
+        }
+    }
+}
+
+/// Reject options whose support was compiled out, rather than silently
+/// launching without them.
+fn check_features(config: &BrowserConfig) -> Result<()> {
+    let missing = |option: &str, feature: &str| {
+        Err(Error::ConfigError(format!("{option} needs the `{feature}` cargo feature")))
+    };
+    if config.stealth && !cfg!(feature = "stealth") {
+        return missing("stealth mode", "stealth");
+    }
+    let proxy_auth = config.proxy.as_ref().is_some_and(|p| p.username.is_some() || p.password.is_some());
+    if proxy_auth && !cfg!(feature = "proxy-auth") {
+        return missing("proxy authentication", "proxy-auth");
+    }
+    if config.block_ads && !cfg!(feature = "low-resource") {
+        return missing("block_ads", "low-resource");
+    }
+    if config.cdp_log.is_some() && !cfg!(feature = "cdp-log") {
+        return missing("cdp_log", "cdp-log");
+    }
+    Ok(())
+}
+
+/// Chrome version information returned by `AgenticBrowser::version()`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BrowserVersion {
+    /// Product name and version, e.g. "HeadlessChrome/131.0.6778.85".
+    pub product: String,
+    pub revision: String,
+    pub protocol_version: String,
+    /// V8 version.
+    pub js_version: String,
+    /// Default user agent (before any stealth override).
+    pub user_agent: String,
+}
+
+impl BrowserVersion {
+    /// Major Chrome version parsed from `product`, e.g. 131.
+    pub fn major(&self) -> Option<u32> {
+        self.product.split_once('/')?.1.split('.').next()?.parse().ok()
     }
 }
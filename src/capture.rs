@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+use base64::Engine;
+use chromiumoxide::cdp::browser_protocol::network::{
+    EnableParams, EventLoadingFailed, EventLoadingFinished, EventResponseReceived,
+    GetResponseBodyParams, RequestId, ResourceType,
+};
+use chromiumoxide::page::Page as CrPage;
+use futures::channel::mpsc;
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+
+use crate::error::{Error, Result};
+
+/// An XHR/fetch response captured by `Page::capture_responses`.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct CapturedResponse {
+    pub url: String,
+    pub status: u16,
+    pub mime_type: String,
+    /// Response body decoded as text (binary bodies are decoded lossily).
+    pub body: String,
+}
+
+impl CapturedResponse {
+    /// Deserialize the body as JSON.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_str(&self.body).map_err(|e| Error::JsError(format!("Invalid JSON response: {e}")))
+    }
+}
+
+/// Match `text` against a pattern where `*` matches any run of characters.
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*` in the pattern: exact match
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+enum NetworkEvent {
+    Response(std::sync::Arc<EventResponseReceived>),
+    Finished(RequestId),
+    Failed(RequestId),
+}
+
+/// Stream the bodies of XHR/fetch responses from `page` whose URL matches `pattern`.
+/// Capturing stops when the returned stream is dropped.
+pub(crate) async fn capture(
+    page: &CrPage,
+    pattern: &str,
+) -> Result<impl Stream<Item = CapturedResponse> + Send + Unpin> {
+    let listen_err = |e: chromiumoxide::error::CdpError| {
+        Error::JsError(format!("Failed to listen for network events: {e}"))
+    };
+    let responses = page.event_listener::<EventResponseReceived>().await.map_err(listen_err)?;
+    let finished = page.event_listener::<EventLoadingFinished>().await.map_err(listen_err)?;
+    let failed = page.event_listener::<EventLoadingFailed>().await.map_err(listen_err)?;
+    page.execute(EnableParams::default())
+        .await
+        .map_err(|e| Error::JsError(format!("Failed to enable network domain: {e}")))?;
+
+    let mut events = futures::stream::select_all([
+        responses.map(NetworkEvent::Response).boxed(),
+        finished.map(|e| NetworkEvent::Finished(e.request_id.clone())).boxed(),
+        failed.map(|e| NetworkEvent::Failed(e.request_id.clone())).boxed(),
+    ]);
+    let pattern = pattern.to_string();
+    let page = page.clone();
+    let (tx, rx) = mpsc::unbounded();
+
+    tokio::spawn(async move {
+        // Bodies are only available once loading has finished
+        let mut pending: HashMap<RequestId, (String, u16, String)> = HashMap::new();
+        while let Some(event) = events.next().await {
+            if tx.is_closed() {
+                break;
+            }
+            match event {
+                NetworkEvent::Response(event) => {
+                    let is_api = matches!(event.r#type, ResourceType::Xhr | ResourceType::Fetch);
+                    if is_api && wildcard_match(&pattern, &event.response.url) {
+                        pending.insert(
+                            event.request_id.clone(),
+                            (
+                                event.response.url.clone(),
+                                event.response.status as u16,
+                                event.response.mime_type.clone(),
+                            ),
+                        );
+                    }
+                }
+                NetworkEvent::Finished(request_id) => {
+                    let Some((url, status, mime_type)) = pending.remove(&request_id) else {
+                        continue;
+                    };
+                    let Ok(response) = page.execute(GetResponseBodyParams::new(request_id)).await else {
+                        continue;
+                    };
+                    let body = response.result;
+                    let body = if body.base64_encoded {
+                        base64::engine::general_purpose::STANDARD
+                            .decode(&body.body)
+                            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                            .unwrap_or_default()
+                    } else {
+                        body.body
+                    };
+                    let captured = CapturedResponse { url, status, mime_type, body };
+                    if tx.unbounded_send(captured).is_err() {
+                        break;
+                    }
+                }
+                NetworkEvent::Failed(request_id) => {
+                    pending.remove(&request_id);
+                }
+            }
+        }
+    });
+
+    Ok(rx)
+}
@@ -0,0 +1,353 @@
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+use crate::error::{Error, Result};
+
+/// Keys whose string values are replaced with `[redacted]` wherever they
+/// appear: proxy and HTTP auth credentials, auth and cookie headers, and
+/// request bodies.
+const SECRET_KEYS: &[&str] = &[
+    "username",
+    "password",
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "postdata",
+    "post_data",
+];
+
+/// Keys redacted only in messages of a method (or, for cookie lists, of any
+/// message carrying one), in both JSON (`"unmodifiedText"`) and Debug
+/// (`unmodified_text`) spelling, lowercased.
+const METHOD_KEYS: &[(&str, &[&str])] = &[
+    ("\"input.inserttext\"", &["text"]),
+    ("\"input.dispatchkeyevent\"", &["text", "unmodifiedtext", "key", "code"]),
+    ("\"cookies\"", &["value"]),
+    ("cookies:", &["value"]),
+    ("\"network.setcookie\"", &["value"]),
+];
+
+/// Methods whose `arguments` are redacted whole: the values passed to page
+/// functions, such as the text `Element::fill` sets.
+const ARGUMENT_METHODS: &[&str] = &["\"runtime.callfunctionon\""];
+
+/// Header names whose values are redacted in `{name, value}` header entries.
+const SECRET_HEADERS: &[&str] = &["authorization", "proxy-authorization", "cookie", "set-cookie"];
+
+/// Path that means standard error instead of a file.
+const STDERR: &str = "-";
+
+/// `tracing` layer that writes chromiumoxide's protocol traffic (every CDP command
+/// sent, every response and event received) and its connection errors, one
+/// timestamped line each, with credentials redacted.
+///
+/// `BrowserBuilder::cdp_log` installs it as the global subscriber; if the program
+/// already has one, add `layer(path)` to that subscriber instead.
+pub struct CdpLogLayer {
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+/// A layer writing to `path`, or to standard error if `path` is `-`. The file is
+/// truncated first.
+pub fn layer(path: impl AsRef<Path>) -> Result<CdpLogLayer> {
+    let path = path.as_ref();
+    let out: Box<dyn Write + Send> = if path.as_os_str() == STDERR {
+        Box::new(std::io::stderr())
+    } else {
+        Box::new(File::create(path)?)
+    };
+    Ok(CdpLogLayer { out: Mutex::new(out) })
+}
+
+/// Install `layer(path)` as the global subscriber, once per process; later calls
+/// keep the first log.
+pub(crate) fn install(path: &Path) -> Result<()> {
+    static INSTALLED: OnceLock<()> = OnceLock::new();
+    if INSTALLED.get().is_some() {
+        return Ok(());
+    }
+    let subscriber = tracing_subscriber::registry().with(layer(path)?);
+    tracing::subscriber::set_global_default(subscriber).map_err(|_| {
+        Error::ConfigError(
+            "cdp_log: a tracing subscriber is already installed; add agentic_browser::cdp_log::layer(..) to it instead"
+                .to_string(),
+        )
+    })?;
+    let _ = INSTALLED.set(());
+    Ok(())
+}
+
+impl<S: Subscriber> Layer<S> for CdpLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if !metadata.target().starts_with("chromiumoxide") {
+            return;
+        }
+        let mut message = MessageVisitor(String::new());
+        event.record(&mut message);
+        let line = format!("{} {:5} {}\n", timestamp(), metadata.level(), redact(&message.0));
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = out.write_all(line.as_bytes());
+    }
+}
+
+/// Collects an event's fields into one line, the message first.
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if !self.0.is_empty() {
+            self.0.push(' ');
+        }
+        if field.name() == "message" {
+            self.0.push_str(&format!("{value:?}"));
+        } else {
+            self.0.push_str(&format!("{}={value:?}", field.name()));
+        }
+    }
+}
+
+/// UTC time of day with milliseconds, e.g. `14:03:27.512`.
+fn timestamp() -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = now.as_secs() % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        now.subsec_millis()
+    )
+}
+
+/// Replace secret string values in a Debug-formatted message: those of
+/// `SECRET_KEYS` and of the `METHOD_KEYS` of its method, and the values of
+/// `SECRET_HEADERS` header entries, as in `"password": String("...")` (JSON
+/// params), `"Cookie": "..."` (header maps) or `password: Some("...")` (typed
+/// structs); and the whole argument lists of `ARGUMENT_METHODS`.
+fn redact(message: &str) -> String {
+    let lower = message.to_ascii_lowercase();
+    let mut secrets: Vec<(usize, usize)> = SECRET_KEYS.iter().flat_map(|key| values_of(&lower, key)).collect();
+    for (marker, keys) in METHOD_KEYS {
+        if lower.contains(marker) {
+            secrets.extend(keys.iter().flat_map(|key| values_of(&lower, key)));
+        }
+    }
+    if ARGUMENT_METHODS.iter().any(|method| lower.contains(method)) {
+        secrets.extend(
+            lower
+                .match_indices("arguments")
+                .filter_map(|(i, key)| list_after(&lower, i, key.len())),
+        );
+    }
+    // Fetch and Network header entries: `{"name": "Authorization", "value": "..."}`
+    for (start, end) in values_of(&lower, "name") {
+        if SECRET_HEADERS.contains(&&lower[start..end]) {
+            secrets.extend(values_of(&lower[end..], "value").next().map(|(s, e)| (end + s, end + e)));
+        }
+    }
+    secrets.sort_unstable();
+
+    let mut out = String::with_capacity(message.len());
+    let mut pos = 0;
+    for (start, end) in secrets {
+        if start < pos {
+            continue;
+        }
+        out.push_str(&message[pos..start]);
+        out.push_str("[redacted]");
+        pos = end;
+    }
+    out.push_str(&message[pos..]);
+    out
+}
+
+/// Ranges of the string values of every `key` in `lower`.
+fn values_of<'a>(lower: &'a str, key: &'a str) -> impl Iterator<Item = (usize, usize)> + 'a {
+    lower
+        .match_indices(key)
+        .filter_map(move |(i, _)| value_after(lower, i, key.len()))
+}
+
+/// If `lower[at..at + len]` is a whole key followed by a string value, the
+/// value's range without its quotes.
+fn value_after(lower: &str, at: usize, len: usize) -> Option<(usize, usize)> {
+    let before = lower[..at].bytes().next_back();
+    if before.is_some_and(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-') {
+        return None;
+    }
+    let rest = &lower[at + len..];
+    let rest = rest.strip_prefix('"').unwrap_or(rest);
+    let rest = rest.strip_prefix(':')?.trim_start();
+    let rest = rest
+        .strip_prefix("string(")
+        .or_else(|| rest.strip_prefix("some("))
+        .unwrap_or(rest);
+    let rest = rest.strip_prefix('"')?;
+    let start = lower.len() - rest.len();
+    let mut escaped = false;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return Some((start, start + i)),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+/// If `lower[at..at + len]` is a whole key followed by a list, the list's
+/// contents without its brackets, as in `"arguments": Array [...]` or
+/// `arguments: Some([...])`.
+fn list_after(lower: &str, at: usize, len: usize) -> Option<(usize, usize)> {
+    let before = lower[..at].bytes().next_back();
+    if before.is_some_and(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-') {
+        return None;
+    }
+    let rest = &lower[at + len..];
+    let rest = rest.strip_prefix('"').unwrap_or(rest);
+    let rest = rest.strip_prefix(':')?.trim_start();
+    let rest = rest
+        .strip_prefix("array ")
+        .or_else(|| rest.strip_prefix("some("))
+        .unwrap_or(rest);
+    let rest = rest.strip_prefix('[')?;
+    let start = lower.len() - rest.len();
+    let (mut depth, mut quoted, mut escaped) = (0usize, false, false);
+    for (i, c) in rest.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '[' if !quoted => depth += 1,
+            ']' if !quoted && depth == 0 => return Some((start, start + i)),
+            ']' if !quoted => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use chromiumoxide::cdp::js_protocol::runtime::RemoteObjectId;
+
+    use super::redact;
+    use crate::element::{self, Element};
+
+    /// A command as chromiumoxide logs it.
+    fn sending(method: &str, params: serde_json::Value) -> String {
+        format!("Sending MethodCall {{ id: CallId(7), method: {method:?}, session_id: None, params: {params:?} }}")
+    }
+
+    fn assert_redacted(message: &str, secrets: &[&str], kept: &[&str]) {
+        let redacted = redact(message);
+        for secret in secrets {
+            assert!(!redacted.contains(secret), "{secret} leaked: {redacted}");
+        }
+        for text in kept {
+            assert!(redacted.contains(text), "{text} lost: {redacted}");
+        }
+    }
+
+    #[test]
+    fn redacts_credentials_and_auth_headers_by_key() {
+        let message = sending(
+            "Fetch.continueWithAuth",
+            json!({ "authChallengeResponse": { "response": "ProvideCredentials", "username": "bob", "password": "hunter2" } }),
+        );
+        assert_redacted(&message, &["bob", "hunter2"], &["ProvideCredentials"]);
+        let message = r#"Received Event { request: Request { url: "https://a.test/", headers: Headers { inner: Object {"Authorization": String("Bearer tok3n"), "Accept": String("*/*")} } } }"#;
+        assert_redacted(message, &["tok3n"], &["*/*", "https://a.test/"]);
+    }
+
+    #[test]
+    fn redacts_set_cookies_values() {
+        let message = sending(
+            "Storage.setCookies",
+            json!({ "cookies": [{ "name": "sid", "value": "c00kie", "domain": "a.test" }] }),
+        );
+        assert_redacted(&message, &["c00kie"], &["sid", "a.test"]);
+        let message = sending("Network.setCookie", json!({ "name": "sid", "value": "c00kie", "url": "https://a.test" }));
+        assert_redacted(&message, &["c00kie"], &["sid"]);
+    }
+
+    #[test]
+    fn redacts_cookie_lists_in_results() {
+        let message = format!(
+            "Received Response {{ id: CallId(9), result: Some({:?}) }}",
+            json!({ "cookies": [{ "name": "sid", "value": "c00kie", "domain": "a.test" }] })
+        );
+        assert_redacted(&message, &["c00kie"], &["sid"]);
+    }
+
+    #[test]
+    fn redacts_secret_header_entries() {
+        let message = sending(
+            "Fetch.continueRequest",
+            json!({ "requestId": "1", "headers": [
+                { "name": "Authorization", "value": "Bearer tok3n" },
+                { "name": "Accept", "value": "text/html" },
+            ] }),
+        );
+        assert_redacted(&message, &["tok3n"], &["Authorization", "text/html"]);
+        let typed = r#"HeaderEntry { name: "Proxy-Authorization", value: "Basic Ym9iOmh1bnRlcjI=" }"#;
+        assert_redacted(typed, &["Ym9iOmh1bnRlcjI="], &["Proxy-Authorization"]);
+    }
+
+    #[test]
+    fn redacts_typed_text() {
+        let message = sending("Input.insertText", json!({ "text": "hunter2" }));
+        assert_redacted(&message, &["hunter2"], &["Input.insertText"]);
+        let message = sending(
+            "Input.dispatchKeyEvent",
+            json!({ "type": "keyDown", "text": "h", "unmodifiedText": "h", "key": "h", "code": "KeyH" }),
+        );
+        assert_redacted(&message, &["\"h\"", "KeyH"], &["keyDown"]);
+        // Text of other methods is kept
+        let message = sending("Page.navigate", json!({ "url": "https://a.test/", "text": "visible" }));
+        assert_redacted(&message, &[], &["visible"]);
+    }
+
+    #[test]
+    fn redacts_post_data() {
+        let message = sending(
+            "Fetch.continueRequest",
+            json!({ "requestId": "1", "postData": "cGFzc3dvcmQ9aHVudGVyMg==" }),
+        );
+        assert_redacted(&message, &["cGFzc3dvcmQ9aHVudGVyMg=="], &["requestId"]);
+        let typed = r#"Request { method: "POST", post_data: Some("card=4111111111111111") }"#;
+        assert_redacted(typed, &["4111111111111111"], &["POST"]);
+    }
+
+    #[test]
+    fn redacts_filled_text() {
+        // The call `Element::fill` makes
+        let params = element::call_params(
+            &RemoteObjectId::new("1234.5.6"),
+            &element::json_result(element::FILL_JS),
+            vec![Element::value_arg("hunter2").unwrap()],
+            true,
+        )
+        .unwrap();
+        let message = sending("Runtime.callFunctionOn", serde_json::to_value(params).unwrap());
+        assert_redacted(&message, &["hunter2"], &["1234.5.6", "setter.call(this, text)"]);
+        // Nested argument values are redacted as well
+        let message = sending(
+            "Runtime.callFunctionOn",
+            json!({ "functionDeclaration": "(req) => fetch(req)", "arguments": [{ "value": { "body": "pin=1234", "tags": ["a]"] } }] }),
+        );
+        assert_redacted(&message, &["pin=1234", "a]"], &["fetch(req)"]);
+    }
+}
@@ -0,0 +1,196 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::error::{Error, Result};
+
+/// A client TLS certificate for mTLS-protected sites.
+#[derive(Debug, Clone)]
+pub struct ClientCertificate {
+    /// PKCS#12 bundle (`.p12` / `.pfx`) containing the certificate and private key.
+    pub pkcs12: PathBuf,
+    /// Password of the PKCS#12 bundle (empty if none).
+    pub password: String,
+    /// Origins the certificate is presented to, e.g. `"https://internal.example.com"`
+    /// or `"https://[*.]example.com"` for all subdomains.
+    pub origins: Vec<String>,
+}
+
+impl ClientCertificate {
+    pub fn new(pkcs12: impl Into<PathBuf>, password: impl Into<String>) -> Self {
+        Self {
+            pkcs12: pkcs12.into(),
+            password: password.into(),
+            origins: Vec::new(),
+        }
+    }
+
+    /// Present the certificate to this origin (may be called several times).
+    pub fn origin(mut self, origin: impl Into<String>) -> Self {
+        self.origins.push(origin.into());
+        self
+    }
+}
+
+/// A throwaway home directory and profile holding the launched browser's client
+/// certificates, removed on drop.
+///
+/// Chrome has no command-line switch for client certificates: on Linux it reads
+/// them from the NSS database under `$HOME/.pki/nssdb`, and picks one without
+/// prompting only for origins in its `auto_select_certificate` content setting.
+/// So Chrome runs with `HOME` pointing here, each bundle is imported into a
+/// fresh database with `pk12util` (from the NSS tools package), and the setting
+/// is written to the preferences of a fresh `--user-data-dir`. Nothing outside
+/// this directory is touched.
+pub(crate) struct CertStore {
+    dir: PathBuf,
+}
+
+impl CertStore {
+    /// `HOME` for the Chrome process.
+    pub(crate) fn home(&self) -> PathBuf {
+        self.dir.join("home")
+    }
+
+    /// `--user-data-dir` for the Chrome process.
+    pub(crate) fn user_data_dir(&self) -> PathBuf {
+        self.dir.join("profile")
+    }
+}
+
+impl Drop for CertStore {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Make the certificates available to a browser about to launch, or `None` if
+/// there are none.
+pub(crate) fn install(certs: &[ClientCertificate], extra_args: &[String]) -> Result<Option<CertStore>> {
+    if certs.is_empty() {
+        return Ok(None);
+    }
+    if !cfg!(target_os = "linux") {
+        return Err(Error::ConfigError(
+            "client certificates are only supported on Linux; import the certificate into \
+             the system certificate store instead"
+                .to_string(),
+        ));
+    }
+    if extra_args.iter().any(|arg| arg.trim_start_matches("--").starts_with("user-data-dir")) {
+        return Err(Error::ConfigError(
+            "client certificates need a browser-owned profile; drop --user-data-dir".to_string(),
+        ));
+    }
+    for cert in certs {
+        if !cert.pkcs12.exists() {
+            return Err(Error::ConfigError(format!(
+                "client certificate not found: {}",
+                cert.pkcs12.display()
+            )));
+        }
+    }
+
+    let store = CertStore { dir: create_private_dir()? };
+    let db = store.home().join(".pki/nssdb");
+    std::fs::create_dir_all(&db)?;
+    let db = format!("sql:{}", db.display());
+    run(Command::new("certutil").arg("-N").arg("--empty-password").arg("-d").arg(&db))?;
+    for cert in certs {
+        import_pkcs12(&store, &db, cert)?;
+    }
+
+    let default_profile = store.user_data_dir().join("Default");
+    std::fs::create_dir_all(&default_profile)?;
+    std::fs::write(default_profile.join("Preferences"), auto_select_preferences(certs).to_string())?;
+    Ok(Some(store))
+}
+
+/// A new directory only this user can read, under the system temp directory.
+fn create_private_dir() -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!(
+        "agentic-browser-certs-{}-{:x}",
+        std::process::id(),
+        crate::ratelimit::random_u64()
+    ));
+    let mut builder = std::fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(&dir)?;
+    Ok(dir)
+}
+
+/// Import `cert` into the NSS database `db`, passing its password in a file
+/// rather than on the command line.
+fn import_pkcs12(store: &CertStore, db: &str, cert: &ClientCertificate) -> Result<()> {
+    let password_file = store.dir.join("pkcs12-password");
+    std::fs::write(&password_file, &cert.password)?;
+    let imported = run(Command::new("pk12util")
+        .arg("-i")
+        .arg(&cert.pkcs12)
+        .arg("-d")
+        .arg(db)
+        .arg("-w")
+        .arg(&password_file));
+    let _ = std::fs::remove_file(&password_file);
+    imported
+}
+
+fn run(command: &mut Command) -> Result<()> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command.output().map_err(|e| {
+        Error::LaunchError(format!(
+            "failed to run {program} (install the NSS tools, e.g. libnss3-tools): {e}"
+        ))
+    })?;
+    if !output.status.success() {
+        return Err(Error::LaunchError(format!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Profile preferences auto-selecting a certificate for each configured origin,
+/// as the `AutoSelectCertificateForUrls` policy would.
+fn auto_select_preferences(certs: &[ClientCertificate]) -> serde_json::Value {
+    let exceptions: serde_json::Map<String, serde_json::Value> = certs
+        .iter()
+        .flat_map(|c| &c.origins)
+        .map(|origin| {
+            let setting = serde_json::json!({ "setting": { "filters": [{}] } });
+            (format!("{origin},*"), setting)
+        })
+        .collect();
+    serde_json::json!({
+        "profile": { "content_settings": { "exceptions": { "auto_select_certificate": exceptions } } }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{auto_select_preferences, install, ClientCertificate};
+    use crate::error::Error;
+
+    #[test]
+    fn preferences_auto_select_each_origin() {
+        let certs = [
+            ClientCertificate::new("a.p12", "").origin("https://a.example.com"),
+            ClientCertificate::new("b.p12", "").origin("https://[*.]b.example.com"),
+        ];
+        let prefs = auto_select_preferences(&certs);
+        let exceptions = &prefs["profile"]["content_settings"]["exceptions"]["auto_select_certificate"];
+        assert_eq!(exceptions.as_object().map(|e| e.len()), Some(2));
+        assert_eq!(exceptions["https://a.example.com,*"]["setting"]["filters"], serde_json::json!([{}]));
+        assert!(exceptions.get("https://[*.]b.example.com,*").is_some());
+    }
+
+    #[test]
+    fn install_rejects_bad_config_before_touching_anything() {
+        let missing = [ClientCertificate::new("/nonexistent/client.p12", "secret")];
+        assert!(matches!(install(&missing, &[]), Err(Error::ConfigError(_))));
+        let profile = ["--user-data-dir=/tmp/profile".to_string()];
+        assert!(matches!(install(&missing, &profile), Err(Error::ConfigError(_))));
+        assert!(install(&[], &[]).expect("no certificates is fine").is_none());
+    }
+}
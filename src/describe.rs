@@ -0,0 +1,227 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// How `Page::describe` estimates the token count of text.
+#[derive(Clone)]
+pub enum TokenEstimator {
+    /// Characters per token; about 4 for English with GPT and Claude tokenizers,
+    /// lower for code and non-Latin scripts.
+    CharsPerToken(f32),
+    /// An exact count from a real tokenizer.
+    Custom(Arc<dyn Fn(&str) -> usize + Send + Sync>),
+}
+
+impl Default for TokenEstimator {
+    fn default() -> Self {
+        TokenEstimator::CharsPerToken(4.0)
+    }
+}
+
+impl fmt::Debug for TokenEstimator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenEstimator::CharsPerToken(n) => f.debug_tuple("CharsPerToken").field(n).finish(),
+            TokenEstimator::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl TokenEstimator {
+    /// Wrap a tokenizer's count function, e.g. `|s| bpe.encode_ordinary(s).len()`.
+    pub fn custom(count: impl Fn(&str) -> usize + Send + Sync + 'static) -> Self {
+        TokenEstimator::Custom(Arc::new(count))
+    }
+
+    pub fn estimate(&self, text: &str) -> usize {
+        match self {
+            TokenEstimator::CharsPerToken(n) => {
+                (text.chars().count() as f32 / n.max(0.1)).ceil() as usize
+            }
+            TokenEstimator::Custom(count) => count(text),
+        }
+    }
+
+    /// The longest prefix of `text` (cut at a char boundary, ending in "…" when
+    /// cut) that fits in `tokens`.
+    fn truncate(&self, text: &str, tokens: usize) -> String {
+        if self.estimate(text) <= tokens {
+            return text.to_string();
+        }
+        // bounds[k] ends the prefix of k chars; the whole text doesn't fit
+        let bounds: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+        let (mut lo, mut hi) = (0, bounds.len().saturating_sub(1));
+        while lo < hi {
+            let mid = (lo + hi).div_ceil(2);
+            if self.estimate(&format!("{}…", &text[..bounds[mid]])) <= tokens {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        if lo == 0 {
+            return String::new();
+        }
+        format!("{}…", text[..bounds[lo]].trim_end())
+    }
+}
+
+/// Title, landmarks, interactive elements and main text, read in one evaluation.
+#[derive(Debug, Default, serde::Deserialize)]
+pub(crate) struct PageOutline {
+    url: String,
+    title: String,
+    landmarks: Vec<String>,
+    interactive: Vec<String>,
+    content: String,
+}
+
+/// Collects the visible landmarks and headings, interactive elements (in the
+/// `accessibility_tree` notation) and the text of the main content area.
+pub(crate) const OUTLINE_JS: &str = r#"(() => {
+    const visible = (el) => typeof el.checkVisibility === 'function'
+        ? el.checkVisibility({ checkOpacity: false, checkVisibilityCSS: true })
+        : el.offsetParent !== null;
+    const clean = (s, n) => (s || '').replace(/\s+/g, ' ').trim().substring(0, n);
+    const labelOf = (el) => {
+        const aria = el.getAttribute('aria-label');
+        if (aria) return aria;
+        if (el.id) {
+            const label = document.querySelector('label[for="' + CSS.escape(el.id) + '"]');
+            if (label) return label.innerText;
+        }
+        const wrapping = el.closest('label');
+        if (wrapping && wrapping !== el) return wrapping.innerText;
+        return el.getAttribute('alt') || el.getAttribute('title') || el.getAttribute('placeholder') || '';
+    };
+
+    const landmarks = [];
+    const landmarkSel = 'header, nav, main, aside, footer, form, h1, h2, h3, '
+        + '[role=banner], [role=navigation], [role=main], [role=search], [role=complementary], '
+        + '[role=contentinfo], [role=dialog]';
+    for (const el of document.querySelectorAll(landmarkSel)) {
+        if (!visible(el)) continue;
+        const role = el.getAttribute('role') || el.tagName.toLowerCase();
+        const name = /^h[1-3]$/.test(role) ? el.innerText : el.getAttribute('aria-label') || '';
+        landmarks.push('[' + role + ']' + (name ? ' "' + clean(name, 100) + '"' : ''));
+        if (landmarks.length >= 100) break;
+    }
+
+    const interactive = [];
+    const interactiveSel = 'a[href], button, input:not([type=hidden]), select, textarea, '
+        + '[role=button], [role=link], [role=checkbox], [role=tab], [role=menuitem], [contenteditable=true]';
+    for (const el of document.querySelectorAll(interactiveSel)) {
+        if (!visible(el) || el.disabled) continue;
+        const tag = el.tagName.toLowerCase();
+        const role = el.getAttribute('role') || tag;
+        const name = clean(labelOf(el) || el.innerText || el.value, 80);
+        let desc = '[' + role + ']' + (name ? ' "' + name + '"' : '');
+        if (tag === 'a') desc += ' href=' + el.getAttribute('href').substring(0, 120);
+        if (tag === 'input') desc += ' type=' + (el.type || 'text');
+        if (el.name) desc += ' name=' + el.name;
+        if (el.id) desc += ' #' + el.id;
+        interactive.push(desc);
+        if (interactive.length >= 500) break;
+    }
+
+    const main = document.querySelector('main, [role=main], article') || document.body;
+    const content = main
+        ? main.innerText.split('\n').map(l => l.replace(/\s+/g, ' ').trim()).filter(Boolean).join('\n')
+        : '';
+    return {
+        url: location.href,
+        title: document.title,
+        landmarks,
+        interactive,
+        content: content.substring(0, 200000),
+    };
+})()"#;
+
+/// Add `lines` under `heading` while they fit in `budget`; returns the text and
+/// its estimated size.
+fn section(
+    heading: &str,
+    lines: &[String],
+    budget: usize,
+    estimator: &TokenEstimator,
+) -> (String, usize) {
+    if lines.is_empty() {
+        return (String::new(), 0);
+    }
+    let mut shown = 0;
+    let mut body = String::new();
+    for line in lines {
+        let candidate = format!("{body}{line}\n");
+        let header = format!("{heading} ({} of {} shown):\n", shown + 1, lines.len());
+        if estimator.estimate(&header) + estimator.estimate(&candidate) > budget {
+            break;
+        }
+        body = candidate;
+        shown += 1;
+    }
+    if shown == 0 {
+        return (String::new(), 0);
+    }
+    let header = if shown == lines.len() {
+        format!("{heading}:\n")
+    } else {
+        format!("{heading} ({shown} of {} shown):\n", lines.len())
+    };
+    let text = format!("\n{header}{body}");
+    let cost = estimator.estimate(&text);
+    (text, cost)
+}
+
+/// Render `outline` in at most `budget` tokens. Title and URL come first, then
+/// landmarks (up to a sixth of the budget), interactive elements (up to half of
+/// what remains) and the main text in whatever is left; space a section doesn't
+/// need goes to the next one.
+pub(crate) fn render(outline: &PageOutline, budget: usize, estimator: &TokenEstimator) -> String {
+    let header = format!("Title: {}\nURL: {}\n", outline.title, outline.url);
+    let mut out = estimator.truncate(&header, budget);
+    let mut remaining = budget.saturating_sub(estimator.estimate(&out));
+
+    let (landmarks, cost) = section("Landmarks", &outline.landmarks, remaining / 6, estimator);
+    out.push_str(&landmarks);
+    remaining = remaining.saturating_sub(cost);
+
+    let content_cost = estimator.estimate(&outline.content) + 4;
+    let interactive_budget = remaining.saturating_sub(content_cost).max(remaining / 2);
+    let (interactive, cost) = section(
+        "Interactive elements",
+        &outline.interactive,
+        interactive_budget,
+        estimator,
+    );
+    out.push_str(&interactive);
+    remaining = remaining.saturating_sub(cost);
+
+    if !outline.content.is_empty() {
+        let heading = "\nContent:\n";
+        let room = remaining.saturating_sub(estimator.estimate(heading));
+        let content = estimator.truncate(&outline.content, room);
+        if !content.is_empty() {
+            out.push_str(heading);
+            out.push_str(&content);
+            out.push('\n');
+        }
+    }
+
+    // Sections are sized separately; a tokenizer that doesn't add up across
+    // boundaries may still overshoot, so trim the whole text as a last resort.
+    estimator.truncate(&out, budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TokenEstimator;
+
+    #[test]
+    fn truncate_to_tiny_budgets() {
+        let estimator = TokenEstimator::CharsPerToken(4.0);
+        assert_eq!(estimator.truncate("x", 0), "");
+        assert_eq!(estimator.truncate("x", 1), "x");
+        assert_eq!(estimator.truncate("hello world", 0), "");
+        assert_eq!(estimator.truncate("hello world", 1), "hel…");
+        assert_eq!(estimator.truncate("héllo wörld", 1), "hél…");
+    }
+}
@@ -0,0 +1,111 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+
+/// Executable names searched on `PATH`, in order of preference.
+const PATH_NAMES: &[&str] = &[
+    "google-chrome",
+    "google-chrome-stable",
+    "chrome",
+    "chromium",
+    "chromium-browser",
+    "microsoft-edge",
+    "microsoft-edge-stable",
+    "msedge",
+    "brave-browser",
+    "brave",
+];
+
+/// Well-known install locations, in order of preference (Chrome, Chromium, Edge, Brave).
+fn install_paths() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    if cfg!(target_os = "macos") {
+        let apps = [
+            "Google Chrome.app/Contents/MacOS/Google Chrome",
+            "Chromium.app/Contents/MacOS/Chromium",
+            "Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+            "Brave Browser.app/Contents/MacOS/Brave Browser",
+        ];
+        let home_apps = std::env::var_os("HOME").map(|h| Path::new(&h).join("Applications"));
+        for app in apps {
+            paths.push(Path::new("/Applications").join(app));
+            if let Some(ref dir) = home_apps {
+                paths.push(dir.join(app));
+            }
+        }
+    } else if cfg!(target_os = "windows") {
+        let exes = [
+            r"Google\Chrome\Application\chrome.exe",
+            r"Chromium\Application\chrome.exe",
+            r"Microsoft\Edge\Application\msedge.exe",
+            r"BraveSoftware\Brave-Browser\Application\brave.exe",
+        ];
+        let roots: Vec<PathBuf> = ["PROGRAMFILES", "PROGRAMFILES(X86)", "LOCALAPPDATA"]
+            .iter()
+            .filter_map(|var| std::env::var_os(var).map(PathBuf::from))
+            .collect();
+        for exe in exes {
+            paths.extend(roots.iter().map(|root| root.join(exe)));
+        }
+    } else {
+        paths.extend(
+            [
+                "/usr/bin/google-chrome",
+                "/usr/bin/google-chrome-stable",
+                "/opt/google/chrome/chrome",
+                "/usr/bin/chromium",
+                "/usr/bin/chromium-browser",
+                // Raspberry Pi OS ships Chromium under /usr/lib
+                "/usr/lib/chromium/chromium",
+                "/usr/lib/chromium-browser/chromium-browser",
+                "/snap/bin/chromium",
+                "/usr/bin/microsoft-edge",
+                "/opt/microsoft/msedge/msedge",
+                "/usr/bin/brave-browser",
+                "/opt/brave.com/brave/brave",
+            ]
+            .iter()
+            .map(PathBuf::from),
+        );
+    }
+    paths
+}
+
+/// All locations checked by `find_chrome`, in order: the `CHROME` environment
+/// variable, `PATH` lookups, then well-known install locations.
+pub fn candidates() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(path) = std::env::var_os("CHROME") {
+        candidates.push(PathBuf::from(path));
+    }
+    if let Some(path_var) = std::env::var_os("PATH") {
+        for dir in std::env::split_paths(&path_var) {
+            for name in PATH_NAMES {
+                let file = if cfg!(target_os = "windows") {
+                    format!("{name}.exe")
+                } else {
+                    name.to_string()
+                };
+                candidates.push(dir.join(file));
+            }
+        }
+    }
+    candidates.extend(install_paths());
+    candidates
+}
+
+/// Find an installed Chrome-family browser (Chrome, Chromium, Edge, Brave).
+/// Fails with a `LaunchError` listing every location that was tried.
+pub fn find_chrome() -> Result<PathBuf> {
+    let candidates = candidates();
+    if let Some(found) = candidates.iter().find(|p| p.is_file()) {
+        return Ok(found.clone());
+    }
+
+    let tried: Vec<String> = candidates.iter().map(|p| format!("  {}", p.display())).collect();
+    Err(Error::LaunchError(format!(
+        "no Chrome, Chromium, Edge or Brave executable found; set chrome_path or the CHROME \
+         environment variable. Tried:\n{}",
+        tried.join("\n")
+    )))
+}
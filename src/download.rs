@@ -0,0 +1,126 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use chromiumoxide::cdp::browser_protocol::io::{CloseParams, ReadParams, StreamHandle};
+use chromiumoxide::cdp::browser_protocol::network::{
+    LoadNetworkResourceOptions, LoadNetworkResourceParams,
+};
+use chromiumoxide::page::Page as CrPage;
+
+use crate::error::{Error, Result};
+
+/// How far `Page::download_url_with` has got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadProgress {
+    /// Bytes written so far.
+    pub received: u64,
+    /// The `Content-Length`, if the server sent one.
+    pub total: Option<u64>,
+}
+
+/// Bytes asked for per `IO.read`.
+const CHUNK_SIZE: i64 = 1 << 20;
+
+/// Load `url` in `page`'s main frame with its cookies (Network.loadNetworkResource)
+/// and stream it into `path` through a `.part` file renamed when complete.
+pub(crate) async fn download(
+    page: &CrPage,
+    url: &str,
+    path: &Path,
+    mut progress: impl FnMut(DownloadProgress) + Send,
+) -> Result<u64> {
+    let frame_id = page
+        .mainframe()
+        .await?
+        .ok_or_else(|| Error::NavigationError("page has no main frame".to_string()))?;
+    let options = LoadNetworkResourceOptions::builder()
+        .disable_cache(true)
+        .include_credentials(true)
+        .build()
+        .map_err(Error::NavigationError)?;
+    let params = LoadNetworkResourceParams::builder()
+        .frame_id(frame_id)
+        .url(url)
+        .options(options)
+        .build()
+        .map_err(Error::NavigationError)?;
+    let resource = page.execute(params).await?.result.resource;
+
+    let status = resource.http_status_code.map(|s| s as u16);
+    let stream = match resource.stream {
+        Some(stream) if resource.success && status.is_none_or(|s| s < 400) => stream,
+        stream => {
+            if let Some(stream) = stream {
+                let _ = page.execute(CloseParams::new(stream)).await;
+            }
+            let reason = resource
+                .net_error_name
+                .or(status.map(|s| format!("HTTP {s}")))
+                .unwrap_or_else(|| "no response".to_string());
+            return Err(Error::NavigationError(format!("Download of {url} failed: {reason}")));
+        }
+    };
+    let total = resource.headers.as_ref().and_then(|headers| {
+        let headers = headers.inner().as_object()?;
+        let (_, value) = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))?;
+        value.as_str()?.trim().parse().ok()
+    });
+
+    let partial = partial_path(path);
+    let copied = copy(page, &stream, &partial, total, &mut progress).await;
+    let _ = page.execute(CloseParams::new(stream)).await;
+    match copied {
+        Ok(bytes) => {
+            std::fs::rename(&partial, path)?;
+            Ok(bytes)
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&partial);
+            Err(e)
+        }
+    }
+}
+
+async fn copy(
+    page: &CrPage,
+    stream: &StreamHandle,
+    partial: &Path,
+    total: Option<u64>,
+    progress: &mut (impl FnMut(DownloadProgress) + Send),
+) -> Result<u64> {
+    let mut file = std::fs::File::create(partial)?;
+    let mut received = 0;
+    loop {
+        let read = ReadParams::builder()
+            .handle(stream.clone())
+            .size(CHUNK_SIZE)
+            .build()
+            .map_err(Error::NavigationError)?;
+        let chunk = page.execute(read).await?.result;
+        let bytes = if chunk.base64_encoded == Some(true) {
+            base64::engine::general_purpose::STANDARD
+                .decode(&chunk.data)
+                .map_err(|e| Error::NavigationError(format!("Invalid download chunk: {e}")))?
+        } else {
+            chunk.data.into_bytes()
+        };
+        if !bytes.is_empty() {
+            file.write_all(&bytes)?;
+            received += bytes.len() as u64;
+            progress(DownloadProgress { received, total });
+        }
+        if chunk.eof {
+            return Ok(received);
+        }
+    }
+}
+
+/// `report.csv` → `report.csv.part`, next to it.
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
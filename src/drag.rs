@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use crate::ratelimit::{random_signed, random_up_to};
+
+/// Where `Page::drag_slider` drags the handle to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DragTarget {
+    /// This many CSS pixels to the right (left if negative).
+    Distance(f64),
+    /// The horizontal center of the element matching this CSS selector, such as
+    /// the slot a puzzle piece has to fill or the end of the track.
+    Element(String),
+}
+
+impl From<f64> for DragTarget {
+    fn from(distance: f64) -> Self {
+        DragTarget::Distance(distance)
+    }
+}
+
+impl From<&str> for DragTarget {
+    fn from(selector: &str) -> Self {
+        DragTarget::Element(selector.to_string())
+    }
+}
+
+/// One pointer move of a drag: where to, after how long.
+pub(crate) struct Step {
+    pub x: f64,
+    pub y: f64,
+    pub delay: Duration,
+}
+
+/// Ease in and out: slow start, fast middle, slow end.
+fn ease(t: f64) -> f64 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+/// A hand-like horizontal drag from `(x, y)` by `distance`: accelerates and
+/// decelerates, wobbles a little vertically, jitters in speed, and usually
+/// overshoots by a few pixels before settling on the target.
+pub(crate) fn path(x: f64, y: f64, distance: f64) -> Vec<Step> {
+    let direction = distance.signum();
+    let overshoot = if random_signed() > -0.4 { direction * (2.0 + 3.0 * random_signed().abs()) } else { 0.0 };
+    let steps = 28 + (random_signed().abs() * 12.0) as usize;
+    let mut drift = 0.0;
+    let mut path = Vec::with_capacity(steps + 4);
+    for i in 1..=steps {
+        let t = i as f64 / steps as f64;
+        // Jitter fades out so the drag ends where it should
+        let jitter = random_signed() * 0.8 * (1.0 - t);
+        drift = (drift + random_signed() * 0.6).clamp(-2.0, 2.0);
+        path.push(Step {
+            x: x + (distance + overshoot) * ease(t) + jitter,
+            y: y + drift,
+            delay: Duration::from_millis(12) + random_up_to(Duration::from_millis(18)),
+        });
+    }
+    if overshoot != 0.0 {
+        // Notice the overshoot, then pull back
+        let settle = 3 + (random_signed().abs() * 2.0) as usize;
+        for i in 1..=settle {
+            let t = i as f64 / settle as f64;
+            path.push(Step {
+                x: x + distance + overshoot * (1.0 - t),
+                y: y + drift * (1.0 - t),
+                delay: Duration::from_millis(30) + random_up_to(Duration::from_millis(40)),
+            });
+        }
+    } else {
+        path.push(Step {
+            x: x + distance,
+            y,
+            delay: Duration::from_millis(20) + random_up_to(Duration::from_millis(20)),
+        });
+    }
+    path
+}
@@ -1,137 +1,537 @@
+use std::collections::HashMap;
+
+use chromiumoxide::cdp::browser_protocol::dom::{GetBoxModelParams, GetContentQuadsParams, ScrollIntoViewIfNeededParams};
+use chromiumoxide::cdp::browser_protocol::input::{DispatchKeyEventParams, DispatchKeyEventType};
+use chromiumoxide::cdp::browser_protocol::page::{CaptureScreenshotFormat, Viewport};
+use chromiumoxide::cdp::js_protocol::runtime::{CallArgument, CallFunctionOnParams, RemoteObject, RemoteObjectId};
 use chromiumoxide::element::Element as CrElement;
-use chromiumoxide::cdp::browser_protocol::page::CaptureScreenshotFormat;
+use chromiumoxide::keys;
+use chromiumoxide::layout::{ElementQuad, Point};
+use chromiumoxide::page::{Page as CrPage, ScreenshotParams};
 
 use crate::error::{Error, Result};
+use crate::locator;
+use crate::page::{BoundingBox, ElementData};
+use crate::snapshot::{self, ExtractOptions, TreeOptions};
+
+/// An `<option>` of a `<select>` element, as returned by `Element::options`.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SelectOption {
+    pub value: String,
+    pub label: String,
+    pub selected: bool,
+    pub disabled: bool,
+}
 
-/// Wrapper around a chromiumoxide Element, providing a simplified API.
+/// Replaces the value of `this` with the text it is called with (see `Element::fill`).
+pub(crate) const FILL_JS: &str = r#"function(text) {
+    this.focus();
+    if (this.isContentEditable) {
+        this.textContent = text;
+    } else {
+        // The setter of the element's own class (input, textarea, select)
+        let proto = Object.getPrototypeOf(this), setter;
+        while (proto && !(setter = Object.getOwnPropertyDescriptor(proto, 'value')?.set)) {
+            proto = Object.getPrototypeOf(proto);
+        }
+        if (setter) setter.call(this, text); else this.value = text;
+    }
+    this.dispatchEvent(new Event('input', { bubbles: true }));
+    this.dispatchEvent(new Event('change', { bubbles: true }));
+}"#;
+
+/// Runtime.callFunctionOn calling `function` with `this` bound to `object_id`.
+/// Promises are awaited.
+pub(crate) fn call_params(
+    object_id: &RemoteObjectId,
+    function: &str,
+    args: Vec<CallArgument>,
+    by_value: bool,
+) -> Result<CallFunctionOnParams> {
+    CallFunctionOnParams::builder()
+        .function_declaration(function)
+        .object_id(object_id.clone())
+        .arguments(args)
+        .await_promise(true)
+        .return_by_value(by_value)
+        .build()
+        .map_err(Error::JsError)
+}
+
+/// `function` wrapped to return its awaited result through `JSON.stringify`.
+pub(crate) fn json_result(function: &str) -> String {
+    format!(
+        "async function(...args) {{ const r = await ({function}).apply(this, args); \
+         return JSON.stringify(r === undefined ? null : r); }}"
+    )
+}
+
+/// A handle to a DOM element, held as its CDP remote object.
+///
+/// The handle refers to the node itself, not to a selector, so it keeps working
+/// inside shadow roots and same-origin frames, and goes stale once the node is
+/// gone (e.g. after a navigation).
 pub struct Element {
-    inner: CrElement,
+    object_id: RemoteObjectId,
+    page: CrPage,
 }
 
 impl Element {
-    pub(crate) fn new(inner: CrElement) -> Self {
-        Self { inner }
+    pub(crate) fn new(inner: CrElement, page: CrPage) -> Self {
+        Self::from_object(inner.remote_object_id, page)
+    }
+
+    pub(crate) fn from_object(object_id: RemoteObjectId, page: CrPage) -> Self {
+        Self { object_id, page }
+    }
+
+    /// The element's remote object id, for CDP commands taking an `objectId`.
+    pub fn object_id(&self) -> &RemoteObjectId {
+        &self.object_id
+    }
+
+    /// Runtime.callFunctionOn with `this` bound to this element.
+    async fn call(&self, function: &str, args: Vec<CallArgument>, by_value: bool) -> Result<RemoteObject> {
+        let params = call_params(&self.object_id, function, args, by_value)?;
+        let ret = self
+            .page
+            .execute(params)
+            .await
+            .map_err(|e| Error::JsError(e.to_string()))?
+            .result;
+        if let Some(details) = ret.exception_details {
+            let msg = details
+                .exception
+                .and_then(|ex| ex.description)
+                .unwrap_or(details.text);
+            return Err(Error::JsError(msg));
+        }
+        Ok(ret.result)
+    }
+
+    /// Call a JS function with `this` bound to this element and return its result.
+    /// The result is passed through `JSON.stringify`, so objects and arrays come back
+    /// intact. Promises are awaited; exceptions are surfaced as `JsError`.
+    pub(crate) async fn call_js(&self, function: &str) -> Result<serde_json::Value> {
+        self.call_js_with(function, Vec::new()).await
+    }
+
+    /// `call_js`, passing `args` to the function (see `value_arg` and `object_arg`)
+    /// instead of splicing them into its source.
+    pub(crate) async fn call_js_with(&self, function: &str, args: Vec<CallArgument>) -> Result<serde_json::Value> {
+        match self.call(&json_result(function), args, true).await?.value {
+            Some(serde_json::Value::String(json)) => {
+                serde_json::from_str(&json).map_err(|e| Error::JsError(e.to_string()))
+            }
+            _ => Ok(serde_json::Value::Null),
+        }
+    }
+
+    /// Call a JS function with `this` bound to this element and hand the elements
+    /// of the array it returns over as handles, in order. Nulls are skipped.
+    pub(crate) async fn call_js_elements(&self, function: &str) -> Result<Vec<Element>> {
+        let array = self.call(function, Vec::new(), false).await?;
+        match array.object_id {
+            Some(array) => locator::elements_of(&self.page, array).await,
+            None => Ok(Vec::new()),
+        }
     }
 
-    /// Returns a reference to the underlying chromiumoxide Element.
-    pub fn inner(&self) -> &CrElement {
-        &self.inner
+    /// An argument of `call_js_with` passed by value.
+    pub(crate) fn value_arg(value: impl serde::Serialize) -> Result<CallArgument> {
+        let value = serde_json::to_value(value).map_err(|e| Error::JsError(e.to_string()))?;
+        Ok(CallArgument::builder().value(value).build())
+    }
+
+    /// An argument of `call_js_with` passing this element itself.
+    pub(crate) fn object_arg(&self) -> CallArgument {
+        CallArgument::builder().object_id(self.object_id.clone()).build()
+    }
+
+    /// The center of the first quad of the element's content big enough to click.
+    async fn clickable_point(&self) -> Result<Point> {
+        let params = GetContentQuadsParams::builder()
+            .object_id(self.object_id.clone())
+            .build();
+        let quads = self.page.execute(params).await?.result.quads;
+        quads
+            .iter()
+            .filter(|q| q.inner().len() == 8)
+            .map(ElementQuad::from_quad)
+            .find(|q| q.quad_area() > 1.)
+            .map(|q| q.quad_center())
+            .ok_or_else(|| Error::ElementNotFound("element is not visible or not an HTMLElement".into()))
     }
 
     /// Click this element (scrolls into view first).
     pub async fn click(&self) -> Result<()> {
-        self.inner
-            .click()
-            .await
-            .map_err(|e| Error::CdpError(e))?;
+        self.scroll_into_view().await?;
+        let point = self.clickable_point().await?;
+        self.page.click(point).await.map_err(Error::CdpError)?;
         Ok(())
     }
 
-    /// Type text into this element (wraps type_str).
+    /// Type text into the focused element, one key press per character. Click or
+    /// focus this element first; characters without a US keyboard key fail.
     pub async fn type_text(&self, text: &str) -> Result<()> {
-        self.inner
-            .type_str(text)
-            .await
-            .map_err(|e| Error::CdpError(e))?;
+        for c in text.chars() {
+            self.press_key(c.encode_utf8(&mut [0; 4])).await?;
+        }
+        Ok(())
+    }
+
+    /// Replace the current value of this input, textarea, select or contenteditable
+    /// element with `text`. Unlike `type_text`, which appends, existing content is cleared first.
+    /// Uses the native value setter so framework-controlled inputs (React, Vue) pick
+    /// up the change, and dispatches `input` and `change` events.
+    pub async fn fill(&self, text: &str) -> Result<()> {
+        self.call_js_with(FILL_JS, vec![Self::value_arg(text)?]).await?;
         Ok(())
     }
 
     /// Press a key on this element (e.g. "Enter", "Tab").
     pub async fn press_key(&self, key: &str) -> Result<()> {
-        self.inner
-            .press_key(key)
-            .await
-            .map_err(|e| Error::CdpError(e))?;
+        let definition = keys::get_key_definition(key)
+            .ok_or_else(|| Error::JsError(format!("Key not found: {key}")))?;
+        let mut event = DispatchKeyEventParams::builder()
+            .key(definition.key)
+            .code(definition.code)
+            .windows_virtual_key_code(definition.key_code)
+            .native_virtual_key_code(definition.key_code);
+        // Keys producing text go down as keyDown, others as rawKeyDown
+        let down = match definition.text.or((definition.key.len() == 1).then_some(definition.key)) {
+            Some(text) => {
+                event = event.text(text);
+                DispatchKeyEventType::KeyDown
+            }
+            None => DispatchKeyEventType::RawKeyDown,
+        };
+        for kind in [down, DispatchKeyEventType::KeyUp] {
+            let params = event.clone().r#type(kind).build().map_err(Error::JsError)?;
+            self.page.execute(params).await?;
+        }
         Ok(())
     }
 
     /// Focus this element.
     pub async fn focus(&self) -> Result<()> {
-        self.inner
-            .focus()
-            .await
-            .map_err(|e| Error::CdpError(e))?;
+        self.call_js("function() { this.focus(); }").await?;
         Ok(())
     }
 
     /// Hover over this element (scrolls into view first).
     pub async fn hover(&self) -> Result<()> {
-        self.inner
-            .hover()
-            .await
-            .map_err(|e| Error::CdpError(e))?;
+        self.scroll_into_view().await?;
+        let point = self.clickable_point().await?;
+        self.page.move_mouse(point).await.map_err(Error::CdpError)?;
         Ok(())
     }
 
     /// Scroll this element into view.
     pub async fn scroll_into_view(&self) -> Result<()> {
-        self.inner
-            .scroll_into_view()
-            .await
-            .map_err(|e| Error::CdpError(e))?;
+        let params = ScrollIntoViewIfNeededParams::builder()
+            .object_id(self.object_id.clone())
+            .build();
+        self.page.execute(params).await?;
         Ok(())
     }
 
+    /// A string property of this element, `None` if empty.
+    async fn string_property(&self, name: &str) -> Result<Option<String>> {
+        let value = self.call_js_with("function(name) { return this[name]; }", vec![Self::value_arg(name)?]).await?;
+        Ok(value.as_str().filter(|s| !s.is_empty()).map(str::to_string))
+    }
+
     /// Get the inner text of this element.
     pub async fn inner_text(&self) -> Result<String> {
-        self.inner
-            .inner_text()
-            .await
-            .map_err(|e| Error::CdpError(e))?
+        self.string_property("innerText")
+            .await?
             .ok_or_else(|| Error::ElementNotFound("inner text is empty".into()))
     }
 
     /// Get the inner HTML of this element.
     pub async fn inner_html(&self) -> Result<String> {
-        self.inner
-            .inner_html()
-            .await
-            .map_err(|e| Error::CdpError(e))?
+        self.string_property("innerHTML")
+            .await?
             .ok_or_else(|| Error::ElementNotFound("inner HTML is empty".into()))
     }
 
     /// Get the outer HTML of this element.
     pub async fn outer_html(&self) -> Result<String> {
-        self.inner
-            .outer_html()
-            .await
-            .map_err(|e| Error::CdpError(e))?
+        self.string_property("outerHTML")
+            .await?
             .ok_or_else(|| Error::ElementNotFound("outer HTML is empty".into()))
     }
 
     /// Get the value of an attribute on this element.
     pub async fn get_attribute(&self, name: &str) -> Result<Option<String>> {
-        self.inner
-            .attribute(name)
-            .await
-            .map_err(|e| Error::CdpError(e))
+        let value = self
+            .call_js_with("function(name) { return this.getAttribute(name); }", vec![Self::value_arg(name)?])
+            .await?;
+        Ok(value.as_str().map(str::to_string))
+    }
+
+    /// Get a live JS property of this element (e.g. `value`, `checked`, `scrollHeight`),
+    /// deserialized into `T`. Unlike `get_attribute`, this reflects the current state
+    /// rather than the original markup. Missing properties deserialize from `null`,
+    /// so use `Option<T>` when the property may not exist.
+    pub async fn get_property<T: serde::de::DeserializeOwned>(&self, name: &str) -> Result<T> {
+        let name_js = serde_json::to_string(name).map_err(|e| Error::JsError(e.to_string()))?;
+        let value = self
+            .call_js(&format!("function() {{ return this[{name_js}]; }}"))
+            .await?;
+        serde_json::from_value(value)
+            .map_err(|e| Error::JsError(format!("Property {name} has unexpected type: {e}")))
+    }
+
+    /// Get computed CSS values for the given properties (e.g. `display`, `visibility`,
+    /// `z-index`, `color`). Property names use CSS syntax (`z-index`, not `zIndex`).
+    pub async fn computed_style(&self, props: &[&str]) -> Result<HashMap<String, String>> {
+        let props_js = serde_json::to_string(props).map_err(|e| Error::JsError(e.to_string()))?;
+        let value = self
+            .call_js(&format!(
+                r#"function() {{
+                    const style = window.getComputedStyle(this);
+                    const out = {{}};
+                    for (const p of {props_js}) out[p] = style.getPropertyValue(p);
+                    return out;
+                }}"#
+            ))
+            .await?;
+        serde_json::from_value(value).map_err(|e| Error::JsError(e.to_string()))
+    }
+
+    /// List the options of a `<select>` element, so valid values can be discovered
+    /// before choosing one.
+    pub async fn options(&self) -> Result<Vec<SelectOption>> {
+        let value = self
+            .call_js(
+                r#"function() {
+                    if (!this.options) throw new Error('Element is not a <select>');
+                    return Array.from(this.options).map(o => ({
+                        value: o.value,
+                        label: o.label.trim(),
+                        selected: o.selected,
+                        disabled: o.disabled,
+                    }));
+                }"#,
+            )
+            .await?;
+        serde_json::from_value(value).map_err(|e| Error::JsError(e.to_string()))
     }
 
     /// Take a screenshot of this element (PNG format).
     pub async fn screenshot(&self) -> Result<Vec<u8>> {
-        self.inner
-            .screenshot(CaptureScreenshotFormat::Png)
-            .await
-            .map_err(|e| Error::CdpError(e))
+        self.scroll_into_view().await?;
+        let bounds = self.bounding_box().await?;
+        let viewport = self.page.layout_metrics().await?.css_layout_viewport;
+        let clip = Viewport {
+            x: viewport.page_x as f64 + bounds.x,
+            y: viewport.page_y as f64 + bounds.y,
+            width: bounds.width,
+            height: bounds.height,
+            scale: 1.,
+        };
+        let params = ScreenshotParams::builder()
+            .format(CaptureScreenshotFormat::Png)
+            .clip(clip)
+            .build();
+        self.page.screenshot(params).await.map_err(|e| Error::ScreenshotError(e.to_string()))
+    }
+
+    /// This element's border box, in CSS pixels relative to the viewport.
+    pub async fn bounding_box(&self) -> Result<BoundingBox> {
+        let params = GetBoxModelParams::builder()
+            .object_id(self.object_id.clone())
+            .build();
+        let border = ElementQuad::from_quad(&self.page.execute(params).await?.result.model.border);
+        let (x, y) = (border.most_left(), border.most_top());
+        Ok(BoundingBox { x, y, width: border.most_right() - x, height: border.most_bottom() - y })
     }
 
     /// Find a child element matching the given CSS selector.
     pub async fn find_element(&self, selector: &str) -> Result<Element> {
-        let el = self
-            .inner
-            .find_element(selector)
-            .await
-            .map_err(|e| Error::CdpError(e))?;
-        Ok(Element::new(el))
+        self.find_elements_with(selector, "[this.querySelector(selector)]")
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::ElementNotFound(selector.to_string()))
     }
 
     /// Find all child elements matching the given CSS selector.
     pub async fn find_elements(&self, selector: &str) -> Result<Vec<Element>> {
-        let els = self
-            .inner
-            .find_elements(selector)
+        self.find_elements_with(selector, "this.querySelectorAll(selector)").await
+    }
+
+    async fn find_elements_with(&self, selector: &str, elements_js: &str) -> Result<Vec<Element>> {
+        let selector = serde_json::to_string(selector).map_err(|e| Error::JsError(e.to_string()))?;
+        self.related(&format!("(selector => {elements_js})({selector})")).await
+    }
+
+    /// `Page::query_selector_all_with_data` limited to this element's
+    /// descendants, e.g. the fields of one product card.
+    pub async fn query_all_with_data(&self, selector: &str, attributes: &[&str]) -> Result<Vec<ElementData>> {
+        self.query_all_with(selector, attributes, ExtractOptions::default()).await
+    }
+
+    /// `query_all_with_data` with text truncation set by `options`.
+    pub async fn query_all_with(
+        &self,
+        selector: &str,
+        attributes: &[&str],
+        options: ExtractOptions,
+    ) -> Result<Vec<ElementData>> {
+        let args = serde_json::to_string(&(selector, attributes, options.js_args()))
+            .map_err(|e| Error::JsError(e.to_string()))?;
+        let value = self
+            .call_js(&format!(
+                "function() {{ return ({})(this, ...{args}); }}",
+                snapshot::QUERY_WITH_DATA_JS
+            ))
+            .await?;
+        serde_json::from_value(value).map_err(|e| Error::JsError(e.to_string()))
+    }
+
+    /// `Page::get_links` limited to the links inside this element.
+    pub async fn get_links(&self) -> Result<Vec<(String, String)>> {
+        let value = self
+            .call_js(&format!("function() {{ return ({})(this); }}", snapshot::LINKS_JS))
+            .await?;
+        serde_json::from_value(value).map_err(|e| Error::JsError(e.to_string()))
+    }
+
+    /// `Page::accessibility_tree` of this element and its descendants only.
+    pub async fn accessibility_subtree(&self) -> Result<String> {
+        self.accessibility_subtree_with(TreeOptions::default()).await
+    }
+
+    /// `accessibility_subtree` with text truncation set by `options`. Frames and
+    /// shadow roots are not walked into.
+    pub async fn accessibility_subtree_with(&self, options: TreeOptions) -> Result<String> {
+        let options = TreeOptions { frames: false, shadow_roots: false, ..options };
+        let value = self
+            .call_js(&format!(
+                "function() {{ return ({})({}, this); }}",
+                snapshot::ACCESSIBILITY_TREE_JS,
+                options.js_args()
+            ))
+            .await?;
+        let lines: Vec<String> = serde_json::from_value(value).map_err(|e| Error::JsError(e.to_string()))?;
+        Ok(lines.join("\n"))
+    }
+
+    /// Evaluate a boolean JS predicate with `this` bound to the element.
+    async fn check(&self, function: &str) -> Result<bool> {
+        Ok(self.call_js(function).await?.as_bool().unwrap_or(false))
+    }
+
+    /// Whether the element is rendered: attached, not `display:none` /
+    /// `visibility:hidden`, and with a non-empty bounding box.
+    pub async fn is_visible(&self) -> Result<bool> {
+        self.check(
+            r#"function() {
+                if (!this.isConnected) return false;
+                const visible = typeof this.checkVisibility === 'function'
+                    ? this.checkVisibility({ checkOpacity: true, checkVisibilityCSS: true })
+                    : this.offsetParent !== null;
+                const rect = this.getBoundingClientRect();
+                return visible && rect.width > 0 && rect.height > 0;
+            }"#,
+        )
+        .await
+    }
+
+    /// Whether the element is enabled (not `disabled`, not inside a disabled
+    /// fieldset, not `aria-disabled="true"`).
+    pub async fn is_enabled(&self) -> Result<bool> {
+        self.check(
+            r#"function() {
+                return !(this.disabled
+                    || this.closest('fieldset:disabled')
+                    || this.getAttribute('aria-disabled') === 'true');
+            }"#,
+        )
+        .await
+    }
+
+    /// Whether a checkbox/radio is checked (or an ARIA checkbox/switch has `aria-checked="true"`).
+    pub async fn is_checked(&self) -> Result<bool> {
+        self.check(
+            r#"function() {
+                if (this.type === 'checkbox' || this.type === 'radio') return this.checked;
+                return this.getAttribute('aria-checked') === 'true'
+                    || this.getAttribute('aria-pressed') === 'true';
+            }"#,
+        )
+        .await
+    }
+
+    /// Whether the element accepts text input: an enabled, non-readonly
+    /// input/textarea/select, or a contenteditable element.
+    pub async fn is_editable(&self) -> Result<bool> {
+        self.check(
+            r#"function() {
+                if (this.disabled || this.closest('fieldset:disabled')
+                    || this.getAttribute('aria-disabled') === 'true') return false;
+                const tag = this.tagName;
+                if (tag === 'INPUT' || tag === 'TEXTAREA' || tag === 'SELECT') {
+                    return !this.readOnly && this.getAttribute('aria-readonly') !== 'true';
+                }
+                return this.isContentEditable;
+            }"#,
+        )
+        .await
+    }
+
+    /// Whether any part of the element is inside the current viewport.
+    pub async fn is_in_viewport(&self) -> Result<bool> {
+        self.check(
+            r#"function() {
+                const r = this.getBoundingClientRect();
+                return r.width > 0 && r.height > 0
+                    && r.bottom > 0 && r.right > 0
+                    && r.top < window.innerHeight && r.left < window.innerWidth;
+            }"#,
+        )
+        .await
+    }
+
+    /// Resolve the elements returned by a JS expression evaluated with `this`
+    /// bound to this element (e.g. `[this.parentElement]`). Nulls are skipped.
+    async fn related(&self, elements_js: &str) -> Result<Vec<Element>> {
+        self.call_js_elements(&format!("function() {{ return Array.from({elements_js}).filter(Boolean); }}"))
             .await
-            .map_err(|e| Error::CdpError(e))?;
-        Ok(els.into_iter().map(Element::new).collect())
+    }
+
+    /// Get the parent element, or `None` for the root element.
+    pub async fn parent(&self) -> Result<Option<Element>> {
+        Ok(self.related("[this.parentElement]").await?.into_iter().next())
+    }
+
+    /// Get all child elements, in document order.
+    pub async fn children(&self) -> Result<Vec<Element>> {
+        self.related("this.children").await
+    }
+
+    /// Get the next sibling element, if any.
+    pub async fn next_sibling(&self) -> Result<Option<Element>> {
+        Ok(self.related("[this.nextElementSibling]").await?.into_iter().next())
+    }
+
+    /// Get the previous sibling element, if any.
+    pub async fn previous_sibling(&self) -> Result<Option<Element>> {
+        Ok(self.related("[this.previousElementSibling]").await?.into_iter().next())
+    }
+
+    /// Get the closest ancestor (including this element itself) matching the
+    /// given CSS selector, if any.
+    pub async fn closest(&self, selector: &str) -> Result<Option<Element>> {
+        let selector_js = serde_json::to_string(selector)
+            .map_err(|e| Error::JsError(e.to_string()))?;
+        Ok(self
+            .related(&format!("[this.closest({selector_js})]"))
+            .await?
+            .into_iter()
+            .next())
     }
 }
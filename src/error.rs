@@ -5,6 +5,9 @@ pub enum Error {
     #[error("Browser launch failed: {0}")]
     LaunchError(String),
 
+    #[error("Invalid configuration: {0}")]
+    ConfigError(String),
+
     #[error("Navigation failed: {0}")]
     NavigationError(String),
 
@@ -20,6 +23,21 @@ pub enum Error {
     #[error("Screenshot failed: {0}")]
     ScreenshotError(String),
 
+    #[error("Scenario failed: {0}")]
+    ScenarioError(String),
+
+    #[error("Tool call failed: {0}")]
+    ToolError(String),
+
+    #[error("Assertion failed: {0}")]
+    AssertionFailed(String),
+
+    #[error("OCR failed: {0}")]
+    OcrError(String),
+
+    #[error("Action rejected: {0}")]
+    ActionRejected(String),
+
     #[error("CDP error: {0}")]
     CdpError(#[from] chromiumoxide::error::CdpError),
 
@@ -0,0 +1,187 @@
+use std::sync::{Arc, RwLock};
+
+use chromiumoxide::browser::Browser as CrBrowser;
+use chromiumoxide::cdp::IntoEventKind;
+use chromiumoxide::page::Page as CrPage;
+use chromiumoxide::cdp::browser_protocol::page::EventJavascriptDialogOpening;
+use futures::StreamExt;
+
+use crate::error::{Error, Result};
+use crate::watchdog::BudgetAction;
+
+/// Kinds of browser events that can be subscribed to with `AgenticBrowser::on`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// A tab, popup, worker or other target was created.
+    TargetCreated,
+    /// A target was closed.
+    TargetDestroyed,
+    /// A download was started by a page of the default context or of a profile
+    /// opened after subscribing. Subscribing turns on download events for those
+    /// contexts only, with Chrome's default download behavior.
+    DownloadStarted,
+    /// A page opened an `alert`, `confirm`, `prompt` or `beforeunload` dialog.
+    DialogOpened,
+    /// A target (renderer process) crashed.
+    Crashed,
+    /// A page or the browser went over its `MemoryBudget`.
+    MemoryBudgetExceeded,
+    /// A page's request interception failed to answer a request, with
+    /// `BackgroundErrorPolicy::Emit`.
+    BackgroundError,
+}
+
+/// A browser event delivered to handlers registered with `AgenticBrowser::on`.
+#[derive(Debug, Clone)]
+pub enum BrowserEvent {
+    TargetCreated {
+        target_id: String,
+        /// "page", "iframe", "service_worker", ...
+        target_type: String,
+        url: String,
+    },
+    TargetDestroyed {
+        target_id: String,
+    },
+    DownloadStarted {
+        guid: String,
+        url: String,
+        suggested_filename: String,
+    },
+    DialogOpened {
+        /// Target id of the page that opened the dialog.
+        target_id: String,
+        /// "alert", "confirm", "prompt" or "beforeunload".
+        dialog_type: String,
+        message: String,
+        url: String,
+    },
+    Crashed {
+        target_id: String,
+        /// Termination status, e.g. "crashed" or "killed".
+        status: String,
+        error_code: i64,
+    },
+    MemoryBudgetExceeded {
+        /// The page over its JS heap budget, or `None` for the browser's RSS budget.
+        target_id: Option<String>,
+        used_bytes: u64,
+        limit_bytes: u64,
+        /// What the watchdog did about it.
+        action: BudgetAction,
+    },
+    BackgroundError(BackgroundError),
+}
+
+impl BrowserEvent {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            BrowserEvent::TargetCreated { .. } => EventKind::TargetCreated,
+            BrowserEvent::TargetDestroyed { .. } => EventKind::TargetDestroyed,
+            BrowserEvent::DownloadStarted { .. } => EventKind::DownloadStarted,
+            BrowserEvent::DialogOpened { .. } => EventKind::DialogOpened,
+            BrowserEvent::Crashed { .. } => EventKind::Crashed,
+            BrowserEvent::MemoryBudgetExceeded { .. } => EventKind::MemoryBudgetExceeded,
+            BrowserEvent::BackgroundError(_) => EventKind::BackgroundError,
+        }
+    }
+}
+
+/// A failure in a page's request interception task (proxy and HTTP auth,
+/// resource blocking, navigation policy, fixture replay). Left unanswered, the
+/// request stays paused and the page appears to hang.
+#[derive(Debug, Clone)]
+pub struct BackgroundError {
+    /// Target id of the page.
+    pub target_id: String,
+    /// The CDP command that failed, e.g. "Fetch.continueRequest".
+    pub command: String,
+    /// URL of the request it was answering.
+    pub url: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for BackgroundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} failed for {}: {}", self.command, self.url, self.message)
+    }
+}
+
+/// What happens to a `BackgroundError`, besides being sent to the streams from
+/// `Page::background_errors`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackgroundErrorPolicy {
+    /// Log it at warn level through the `log` facade.
+    #[default]
+    Log,
+    /// Emit `BrowserEvent::BackgroundError` to handlers registered for
+    /// `EventKind::BackgroundError`.
+    Emit,
+    /// Stop the page loading and fail the navigation in progress, or the next
+    /// one, with the error. Later navigations run normally.
+    FailPage,
+}
+
+pub(crate) type EventHandler = Arc<dyn Fn(BrowserEvent) + Send + Sync>;
+
+/// Forward every browser-level CDP event of type `T` to `handler`.
+pub(crate) async fn forward<T>(
+    browser: &CrBrowser,
+    handler: EventHandler,
+    map: fn(&T) -> BrowserEvent,
+) -> Result<()>
+where
+    T: IntoEventKind + Unpin + Send + Sync + 'static,
+{
+    let mut events = browser
+        .event_listener::<T>()
+        .await
+        .map_err(|e| Error::LaunchError(format!("Failed to listen for browser events: {e}")))?;
+    tokio::spawn(async move {
+        while let Some(event) = events.next().await {
+            handler(map(&event));
+        }
+    });
+    Ok(())
+}
+
+/// Handlers for events that don't come from a single browser-level CDP stream:
+/// dialogs (page-level CDP events, forwarded by each page to the handlers registered
+/// so far) and events raised by the crate itself, such as the memory watchdog's.
+#[derive(Default)]
+pub(crate) struct SharedHandlers(RwLock<Vec<EventHandler>>);
+
+impl SharedHandlers {
+    pub fn push(&self, handler: EventHandler) {
+        self.0.write().unwrap_or_else(|e| e.into_inner()).push(handler);
+    }
+
+    /// Call every registered handler with `event`.
+    pub fn emit(&self, event: BrowserEvent) {
+        let handlers = self.0.read().unwrap_or_else(|e| e.into_inner()).clone();
+        for handler in handlers {
+            handler(event.clone());
+        }
+    }
+
+    /// Forward dialogs opened by `page` to the registered handlers.
+    pub async fn watch(self: &Arc<Self>, page: &CrPage) -> Result<()> {
+        let mut events = page
+            .event_listener::<EventJavascriptDialogOpening>()
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to listen for dialog events: {e}")))?;
+        let target_id = page.target_id().inner().clone();
+        let handlers = Arc::clone(self);
+        tokio::spawn(async move {
+            while let Some(event) = events.next().await {
+                handlers.emit(BrowserEvent::DialogOpened {
+                    target_id: target_id.clone(),
+                    dialog_type: event.r#type.as_ref().to_string(),
+                    message: event.message.clone(),
+                    url: event.url.clone(),
+                });
+            }
+        });
+        Ok(())
+    }
+}
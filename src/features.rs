@@ -0,0 +1,112 @@
+use chromiumoxide::cdp::browser_protocol::page::{
+    PrintToPdfParams, StartScreencastParams, StopScreencastParams,
+};
+use chromiumoxide::cdp::js_protocol::heap_profiler;
+use chromiumoxide::page::Page as CrPage;
+
+/// An optional browser capability that may not be available in every environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    /// Hardware-accelerated rendering (WebGL).
+    Gpu,
+    /// Frame streaming via `Page.startScreencast`.
+    Screencast,
+    /// PDF rendering via `Page.printToPDF` (headless only).
+    Pdf,
+    /// JS heap snapshots via the HeapProfiler domain.
+    HeapProfiler,
+    /// Loading Chrome extensions.
+    Extensions,
+}
+
+/// Whether a single feature is usable, and why not if it isn't.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FeatureStatus {
+    pub feature: Feature,
+    pub available: bool,
+    /// Why the feature is unavailable (`None` when available).
+    pub reason: Option<String>,
+}
+
+/// Report of which optional capabilities work in the current environment.
+/// Returned by `AgenticBrowser::feature_report()`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FeatureReport {
+    pub features: Vec<FeatureStatus>,
+}
+
+impl FeatureReport {
+    /// Whether the given feature is available.
+    pub fn is_available(&self, feature: Feature) -> bool {
+        self.get(feature).is_some_and(|f| f.available)
+    }
+
+    /// Look up the status of a single feature.
+    pub fn get(&self, feature: Feature) -> Option<&FeatureStatus> {
+        self.features.iter().find(|f| f.feature == feature)
+    }
+}
+
+fn status(feature: Feature, result: std::result::Result<(), String>) -> FeatureStatus {
+    match result {
+        Ok(()) => FeatureStatus { feature, available: true, reason: None },
+        Err(reason) => FeatureStatus { feature, available: false, reason: Some(reason) },
+    }
+}
+
+/// Probe each optional feature on a scratch page.
+/// `args` are the Chrome switches the browser was launched with (without `--`).
+pub(crate) async fn probe(page: &CrPage, headless: bool, args: &[&str]) -> FeatureReport {
+    let gpu = if args.contains(&"disable-gpu") {
+        Err("disabled by --disable-gpu".to_string())
+    } else {
+        let js = "!!document.createElement('canvas').getContext('webgl')";
+        match page.evaluate(js).await.map(|r| r.into_value::<bool>()) {
+            Ok(Ok(true)) => Ok(()),
+            Ok(_) => Err("WebGL context could not be created".to_string()),
+            Err(e) => Err(e.to_string()),
+        }
+    };
+
+    let screencast = match page.execute(StartScreencastParams::default()).await {
+        Ok(_) => {
+            let _ = page.execute(StopScreencastParams::default()).await;
+            Ok(())
+        }
+        Err(e) => Err(e.to_string()),
+    };
+
+    let pdf = if !headless {
+        Err("PDF rendering requires headless mode".to_string())
+    } else {
+        page.execute(PrintToPdfParams::default())
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    };
+
+    let heap_profiler = match page.execute(heap_profiler::EnableParams::default()).await {
+        Ok(_) => {
+            let _ = page.execute(heap_profiler::DisableParams::default()).await;
+            Ok(())
+        }
+        Err(e) => Err(e.to_string()),
+    };
+
+    let extensions = if args.contains(&"disable-extensions") {
+        Err("disabled by --disable-extensions".to_string())
+    } else {
+        Ok(())
+    };
+
+    FeatureReport {
+        features: vec![
+            status(Feature::Gpu, gpu),
+            status(Feature::Screencast, screencast),
+            status(Feature::Pdf, pdf),
+            status(Feature::HeapProfiler, heap_profiler),
+            status(Feature::Extensions, extensions),
+        ],
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use base64::Engine;
+use serde::de::DeserializeOwned;
+
+use crate::error::{Error, Result};
+
+/// An HTTP request made from inside a page by `Page::fetch`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FetchRequest {
+    pub url: String,
+    pub method: String,
+    pub headers: HashMap<String, String>,
+    pub body: Option<String>,
+    /// A binary body; sent instead of `body` when set.
+    #[serde(serialize_with = "serialize_base64")]
+    pub bytes: Option<Vec<u8>>,
+    /// Multipart form fields; when any are set they are the body, and the
+    /// browser sets the `Content-Type` and its boundary.
+    pub parts: Vec<FormPart>,
+}
+
+/// A field of a multipart `FetchRequest`.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FormPart {
+    Text { name: String, value: String },
+    /// A local file, read by the browser from disk as it uploads, so large
+    /// files never pass through the page's memory or the DevTools connection.
+    File {
+        name: String,
+        path: PathBuf,
+        /// Defaults to the file's name.
+        filename: Option<String>,
+        /// Defaults to one guessed from the extension.
+        content_type: Option<String>,
+    },
+}
+
+impl FormPart {
+    pub fn text(name: impl Into<String>, value: impl Into<String>) -> Self {
+        FormPart::Text { name: name.into(), value: value.into() }
+    }
+
+    pub fn file(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        FormPart::File {
+            name: name.into(),
+            path: path.into(),
+            filename: None,
+            content_type: None,
+        }
+    }
+
+    /// Send a file part under another name. No effect on text parts.
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        if let FormPart::File { filename: ref mut f, .. } = self {
+            *f = Some(filename.into());
+        }
+        self
+    }
+
+    /// Send a file part with this MIME type. No effect on text parts.
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        if let FormPart::File { content_type: ref mut c, .. } = self {
+            *c = Some(content_type.into());
+        }
+        self
+    }
+}
+
+impl FetchRequest {
+    pub fn new(method: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            method: method.into(),
+            headers: HashMap::new(),
+            body: None,
+            bytes: None,
+            parts: Vec::new(),
+        }
+    }
+
+    pub fn get(url: impl Into<String>) -> Self {
+        Self::new("GET", url)
+    }
+
+    pub fn post(url: impl Into<String>, body: impl Into<String>) -> Self {
+        Self::new("POST", url).body(body)
+    }
+
+    /// A POST with a JSON body and `Content-Type: application/json`.
+    pub fn post_json(url: impl Into<String>, body: &impl serde::Serialize) -> Result<Self> {
+        let body = serde_json::to_string(body).map_err(|e| Error::JsError(e.to_string()))?;
+        Ok(Self::post(url, body).header("Content-Type", "application/json"))
+    }
+
+    /// A POST of `fields` as `application/x-www-form-urlencoded`, like a plain
+    /// HTML form.
+    pub fn post_form(url: impl Into<String>, fields: &[(&str, &str)]) -> Self {
+        let body = fields
+            .iter()
+            .map(|(name, value)| format!("{}={}", form_encode(name), form_encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+        Self::post(url, body).header("Content-Type", "application/x-www-form-urlencoded")
+    }
+
+    /// A POST of `parts` as `multipart/form-data`, e.g. an upload with
+    /// `FormPart::file("attachment", "report.pdf")`.
+    pub fn post_multipart(url: impl Into<String>, parts: Vec<FormPart>) -> Self {
+        Self::new("POST", url).parts(parts)
+    }
+
+    /// A POST of raw bytes with the given `Content-Type`.
+    pub fn post_bytes(
+        url: impl Into<String>,
+        bytes: impl Into<Vec<u8>>,
+        content_type: &str,
+    ) -> Self {
+        Self::new("POST", url).bytes(bytes).header("Content-Type", content_type)
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    pub fn bytes(mut self, bytes: impl Into<Vec<u8>>) -> Self {
+        self.bytes = Some(bytes.into());
+        self
+    }
+
+    pub fn part(mut self, part: FormPart) -> Self {
+        self.parts.push(part);
+        self
+    }
+
+    pub fn parts(mut self, parts: Vec<FormPart>) -> Self {
+        self.parts.extend(parts);
+        self
+    }
+}
+
+fn serialize_base64<S: serde::Serializer>(
+    bytes: &Option<Vec<u8>>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    let encoded = bytes.as_ref().map(|b| base64::engine::general_purpose::STANDARD.encode(b));
+    serde::Serialize::serialize(&encoded, serializer)
+}
+
+/// Percent-encode for `application/x-www-form-urlencoded`.
+pub(crate) fn form_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => {
+                out.push(byte as char)
+            }
+            b' ' => out.push('+'),
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+impl From<&str> for FetchRequest {
+    fn from(url: &str) -> Self {
+        Self::get(url)
+    }
+}
+
+/// Response to a `Page::fetch` request.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct FetchResponse {
+    /// Final URL after redirects.
+    pub url: String,
+    pub status: u16,
+    pub status_text: String,
+    /// Header names are lowercase.
+    pub headers: HashMap<String, String>,
+    /// Response body decoded as text.
+    pub body: String,
+}
+
+impl FetchResponse {
+    /// Whether the status is 2xx.
+    pub fn ok(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Deserialize the body as JSON.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_str(&self.body).map_err(|e| Error::JsError(format!("Invalid JSON response: {e}")))
+    }
+}
+
+/// `(url) => url` resolved against the document's base URL.
+pub(crate) const RESOLVE_URL_JS: &str = "(url) => new URL(url, document.baseURI).href";
+
+/// Runs `fetch()` in the page with the page's cookies and returns a plain object
+/// matching `FetchResponse`. `inputs` are the file inputs holding the request's
+/// file parts, in order.
+pub(crate) const FETCH_JS: &str = r#"async function(req, ...inputs) {
+    const init = { method: req.method, headers: req.headers, credentials: 'include' };
+    if (req.parts.length > 0) {
+        const form = new FormData();
+        let next = 0;
+        for (const part of req.parts) {
+            if (part.kind === 'text') {
+                form.append(part.name, part.value);
+                continue;
+            }
+            let file = inputs[next++].files[0];
+            if (part.content_type !== null) file = new File([file], file.name, { type: part.content_type });
+            form.append(part.name, file, part.filename !== null ? part.filename : file.name);
+        }
+        init.body = form;
+    } else if (req.bytes !== null) {
+        init.body = Uint8Array.from(atob(req.bytes), c => c.charCodeAt(0));
+    } else if (req.body !== null) {
+        init.body = req.body;
+    }
+    const res = await fetch(req.url, init);
+    const headers = {};
+    res.headers.forEach((value, name) => { headers[name] = value; });
+    return {
+        url: res.url,
+        status: res.status,
+        status_text: res.statusText,
+        headers,
+        body: await res.text(),
+    };
+}"#;
+
+/// A file input for a file part, never added to the document so page scripts
+/// can't see it.
+pub(crate) const NEW_FILE_INPUT_JS: &str = r#"(() => {
+    const input = document.createElement('input');
+    input.type = 'file';
+    return input;
+})()"#;
@@ -0,0 +1,266 @@
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::fmt::Display;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::sync::OnceLock;
+
+use crate::browser::AgenticBrowser;
+use crate::config::BrowserConfig;
+use crate::page::Page;
+
+// C ABI for embedding the browser in other languages; declared in
+// `include/agentic_browser.h`. Functions returning `c_int` give 0 on success and
+// -1 on failure; functions returning pointers give NULL on failure. After a
+// failure, `ab_last_error` describes it. Panics are caught at the boundary and
+// reported the same way.
+
+/// Browser handle for C callers.
+pub struct AbBrowser(AgenticBrowser);
+
+/// Page handle for C callers.
+pub struct AbPage(Page);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn runtime() -> std::result::Result<&'static tokio::runtime::Runtime, String> {
+    static RUNTIME: OnceLock<std::result::Result<tokio::runtime::Runtime, String>> = OnceLock::new();
+    RUNTIME
+        .get_or_init(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| format!("failed to start the tokio runtime: {e}"))
+        })
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+/// Run `future` to completion on the shared runtime.
+fn block_on<T, E: Display>(future: impl Future<Output = std::result::Result<T, E>>) -> std::result::Result<T, String> {
+    runtime()?.block_on(future).map_err(|e| e.to_string())
+}
+
+fn set_error(message: impl Display) {
+    let message = CString::new(message.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// Run `body`, or record its error or panic and return `fallback`.
+fn guard<T>(fallback: T, body: impl FnOnce() -> std::result::Result<T, String>) -> T {
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(value)) => value,
+        Ok(Err(e)) => {
+            set_error(e);
+            fallback
+        }
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            set_error(format!("panicked: {message}"));
+            fallback
+        }
+    }
+}
+
+/// Borrow a C string argument as UTF-8.
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> std::result::Result<&'a str, String> {
+    if ptr.is_null() {
+        return Err(format!("{name} is NULL"));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| format!("{name} is not valid UTF-8"))
+}
+
+unsafe fn page_arg<'a>(page: *mut AbPage) -> std::result::Result<&'a Page, String> {
+    page.as_ref().map(|p| &p.0).ok_or_else(|| "page is NULL".to_string())
+}
+
+/// Message of the last failure on this thread, or NULL. Valid until the next
+/// failing call on the same thread; do not free.
+#[no_mangle]
+pub extern "C" fn ab_last_error() -> *const c_char {
+    panic::catch_unwind(|| LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr())))
+        .unwrap_or(ptr::null())
+}
+
+/// Launch a browser configured from a TOML file (see `BrowserConfig::from_file`),
+/// or from `AGENTIC_BROWSER_*` environment variables if `config_path` is NULL.
+///
+/// # Safety
+/// `config_path` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn ab_browser_new(config_path: *const c_char) -> *mut AbBrowser {
+    guard(ptr::null_mut(), || {
+        let config = if config_path.is_null() {
+            BrowserConfig::from_env()
+        } else {
+            BrowserConfig::from_file(str_arg(config_path, "config_path")?)
+        };
+        let browser = block_on(AgenticBrowser::launch(config.map_err(|e| e.to_string())?))?;
+        Ok(Box::into_raw(Box::new(AbBrowser(browser))))
+    })
+}
+
+/// Shut the browser down. Free its pages first.
+///
+/// # Safety
+/// `browser` must be NULL or a handle from `ab_browser_new`, not used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ab_browser_free(browser: *mut AbBrowser) {
+    guard((), || {
+        if !browser.is_null() {
+            let browser = Box::from_raw(browser);
+            // Dropping it shuts the handler task down on the runtime
+            let _guard = runtime()?.enter();
+            drop(browser);
+        }
+        Ok(())
+    })
+}
+
+/// Open a page at `url`, or a blank page if `url` is NULL.
+///
+/// # Safety
+/// `browser` must be a live handle from `ab_browser_new`; `url` NULL or a
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn ab_page_new(browser: *mut AbBrowser, url: *const c_char) -> *mut AbPage {
+    guard(ptr::null_mut(), || {
+        let browser = browser.as_ref().ok_or("browser is NULL")?;
+        let page = if url.is_null() {
+            block_on(browser.0.new_blank_page())?
+        } else {
+            block_on(browser.0.new_page(str_arg(url, "url")?))?
+        };
+        Ok(Box::into_raw(Box::new(AbPage(page))))
+    })
+}
+
+/// Close the page's tab and free the handle.
+///
+/// # Safety
+/// `page` must be NULL or a handle from `ab_page_new`, not used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ab_page_free(page: *mut AbPage) {
+    guard((), || {
+        if !page.is_null() {
+            let page = Box::from_raw(page);
+            let tab = page.0.inner().clone();
+            let _ = block_on(tab.close());
+        }
+        Ok(())
+    })
+}
+
+/// # Safety
+/// `page` must be a live handle from `ab_page_new`; `url` a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn ab_page_goto(page: *mut AbPage, url: *const c_char) -> c_int {
+    guard(-1, || {
+        block_on(page_arg(page)?.goto(str_arg(url, "url")?))?;
+        Ok(0)
+    })
+}
+
+/// Click the first element matching a CSS selector.
+///
+/// # Safety
+/// `page` must be a live handle from `ab_page_new`; `selector` a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn ab_page_click(page: *mut AbPage, selector: *const c_char) -> c_int {
+    guard(-1, || {
+        block_on(page_arg(page)?.click(str_arg(selector, "selector")?))?;
+        Ok(0)
+    })
+}
+
+/// Type text into the first element matching a CSS selector.
+///
+/// # Safety
+/// `page` must be a live handle from `ab_page_new`; `selector` and `text`
+/// NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn ab_page_type(page: *mut AbPage, selector: *const c_char, text: *const c_char) -> c_int {
+    guard(-1, || {
+        let page = page_arg(page)?;
+        block_on(page.type_text(str_arg(selector, "selector")?, str_arg(text, "text")?))?;
+        Ok(0)
+    })
+}
+
+/// Take a PNG screenshot of the viewport into a new buffer, stored in `*out_data`
+/// and `*out_len`. Free it with `ab_buffer_free`.
+///
+/// # Safety
+/// `page` must be a live handle from `ab_page_new`; `out_data` and `out_len`
+/// valid pointers.
+#[no_mangle]
+pub unsafe extern "C" fn ab_page_screenshot(page: *mut AbPage, out_data: *mut *mut u8, out_len: *mut usize) -> c_int {
+    guard(-1, || {
+        if out_data.is_null() || out_len.is_null() {
+            return Err("out_data or out_len is NULL".to_string());
+        }
+        let png = block_on(page_arg(page)?.screenshot())?.into_boxed_slice();
+        *out_len = png.len();
+        *out_data = Box::into_raw(png) as *mut u8;
+        Ok(0)
+    })
+}
+
+/// The page as a JSON string: `observation` (url, title, scroll, viewport),
+/// `links`, `form_fields` and `accessibility_tree`. Free it with `ab_string_free`.
+///
+/// # Safety
+/// `page` must be a live handle from `ab_page_new`.
+#[no_mangle]
+pub unsafe extern "C" fn ab_page_snapshot(page: *mut AbPage) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let page = page_arg(page)?;
+        let json = block_on(async {
+            let snapshot = serde_json::json!({
+                "observation": page.observe().await?,
+                "links": page.get_links().await?,
+                "form_fields": page.get_form_fields().await?,
+                "accessibility_tree": page.accessibility_tree().await?,
+            });
+            Ok::<_, crate::error::Error>(snapshot.to_string())
+        })?;
+        Ok(CString::new(json).map_err(|e| e.to_string())?.into_raw())
+    })
+}
+
+/// Free a string returned by this library.
+///
+/// # Safety
+/// `s` must be NULL or a string from this library, not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn ab_string_free(s: *mut c_char) {
+    guard((), || {
+        if !s.is_null() {
+            drop(CString::from_raw(s));
+        }
+        Ok(())
+    })
+}
+
+/// Free a buffer returned by `ab_page_screenshot`.
+///
+/// # Safety
+/// `data` and `len` must come from the same `ab_page_screenshot` call, not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn ab_buffer_free(data: *mut u8, len: usize) {
+    guard((), || {
+        if !data.is_null() {
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(data, len)));
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,119 @@
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+
+use crate::error::{Error, Result};
+
+/// Record every network response to a directory, or serve responses back from one,
+/// so tests of agent logic run without touching the network.
+/// Set with `BrowserBuilder::record_fixtures` / `BrowserBuilder::replay_fixtures`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetworkFixtures {
+    /// Save each response as one JSON file in this directory, keyed by method,
+    /// URL and request body. A later response for the same key overwrites the earlier one.
+    Record(PathBuf),
+    /// Fulfill requests from the files in this directory. Requests without a
+    /// recorded response fail with `net::ERR_INTERNET_DISCONNECTED`.
+    Replay(PathBuf),
+}
+
+impl NetworkFixtures {
+    pub(crate) fn is_record(&self) -> bool {
+        matches!(self, NetworkFixtures::Record(_))
+    }
+
+    pub(crate) fn is_replay(&self) -> bool {
+        matches!(self, NetworkFixtures::Replay(_))
+    }
+
+    fn dir(&self) -> &Path {
+        match self {
+            NetworkFixtures::Record(dir) | NetworkFixtures::Replay(dir) => dir,
+        }
+    }
+
+    /// Read the recorded response for `method` + `url` + `request_body`, if any.
+    pub(crate) fn load(&self, method: &str, url: &str, request_body: &[u8]) -> Option<Fixture> {
+        let text = std::fs::read_to_string(self.dir().join(file_name(method, url, request_body))).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    /// Write `fixture`, creating the directory if needed.
+    pub(crate) fn save(&self, fixture: &Fixture) -> Result<()> {
+        let dir = self.dir();
+        std::fs::create_dir_all(dir)?;
+        let json = serde_json::to_string_pretty(fixture)
+            .map_err(|e| Error::ConfigError(format!("Failed to serialize fixture: {e}")))?;
+        let request_body = match fixture.request_body {
+            Some(ref body) => base64::engine::general_purpose::STANDARD
+                .decode(body)
+                .map_err(|e| Error::ConfigError(format!("Invalid fixture request body: {e}")))?,
+            None => Vec::new(),
+        };
+        std::fs::write(dir.join(file_name(&fixture.method, &fixture.url, &request_body)), json)?;
+        Ok(())
+    }
+}
+
+/// A recorded response, stored as `<hash of method, URL and request body>.json`.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub(crate) struct Fixture {
+    pub method: String,
+    pub url: String,
+    /// Request body (e.g. of a POST), base64-encoded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    /// Response body, base64-encoded.
+    pub body: String,
+}
+
+impl Fixture {
+    /// Build from a body as returned by Fetch.getResponseBody.
+    pub fn new(
+        method: String,
+        url: String,
+        request_body: &[u8],
+        status: u16,
+        headers: Vec<(String, String)>,
+        body: String,
+        base64_encoded: bool,
+    ) -> Self {
+        let body = if base64_encoded {
+            body
+        } else {
+            base64::engine::general_purpose::STANDARD.encode(body)
+        };
+        let request_body =
+            (!request_body.is_empty()).then(|| base64::engine::general_purpose::STANDARD.encode(request_body));
+        Self { method, url, request_body, status, headers, body }
+    }
+}
+
+/// Stable file name for a request. FNV-1a rather than `DefaultHasher`, whose output
+/// may change between Rust releases and would orphan checked-in fixtures. Requests
+/// without a body hash as before bodies were part of the key.
+fn file_name(method: &str, url: &str, request_body: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let separator: &[u8] = if request_body.is_empty() { b"" } else { b" " };
+    for byte in [method.as_bytes(), b" ", url.as_bytes(), separator, request_body].concat() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{hash:016x}.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::file_name;
+
+    #[test]
+    fn file_name_keys_on_method_url_and_body() {
+        let url = "https://example.com/api";
+        assert_eq!(file_name("GET", url, b""), "ed0e9cca74d3289c.json");
+        assert_ne!(file_name("GET", url, b""), file_name("POST", url, b""));
+        assert_ne!(file_name("POST", url, b"q=1"), file_name("POST", url, b"q=2"));
+        assert_ne!(file_name("POST", url, b""), file_name("POST", url, b" "));
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+
+use base64::Engine;
+use chromiumoxide::cdp::browser_protocol::network::{
+    EnableParams, EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent,
+    EventResponseReceived, GetRequestPostDataParams, GetResponseBodyParams, RequestId,
+    ResourceType,
+};
+use chromiumoxide::page::Page as CrPage;
+use futures::channel::mpsc;
+use futures::{Stream, StreamExt};
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+use crate::fetch::{self, FetchRequest};
+
+/// A GraphQL operation the page sent, with its response, from
+/// `Page::capture_graphql`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct GraphqlOperation {
+    /// The endpoint, without the query string of GET requests.
+    pub endpoint: String,
+    /// `POST`, or `GET` (typical of persisted queries).
+    pub method: String,
+    pub operation_name: Option<String>,
+    /// The query document; `None` for persisted queries sent by hash only.
+    pub query: Option<String>,
+    pub variables: Value,
+    /// e.g. the `persistedQuery` hash.
+    pub extensions: Option<Value>,
+    /// The request's headers, which carry auth tokens sites don't keep in cookies.
+    pub headers: HashMap<String, String>,
+    pub status: u16,
+    /// The response (`data` and `errors`); `Null` if it wasn't JSON.
+    pub response: Value,
+}
+
+impl GraphqlOperation {
+    /// The response's `data`.
+    pub fn data(&self) -> Option<&Value> {
+        self.response.get("data").filter(|data| !data.is_null())
+    }
+
+    /// This operation with the top-level keys of `variables` replacing its own,
+    /// e.g. `op.with_variables(json!({"after": cursor}))` for the next page.
+    pub fn with_variables(&self, variables: Value) -> Self {
+        let mut operation = self.clone();
+        match (&mut operation.variables, variables) {
+            (Value::Object(own), Value::Object(new)) => own.extend(new),
+            (own, new) => *own = new,
+        }
+        operation
+    }
+
+    /// A request sending the operation again, with its headers, for `Page::fetch`.
+    pub fn to_request(&self) -> FetchRequest {
+        let mut payload = Map::new();
+        if let Some(ref name) = self.operation_name {
+            payload.insert("operationName".to_string(), Value::String(name.clone()));
+        }
+        if let Some(ref query) = self.query {
+            payload.insert("query".to_string(), Value::String(query.clone()));
+        }
+        payload.insert("variables".to_string(), self.variables.clone());
+        if let Some(ref extensions) = self.extensions {
+            payload.insert("extensions".to_string(), extensions.clone());
+        }
+
+        let mut request = if self.method.eq_ignore_ascii_case("GET") {
+            let params: Vec<String> = payload
+                .iter()
+                .map(|(name, value)| {
+                    let value = match value {
+                        Value::String(s) => s.clone(),
+                        value => value.to_string(),
+                    };
+                    format!("{}={}", fetch::form_encode(name), fetch::form_encode(&value))
+                })
+                .collect();
+            FetchRequest::get(format!("{}?{}", self.endpoint, params.join("&")))
+        } else {
+            FetchRequest::post(&self.endpoint, Value::Object(payload).to_string())
+                .header("Content-Type", "application/json")
+        };
+        for (name, value) in &self.headers {
+            if replayable(name) {
+                request = request.header(name, value);
+            }
+        }
+        request
+    }
+}
+
+/// Whether a captured header can be set by `fetch()` and belongs to the
+/// operation rather than the connection.
+fn replayable(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    !name.starts_with(':')
+        && !name.starts_with("sec-")
+        && !matches!(
+            name.as_str(),
+            "host" | "cookie" | "origin" | "referer" | "user-agent" | "content-length"
+                | "accept-encoding" | "connection"
+        )
+}
+
+/// The operations of a request, before its response is in.
+struct Pending {
+    endpoint: String,
+    method: String,
+    headers: HashMap<String, String>,
+    /// Whether the body was a batch (an array), answered by an array.
+    batched: bool,
+    operations: Vec<Map<String, Value>>,
+    status: u16,
+}
+
+enum NetworkEvent {
+    Request(std::sync::Arc<EventRequestWillBeSent>),
+    Response(RequestId, u16),
+    Finished(RequestId),
+    Failed(RequestId),
+}
+
+/// Stream the GraphQL operations `page` sends over XHR/fetch, each once its
+/// response has loaded. Capturing stops when the returned stream is dropped.
+pub(crate) async fn capture(
+    page: &CrPage,
+) -> Result<impl Stream<Item = GraphqlOperation> + Send + Unpin> {
+    let listen_err = |e: chromiumoxide::error::CdpError| {
+        Error::JsError(format!("Failed to listen for network events: {e}"))
+    };
+    let requests = page.event_listener::<EventRequestWillBeSent>().await.map_err(listen_err)?;
+    let responses = page.event_listener::<EventResponseReceived>().await.map_err(listen_err)?;
+    let finished = page.event_listener::<EventLoadingFinished>().await.map_err(listen_err)?;
+    let failed = page.event_listener::<EventLoadingFailed>().await.map_err(listen_err)?;
+    page.execute(EnableParams::default())
+        .await
+        .map_err(|e| Error::JsError(format!("Failed to enable network domain: {e}")))?;
+
+    let mut events = futures::stream::select_all([
+        requests.map(NetworkEvent::Request).boxed(),
+        responses
+            .map(|e| NetworkEvent::Response(e.request_id.clone(), e.response.status as u16))
+            .boxed(),
+        finished.map(|e| NetworkEvent::Finished(e.request_id.clone())).boxed(),
+        failed.map(|e| NetworkEvent::Failed(e.request_id.clone())).boxed(),
+    ]);
+    let page = page.clone();
+    let (tx, rx) = mpsc::unbounded();
+
+    tokio::spawn(async move {
+        let mut pending: HashMap<RequestId, Pending> = HashMap::new();
+        while let Some(event) = events.next().await {
+            if tx.is_closed() {
+                break;
+            }
+            match event {
+                NetworkEvent::Request(event) => {
+                    let is_api = matches!(event.r#type, Some(ResourceType::Xhr | ResourceType::Fetch));
+                    if !is_api {
+                        continue;
+                    }
+                    let request = &event.request;
+                    let body = if request.has_post_data == Some(true) {
+                        page.execute(GetRequestPostDataParams::new(event.request_id.clone()))
+                            .await
+                            .ok()
+                            .map(|r| r.result.post_data)
+                    } else {
+                        None
+                    };
+                    let parsed = parse_request(&request.url, body.as_deref());
+                    let Some((endpoint, batched, operations)) = parsed else {
+                        continue;
+                    };
+                    let headers = request
+                        .headers
+                        .inner()
+                        .as_object()
+                        .map(|headers| {
+                            headers
+                                .iter()
+                                .filter_map(|(name, value)| {
+                                    Some((name.clone(), value.as_str()?.to_string()))
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    pending.insert(
+                        event.request_id.clone(),
+                        Pending {
+                            endpoint,
+                            method: request.method.clone(),
+                            headers,
+                            batched,
+                            operations,
+                            status: 0,
+                        },
+                    );
+                }
+                NetworkEvent::Response(request_id, status) => {
+                    if let Some(request) = pending.get_mut(&request_id) {
+                        request.status = status;
+                    }
+                }
+                NetworkEvent::Finished(request_id) => {
+                    let Some(request) = pending.remove(&request_id) else {
+                        continue;
+                    };
+                    let response = match page.execute(GetResponseBodyParams::new(request_id)).await {
+                        Ok(response) => parse_body(response.result.body, response.result.base64_encoded),
+                        Err(_) => Value::Null,
+                    };
+                    for operation in operations(request, response) {
+                        if tx.unbounded_send(operation).is_err() {
+                            break;
+                        }
+                    }
+                }
+                NetworkEvent::Failed(request_id) => {
+                    pending.remove(&request_id);
+                }
+            }
+        }
+    });
+
+    Ok(rx)
+}
+
+/// Pair each operation of `request` with its part of `response`.
+fn operations(request: Pending, response: Value) -> Vec<GraphqlOperation> {
+    let responses: Vec<Value> = match response {
+        Value::Array(items) if request.batched => items,
+        response => vec![response],
+    };
+    request
+        .operations
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut payload)| GraphqlOperation {
+            endpoint: request.endpoint.clone(),
+            method: request.method.clone(),
+            operation_name: payload.remove("operationName").and_then(|v| v.as_str().map(String::from)),
+            query: payload.remove("query").and_then(|v| v.as_str().map(String::from)),
+            variables: payload.remove("variables").unwrap_or(Value::Null),
+            extensions: payload.remove("extensions").filter(|v| !v.is_null()),
+            headers: request.headers.clone(),
+            status: request.status,
+            response: responses.get(i).cloned().unwrap_or(Value::Null),
+        })
+        .collect()
+}
+
+fn parse_body(body: String, base64_encoded: bool) -> Value {
+    let text = if base64_encoded {
+        match base64::engine::general_purpose::STANDARD.decode(&body) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(_) => return Value::Null,
+        }
+    } else {
+        body
+    };
+    serde_json::from_str(&text).unwrap_or(Value::Null)
+}
+
+/// A request's endpoint, whether it was a batch, and its operations.
+type ParsedRequest = (String, bool, Vec<Map<String, Value>>);
+
+/// The endpoint and operations of a GraphQL request, or `None` if it isn't one:
+/// a JSON body (or batch) with a GraphQL `query` or a persisted query hash, or
+/// the same as GET parameters.
+fn parse_request(url: &str, body: Option<&str>) -> Option<ParsedRequest> {
+    let (endpoint, query_string) = url.split_once('?').unwrap_or((url, ""));
+    if let Some(body) = body {
+        let (batched, operations) = match serde_json::from_str(body).ok()? {
+            Value::Object(operation) => (false, vec![operation]),
+            Value::Array(items) => {
+                let operations: Option<Vec<_>> = items
+                    .into_iter()
+                    .map(|item| match item {
+                        Value::Object(operation) => Some(operation),
+                        _ => None,
+                    })
+                    .collect();
+                (true, operations?)
+            }
+            _ => return None,
+        };
+        if operations.is_empty() || !operations.iter().all(is_operation) {
+            return None;
+        }
+        return Some((url.to_string(), batched, operations));
+    }
+
+    let mut operation = Map::new();
+    for pair in query_string.split('&') {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = decode_component(value);
+        let value = match name {
+            "variables" | "extensions" => serde_json::from_str(&value).ok()?,
+            "query" | "operationName" => Value::String(value),
+            _ => continue,
+        };
+        operation.insert(name.to_string(), value);
+    }
+    is_operation(&operation).then(|| (endpoint.to_string(), false, vec![operation]))
+}
+
+/// Whether `payload` has a GraphQL document as its `query`, or a persisted
+/// query hash in its extensions.
+fn is_operation(payload: &Map<String, Value>) -> bool {
+    let persisted = payload
+        .get("extensions")
+        .is_some_and(|extensions| extensions.get("persistedQuery").is_some());
+    let document = payload.get("query").and_then(Value::as_str).is_some_and(|query| {
+        let query = query.trim_start();
+        query.starts_with('{')
+            || ["query", "mutation", "subscription", "fragment"].iter().any(|keyword| {
+                query
+                    .strip_prefix(keyword)
+                    .and_then(|rest| rest.chars().next())
+                    .is_some_and(|c| c.is_whitespace() || c == '{' || c == '(')
+            })
+    });
+    persisted || document
+}
+
+/// Decode a percent-encoded query string component (`+` is a space).
+fn decode_component(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[i], escaped) {
+            (_, Some(byte)) => {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+            (b'+', None) => out.push(b' '),
+            (byte, None) => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
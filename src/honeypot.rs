@@ -0,0 +1,105 @@
+/// Why `Page::fill_form_safe` left a field alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrapReason {
+    /// Not rendered, transparent, `aria-hidden`, or one pixel or less in size.
+    Hidden,
+    /// Positioned outside the page (e.g. `left: -9999px`).
+    OffScreen,
+    /// Labeled like a bot trap ("leave this empty", ...), or named like one
+    /// ("honeypot", ...) and also kept out of reach (`tabindex="-1"`,
+    /// `autocomplete="off"`, clipped or tiny).
+    TrapName,
+}
+
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct SkippedTrap {
+    pub selector: String,
+    pub reason: TrapReason,
+}
+
+/// Result of `Page::fill_form_safe`.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct FillFormReport {
+    /// Selectors of the fields that were filled, in order.
+    pub filled: Vec<String>,
+    /// Fields that look like honeypots and were not touched.
+    pub skipped: Vec<SkippedTrap>,
+}
+
+/// `(fields, safe) => FillFormReport` for `fields` as `[{selector, value}]`:
+/// fills the fields with their element's own native value setter, skipping likely
+/// honeypots when `safe` is set. Throws if a selector matches nothing.
+pub(crate) const FILL_FORM_JS: &str = r#"(fields, safe) => {
+    const TRAP_NAME = /honey|hpot|bot[-_ ]?(check|trap|field)|(^|[\W_])trap($|[\W_])|do[-_ ]?not[-_ ]?fill|leave[-_ ]?(this[-_ ]?)?(field[-_ ]?)?(blank|empty)/i;
+    const TRAP_LABEL = /leave (this )?(field )?(blank|empty)|do(n't| not) (fill|enter|change)|if you are (a )?human/i;
+    // A trap-like name alone is too weak (real fields get odd names); it also has
+    // to be kept out of reach: skipped by Tab, autocomplete off, clipped or tiny
+    const outOfReach = (el, rect) => {
+        if (el.tabIndex < 0) return true;
+        if ((el.getAttribute('autocomplete') || '').toLowerCase() === 'off') return true;
+        const style = getComputedStyle(el);
+        if (style.clipPath !== 'none' || (style.clip && style.clip !== 'auto')) return true;
+        return rect.width < 8 || rect.height < 8;
+    };
+    const trapReason = el => {
+        if (el.type === 'hidden') return 'hidden';
+        if (typeof el.checkVisibility === 'function'
+            && !el.checkVisibility({ checkOpacity: true, checkVisibilityCSS: true })) return 'hidden';
+        if (el.closest('[aria-hidden="true"]')) return 'hidden';
+        for (let node = el; node && node.nodeType === Node.ELEMENT_NODE; node = node.parentElement) {
+            if (parseFloat(getComputedStyle(node).opacity) < 0.1) return 'hidden';
+        }
+        const rect = el.getBoundingClientRect();
+        if (rect.width <= 1 || rect.height <= 1) return 'hidden';
+        const left = rect.left + window.scrollX, top = rect.top + window.scrollY;
+        const root = document.documentElement;
+        if (left + rect.width <= 0 || top + rect.height <= 0
+            || left >= Math.max(root.scrollWidth, window.innerWidth)) return 'off_screen';
+        const names = [el.name, el.id, el.getAttribute('class'), el.getAttribute('autocomplete')];
+        if (names.some(n => n && TRAP_NAME.test(n)) && outOfReach(el, rect)) return 'trap_name';
+        const labels = Array.from(el.labels || []).map(l => l.innerText || '');
+        labels.push(el.getAttribute('aria-label') || '', el.placeholder || '');
+        if (labels.some(l => TRAP_LABEL.test(l))) return 'trap_name';
+        return null;
+    };
+    const setValue = (el, value) => {
+        if (el.isContentEditable) {
+            el.textContent = value;
+            return;
+        }
+        // The setter of the element's own class (input, textarea, select)
+        let proto = Object.getPrototypeOf(el), setter;
+        while (proto && !(setter = Object.getOwnPropertyDescriptor(proto, 'value')?.set)) {
+            proto = Object.getPrototypeOf(proto);
+        }
+        if (setter) setter.call(el, value); else el.value = value;
+    };
+
+    const missing = fields.filter(f => !document.querySelector(f.selector)).map(f => 'Not found: ' + f.selector);
+    if (missing.length > 0) throw new Error(missing.join('; '));
+    const report = { filled: [], skipped: [] };
+    for (const f of fields) {
+        const el = document.querySelector(f.selector);
+        const reason = safe ? trapReason(el) : null;
+        if (reason) {
+            report.skipped.push({ selector: f.selector, reason });
+            continue;
+        }
+        el.focus();
+        setValue(el, f.value);
+        el.dispatchEvent(new Event('input', { bubbles: true }));
+        el.dispatchEvent(new Event('change', { bubbles: true }));
+        el.blur();
+        report.filled.push(f.selector);
+    }
+    return report;
+}"#;
+
+/// The `fields` argument of `FILL_FORM_JS`.
+pub(crate) fn fill_args(fields: &[(&str, &str)]) -> Vec<serde_json::Value> {
+    fields
+        .iter()
+        .map(|(selector, value)| serde_json::json!({ "selector": selector, "value": value }))
+        .collect()
+}
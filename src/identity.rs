@@ -0,0 +1,199 @@
+use crate::ratelimit;
+
+/// The browser traits one page presents: user agent, client hints,
+/// `navigator.platform`, languages, viewport and time zone. From
+/// `RequestIdentity::next`; apply with `Page::set_identity`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Identity {
+    /// The `User-Agent` header and `navigator.userAgent`. Like real Chrome's, its
+    /// version is reduced to `<major>.0.0.0`.
+    pub user_agent: String,
+    /// The full Chrome version of the client hints (`Sec-CH-UA-Full-Version-List`,
+    /// `navigator.userAgentData`), e.g. `145.0.7632.117`.
+    pub chrome_version: String,
+    /// `navigator.platform`, e.g. `MacIntel`.
+    pub platform: String,
+    /// The client hints platform (`macOS`, `Windows`, `Linux`).
+    pub hints_platform: String,
+    pub hints_platform_version: String,
+    /// `x86` or `arm`.
+    pub architecture: String,
+    /// The `Accept-Language` header; also sets `navigator.languages`.
+    pub accept_language: String,
+    pub viewport_width: u32,
+    pub viewport_height: u32,
+    /// Emulate a phone: client hints report a mobile device and pages lay out
+    /// for their `<meta name="viewport">`.
+    #[serde(default)]
+    pub mobile: bool,
+    /// IANA time zone for `Date` and `Intl`, e.g. `Europe/Berlin`; `None` keeps
+    /// the machine's.
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+/// An operating system of the `RequestIdentity` pool, with traits that go
+/// together.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct PlatformProfile {
+    /// The OS part of the user agent, e.g. `Windows NT 10.0; Win64; x64`.
+    pub user_agent_os: String,
+    pub platform: String,
+    pub hints_platform: String,
+    pub hints_platform_version: String,
+    pub architecture: String,
+    /// Typical browser viewports (window minus toolbars) on this OS.
+    pub viewports: Vec<(u32, u32)>,
+}
+
+impl PlatformProfile {
+    pub fn mac() -> Self {
+        Self {
+            user_agent_os: "Macintosh; Intel Mac OS X 10_15_7".to_string(),
+            platform: "MacIntel".to_string(),
+            hints_platform: "macOS".to_string(),
+            hints_platform_version: "15.3.0".to_string(),
+            architecture: "arm".to_string(),
+            viewports: vec![(1440, 789), (1512, 862), (1680, 939), (1920, 969)],
+        }
+    }
+
+    pub fn windows() -> Self {
+        Self {
+            user_agent_os: "Windows NT 10.0; Win64; x64".to_string(),
+            platform: "Win32".to_string(),
+            hints_platform: "Windows".to_string(),
+            hints_platform_version: "19.0.0".to_string(),
+            architecture: "x86".to_string(),
+            viewports: vec![(1280, 665), (1366, 641), (1536, 730), (1920, 945)],
+        }
+    }
+
+    pub fn linux() -> Self {
+        Self {
+            user_agent_os: "X11; Linux x86_64".to_string(),
+            platform: "Linux x86_64".to_string(),
+            hints_platform: "Linux".to_string(),
+            hints_platform_version: "6.8.0".to_string(),
+            architecture: "x86".to_string(),
+            viewports: vec![(1366, 657), (1920, 955)],
+        }
+    }
+}
+
+/// A pool of consistent identities to draw one from per page, so pages sharing
+/// a proxy don't all send byte-identical headers. Set with
+/// `BrowserBuilder::request_identity`.
+#[derive(Debug, Clone)]
+pub struct RequestIdentity {
+    pub platforms: Vec<PlatformProfile>,
+    /// `Accept-Language` values.
+    pub languages: Vec<String>,
+    /// Full Chrome versions; keep them to the major version of the Chrome
+    /// actually running, or feature checks will give the lie away.
+    pub chrome_versions: Vec<String>,
+    /// Each viewport dimension is moved by up to this many pixels either way
+    /// (default 16).
+    pub viewport_jitter: u32,
+    /// IANA time zones; match them to where the proxy exits. Empty (the
+    /// default) keeps the machine's.
+    pub timezones: Vec<String>,
+}
+
+impl Default for RequestIdentity {
+    fn default() -> Self {
+        Self {
+            platforms: vec![PlatformProfile::mac(), PlatformProfile::windows(), PlatformProfile::linux()],
+            languages: vec![
+                "en-US,en;q=0.9".to_string(),
+                "en-US,en;q=0.9,es;q=0.8".to_string(),
+                "en-GB,en-US;q=0.9,en;q=0.8".to_string(),
+                "en-US,en;q=0.8".to_string(),
+            ],
+            chrome_versions: vec![
+                "145.0.7632.45".to_string(),
+                "145.0.7632.76".to_string(),
+                "145.0.7632.109".to_string(),
+                "145.0.7632.117".to_string(),
+            ],
+            viewport_jitter: 16,
+            timezones: Vec::new(),
+        }
+    }
+}
+
+impl RequestIdentity {
+    /// Mac, Windows and Linux desktops, English locales, Chrome 145.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn platforms(mut self, platforms: Vec<PlatformProfile>) -> Self {
+        self.platforms = platforms;
+        self
+    }
+
+    pub fn languages(mut self, languages: &[&str]) -> Self {
+        self.languages = languages.iter().map(|l| l.to_string()).collect();
+        self
+    }
+
+    pub fn chrome_versions(mut self, versions: &[&str]) -> Self {
+        self.chrome_versions = versions.iter().map(|v| v.to_string()).collect();
+        self
+    }
+
+    pub fn viewport_jitter(mut self, pixels: u32) -> Self {
+        self.viewport_jitter = pixels;
+        self
+    }
+
+    pub fn timezones(mut self, timezones: &[&str]) -> Self {
+        self.timezones = timezones.iter().map(|t| t.to_string()).collect();
+        self
+    }
+
+    /// Draw a random identity from the pool.
+    pub fn next(&self) -> Identity {
+        let platform = pick(&self.platforms).cloned().unwrap_or_else(PlatformProfile::mac);
+        let chrome_version = pick(&self.chrome_versions).cloned().unwrap_or_else(|| "145.0.7632.117".to_string());
+        let accept_language = pick(&self.languages).cloned().unwrap_or_else(|| "en-US,en;q=0.9".to_string());
+        let (width, height) = pick(&platform.viewports).copied().unwrap_or((1920, 1080));
+        let jitter = |size: u32| {
+            let offset = (ratelimit::random_signed() * self.viewport_jitter as f64).round() as i64;
+            (size as i64 + offset).max(320) as u32
+        };
+        let major = chrome_version.split('.').next().unwrap_or("145");
+        Identity {
+            user_agent: format!(
+                "Mozilla/5.0 ({}) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/{major}.0.0.0 Safari/537.36",
+                platform.user_agent_os
+            ),
+            chrome_version,
+            platform: platform.platform,
+            hints_platform: platform.hints_platform,
+            hints_platform_version: platform.hints_platform_version,
+            architecture: platform.architecture,
+            accept_language,
+            viewport_width: jitter(width),
+            viewport_height: jitter(height),
+            mobile: false,
+            timezone: pick(&self.timezones).cloned(),
+        }
+    }
+}
+
+fn pick<T>(items: &[T]) -> Option<&T> {
+    if items.is_empty() {
+        return None;
+    }
+    items.get(ratelimit::random_u64() as usize % items.len())
+}
+
+/// Removes the `navigator` properties the stealth script pins to fixed values,
+/// so the identity's overrides show through.
+pub(crate) const UNPIN_NAVIGATOR_JS: &str = r#"
+for (const key of ['languages', 'platform', 'userAgentData']) {
+    if (Object.getOwnPropertyDescriptor(navigator, key)) delete navigator[key];
+}
+"#;
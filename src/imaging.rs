@@ -0,0 +1,205 @@
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+
+use crate::error::{Error, Result};
+use crate::page::BoundingBox;
+
+/// A decoded screenshot to crop, resize, gray out or annotate before encoding
+/// it again. Boxes are in CSS pixels relative to the viewport, like those of
+/// `Page::interactive_elements` or `Element::bounding_box`, and keep pointing at
+/// the same content through crops and resizes.
+#[derive(Debug, Clone)]
+pub struct Screenshot {
+    image: RgbaImage,
+    /// Image pixels per CSS pixel.
+    scale: f64,
+    /// The viewport point (CSS pixels) at the image's top left corner.
+    origin: (f64, f64),
+}
+
+impl Screenshot {
+    /// Decode a PNG or JPEG taken of the viewport with `scale` image pixels per
+    /// CSS pixel (the device pixel ratio for a plain `Page::screenshot`).
+    pub fn decode(bytes: &[u8], scale: f64) -> Result<Self> {
+        let image = image::load_from_memory(bytes)
+            .map_err(|e| Error::ScreenshotError(format!("Failed to decode screenshot: {e}")))?
+            .to_rgba8();
+        Ok(Self { image, scale, origin: (0.0, 0.0) })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.image.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.image.height()
+    }
+
+    /// Keep only `bounds`, e.g. an element's box, clipped to the image.
+    pub fn crop(mut self, bounds: BoundingBox) -> Self {
+        let (x, y, width, height) = self.pixels(bounds);
+        self.image = imageops::crop_imm(&self.image, x, y, width, height).to_image();
+        self.origin = (
+            self.origin.0 + x as f64 / self.scale,
+            self.origin.1 + y as f64 / self.scale,
+        );
+        self
+    }
+
+    /// Shrink to fit in `max_width` by `max_height` pixels, keeping the aspect
+    /// ratio. Never enlarges.
+    pub fn resize(mut self, max_width: u32, max_height: u32) -> Self {
+        let (width, height) = self.image.dimensions();
+        let factor = (max_width as f64 / width as f64)
+            .min(max_height as f64 / height as f64)
+            .min(1.0);
+        if factor < 1.0 {
+            let new_width = ((width as f64 * factor).round() as u32).max(1);
+            let new_height = ((height as f64 * factor).round() as u32).max(1);
+            self.image = imageops::resize(&self.image, new_width, new_height, FilterType::Triangle);
+            self.scale *= factor;
+        }
+        self
+    }
+
+    pub fn grayscale(mut self) -> Self {
+        self.image = DynamicImage::ImageRgba8(self.image).grayscale().to_rgba8();
+        self
+    }
+
+    /// Draw the outline of `bounds` in `color` (RGB).
+    pub fn outline(mut self, bounds: BoundingBox, color: [u8; 3]) -> Self {
+        let (x, y, width, height) = self.pixels(bounds);
+        let thickness = ((2.0 * self.scale).round() as u32).max(2);
+        let color = Rgba([color[0], color[1], color[2], 255]);
+        self.fill(x, y, width, thickness, color);
+        self.fill(x, (y + height).saturating_sub(thickness), width, thickness, color);
+        self.fill(x, y, thickness, height, color);
+        self.fill((x + width).saturating_sub(thickness), y, thickness, height, color);
+        self
+    }
+
+    /// Outline `bounds` and tag it with `label` (digits, letters and `-.:#`) at
+    /// its top left, for set-of-marks prompts that refer to elements by number.
+    pub fn annotate(self, bounds: BoundingBox, label: &str, color: [u8; 3]) -> Self {
+        let mut shot = self.outline(bounds, color);
+        if label.is_empty() {
+            return shot;
+        }
+        let dot = ((2.0 * shot.scale).round() as u32).max(1);
+        let (x, y, _, _) = shot.pixels(bounds);
+        let tag_width = (label.chars().count() as u32 * 6 + 1) * dot;
+        let tag_height = 9 * dot;
+        // Above the box if there is room, else inside it
+        let tag_y = if y >= tag_height { y - tag_height } else { y };
+        let background = Rgba([color[0], color[1], color[2], 255]);
+        shot.fill(x, tag_y, tag_width, tag_height, background);
+        let luma = 0.299 * color[0] as f64 + 0.587 * color[1] as f64 + 0.114 * color[2] as f64;
+        let ink = if luma > 150.0 { Rgba([0, 0, 0, 255]) } else { Rgba([255, 255, 255, 255]) };
+        for (i, c) in label.chars().enumerate() {
+            let left = x + (1 + 6 * i as u32) * dot;
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for col in 0..5 {
+                    if bits & (0x10 >> col) != 0 {
+                        shot.fill(left + col * dot, tag_y + (1 + row as u32) * dot, dot, dot, ink);
+                    }
+                }
+            }
+        }
+        shot
+    }
+
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.image
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .map_err(|e| Error::ScreenshotError(format!("Failed to encode PNG: {e}")))?;
+        Ok(bytes)
+    }
+
+    /// Encode as JPEG with `quality` from 1 to 100.
+    pub fn to_jpeg(&self, quality: u8) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        let rgb = DynamicImage::ImageRgba8(self.image.clone()).to_rgb8();
+        JpegEncoder::new_with_quality(&mut bytes, quality.clamp(1, 100))
+            .encode_image(&rgb)
+            .map_err(|e| Error::ScreenshotError(format!("Failed to encode JPEG: {e}")))?;
+        Ok(bytes)
+    }
+
+    /// `bounds` in image pixels as (x, y, width, height), clipped to the image.
+    fn pixels(&self, bounds: BoundingBox) -> (u32, u32, u32, u32) {
+        let (width, height) = self.image.dimensions();
+        let to_px = |css: f64, origin: f64, max: u32| {
+            ((css - origin) * self.scale).round().clamp(0.0, max as f64) as u32
+        };
+        let left = to_px(bounds.x, self.origin.0, width);
+        let top = to_px(bounds.y, self.origin.1, height);
+        let right = to_px(bounds.x + bounds.width, self.origin.0, width);
+        let bottom = to_px(bounds.y + bounds.height, self.origin.1, height);
+        (left, top, right - left, bottom - top)
+    }
+
+    /// Fill a rectangle of pixels, clipped to the image.
+    fn fill(&mut self, x: u32, y: u32, width: u32, height: u32, color: Rgba<u8>) {
+        let right = (x + width).min(self.image.width());
+        let bottom = (y + height).min(self.image.height());
+        for py in y..bottom {
+            for px in x..right {
+                self.image.put_pixel(px, py, color);
+            }
+        }
+    }
+}
+
+/// A 5x7 bitmap of `c`, one row per byte with the leftmost dot in bit 4; `?`
+/// for characters it doesn't have.
+fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        ' ' => [0x00; 7],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
@@ -0,0 +1,147 @@
+use crate::page::{BoundingBox, ViewportInfo};
+
+/// A clickable or typeable element, from `Page::interactive_elements`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct InteractiveElement {
+    /// The ARIA role: explicit, else implied by the tag (`link`, `button`,
+    /// `textbox`, `checkbox`, `combobox`, ...).
+    pub role: String,
+    /// The accessible name: label, `aria-label`, text, value or placeholder,
+    /// cut to 100 characters.
+    pub text: String,
+    /// A CSS selector matching only this element when listed.
+    pub selector: String,
+    pub bounds: BoundingBox,
+    /// Not disabled, by itself, a `<fieldset>` or `aria-disabled`.
+    pub enabled: bool,
+    /// Rendered with a non-empty box; it may still be scrolled out of view.
+    pub visible: bool,
+}
+
+/// A screenshot and the elements on it, from `Page::observe_visual`.
+#[derive(Debug, Clone)]
+pub struct VisualObservation {
+    /// PNG of the viewport.
+    pub screenshot: Vec<u8>,
+    /// The visible interactive elements in the viewport, in page order; an
+    /// element's label is its index here. The screenshot is not annotated.
+    pub elements: Vec<InteractiveElement>,
+    pub viewport: ViewportInfo,
+    /// Whether the elements and scroll position were the same right before and
+    /// right after the screenshot. False if the page kept changing through every
+    /// attempt; the boxes may then be off.
+    pub consistent: bool,
+}
+
+/// The elements and viewport read around a screenshot.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub(crate) struct ScreenState {
+    pub elements: Vec<InteractiveElement>,
+    pub viewport: ViewportInfo,
+}
+
+/// `(interactiveElements, selectorFor) => ScreenState`: the visible elements of
+/// `INTERACTIVE_ELEMENTS_JS` that are at least partly in the viewport, and the
+/// viewport itself, read in one go.
+pub(crate) const SCREEN_STATE_JS: &str = r#"(interactiveElements, selectorFor) => {
+    const width = window.innerWidth, height = window.innerHeight;
+    const elements = interactiveElements(selectorFor).filter(e => e.visible
+        && e.bounds.x < width && e.bounds.y < height
+        && e.bounds.x + e.bounds.width > 0 && e.bounds.y + e.bounds.height > 0);
+    return {
+        elements,
+        viewport: {
+            width,
+            height,
+            dpr: window.devicePixelRatio,
+            scroll_x: window.scrollX,
+            scroll_y: window.scrollY,
+            page_height: Math.max(
+                document.documentElement.scrollHeight,
+                document.body ? document.body.scrollHeight : 0,
+            ),
+        },
+    };
+}"#;
+
+/// `(element) => string`: a CSS selector matching only `element`, preferring
+/// its id, test ids, name or label over a path of tags from the body.
+pub(crate) const SELECTOR_FOR_JS: &str = r#"(el) => {
+    const unique = sel => {
+        try { return document.querySelectorAll(sel).length === 1; } catch (e) { return false; }
+    };
+    if (el.id && unique('#' + CSS.escape(el.id))) return '#' + CSS.escape(el.id);
+    for (const attr of ['data-testid', 'data-test', 'name', 'aria-label']) {
+        const v = el.getAttribute(attr);
+        if (!v) continue;
+        const sel = el.tagName.toLowerCase() + '[' + attr + '=' + JSON.stringify(v) + ']';
+        if (unique(sel)) return sel;
+    }
+    const parts = [];
+    for (let node = el; node && node.nodeType === 1 && node !== document.body; node = node.parentElement) {
+        if (node.id && unique('#' + CSS.escape(node.id))) {
+            parts.unshift('#' + CSS.escape(node.id));
+            return parts.join(' > ');
+        }
+        let part = node.tagName.toLowerCase();
+        const siblings = node.parentElement
+            ? [...node.parentElement.children].filter(c => c.tagName === node.tagName)
+            : [];
+        if (siblings.length > 1) part += ':nth-of-type(' + (siblings.indexOf(node) + 1) + ')';
+        parts.unshift(part);
+    }
+    return 'body > ' + parts.join(' > ');
+}"#;
+
+/// `(selectorFor) => InteractiveElement[]` for the links, buttons, form fields
+/// and elements with an interactive role in the document, in page order;
+/// `selectorFor` is `SELECTOR_FOR_JS`.
+pub(crate) const INTERACTIVE_ELEMENTS_JS: &str = r#"(selectorFor) => {
+    const SELECTOR = 'a[href], button, input:not([type=hidden]), select, textarea, summary, '
+        + '[contenteditable=""], [contenteditable=true], [onclick], '
+        + '[role=button], [role=link], [role=checkbox], [role=radio], [role=switch], [role=tab], '
+        + '[role=menuitem], [role=menuitemcheckbox], [role=menuitemradio], [role=option], '
+        + '[role=combobox], [role=textbox], [role=searchbox], [role=slider], [role=spinbutton]';
+    const INPUT_ROLES = {
+        checkbox: 'checkbox', radio: 'radio', range: 'slider', number: 'spinbutton', search: 'searchbox',
+        submit: 'button', button: 'button', reset: 'button', image: 'button', file: 'button',
+    };
+    const clean = (s, max) => (s || '').replace(/\s+/g, ' ').trim().substring(0, max);
+    const roleOf = el => {
+        const explicit = el.getAttribute('role');
+        if (explicit) return explicit.split(/\s+/)[0];
+        switch (el.tagName) {
+            case 'A': return 'link';
+            case 'BUTTON': case 'SUMMARY': return 'button';
+            case 'SELECT': return el.multiple || el.size > 1 ? 'listbox' : 'combobox';
+            case 'TEXTAREA': return 'textbox';
+            case 'INPUT': return INPUT_ROLES[(el.type || 'text').toLowerCase()] || 'textbox';
+        }
+        return el.isContentEditable ? 'textbox' : 'generic';
+    };
+    const nameOf = el => {
+        const labelledBy = (el.getAttribute('aria-labelledby') || '').split(/\s+/)
+            .map(id => document.getElementById(id)).filter(Boolean)
+            .map(l => l.innerText).join(' ');
+        const labels = Array.from(el.labels || []).map(l => l.innerText).join(' ');
+        const images = Array.from(el.querySelectorAll('img[alt]')).map(img => img.alt).join(' ');
+        const value = el.tagName === 'INPUT' && INPUT_ROLES[el.type] === 'button' ? el.value : '';
+        return clean(el.getAttribute('aria-label') || labelledBy || labels || el.innerText || images
+            || value || el.placeholder || el.title, 100);
+    };
+
+    return Array.from(document.querySelectorAll(SELECTOR)).map(el => {
+        const rect = el.getBoundingClientRect();
+        const rendered = typeof el.checkVisibility === 'function'
+            ? el.checkVisibility({ checkOpacity: true, checkVisibilityCSS: true })
+            : el.offsetParent !== null;
+        return {
+            role: roleOf(el),
+            text: nameOf(el),
+            selector: selectorFor(el),
+            bounds: { x: rect.x, y: rect.y, width: rect.width, height: rect.height },
+            enabled: !el.matches(':disabled') && !el.closest('[aria-disabled="true"]'),
+            visible: rendered && rect.width > 0 && rect.height > 0,
+        };
+    });
+}"#;
@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use base64::Engine;
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    self, AuthChallengeResponseResponse, AuthChallengeSource, ContinueWithAuthParams,
+    EnableParams, EventAuthRequired, EventRequestPaused, FailRequestParams, FulfillRequestParams,
+    GetResponseBodyParams, HeaderEntry, RequestPattern, RequestStage,
+};
+use chromiumoxide::cdp::browser_protocol::network::{ErrorReason, ResourceType};
+use chromiumoxide::cdp::browser_protocol::page::StopLoadingParams;
+use chromiumoxide::page::Page as CrPage;
+use chromiumoxide::Command;
+use futures::channel::mpsc;
+use futures::StreamExt;
+
+use crate::error::{Error, Result};
+use crate::events::{BackgroundError, BackgroundErrorPolicy, BrowserEvent, SharedHandlers};
+use crate::fixtures::{Fixture, NetworkFixtures};
+use crate::policy::NavigationPolicy;
+
+/// Map a resource type name ("image", "stylesheet", "font", "media", "script")
+/// to its CDP resource type. Unknown names return `None`.
+pub(crate) fn parse_resource_type(name: &str) -> Option<ResourceType> {
+    match name {
+        "image" => Some(ResourceType::Image),
+        "stylesheet" => Some(ResourceType::Stylesheet),
+        "font" => Some(ResourceType::Font),
+        "media" => Some(ResourceType::Media),
+        "script" => Some(ResourceType::Script),
+        _ => None,
+    }
+}
+
+/// Username/password pair (Arc to avoid per-event cloning).
+pub(crate) type Credentials = (Arc<str>, Arc<str>);
+
+/// Normalize an origin for credential lookup: lowercase, no trailing slash.
+pub(crate) fn normalize_origin(origin: &str) -> String {
+    origin.trim().trim_end_matches('/').to_ascii_lowercase()
+}
+
+/// Fetch-domain interception rules. The browser holds the rules every new page
+/// starts with; each page then owns a copy through its `Interceptor`.
+#[derive(Clone, Default)]
+pub(crate) struct InterceptRules {
+    /// Proxy credentials used to answer 407 challenges.
+    #[cfg(feature = "proxy-auth")]
+    pub proxy_auth: Option<Credentials>,
+    /// Server credentials used to answer 401 challenges, keyed by normalized origin.
+    pub origin_auth: HashMap<String, Credentials>,
+    /// Resource types that are failed with `BlockedByClient`.
+    pub blocked_resources: Vec<ResourceType>,
+    /// Domain restrictions; disallowed requests are failed with `BlockedByClient`.
+    pub navigation_policy: Option<Arc<NavigationPolicy>>,
+    /// Fail requests other than `GET`, `HEAD` and `OPTIONS` with `BlockedByClient`
+    /// (`ReadOnly`, unless `allow_writes`).
+    pub block_writes: bool,
+    /// Record responses to, or replay them from, a fixture directory.
+    pub fixtures: Option<Arc<NetworkFixtures>>,
+}
+
+impl InterceptRules {
+    /// Whether any rule requires the Fetch domain to be enabled.
+    pub fn is_empty(&self) -> bool {
+        !self.handles_auth()
+            && self.blocked_resources.is_empty()
+            && self.navigation_policy.is_none()
+            && !self.block_writes
+            && self.fixtures.is_none()
+    }
+
+    fn handles_auth(&self) -> bool {
+        #[cfg(feature = "proxy-auth")]
+        if self.proxy_auth.is_some() {
+            return true;
+        }
+        !self.origin_auth.is_empty()
+    }
+
+    /// Whether a paused request must be failed rather than continued.
+    fn should_block(&self, event: &EventRequestPaused) -> bool {
+        if self.blocked_resources.contains(&event.resource_type) {
+            return true;
+        }
+        if self.block_writes && !matches!(event.request.method.as_str(), "GET" | "HEAD" | "OPTIONS") {
+            return true;
+        }
+        match self.navigation_policy {
+            Some(ref policy) => {
+                let applies = policy.restricts_subresources()
+                    || event.resource_type == ResourceType::Document;
+                applies && !policy.is_allowed(&event.request.url)
+            }
+            None => false,
+        }
+    }
+
+    /// Credentials configured for an auth challenge, if any.
+    fn credentials_for(&self, event: &EventAuthRequired) -> Option<Credentials> {
+        let challenge = &event.auth_challenge;
+        match challenge.source {
+            #[cfg(feature = "proxy-auth")]
+            Some(AuthChallengeSource::Proxy) => self.proxy_auth.clone(),
+            #[cfg(not(feature = "proxy-auth"))]
+            Some(AuthChallengeSource::Proxy) => None,
+            _ => self
+                .origin_auth
+                .get(&normalize_origin(&challenge.origin))
+                .cloned(),
+        }
+    }
+
+    fn replays(&self) -> bool {
+        self.fixtures.as_ref().is_some_and(|f| f.is_replay())
+    }
+
+    fn records(&self) -> bool {
+        self.fixtures.as_ref().is_some_and(|f| f.is_record())
+    }
+
+    /// `Fetch.enable` parameters for the current rules.
+    fn enable_params(&self) -> EnableParams {
+        // Auth challenges can arrive for any request, and the navigation policy,
+        // write blocking and fixture replay have to see every request, so any of
+        // them pauses all requests.
+        // Otherwise only pause the resource types we are going to block.
+        let mut enable = EnableParams::builder();
+        if self.handles_auth() {
+            enable = enable.handle_auth_requests(true);
+        }
+        let pause_all =
+            self.handles_auth() || self.navigation_policy.is_some() || self.block_writes || self.replays();
+        let mut patterns: Vec<RequestPattern> = if pause_all {
+            vec![RequestPattern::builder().url_pattern("*").build()]
+        } else {
+            self.blocked_resources
+                .iter()
+                .map(|rt| RequestPattern::builder().resource_type(rt.clone()).build())
+                .collect()
+        };
+        // Recording additionally pauses every response, once its headers are in
+        if self.records() {
+            patterns.push(
+                RequestPattern::builder()
+                    .url_pattern("*")
+                    .request_stage(RequestStage::Response)
+                    .build(),
+            );
+        }
+        enable.patterns(patterns).build()
+    }
+}
+
+/// How long an answered auth challenge is remembered; a challenge for rejected
+/// credentials comes back within one round trip.
+const AUTH_RETRY_WINDOW: Duration = Duration::from_secs(60);
+
+/// Owns the Fetch domain of a single page.
+///
+/// Proxy auth, origin auth, resource blocking and the navigation policy share a
+/// single `Fetch.enable` call, since a second `Fetch.enable` on the same page
+/// replaces the first. Rules can change after the page is created: `apply`
+/// re-issues `Fetch.enable` and the running listeners read the new rules.
+///
+/// Commands that fail while answering paused requests are reported as
+/// `BackgroundError`s, handled according to `policy`.
+pub(crate) struct Interceptor {
+    page: CrPage,
+    rules: RwLock<InterceptRules>,
+    listening: tokio::sync::Mutex<bool>,
+    policy: BackgroundErrorPolicy,
+    handlers: Arc<SharedHandlers>,
+    subscribers: Mutex<Vec<mpsc::UnboundedSender<BackgroundError>>>,
+    /// The error that failed the page and that the next navigation check
+    /// reports, with `BackgroundErrorPolicy::FailPage`.
+    failure: Mutex<Option<BackgroundError>>,
+}
+
+impl Interceptor {
+    pub fn new(
+        page: CrPage,
+        rules: InterceptRules,
+        policy: BackgroundErrorPolicy,
+        handlers: Arc<SharedHandlers>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            page,
+            rules: RwLock::new(rules),
+            listening: tokio::sync::Mutex::new(false),
+            policy,
+            handlers,
+            subscribers: Mutex::default(),
+            failure: Mutex::default(),
+        })
+    }
+
+    /// The page this interceptor belongs to.
+    pub fn page(&self) -> &CrPage {
+        &self.page
+    }
+
+    /// Change the rules. Takes effect on the next `apply`.
+    pub fn update(&self, f: impl FnOnce(&mut InterceptRules)) {
+        let mut rules = self.rules.write().unwrap_or_else(|e| e.into_inner());
+        f(&mut rules);
+    }
+
+    fn rules(&self) -> InterceptRules {
+        self.rules.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Fail if the navigation policy denies `url`, before any request is made:
+    /// not every scheme goes through request interception.
+    pub fn check_navigation(&self, url: &str) -> Result<()> {
+        let rules = self.rules.read().unwrap_or_else(|e| e.into_inner());
+        match rules.navigation_policy {
+            Some(ref policy) if !policy.is_allowed(url) => Err(Error::NavigationError(format!(
+                "Navigation to {url} is not allowed by the navigation policy"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Receive every `BackgroundError` from now on.
+    pub fn subscribe(&self) -> mpsc::UnboundedReceiver<BackgroundError> {
+        let (tx, rx) = mpsc::unbounded();
+        self.subscribers.lock().unwrap_or_else(|e| e.into_inner()).push(tx);
+        rx
+    }
+
+    /// Fail if a background error failed the page (`BackgroundErrorPolicy::FailPage`).
+    /// The error is reported once; the page can navigate again afterwards.
+    pub fn check(&self) -> Result<()> {
+        match self.failure.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            Some(error) => Err(Error::NavigationError(format!("Request interception failed: {error}"))),
+            None => Ok(()),
+        }
+    }
+
+    /// Run a command answering the paused request for `url`, reporting failures.
+    async fn answer<C: Command>(&self, url: &str, params: C) {
+        let command = params.identifier().to_string();
+        if let Err(e) = self.page.execute(params).await {
+            self.report(command, url, e).await;
+        }
+    }
+
+    async fn report(&self, command: String, url: &str, message: impl Display) {
+        let error = BackgroundError {
+            target_id: self.page.target_id().inner().clone(),
+            command,
+            url: url.to_string(),
+            message: message.to_string(),
+        };
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|tx| tx.unbounded_send(error.clone()).is_ok());
+        match self.policy {
+            BackgroundErrorPolicy::Log => log::warn!("Request interception: {error}"),
+            BackgroundErrorPolicy::Emit => self.handlers.emit(BrowserEvent::BackgroundError(error)),
+            BackgroundErrorPolicy::FailPage => {
+                let first = {
+                    let mut failure = self.failure.lock().unwrap_or_else(|e| e.into_inner());
+                    let first = failure.is_none();
+                    if first {
+                        *failure = Some(error);
+                    }
+                    first
+                };
+                if first {
+                    // Unblock a navigation waiting on the unanswered request
+                    let _ = self.page.execute(StopLoadingParams::default()).await;
+                }
+            }
+        }
+    }
+
+    /// Enable the Fetch domain for the current rules, subscribing the listeners
+    /// on first use. Does nothing while there are no rules.
+    pub async fn apply(self: &Arc<Self>) -> Result<()> {
+        let rules = self.rules();
+        if rules.is_empty() {
+            return Ok(());
+        }
+
+        let mut listening = self.listening.lock().await;
+        if !*listening {
+            self.listen().await?;
+            *listening = true;
+        }
+
+        // Now enable fetch domain — listeners are already subscribed
+        self.page
+            .execute(rules.enable_params())
+            .await
+            .map_err(|e| Error::LaunchError(format!("Failed to enable fetch interception: {e}")))?;
+        Ok(())
+    }
+
+    /// Set up event listeners. Must run BEFORE enabling the fetch domain to avoid
+    /// a race condition.
+    async fn listen(self: &Arc<Self>) -> Result<()> {
+        let mut pause_events = self
+            .page
+            .event_listener::<EventRequestPaused>()
+            .await
+            .map_err(|e| Error::LaunchError(format!("Failed to listen for request paused events: {e}")))?;
+        let mut auth_events = self
+            .page
+            .event_listener::<EventAuthRequired>()
+            .await
+            .map_err(|e| Error::LaunchError(format!("Failed to listen for auth events: {e}")))?;
+
+        // Listen for auth challenges and respond with credentials
+        let this = Arc::clone(self);
+        tokio::spawn(async move {
+            // A second challenge for the same request means the credentials were
+            // rejected; cancel instead of retrying them forever. That ends the
+            // request, as does accepting the credentials, after which the entry
+            // expires.
+            let mut answered = HashMap::new();
+            while let Some(event) = auth_events.next().await {
+                answered.retain(|_, at: &mut Instant| at.elapsed() < AUTH_RETRY_WINDOW);
+                let credentials = match answered.remove(&event.request_id) {
+                    Some(_) => None,
+                    None => this.rules().credentials_for(&event),
+                };
+                if credentials.is_some() {
+                    answered.insert(event.request_id.clone(), Instant::now());
+                }
+                let builder = match credentials {
+                    Some((username, password)) => fetch::AuthChallengeResponse::builder()
+                        .response(AuthChallengeResponseResponse::ProvideCredentials)
+                        .username(username.as_ref())
+                        .password(password.as_ref()),
+                    None => fetch::AuthChallengeResponse::builder()
+                        .response(AuthChallengeResponseResponse::CancelAuth),
+                };
+                let url = &event.request.url;
+                let auth_response = match builder.build() {
+                    Ok(r) => r,
+                    Err(e) => {
+                        this.report(ContinueWithAuthParams::IDENTIFIER.to_string(), url, e).await;
+                        continue;
+                    }
+                };
+                let params = ContinueWithAuthParams::new(
+                    event.request_id.clone(),
+                    auth_response,
+                );
+                this.answer(url, params).await;
+            }
+        });
+
+        // Fail blocked requests, serve or record fixtures, continue everything else
+        let this = Arc::clone(self);
+        tokio::spawn(async move {
+            while let Some(event) = pause_events.next().await {
+                let rules = this.rules();
+                let url = &event.request.url;
+                let at_response =
+                    event.response_status_code.is_some() || event.response_error_reason.is_some();
+                if at_response {
+                    if let Some(fixtures) = rules.fixtures.as_ref().filter(|f| f.is_record()) {
+                        this.record(fixtures, &event).await;
+                    }
+                    let params = fetch::ContinueRequestParams::new(event.request_id.clone());
+                    this.answer(url, params).await;
+                } else if rules.should_block(&event) {
+                    let params = FailRequestParams::new(
+                        event.request_id.clone(),
+                        ErrorReason::BlockedByClient,
+                    );
+                    this.answer(url, params).await;
+                } else if let Some(fixtures) = rules.fixtures.as_ref().filter(|f| f.is_replay()) {
+                    this.replay(fixtures, &event).await;
+                } else {
+                    let params = fetch::ContinueRequestParams::new(event.request_id.clone());
+                    this.answer(url, params).await;
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Save the paused response of `event` as a fixture. Errors are ignored so a
+    /// failed write never breaks the page.
+    async fn record(&self, fixtures: &NetworkFixtures, event: &EventRequestPaused) {
+        let Some(status) = event.response_status_code else {
+            return;
+        };
+        // Redirects and failed loads have no body
+        let (body, base64_encoded) = match self
+            .page
+            .execute(GetResponseBodyParams::new(event.request_id.clone()))
+            .await
+        {
+            Ok(response) => (response.result.body.clone(), response.result.base64_encoded),
+            Err(_) => (String::new(), false),
+        };
+        let headers = event
+            .response_headers
+            .iter()
+            .flatten()
+            .map(|h| (h.name.clone(), h.value.clone()))
+            .collect();
+        let fixture = Fixture::new(
+            event.request.method.clone(),
+            event.request.url.clone(),
+            &request_body(event),
+            status as u16,
+            headers,
+            body,
+            base64_encoded,
+        );
+        let _ = fixtures.save(&fixture);
+    }
+
+    /// Fulfill the paused request of `event` from its fixture, or fail it.
+    async fn replay(&self, fixtures: &NetworkFixtures, event: &EventRequestPaused) {
+        let Some(fixture) = fixtures.load(&event.request.method, &event.request.url, &request_body(event)) else {
+            let params = FailRequestParams::new(
+                event.request_id.clone(),
+                ErrorReason::InternetDisconnected,
+            );
+            self.answer(&event.request.url, params).await;
+            return;
+        };
+        let mut params = FulfillRequestParams::new(event.request_id.clone(), fixture.status);
+        // The recorded body is already decoded, so the original encoding and
+        // length no longer describe it
+        params.response_headers = Some(
+            fixture
+                .headers
+                .into_iter()
+                .filter(|(name, _)| {
+                    !name.eq_ignore_ascii_case("content-encoding") && !name.eq_ignore_ascii_case("content-length")
+                })
+                .map(|(name, value)| HeaderEntry::new(name, value))
+                .collect(),
+        );
+        params.body = Some(fixture.body.into());
+        self.answer(&event.request.url, params).await;
+    }
+}
+
+/// The body of a paused request (e.g. of a POST), empty if it has none.
+fn request_body(event: &EventRequestPaused) -> Vec<u8> {
+    event
+        .request
+        .post_data_entries
+        .iter()
+        .flatten()
+        .filter_map(|entry| entry.bytes.as_ref())
+        .filter_map(|bytes| base64::engine::general_purpose::STANDARD.decode(AsRef::<str>::as_ref(bytes)).ok())
+        .flatten()
+        .collect()
+}
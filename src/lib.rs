@@ -1,11 +1,130 @@
+pub mod action;
+pub mod agent;
+pub mod approval;
+pub mod assertions;
+mod archive;
+#[cfg(feature = "extraction")]
+pub mod autofill;
+pub mod batch;
+pub mod blocking;
+#[cfg(feature = "low-resource")]
+pub mod blocklist;
 pub mod browser;
+pub mod capture;
+#[cfg(feature = "cdp-log")]
+pub mod cdp_log;
+pub mod certs;
 pub mod config;
+#[cfg(feature = "extraction")]
+pub mod describe;
+pub mod detect;
+pub mod download;
+pub mod drag;
 pub mod element;
 pub mod error;
+pub mod events;
+pub mod features;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fetch;
+pub mod fixtures;
+pub mod graphql;
+pub mod honeypot;
+pub mod identity;
+#[cfg(feature = "image")]
+pub mod imaging;
+pub mod interactive;
+mod intercept;
+mod lifecycle;
+pub mod locator;
+pub mod login;
+pub mod metrics;
+pub mod navigation;
+#[cfg(feature = "ocr")]
+pub mod ocr;
 pub mod page;
+pub mod policy;
+pub mod profiles;
+pub mod ratelimit;
+pub mod readonly;
+pub mod recorder;
+pub mod redact;
+pub mod regions;
+pub mod scenario;
+pub mod snapshot;
+#[cfg(feature = "stealth")]
 pub mod stealth;
+mod style;
+pub mod telemetry;
+#[cfg(feature = "test-server")]
+pub mod test_server;
+pub mod tools;
+#[cfg(feature = "totp")]
+pub mod totp;
+pub mod validation;
+#[cfg(feature = "visual")]
+pub mod visual;
+pub mod watchdog;
+pub mod xvfb;
 
-pub use browser::AgenticBrowser;
-pub use config::{BrowserBuilder, BrowserConfig, ProxyConfig};
+pub use action::{Action, ActionResult};
+pub use agent::{
+    AgentDecision, AgentObservation, AgentOutcome, AgentRunner, LlmPolicy, StopReason,
+    TranscriptEntry,
+};
+pub use approval::{Approval, ApprovalPolicy, ApprovalRequest, ApproveAll, RiskRules};
+#[cfg(feature = "extraction")]
+pub use autofill::{
+    AutofillReport, FillProfile, FilledField, ProfileField, SkipReason, SkippedField,
+};
+pub use batch::{MapOptions, UrlResult};
+pub use blocking::Blocking;
+pub use browser::{AgenticBrowser, BrowserVersion};
+pub use capture::CapturedResponse;
+pub use certs::ClientCertificate;
+pub use config::{
+    BrowserBuilder, BrowserConfig, FakeMedia, HeadlessMode, NetworkThrottle, ProxyConfig, SecureDns,
+    SecureDnsMode, WindowBounds, WindowState,
+};
+#[cfg(feature = "extraction")]
+pub use describe::TokenEstimator;
+pub use download::DownloadProgress;
+pub use drag::DragTarget;
+pub use element::SelectOption;
 pub use error::{Error, Result};
-pub use page::{ElementData, FormField, Page};
+pub use events::{BackgroundError, BackgroundErrorPolicy, BrowserEvent, EventKind};
+pub use features::{Feature, FeatureReport, FeatureStatus};
+pub use fetch::{FetchRequest, FetchResponse, FormPart};
+pub use fixtures::NetworkFixtures;
+pub use graphql::GraphqlOperation;
+pub use honeypot::{FillFormReport, SkippedTrap, TrapReason};
+pub use identity::{Identity, PlatformProfile, RequestIdentity};
+#[cfg(feature = "image")]
+pub use imaging::Screenshot;
+pub use interactive::{InteractiveElement, VisualObservation};
+pub use locator::{By, ElementState, Locator, Role, WaitOptions};
+pub use login::{LoginChallenge, LoginHints, LoginOutcome, LoginSpec};
+pub use metrics::{MemoryUsage, PageMetrics, PerformanceTimings, RequestTiming};
+pub use navigation::{GotoOptions, History, HistoryEntry, WaitUntil};
+#[cfg(feature = "ocr")]
+pub use ocr::{OcrOptions, OcrText};
+pub use page::{BoundingBox, ElementData, FittedScreenshot, FormField, FormTarget, Observation, Page, ViewportInfo};
+pub use policy::NavigationPolicy;
+pub use profiles::{Profile, ProfileBundle, ProfileContext, ProfileManager, StoredCookie};
+pub use ratelimit::{RateLimit, RateLimiter, RatePermit};
+pub use readonly::ReadOnly;
+pub use recorder::{ActionRecorder, ActionScript};
+pub use redact::Redaction;
+pub use regions::{PageRegion, RegionKind};
+pub use scenario::{Scenario, ScenarioReport, ScenarioStep, StepAction};
+pub use snapshot::{ExtractOptions, ExtractionBackend, TreeOptions};
+pub use tools::ToolSchema;
+#[cfg(feature = "test-server")]
+pub use test_server::TestServer;
+#[cfg(feature = "totp")]
+pub use totp::{Totp, TotpAlgorithm};
+pub use validation::{ValidationError, ValidationSource};
+#[cfg(feature = "visual")]
+pub use visual::{compare_screenshots, compare_screenshots_with, DiffOptions, DiffResult, Region};
+pub use watchdog::{BudgetAction, MemoryBudget};
+pub use xvfb::Xvfb;
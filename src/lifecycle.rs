@@ -0,0 +1,27 @@
+/// Makes the document report itself visible (`visibilityState`, `hidden`) and
+/// hides `visibilitychange` events from the page, so sites don't pause their
+/// polling and rendering in a background tab. Running it again on a document
+/// re-enables it; `RELEASE_VISIBLE_JS` turns it off.
+pub(crate) const KEEP_VISIBLE_JS: &str = r#"(() => {
+    const key = Symbol.for('agentic.keepVisible');
+    if (document[key]) { document[key](true); return; }
+    let on = true;
+    const proto = Document.prototype;
+    const spoofed = [['visibilityState', 'visible'], ['hidden', false],
+        ['webkitVisibilityState', 'visible'], ['webkitHidden', false]];
+    for (const [name, value] of spoofed) {
+        const original = Object.getOwnPropertyDescriptor(proto, name);
+        if (!original || !original.get) continue;
+        Object.defineProperty(proto, name, {
+            ...original,
+            get() { return on ? value : original.get.call(this); },
+        });
+    }
+    // The event bubbles to window; capturing there runs before any page listener
+    window.addEventListener('visibilitychange', e => { if (on) e.stopImmediatePropagation(); }, true);
+    Object.defineProperty(document, key, { value: enabled => { on = enabled; } });
+})()"#;
+
+/// Turns `KEEP_VISIBLE_JS` off in the current document.
+pub(crate) const RELEASE_VISIBLE_JS: &str =
+    "(() => { const release = document[Symbol.for('agentic.keepVisible')]; if (release) release(false); })()";
@@ -0,0 +1,561 @@
+use std::future::Future;
+use std::time::Duration;
+
+use chromiumoxide::cdp::js_protocol::runtime::{
+    EvaluateParams, GetPropertiesParams, ReleaseObjectParams, RemoteObjectId, RemoteObjectSubtype,
+};
+use chromiumoxide::page::Page as CrPage;
+use tokio::time::Instant;
+
+use crate::element::Element;
+use crate::error::{Error, Result};
+
+/// ARIA roles understood by `Page::get_by_role`. Implicit roles of native
+/// elements are resolved too (e.g. `<button>` is a `Button`, `<a href>` a `Link`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+    Alert,
+    Article,
+    Banner,
+    Button,
+    Cell,
+    Checkbox,
+    Combobox,
+    Complementary,
+    ContentInfo,
+    Dialog,
+    Form,
+    Heading,
+    Img,
+    Link,
+    List,
+    ListItem,
+    Listbox,
+    Main,
+    Menu,
+    MenuItem,
+    Navigation,
+    Option,
+    Radio,
+    Region,
+    Row,
+    Searchbox,
+    Slider,
+    Switch,
+    Tab,
+    Table,
+    Tabpanel,
+    Textbox,
+}
+
+impl Role {
+    /// The ARIA role name, e.g. `"button"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Alert => "alert",
+            Role::Article => "article",
+            Role::Banner => "banner",
+            Role::Button => "button",
+            Role::Cell => "cell",
+            Role::Checkbox => "checkbox",
+            Role::Combobox => "combobox",
+            Role::Complementary => "complementary",
+            Role::ContentInfo => "contentinfo",
+            Role::Dialog => "dialog",
+            Role::Form => "form",
+            Role::Heading => "heading",
+            Role::Img => "img",
+            Role::Link => "link",
+            Role::List => "list",
+            Role::ListItem => "listitem",
+            Role::Listbox => "listbox",
+            Role::Main => "main",
+            Role::Menu => "menu",
+            Role::MenuItem => "menuitem",
+            Role::Navigation => "navigation",
+            Role::Option => "option",
+            Role::Radio => "radio",
+            Role::Region => "region",
+            Role::Row => "row",
+            Role::Searchbox => "searchbox",
+            Role::Slider => "slider",
+            Role::Switch => "switch",
+            Role::Tab => "tab",
+            Role::Table => "table",
+            Role::Tabpanel => "tabpanel",
+            Role::Textbox => "textbox",
+        }
+    }
+}
+
+/// A way of finding elements on the page.
+#[derive(Debug, Clone)]
+pub enum By {
+    /// CSS selector.
+    Css(String),
+    /// ARIA role (explicit or implicit), optionally filtered by accessible name
+    /// (case-insensitive substring match). Hidden elements are skipped.
+    Role { role: Role, name: Option<String> },
+    /// Form control whose label text contains the given string.
+    Label(String),
+    /// Input or textarea whose placeholder contains the given string.
+    Placeholder(String),
+    /// Element with `data-testid` equal to the given id.
+    TestId(String),
+}
+
+impl By {
+    /// Human-readable description, used in error messages.
+    pub fn describe(&self) -> String {
+        match self {
+            By::Css(selector) => selector.clone(),
+            By::Role { role, name: Some(name) } => format!("role={} name={:?}", role.as_str(), name),
+            By::Role { role, name: None } => format!("role={}", role.as_str()),
+            By::Label(text) => format!("label={text:?}"),
+            By::Placeholder(text) => format!("placeholder={text:?}"),
+            By::TestId(id) => format!("test id={id:?}"),
+        }
+    }
+
+    fn js_args(&self) -> (&'static str, &str, Option<&str>) {
+        match self {
+            By::Css(selector) => ("css", selector, None),
+            By::Role { role, name } => ("role", role.as_str(), name.as_deref()),
+            By::Label(text) => ("label", text, None),
+            By::Placeholder(text) => ("placeholder", text, None),
+            By::TestId(id) => ("testid", id, None),
+        }
+    }
+}
+
+/// In-page finder. Called as `(FIND_JS)(kind, arg, name)`; returns the matches
+/// in document order.
+const FIND_JS: &str = r#"function(kind, arg, name) {
+    const norm = s => (s || '').replace(/\s+/g, ' ').trim().toLowerCase();
+    const visible = el => typeof el.checkVisibility === 'function'
+        ? el.checkVisibility({ checkVisibilityCSS: true })
+        : el.offsetParent !== null;
+    const implicitRole = el => {
+        const tag = el.tagName.toLowerCase();
+        const type = (el.getAttribute('type') || '').toLowerCase();
+        switch (tag) {
+            case 'a': case 'area': return el.hasAttribute('href') ? 'link' : '';
+            case 'button': return 'button';
+            case 'input':
+                if (['button', 'submit', 'reset', 'image'].includes(type)) return 'button';
+                if (type === 'checkbox') return 'checkbox';
+                if (type === 'radio') return 'radio';
+                if (type === 'range') return 'slider';
+                if (type === 'search') return el.hasAttribute('list') ? 'combobox' : 'searchbox';
+                if (type === 'hidden') return '';
+                return el.hasAttribute('list') ? 'combobox' : 'textbox';
+            case 'textarea': return 'textbox';
+            case 'select': return (el.multiple || el.size > 1) ? 'listbox' : 'combobox';
+            case 'option': return 'option';
+            case 'h1': case 'h2': case 'h3': case 'h4': case 'h5': case 'h6': return 'heading';
+            case 'img': return el.getAttribute('alt') === '' ? '' : 'img';
+            case 'nav': return 'navigation';
+            case 'main': return 'main';
+            case 'form': return 'form';
+            case 'dialog': return 'dialog';
+            case 'article': return 'article';
+            case 'aside': return 'complementary';
+            case 'header': return el.closest('article,aside,main,nav,section') ? '' : 'banner';
+            case 'footer': return el.closest('article,aside,main,nav,section') ? '' : 'contentinfo';
+            case 'section': return el.hasAttribute('aria-label') || el.hasAttribute('aria-labelledby') ? 'region' : '';
+            case 'ul': case 'ol': return 'list';
+            case 'li': return 'listitem';
+            case 'table': return 'table';
+            case 'tr': return 'row';
+            case 'td': case 'th': return 'cell';
+            default: return '';
+        }
+    };
+    const role = el => (el.getAttribute('role') || '').trim().split(/\s+/)[0] || implicitRole(el);
+    const labelText = el => {
+        const parts = [];
+        if (el.labels) for (const l of el.labels) parts.push(l.innerText);
+        const by = el.getAttribute('aria-labelledby');
+        if (by) for (const id of by.split(/\s+/)) {
+            const ref = document.getElementById(id);
+            if (ref) parts.push(ref.innerText);
+        }
+        if (el.getAttribute('aria-label')) parts.push(el.getAttribute('aria-label'));
+        return norm(parts.join(' '));
+    };
+    const accessibleName = el => {
+        const label = labelText(el);
+        if (label) return label;
+        const tag = el.tagName.toLowerCase();
+        if (tag === 'input' && ['button', 'submit', 'reset'].includes(el.type)) return norm(el.value);
+        if (tag === 'img' || (tag === 'input' && el.type === 'image')) return norm(el.getAttribute('alt'));
+        if (['input', 'textarea', 'select'].includes(tag)) {
+            return norm(el.getAttribute('title') || el.getAttribute('placeholder'));
+        }
+        return norm(el.innerText || el.textContent || el.getAttribute('title'));
+    };
+
+    const needle = norm(arg);
+    let found;
+    if (kind === 'role') {
+        const wanted = norm(name);
+        found = Array.from(document.querySelectorAll('*')).filter(el =>
+            role(el) === arg && visible(el) && (!name || accessibleName(el).includes(wanted)));
+    } else if (kind === 'css') {
+        found = Array.from(document.querySelectorAll(arg));
+    } else if (kind === 'label') {
+        found = Array.from(document.querySelectorAll('input, textarea, select, [contenteditable], [role]'))
+            .filter(el => labelText(el).includes(needle));
+    } else if (kind === 'placeholder') {
+        found = Array.from(document.querySelectorAll('[placeholder]'))
+            .filter(el => norm(el.getAttribute('placeholder')).includes(needle));
+    } else {
+        found = Array.from(document.querySelectorAll('[data-testid]'))
+            .filter(el => el.getAttribute('data-testid') === arg);
+    }
+    return found;
+}"#;
+
+/// Hand the elements of a JS array held in the page (by its remote object id)
+/// over as handles, in array order, and release the array. Other items are skipped.
+pub(crate) async fn elements_of(page: &CrPage, array: RemoteObjectId) -> Result<Vec<Element>> {
+    let mut params = GetPropertiesParams::new(array.clone());
+    params.own_properties = Some(true);
+    let properties = page.execute(params).await;
+    let _ = page.execute(ReleaseObjectParams::new(array)).await;
+    let mut items: Vec<(usize, RemoteObjectId)> = properties
+        .map_err(|e| Error::JsError(e.to_string()))?
+        .result
+        .result
+        .into_iter()
+        .filter(|p| p.value.as_ref().is_some_and(|v| v.subtype == Some(RemoteObjectSubtype::Node)))
+        .filter_map(|p| Some((p.name.parse().ok()?, p.value?.object_id?)))
+        .collect();
+    items.sort_unstable_by_key(|(index, _)| *index);
+    Ok(items.into_iter().map(|(_, id)| Element::from_object(id, page.clone())).collect())
+}
+
+/// Evaluate `expression` and hand the elements of the array it returns over
+/// as handles (see `elements_of`).
+pub(crate) async fn evaluate_elements(page: &CrPage, expression: String) -> Result<Vec<Element>> {
+    let params = EvaluateParams::builder()
+        .expression(expression)
+        .return_by_value(false)
+        .build()
+        .map_err(Error::JsError)?;
+    let evaluated = page
+        .execute(params)
+        .await
+        .map_err(|e| Error::JsError(e.to_string()))?
+        .result;
+    if let Some(details) = evaluated.exception_details {
+        let message = details.exception.and_then(|e| e.description).unwrap_or(details.text);
+        return Err(Error::JsError(message));
+    }
+    match evaluated.result.object_id {
+        Some(array) => elements_of(page, array).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Find all elements matching `by`, in document order.
+pub(crate) async fn find_all(page: &CrPage, by: &By) -> Result<Vec<Element>> {
+    let (kind, arg, name) = by.js_args();
+    let args = serde_json::to_string(&(kind, arg, name)).map_err(|e| Error::JsError(e.to_string()))?;
+    // Serialized tuple is a JSON array: spread it into the call
+    evaluate_elements(page, format!("({FIND_JS})(...{args})")).await
+}
+
+/// Pause between the Tab presses of `Page::tab_to`, plus up to as much again at
+/// random, roughly a fast typist's pace.
+pub(crate) const TAB_PAUSE: Duration = Duration::from_millis(40);
+
+/// Number of elements Tab can stop at, bounding `Page::tab_to`.
+pub(crate) const TABBABLE_COUNT_JS: &str = r#"document.querySelectorAll(
+    'a[href], area[href], button, input, select, textarea, iframe, summary, [tabindex], [contenteditable]'
+).length"#;
+
+/// The focused element; null for none or `<body>`.
+pub(crate) const FOCUSED_JS: &str = r#"(() => {
+    const el = document.activeElement;
+    return el && el !== document.body && el !== document.documentElement ? el : null;
+})()"#;
+
+/// Find the first element matching `by`.
+pub(crate) async fn find_first(page: &CrPage, by: &By) -> Result<Element> {
+    find_all(page, by)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::ElementNotFound(by.describe()))
+}
+
+/// How often a `Locator` re-checks the page while waiting.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Run on a candidate element; returns "ok" or the reason it can't be acted on yet.
+const ACTIONABLE_JS: &str = r#"function(editable) {
+    if (!this.isConnected) return 'detached from the DOM';
+    const visible = typeof this.checkVisibility === 'function'
+        ? this.checkVisibility({ checkOpacity: true, checkVisibilityCSS: true })
+        : this.offsetParent !== null;
+    const rect = this.getBoundingClientRect();
+    if (!visible || rect.width === 0 || rect.height === 0) return 'not visible';
+    if (this.disabled || this.closest('fieldset:disabled') || this.getAttribute('aria-disabled') === 'true') {
+        return 'disabled';
+    }
+    if (editable) {
+        const tag = this.tagName;
+        const input = tag === 'INPUT' || tag === 'TEXTAREA' || tag === 'SELECT';
+        if ((input && this.readOnly) || (!input && !this.isContentEditable)) return 'not editable';
+    }
+    return 'ok';
+}"#;
+
+/// Element state `Page::wait_for_selector_with` waits for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ElementState {
+    /// In the DOM, rendered or not.
+    Attached,
+    /// In the DOM and rendered (see `Element::is_visible`).
+    #[default]
+    Visible,
+    /// Not rendered, or not in the DOM at all.
+    Hidden,
+}
+
+impl ElementState {
+    fn as_str(self) -> &'static str {
+        match self {
+            ElementState::Attached => "attached",
+            ElementState::Visible => "visible",
+            ElementState::Hidden => "hidden",
+        }
+    }
+}
+
+/// Settings for `Page::wait_for_selector_with`.
+#[derive(Debug, Clone, Default)]
+pub struct WaitOptions {
+    pub state: ElementState,
+    /// How long to wait (default: the page timeout).
+    pub timeout: Option<Duration>,
+    /// How often to re-check besides on DOM mutations, which miss visibility
+    /// changes from stylesheets or animations (default: 100ms).
+    pub poll: Option<Duration>,
+}
+
+impl WaitOptions {
+    pub fn new(state: ElementState) -> Self {
+        Self { state, ..Self::default() }
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn poll(mut self, poll: Duration) -> Self {
+        self.poll = Some(poll);
+        self
+    }
+
+    /// Arguments of `WAIT_FOR_JS`.
+    pub(crate) fn js_args<'a>(
+        &self,
+        selector: &'a str,
+        default_timeout: Duration,
+    ) -> (&'a str, &'static str, u64, u64) {
+        let timeout = self.timeout.unwrap_or(default_timeout);
+        let poll = self.poll.unwrap_or(POLL_INTERVAL).max(Duration::from_millis(10));
+        (selector, self.state.as_str(), timeout.as_millis() as u64, poll.as_millis() as u64)
+    }
+}
+
+/// Resolves once the first match of `sel` is in `state` ("attached", "visible" or
+/// "hidden"), checking on DOM mutations and every `pollMs`; rejects after
+/// `timeoutMs` with "Timeout waiting for selector".
+pub(crate) const WAIT_FOR_JS: &str = r#"(sel, state, timeoutMs, pollMs) => new Promise((resolve, reject) => {
+    const visible = el => {
+        const shown = typeof el.checkVisibility === 'function'
+            ? el.checkVisibility({ checkOpacity: true, checkVisibilityCSS: true })
+            : el.offsetParent !== null;
+        const rect = el.getBoundingClientRect();
+        return shown && rect.width > 0 && rect.height > 0;
+    };
+    const reached = () => {
+        const el = document.querySelector(sel);
+        if (state === 'attached') return !!el;
+        if (state === 'visible') return !!el && visible(el);
+        return !el || !visible(el);
+    };
+    if (reached()) { resolve(true); return; }
+    const finish = ok => {
+        observer.disconnect();
+        clearInterval(poll);
+        clearTimeout(timer);
+        if (ok) resolve(true);
+        else reject(new Error('Timeout waiting for selector: ' + sel));
+    };
+    const observer = new MutationObserver(() => { if (reached()) finish(true); });
+    observer.observe(document.documentElement, {
+        childList: true,
+        subtree: true,
+        attributes: true,
+        attributeFilter: ['class', 'id', 'style', 'hidden', 'open']
+    });
+    const poll = setInterval(() => { if (reached()) finish(true); }, pollMs);
+    const timer = setTimeout(() => finish(false), timeoutMs);
+})"#;
+
+/// What a `Locator` waits for before acting.
+#[derive(Debug, Clone, Copy)]
+enum Actionability {
+    /// Attached, visible and enabled.
+    Enabled,
+    /// Attached, visible, enabled and editable.
+    Editable,
+}
+
+/// Whether an error means the element went away between resolving and acting on
+/// it, or the document it was in is being replaced by a navigation.
+fn is_stale(err: &Error) -> bool {
+    let msg = err.to_string();
+    msg.contains("No node")
+        || msg.contains("Could not find node")
+        || msg.contains("not attached")
+        || msg.contains("detached")
+        || msg.contains("Cannot find context")
+        || msg.contains("Execution context was destroyed")
+        || msg.contains("Inspected target navigated or closed")
+}
+
+/// A lazily-resolved reference to an element on a page.
+///
+/// Unlike `Element`, a `Locator` doesn't hold a DOM node: it re-queries the page
+/// on every action, waits (up to its timeout) for the element to be visible and
+/// enabled before clicking or typing, and retries if the node goes stale mid-action.
+#[derive(Clone)]
+pub struct Locator {
+    page: CrPage,
+    by: By,
+    index: usize,
+    pub(crate) timeout: Duration,
+}
+
+impl Locator {
+    pub(crate) fn new(page: CrPage, by: By, timeout: Duration) -> Self {
+        Self { page, by, index: 0, timeout }
+    }
+
+    /// Target the n-th match (0-based) instead of the first.
+    pub fn nth(mut self, index: usize) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Override how long actions wait for the element (default: the page timeout).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Number of elements currently matching (does not wait).
+    pub async fn count(&self) -> Result<usize> {
+        Ok(find_all(&self.page, &self.by).await?.len())
+    }
+
+    /// Wait until the element is visible and enabled, then return a handle to it.
+    pub async fn element(&self) -> Result<Element> {
+        self.act(Actionability::Enabled, |el| async move { Ok(el) }).await
+    }
+
+    /// Wait until the element is actionable, then click it.
+    pub async fn click(&self) -> Result<()> {
+        self.act(Actionability::Enabled, |el| async move { el.click().await }).await
+    }
+
+    /// Wait until the element is actionable, then hover over it.
+    pub async fn hover(&self) -> Result<()> {
+        self.act(Actionability::Enabled, |el| async move { el.hover().await }).await
+    }
+
+    /// Wait until the element is editable, then type text into it (appends).
+    pub async fn type_text(&self, text: &str) -> Result<()> {
+        self.act(Actionability::Editable, |el| async move {
+            el.click().await?;
+            el.type_text(text).await
+        })
+        .await
+    }
+
+    /// Wait until the element is editable, then replace its value with `text`.
+    /// See `Element::fill`.
+    pub async fn fill(&self, text: &str) -> Result<()> {
+        self.act(Actionability::Editable, |el| async move { el.fill(text).await }).await
+    }
+
+    /// Wait until the element is visible, then return its inner text.
+    pub async fn inner_text(&self) -> Result<String> {
+        self.act(Actionability::Enabled, |el| async move { el.inner_text().await }).await
+    }
+
+    /// The element matching now, without waiting or checking its state.
+    pub(crate) async fn current(&self) -> Result<Element> {
+        find_all(&self.page, &self.by)
+            .await?
+            .into_iter()
+            .nth(self.index)
+            .ok_or_else(|| Error::ElementNotFound(self.by.describe()))
+    }
+
+    /// Resolve the target element and check it is actionable. The inner `Err`
+    /// carries the reason it isn't (yet); the outer one an error waiting won't
+    /// fix, such as an invalid selector.
+    async fn resolve(&self, check: Actionability) -> Result<std::result::Result<Element, String>> {
+        let found = match find_all(&self.page, &self.by).await {
+            Ok(found) => found,
+            Err(e @ Error::JsError(_)) if !is_stale(&e) => return Err(e),
+            Err(e) => return Ok(Err(e.to_string())),
+        };
+        let Some(el) = found.into_iter().nth(self.index) else {
+            return Ok(Err("no matching element".to_string()));
+        };
+        let editable = matches!(check, Actionability::Editable);
+        let state = el
+            .call_js(&format!("function() {{ return ({ACTIONABLE_JS}).call(this, {editable}); }}"))
+            .await;
+        Ok(match state {
+            Ok(state) => match state.as_str() {
+                Some("ok") => Ok(el),
+                Some(reason) => Err(reason.to_string()),
+                None => Err("could not determine element state".to_string()),
+            },
+            Err(e) => Err(e.to_string()),
+        })
+    }
+
+    /// Wait for the element to become actionable and run `action` on it,
+    /// retrying if the element goes stale, until the timeout elapses.
+    async fn act<T, F, Fut>(&self, check: Actionability, action: F) -> Result<T>
+    where
+        F: Fn(Element) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let reason = match self.resolve(check).await? {
+                Ok(el) => match action(el).await {
+                    Ok(value) => return Ok(value),
+                    Err(e) if is_stale(&e) => e.to_string(),
+                    Err(e) => return Err(e),
+                },
+                Err(reason) => reason,
+            };
+            if Instant::now() >= deadline {
+                return Err(Error::Timeout(format!("{} ({reason})", self.by.describe())));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+
+/// Credentials and optional hints for `Page::login`.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct LoginSpec {
+    pub username: String,
+    pub password: String,
+    #[serde(default)]
+    pub hints: LoginHints,
+}
+
+impl LoginSpec {
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            password: password.into(),
+            hints: LoginHints::default(),
+        }
+    }
+
+    pub fn hints(mut self, hints: LoginHints) -> Self {
+        self.hints = hints;
+        self
+    }
+}
+
+/// Overrides for sites the field discovery gets wrong. Selectors are CSS.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct LoginHints {
+    pub username_selector: Option<String>,
+    pub password_selector: Option<String>,
+    /// Button that submits each step; otherwise a submit/"Next"/"Log in" button
+    /// is looked for, falling back to pressing Enter.
+    pub submit_selector: Option<String>,
+    /// Element that only appears once logged in (e.g. an account menu). Without
+    /// it, login counts as successful once the login form is gone.
+    pub success_selector: Option<String>,
+    /// One-time code field, for `Page::submit_otp`.
+    pub otp_selector: Option<String>,
+    /// How long to wait for each screen (default: the page's default timeout).
+    pub timeout: Option<Duration>,
+}
+
+/// Extra verification a site asked for after the credentials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginChallenge {
+    /// A 2FA / one-time code field (authenticator app, SMS, email).
+    OneTimeCode,
+    /// A reCAPTCHA, hCaptcha or Turnstile widget.
+    Captcha,
+}
+
+/// Result of `Page::login`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(tag = "outcome", content = "detail", rename_all = "snake_case")]
+pub enum LoginOutcome {
+    Success,
+    /// The credentials were accepted, or not yet checked, but the site wants more.
+    Challenge(LoginChallenge),
+    /// The site rejected the login; holds its error message, or why no outcome
+    /// could be determined.
+    Failure(String),
+}
+
+/// What the login helper sees on the current screen, with the paths of the
+/// controls it found so they can be addressed by `selector`.
+#[derive(Debug, Default, serde::Deserialize)]
+pub(crate) struct LoginProbe {
+    /// The document has finished loading.
+    pub ready: bool,
+    pub username: bool,
+    pub username_value: String,
+    pub password: bool,
+    pub submit: bool,
+    pub otp: bool,
+    /// The code is entered one digit per box.
+    #[cfg(feature = "totp")]
+    pub otp_split: bool,
+    pub captcha: bool,
+    pub error: Option<String>,
+    pub success: bool,
+    /// Selectors of the controls found (`:root > :nth-child(n) > ...` paths), by
+    /// role ("username", "password", "otp", "submit").
+    pub controls: HashMap<String, String>,
+}
+
+impl LoginProbe {
+    /// Selector of the control found for `role`.
+    pub(crate) fn selector(&self, role: &str) -> Result<&str> {
+        self.controls
+            .get(role)
+            .map(String::as_str)
+            .ok_or_else(|| Error::ElementNotFound(format!("login {role} field")))
+    }
+}
+
+/// Finds the visible username, password, one-time code and submit controls
+/// (honouring the hints), CAPTCHA widgets and error messages.
+pub(crate) const LOGIN_PROBE_JS: &str = r#"(hints) => {
+    const visible = (el) => !!el && !el.disabled && (typeof el.checkVisibility === 'function'
+        ? el.checkVisibility({ checkOpacity: true, checkVisibilityCSS: true })
+        : el.offsetParent !== null);
+    const first = (sel) => {
+        try { return [...document.querySelectorAll(sel)].find(visible) || null; } catch (e) { return null; }
+    };
+    const describe = (el) => [el.name, el.id, el.getAttribute('autocomplete'), el.placeholder,
+        el.getAttribute('aria-label'), el.labels && el.labels[0] ? el.labels[0].innerText : ''].join(' ');
+    const textInputs = [...document.querySelectorAll(
+        'input:not([type]), input[type=text], input[type=email], input[type=tel]')].filter(visible);
+    // Controls are in the document (found by querySelectorAll), so each has a
+    // parent chain up to the root
+    const path = (el) => {
+        const parts = [];
+        for (; el.parentElement; el = el.parentElement) {
+            parts.unshift(':nth-child(' + (Array.prototype.indexOf.call(el.parentElement.children, el) + 1) + ')');
+        }
+        return [':root', ...parts].join(' > ');
+    };
+    const controls = {};
+    const mark = (el, role) => {
+        if (el) controls[role] = path(el);
+        return !!el;
+    };
+
+    const password = hints.password_selector
+        ? first(hints.password_selector)
+        : first('input[type=password]:not([autocomplete=new-password])') || first('input[type=password]');
+    const otpPattern = /one-time-code|\botp\b|totp|2fa|two.?factor|verification|security.?code|\bcode\b|\bpin\b/i;
+    const otpField = hints.otp_selector
+        ? first(hints.otp_selector)
+        : !password && (first('input[autocomplete=one-time-code]')
+            || textInputs.concat([...document.querySelectorAll('input[type=number]')].filter(visible))
+                .find(el => otpPattern.test(describe(el)))) || null;
+    const otp = !!otpField;
+
+    let username = null;
+    if (hints.username_selector) {
+        username = first(hints.username_selector);
+    } else if (!otp) {
+        username = first('input[autocomplete~=username]')
+            || textInputs.find(el => /user|e-?mail|login|account|phone|identifier|handle/i.test(describe(el)))
+            || first('input[type=email]');
+        if (!username && password) {
+            const scope = password.form || document;
+            username = [...scope.querySelectorAll('input')].filter(visible)
+                .filter(el => el.compareDocumentPosition(password) & Node.DOCUMENT_POSITION_FOLLOWING)
+                .filter(el => textInputs.includes(el)).pop() || null;
+        }
+    }
+
+    const field = password || username || otpField;
+    let submit = hints.submit_selector ? first(hints.submit_selector) : null;
+    if (!submit && field) {
+        const scope = field.form || document;
+        const buttons = [...scope.querySelectorAll('button, input[type=submit], [role=button]')].filter(visible);
+        submit = buttons.find(b => /^(log ?in|sign ?in|next|continue|submit|verify|confirm)$/i.test((b.innerText || b.value || '').trim()))
+            || (field.form && buttons.find(b => b.type === 'submit'))
+            || null;
+    }
+
+    const captcha = [
+        'iframe[src*="recaptcha"]', 'iframe[src*="hcaptcha"]', 'iframe[src*="challenges.cloudflare.com"]',
+        '.g-recaptcha', '.h-captcha', '.cf-turnstile',
+    ].some(sel => first(sel));
+
+    const errorEl = [...document.querySelectorAll(
+        '[role=alert], [aria-live=assertive], .error, .alert-danger, .alert-error, [class*="error" i]')]
+        .find(el => visible(el) && (el.innerText || '').trim());
+
+    return {
+        ready: document.readyState === 'complete',
+        username: mark(username, 'username'),
+        username_value: username ? username.value : '',
+        password: mark(password, 'password'),
+        submit: mark(submit, 'submit'),
+        otp: mark(otpField, 'otp'),
+        otp_split: !!otpField && otpField.maxLength === 1,
+        captcha,
+        error: errorEl ? errorEl.innerText.trim().substring(0, 300) : null,
+        success: !!(hints.success_selector && first(hints.success_selector)),
+        controls,
+    };
+}"#;
@@ -0,0 +1,270 @@
+use std::io;
+
+use chromiumoxide::page::Page as CrPage;
+
+use crate::error::{Error, Result};
+
+/// Renderer metrics for a single page, returned by `Page::metrics`.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct PageMetrics {
+    /// Bytes of JS heap in use.
+    pub js_heap_used_size: u64,
+    /// Bytes of JS heap allocated.
+    pub js_heap_total_size: u64,
+    /// Live DOM nodes.
+    pub nodes: u64,
+    /// Live documents, including iframes.
+    pub documents: u64,
+    pub frames: u64,
+    pub js_event_listeners: u64,
+    /// Full or partial layouts since the page was created.
+    pub layout_count: u64,
+}
+
+/// Read `page`'s metrics with Performance.getMetrics.
+pub(crate) async fn page_metrics(page: &CrPage) -> Result<PageMetrics> {
+    enable_performance(page).await?;
+    read_page_metrics(page).await
+}
+
+/// Enable the Performance domain, which `read_page_metrics` needs.
+pub(crate) async fn enable_performance(page: &CrPage) -> Result<()> {
+    use chromiumoxide::cdp::browser_protocol::performance::EnableParams;
+
+    page.execute(EnableParams::default())
+        .await
+        .map_err(|e| Error::JsError(format!("Failed to enable performance domain: {e}")))?;
+    Ok(())
+}
+
+/// Read the metrics of a page whose Performance domain is enabled.
+pub(crate) async fn read_page_metrics(page: &CrPage) -> Result<PageMetrics> {
+    use chromiumoxide::cdp::browser_protocol::performance::GetMetricsParams;
+
+    let metrics = page
+        .execute(GetMetricsParams::default())
+        .await
+        .map_err(|e| Error::JsError(format!("Failed to get metrics: {e}")))?;
+    Ok(PageMetrics::from_pairs(
+        metrics.result.metrics.iter().map(|m| (m.name.as_str(), m.value)),
+    ))
+}
+
+impl PageMetrics {
+    /// Build from Performance.getMetrics name/value pairs; unknown names are ignored.
+    fn from_pairs<'a>(pairs: impl IntoIterator<Item = (&'a str, f64)>) -> Self {
+        let mut metrics = Self::default();
+        for (name, value) in pairs {
+            let value = value.max(0.0) as u64;
+            match name {
+                "JSHeapUsedSize" => metrics.js_heap_used_size = value,
+                "JSHeapTotalSize" => metrics.js_heap_total_size = value,
+                "Nodes" => metrics.nodes = value,
+                "Documents" => metrics.documents = value,
+                "Frames" => metrics.frames = value,
+                "JSEventListeners" => metrics.js_event_listeners = value,
+                "LayoutCount" => metrics.layout_count = value,
+                _ => {}
+            }
+        }
+        metrics
+    }
+}
+
+/// Memory used by the Chrome process tree, returned by `AgenticBrowser::memory_usage`.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct MemoryUsage {
+    /// Resident set size summed over the browser process and all its descendants
+    /// (renderers, GPU, utility processes), in bytes. Shared pages are counted once
+    /// per process, so this overestimates actual usage somewhat.
+    pub rss_bytes: u64,
+    /// Number of processes included in `rss_bytes`.
+    pub process_count: usize,
+}
+
+/// Sum the RSS of `pid` and all of its descendants from `/proc`.
+#[cfg(target_os = "linux")]
+pub(crate) fn process_tree_memory(pid: u32) -> Result<MemoryUsage> {
+    use std::collections::HashMap;
+    use std::fs;
+
+    // Map every process to its parent, from the 4th field of /proc/<pid>/stat.
+    // The command name (2nd field) may contain spaces, so split after its ')'.
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for entry in fs::read_dir("/proc")?.flatten() {
+        let Some(child) = entry.file_name().to_str().and_then(|n| n.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(stat) = fs::read_to_string(entry.path().join("stat")) else {
+            continue;
+        };
+        let parent = stat
+            .rsplit_once(')')
+            .and_then(|(_, rest)| rest.split_whitespace().nth(1))
+            .and_then(|ppid| ppid.parse::<u32>().ok());
+        if let Some(parent) = parent {
+            children.entry(parent).or_default().push(child);
+        }
+    }
+
+    let mut usage = MemoryUsage::default();
+    let mut stack = vec![pid];
+    while let Some(current) = stack.pop() {
+        // Processes may exit between listing and reading; skip them
+        if let Ok(rss) = rss_bytes(current) {
+            usage.rss_bytes += rss;
+            usage.process_count += 1;
+        }
+        if let Some(kids) = children.get(&current) {
+            stack.extend(kids);
+        }
+    }
+    if usage.process_count == 0 {
+        return Err(Error::IoError(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Chrome process {pid} is not running"),
+        )));
+    }
+    Ok(usage)
+}
+
+#[cfg(target_os = "linux")]
+fn rss_bytes(pid: u32) -> io::Result<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status"))?;
+    let kb = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|v| v.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no VmRSS in /proc status"))?;
+    Ok(kb * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn process_tree_memory(_pid: u32) -> Result<MemoryUsage> {
+    Err(Error::IoError(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "process memory usage is only available on Linux",
+    )))
+}
+
+/// Load timing and Core Web Vitals for the current document, returned by
+/// `Page::performance_timings`. Times are milliseconds since navigation start;
+/// `None` when the browser hasn't recorded the entry (yet).
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct PerformanceTimings {
+    pub url: String,
+    pub dns_ms: Option<f64>,
+    pub connect_ms: Option<f64>,
+    /// Time to first byte.
+    pub ttfb_ms: Option<f64>,
+    pub dom_content_loaded_ms: Option<f64>,
+    pub load_ms: Option<f64>,
+    /// First Contentful Paint.
+    pub fcp_ms: Option<f64>,
+    /// Largest Contentful Paint.
+    pub lcp_ms: Option<f64>,
+    /// Cumulative Layout Shift (largest session window, as reported by web-vitals).
+    pub cls: f64,
+    /// Bytes transferred for the main document, headers included.
+    pub transfer_size: Option<u64>,
+}
+
+/// Reads the navigation and paint entries, and the buffered LCP and layout-shift
+/// entries through short-lived `PerformanceObserver`s.
+pub(crate) const PERFORMANCE_TIMINGS_JS: &str = r#"(async () => {
+    const collect = (type) => new Promise(resolve => {
+        const entries = [];
+        let observer;
+        try {
+            observer = new PerformanceObserver(list => entries.push(...list.getEntries()));
+            observer.observe({ type, buffered: true });
+        } catch (e) { resolve(entries); return; }
+        setTimeout(() => {
+            entries.push(...observer.takeRecords());
+            observer.disconnect();
+            resolve(entries);
+        }, 50);
+    });
+    const [lcp, shifts] = await Promise.all([
+        collect('largest-contentful-paint'),
+        collect('layout-shift'),
+    ]);
+
+    let cls = 0, windowValue = 0, windowStart = 0, last = 0;
+    for (const s of shifts) {
+        if (s.hadRecentInput) continue;
+        if (windowValue && (s.startTime - last > 1000 || s.startTime - windowStart > 5000)) {
+            windowValue = 0;
+        }
+        if (!windowValue) windowStart = s.startTime;
+        windowValue += s.value;
+        last = s.startTime;
+        cls = Math.max(cls, windowValue);
+    }
+
+    const nav = performance.getEntriesByType('navigation')[0];
+    const fcp = performance.getEntriesByName('first-contentful-paint')[0];
+    const positive = (v) => (v > 0 ? v : null);
+    return {
+        url: location.href,
+        dns_ms: nav ? nav.domainLookupEnd - nav.domainLookupStart : null,
+        connect_ms: nav ? nav.connectEnd - nav.connectStart : null,
+        ttfb_ms: nav ? positive(nav.responseStart) : null,
+        dom_content_loaded_ms: nav ? positive(nav.domContentLoadedEventEnd) : null,
+        load_ms: nav ? positive(nav.loadEventEnd) : null,
+        fcp_ms: fcp ? fcp.startTime : null,
+        lcp_ms: lcp.length ? lcp[lcp.length - 1].startTime : null,
+        cls,
+        transfer_size: nav ? nav.transferSize : null,
+    };
+})()"#;
+
+/// One request of the current document's waterfall, returned by
+/// `Page::request_waterfall`. Times are milliseconds since navigation start.
+/// Phase timings are `None` for cross-origin requests without `Timing-Allow-Origin`.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct RequestTiming {
+    pub url: String,
+    /// Initiator: "navigation" for the document, then "script", "img", "css",
+    /// "fetch", "xmlhttprequest", "link", ...
+    pub resource_type: String,
+    pub start_ms: f64,
+    pub end_ms: f64,
+    pub dns_ms: Option<f64>,
+    /// TCP connect, including TLS.
+    pub connect_ms: Option<f64>,
+    /// Time to first byte, from request sent.
+    pub ttfb_ms: Option<f64>,
+    /// Bytes transferred, headers included (0 when served from cache).
+    pub transfer_size: u64,
+    /// Decoded body size.
+    pub body_size: u64,
+    pub from_cache: bool,
+    /// HTTP status, when the browser exposes it (Chrome 109+).
+    pub status: Option<u16>,
+}
+
+/// Maps the navigation and resource timing entries to `RequestTiming`s, ordered
+/// by start time.
+pub(crate) const REQUEST_WATERFALL_JS: &str = r#"(() => {
+    const phase = (start, end) => (start > 0 && end >= start ? end - start : null);
+    const entries = [
+        ...performance.getEntriesByType('navigation'),
+        ...performance.getEntriesByType('resource'),
+    ];
+    return entries
+        .map(e => ({
+            url: e.name,
+            resource_type: e.entryType === 'navigation' ? 'navigation' : e.initiatorType,
+            start_ms: e.startTime,
+            end_ms: e.responseEnd,
+            dns_ms: phase(e.domainLookupStart, e.domainLookupEnd),
+            connect_ms: phase(e.connectStart, e.connectEnd),
+            ttfb_ms: phase(e.requestStart, e.responseStart),
+            transfer_size: e.transferSize || 0,
+            body_size: e.decodedBodySize || 0,
+            from_cache: e.transferSize === 0 && e.decodedBodySize > 0,
+            status: e.responseStatus || null,
+        }))
+        .sort((a, b) => a.start_ms - b.start_ms);
+})()"#;
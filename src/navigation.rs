@@ -0,0 +1,195 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use chromiumoxide::cdp::browser_protocol::network::{
+    EnableParams, EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent, RequestId,
+};
+use chromiumoxide::cdp::browser_protocol::page::StopLoadingParams;
+use chromiumoxide::page::Page as CrPage;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use tokio::time::Instant;
+
+use crate::error::{Error, Result};
+
+/// How long the network or the DOM has to stay quiet for `NetworkIdle` / `Stable`.
+pub(crate) const QUIET: Duration = Duration::from_millis(500);
+
+/// When `Page::goto_with` considers the navigation done.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WaitUntil {
+    /// The load event: the document and its subresources loaded.
+    #[default]
+    Load,
+    /// DOMContentLoaded: the HTML is parsed; images, styles and late scripts may
+    /// still be loading.
+    DomContentLoaded,
+    /// Load, then no requests in flight for 500ms. For pages that fetch their
+    /// content after loading.
+    NetworkIdle,
+    /// Load, then no DOM changes for 500ms. For pages that keep rendering after
+    /// their requests finish.
+    Stable,
+}
+
+/// Settings for `Page::goto_with`.
+#[derive(Debug, Clone, Default)]
+pub struct GotoOptions {
+    pub wait_until: WaitUntil,
+    /// Sent as the `Referer` header and exposed as `document.referrer`.
+    pub referrer: Option<String>,
+    /// How long to wait in all (default: the page timeout).
+    pub timeout: Option<Duration>,
+}
+
+impl GotoOptions {
+    pub fn new(wait_until: WaitUntil) -> Self {
+        Self { wait_until, ..Self::default() }
+    }
+
+    pub fn referrer(mut self, referrer: impl Into<String>) -> Self {
+        self.referrer = Some(referrer.into());
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+/// One entry of a tab's session history.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct HistoryEntry {
+    /// Chrome's id for the entry, stable while the tab lives.
+    pub id: i64,
+    pub url: String,
+    pub title: String,
+}
+
+/// A tab's session history, oldest entry first.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct History {
+    /// Index in `entries` of the page being shown.
+    pub current_index: usize,
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    pub fn current(&self) -> Option<&HistoryEntry> {
+        self.entries.get(self.current_index)
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        self.current_index > 0
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        self.current_index + 1 < self.entries.len()
+    }
+}
+
+/// Resolves once the document has been parsed.
+pub(crate) const DOM_CONTENT_LOADED_JS: &str = r#"new Promise(resolve => {
+    if (document.readyState !== 'loading') { resolve(); return; }
+    document.addEventListener('DOMContentLoaded', () => resolve(), { once: true });
+})"#;
+
+/// Resolves once the document has loaded (immediately for a page restored from
+/// the back/forward cache).
+pub(crate) const LOAD_JS: &str = r#"new Promise(resolve => {
+    if (document.readyState === 'complete') { resolve(); return; }
+    window.addEventListener('load', () => resolve(), { once: true });
+})"#;
+
+/// Resolves once the DOM has gone `quietMs` without a mutation.
+pub(crate) const DOM_QUIET_JS: &str = r#"(quietMs) => new Promise(resolve => {
+    const done = () => { observer.disconnect(); resolve(true); };
+    let timer = setTimeout(done, quietMs);
+    const observer = new MutationObserver(() => {
+        clearTimeout(timer);
+        timer = setTimeout(done, quietMs);
+    });
+    observer.observe(document, { childList: true, subtree: true, attributes: true, characterData: true });
+})"#;
+
+/// Resolves once the next frame has been painted.
+pub(crate) const NEXT_FRAME_JS: &str =
+    "new Promise(resolve => requestAnimationFrame(() => requestAnimationFrame(() => resolve())))";
+
+/// Stops the page loading when dropped before `disarm`, so a navigation whose
+/// future is cancelled (a `select!`, a dropped task, a timeout) doesn't keep
+/// loading behind the caller's back.
+pub(crate) struct StopOnDrop {
+    page: Option<CrPage>,
+}
+
+impl StopOnDrop {
+    pub fn new(page: &CrPage) -> Self {
+        Self { page: Some(page.clone()) }
+    }
+
+    /// The navigation finished; don't stop anything.
+    pub fn disarm(mut self) {
+        self.page = None;
+    }
+}
+
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        let Some(page) = self.page.take() else {
+            return;
+        };
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                let _ = page.execute(StopLoadingParams::default()).await;
+            });
+        }
+    }
+}
+
+enum Request {
+    Started(RequestId),
+    Done(RequestId),
+}
+
+/// Tracks a page's requests in flight, from before a navigation starts.
+pub(crate) struct NetworkIdle {
+    events: BoxStream<'static, Request>,
+}
+
+impl NetworkIdle {
+    pub async fn listen(page: &CrPage) -> Result<Self> {
+        let listen_err = |e: chromiumoxide::error::CdpError| {
+            Error::NavigationError(format!("Failed to listen for network events: {e}"))
+        };
+        let started = page.event_listener::<EventRequestWillBeSent>().await.map_err(listen_err)?;
+        let finished = page.event_listener::<EventLoadingFinished>().await.map_err(listen_err)?;
+        let failed = page.event_listener::<EventLoadingFailed>().await.map_err(listen_err)?;
+        page.execute(EnableParams::default())
+            .await
+            .map_err(|e| Error::NavigationError(format!("Failed to enable network domain: {e}")))?;
+        let events = futures::stream::select_all([
+            started.map(|e| Request::Started(e.request_id.clone())).boxed(),
+            finished.map(|e| Request::Done(e.request_id.clone())).boxed(),
+            failed.map(|e| Request::Done(e.request_id.clone())).boxed(),
+        ]);
+        Ok(Self { events: events.boxed() })
+    }
+
+    /// Wait until no request has been in flight for `quiet`. Long-polling or
+    /// streaming pages never get there; bound this with a timeout.
+    pub async fn wait(mut self, quiet: Duration) {
+        // Far enough away to mean "not while requests are in flight"
+        let never = Duration::from_secs(24 * 60 * 60);
+        let mut in_flight = HashSet::new();
+        let mut deadline = Instant::now() + quiet;
+        while let Ok(Some(request)) = tokio::time::timeout_at(deadline, self.events.next()).await {
+            match request {
+                Request::Started(id) => in_flight.insert(id),
+                Request::Done(id) => in_flight.remove(&id),
+            };
+            deadline = Instant::now() + if in_flight.is_empty() { quiet } else { never };
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::error::{Error, Result};
+use crate::page::BoundingBox;
+
+/// A line of text read off the screen by `Page::ocr`.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct OcrText {
+    pub text: String,
+    /// Tesseract's mean word confidence for the line, 0 to 100.
+    pub confidence: f32,
+    /// Where the line is, in CSS pixels relative to the viewport, like the boxes
+    /// of `Page::interactive_elements`.
+    pub bounds: BoundingBox,
+}
+
+/// Settings for `Page::ocr_with`.
+#[derive(Debug, Clone)]
+pub struct OcrOptions {
+    /// Tesseract language codes, `+`-separated (default `eng`). Each needs its
+    /// traineddata installed.
+    pub language: String,
+    /// Words recognized with less confidence are dropped (default 60).
+    pub min_confidence: f32,
+    /// Zoom of the screenshot handed to Tesseract (default 2). Small text reads
+    /// much better enlarged.
+    pub scale: f64,
+    /// The Tesseract binary (default `tesseract`, looked up on `PATH`).
+    pub command: PathBuf,
+}
+
+impl Default for OcrOptions {
+    fn default() -> Self {
+        Self {
+            language: "eng".to_string(),
+            min_confidence: 60.0,
+            scale: 2.0,
+            command: PathBuf::from("tesseract"),
+        }
+    }
+}
+
+impl OcrOptions {
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = language.into();
+        self
+    }
+
+    pub fn min_confidence(mut self, confidence: f32) -> Self {
+        self.min_confidence = confidence;
+        self
+    }
+
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn command(mut self, command: impl Into<PathBuf>) -> Self {
+        self.command = command.into();
+        self
+    }
+}
+
+/// Run Tesseract over `png`, a screenshot of `region`, and return its lines
+/// positioned in the viewport.
+pub(crate) async fn recognize(png: &[u8], region: BoundingBox, options: &OcrOptions) -> Result<Vec<OcrText>> {
+    // The screenshot's pixels per CSS pixel: the clip scale times the device
+    // pixel ratio
+    let ratio = png_width(png).ok_or_else(|| Error::OcrError("screenshot is not a PNG".to_string()))? as f64
+        / region.width;
+
+    let mut child = Command::new(&options.command)
+        .args(["stdin", "stdout", "-l", &options.language, "tsv"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| {
+            Error::OcrError(format!(
+                "failed to start {} (install tesseract-ocr): {e}",
+                options.command.display()
+            ))
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(png).await?;
+    }
+    let output = child.wait_with_output().await?;
+    if !output.status.success() {
+        return Err(Error::OcrError(format!(
+            "tesseract exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let lines = parse_tsv(&String::from_utf8_lossy(&output.stdout), options.min_confidence);
+    Ok(lines
+        .into_iter()
+        .map(|mut line| {
+            line.bounds = BoundingBox {
+                x: region.x + line.bounds.x / ratio,
+                y: region.y + line.bounds.y / ratio,
+                width: line.bounds.width / ratio,
+                height: line.bounds.height / ratio,
+            };
+            line
+        })
+        .collect())
+}
+
+/// The width in the IHDR chunk of a PNG.
+fn png_width(png: &[u8]) -> Option<u32> {
+    if png.len() < 24 || &png[..8] != b"\x89PNG\r\n\x1a\n" {
+        return None;
+    }
+    Some(u32::from_be_bytes(png[16..20].try_into().ok()?))
+}
+
+/// A word of Tesseract's output, its box in image pixels.
+struct Word {
+    text: String,
+    confidence: f32,
+    left: f64,
+    top: f64,
+    right: f64,
+    bottom: f64,
+}
+
+/// Join the words of Tesseract's TSV output into lines, boxes in image pixels.
+fn parse_tsv(tsv: &str, min_confidence: f32) -> Vec<OcrText> {
+    // Keyed by (page, block, paragraph, line), in reading order
+    let mut lines: BTreeMap<[u32; 4], Vec<Word>> = BTreeMap::new();
+    for row in tsv.lines().skip(1) {
+        let cols: Vec<&str> = row.splitn(12, '\t').collect();
+        if cols.len() < 12 || cols[0] != "5" {
+            continue;
+        }
+        let text = cols[11].trim();
+        let confidence: f32 = cols[10].parse().unwrap_or(-1.0);
+        if text.is_empty() || confidence < min_confidence {
+            continue;
+        }
+        let num = |i: usize| cols[i].parse::<u32>().unwrap_or(0);
+        let (left, top, width, height) = (num(6) as f64, num(7) as f64, num(8) as f64, num(9) as f64);
+        lines
+            .entry([num(1), num(2), num(3), num(4)])
+            .or_default()
+            .push(Word { text: text.to_string(), confidence, left, top, right: left + width, bottom: top + height });
+    }
+
+    lines
+        .into_values()
+        .map(|words| {
+            let left = words.iter().map(|w| w.left).fold(f64::MAX, f64::min);
+            let top = words.iter().map(|w| w.top).fold(f64::MAX, f64::min);
+            let right = words.iter().map(|w| w.right).fold(0.0, f64::max);
+            let bottom = words.iter().map(|w| w.bottom).fold(0.0, f64::max);
+            OcrText {
+                text: words.iter().map(|w| w.text.as_str()).collect::<Vec<_>>().join(" "),
+                confidence: words.iter().map(|w| w.confidence).sum::<f32>() / words.len() as f32,
+                bounds: BoundingBox { x: left, y: top, width: right - left, height: bottom - top },
+            }
+        })
+        .collect()
+}
@@ -1,12 +1,61 @@
-use std::path::Path;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use chromiumoxide::page::Page as CrPage;
 use chromiumoxide::page::ScreenshotParams;
-use chromiumoxide::cdp::browser_protocol::page::CaptureScreenshotFormat;
-
-use crate::element::Element;
+use chromiumoxide::cdp::browser_protocol::emulation::SetDeviceMetricsOverrideParams;
+use chromiumoxide::cdp::browser_protocol::page::{CaptureScreenshotFormat, FrameId, ScriptIdentifier};
+use chromiumoxide::cdp::js_protocol::runtime::{ExecutionContextId, RemoteObjectId};
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::action::{Action, ActionResult};
+use crate::archive::{self, Rewritten, RESOURCE_DIR};
+#[cfg(feature = "extraction")]
+use crate::autofill::{self, AutofillReport, FillFailure, FillProfile, SkippedField};
+use crate::blocking::{self, Blocking};
+#[cfg(feature = "low-resource")]
+use crate::blocklist::ADS_AND_TRACKERS;
+use crate::capture::{self, CapturedResponse};
+use crate::config::NetworkThrottle;
+#[cfg(feature = "extraction")]
+use crate::describe::{self, PageOutline, TokenEstimator};
+use crate::download::{self, DownloadProgress};
+use crate::drag::{self, DragTarget};
+use crate::element::{Element, SelectOption};
 use crate::error::{Error, Result};
+use crate::events::BackgroundError;
+use crate::fetch::{
+    FetchRequest, FetchResponse, FormPart, FETCH_JS, NEW_FILE_INPUT_JS, RESOLVE_URL_JS,
+};
+use crate::graphql::{self, GraphqlOperation};
+use crate::honeypot::{self, FillFormReport};
+use crate::identity::{self, Identity};
+#[cfg(feature = "image")]
+use crate::imaging::Screenshot;
+use crate::interactive::{self, InteractiveElement, ScreenState, VisualObservation};
+use crate::intercept::{normalize_origin, parse_resource_type, Interceptor};
+use crate::lifecycle;
+use crate::locator::{self, By, ElementState, Locator, Role, WaitOptions};
+use crate::login::{self, LoginChallenge, LoginOutcome, LoginProbe, LoginSpec};
+use crate::metrics::{self, PageMetrics, PerformanceTimings, RequestTiming};
+use crate::navigation::{self, GotoOptions, History, HistoryEntry, NetworkIdle, StopOnDrop, WaitUntil};
+#[cfg(feature = "ocr")]
+use crate::ocr::{self, OcrOptions, OcrText};
+use crate::ratelimit::{random_signed, random_up_to, RateLimiter, RatePermit};
+use crate::regions::{self, PageRegion};
+use crate::recorder::{self, ActionRecorder, ActionScript};
+use crate::readonly::{self, ReadOnly};
+use crate::redact::{self, Redaction};
+use crate::snapshot::{self, DomSnapshot, ExtractOptions, ExtractionBackend, TreeOptions};
+use crate::style;
+use crate::telemetry;
+use crate::validation::{self, ValidationError};
 
 /// Data extracted from a single element by `query_selector_all_with_data`.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -26,17 +75,199 @@ pub struct FormField {
     pub value: String,
     pub placeholder: String,
     pub label: String,
+    /// Options of a `<select>`, in order.
+    #[serde(default)]
+    pub options: Vec<SelectOption>,
+    /// Checkboxes and radio buttons: whether this one is checked.
+    #[serde(default)]
+    pub checked: bool,
+    /// Checkboxes and radio buttons: the values of every field in the same form
+    /// with this name and type, this one included, in page order.
+    #[serde(default)]
+    pub group: Vec<String>,
+    #[serde(default)]
+    pub required: bool,
+    /// Disabled itself or by a disabled `<fieldset>`; not submitted.
+    #[serde(default)]
+    pub disabled: bool,
+    /// The form the field is submitted with, if any.
+    #[serde(default)]
+    pub form: Option<FormTarget>,
+}
+
+/// Where a form submits: its absolute `action` URL and lowercase `method`
+/// (`get`, `post` or `dialog`).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct FormTarget {
+    /// The form's `id`, or empty.
+    pub id: String,
+    pub action: String,
+    pub method: String,
+}
+
+/// Page state snapshot returned by `Page::observe`.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Observation {
+    pub url: String,
+    pub title: String,
+    /// `document.readyState`: "loading", "interactive" or "complete".
+    pub ready_state: String,
+    pub scroll_x: f64,
+    pub scroll_y: f64,
+    pub viewport_width: u32,
+    pub viewport_height: u32,
+}
+
+/// Viewport geometry returned by `Page::viewport`. `width`/`height` are CSS
+/// pixels; a screenshot is `width * dpr` by `height * dpr` device pixels, so a
+/// point (px, py) in a screenshot is (px / dpr, py / dpr) in the viewport and
+/// (scroll_x + px / dpr, scroll_y + py / dpr) in the document.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ViewportInfo {
+    pub width: f64,
+    pub height: f64,
+    /// `window.devicePixelRatio`.
+    pub dpr: f64,
+    pub scroll_x: f64,
+    pub scroll_y: f64,
+    /// Full scrollable height of the document.
+    pub page_height: f64,
+}
+
+/// An element's box in CSS pixels, relative to the viewport when it was read.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct BoundingBox {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl BoundingBox {
+    /// The middle of the box, where a click would land.
+    pub fn center(&self) -> (f64, f64) {
+        (self.x + self.width / 2.0, self.y + self.height / 2.0)
+    }
+}
+
+/// A viewport screenshot fitted under a byte cap by `Page::screenshot_within`.
+#[derive(Debug, Clone)]
+pub struct FittedScreenshot {
+    pub data: Vec<u8>,
+    /// `image/png` or `image/jpeg`.
+    pub mime_type: String,
+    /// JPEG quality, `None` for PNG.
+    pub quality: Option<u8>,
+    /// Size relative to a plain `screenshot()`: 1.0, or less if it had to be
+    /// downscaled.
+    pub scale: f64,
 }
 
 /// Wrapper around a chromiumoxide Page with a simplified, agent-friendly API.
 pub struct Page {
     inner: CrPage,
     default_timeout: Duration,
+    interceptor: Arc<Interceptor>,
+    extraction: ExtractionBackend,
+    #[cfg(feature = "extraction")]
+    token_estimator: TokenEstimator,
+    rate_limiter: Option<RateLimiter>,
+    /// Bring the tab to the front before screenshots (`set_screenshot_background`).
+    screenshot_background: bool,
+    /// The new-document script installed by `keep_active`.
+    keep_active_script: Mutex<Option<ScriptIdentifier>>,
+    /// `set_identity`'s device metrics, which `set_zoom` keeps.
+    device_metrics: Mutex<Option<SetDeviceMetricsOverrideParams>>,
+    /// The new-document script installed by `set_identity`.
+    identity_script: Mutex<Option<ScriptIdentifier>>,
+    /// The main frame and execution context of `evaluate_isolated`'s world,
+    /// cleared when a navigation destroys it.
+    isolated_world: Arc<Mutex<Option<(FrameId, ExecutionContextId)>>>,
+    redaction: Option<Redaction>,
+    read_only: Option<ReadOnly>,
 }
 
 impl Page {
-    pub(crate) fn new(inner: CrPage, default_timeout: Duration) -> Self {
-        Self { inner, default_timeout }
+    pub(crate) fn new(inner: CrPage, default_timeout: Duration, interceptor: Arc<Interceptor>) -> Self {
+        Self {
+            inner,
+            default_timeout,
+            interceptor,
+            extraction: ExtractionBackend::default(),
+            #[cfg(feature = "extraction")]
+            token_estimator: TokenEstimator::default(),
+            rate_limiter: None,
+            screenshot_background: false,
+            keep_active_script: Mutex::new(None),
+            device_metrics: Mutex::new(None),
+            identity_script: Mutex::new(None),
+            isolated_world: Arc::new(Mutex::new(None)),
+            redaction: None,
+            read_only: None,
+        }
+    }
+
+    pub(crate) fn default_timeout(&self) -> Duration {
+        self.default_timeout
+    }
+
+    /// Choose how `accessibility_tree`, `get_links` and `get_form_fields` read the
+    /// page: injected JS (default) or a browser-side DOM snapshot.
+    pub fn set_extraction_backend(&mut self, backend: ExtractionBackend) {
+        self.extraction = backend;
+    }
+
+    /// Bring this tab to the front before each screenshot if it sits in the
+    /// background, so the capture isn't blank or stale (default: off). It then
+    /// stays the active tab.
+    pub fn set_screenshot_background(&mut self, enabled: bool) {
+        self.screenshot_background = enabled;
+    }
+
+    /// Choose how `describe` counts tokens (default: 4 characters per token).
+    #[cfg(feature = "extraction")]
+    pub fn set_token_estimator(&mut self, estimator: TokenEstimator) {
+        self.token_estimator = estimator;
+    }
+
+    /// Wait for `limiter` before each navigation and `fetch` (see
+    /// `BrowserBuilder::rate_limiter`); `None` removes the limit.
+    pub fn set_rate_limiter(&mut self, limiter: Option<RateLimiter>) {
+        self.rate_limiter = limiter;
+    }
+
+    /// Redact `redaction.selectors` along with password and one-time-code fields
+    /// in `screenshot_redacted`, `redact_action` and action recordings (see
+    /// `BrowserBuilder::redaction`); `None` goes back to the defaults.
+    pub fn set_redaction(&mut self, redaction: Option<Redaction>) {
+        self.redaction = redaction;
+    }
+
+    /// Reject mutating actions as `read_only` says (see `BrowserBuilder::read_only`);
+    /// `None` allows everything again.
+    pub async fn set_read_only(&mut self, read_only: Option<ReadOnly>) -> Result<()> {
+        let block_writes = read_only.as_ref().is_some_and(ReadOnly::blocks_writes);
+        let mut changed = false;
+        self.interceptor.update(|rules| {
+            changed = rules.block_writes != block_writes;
+            rules.block_writes = block_writes;
+        });
+        self.read_only = read_only;
+        if changed {
+            self.interceptor.apply().await?;
+        }
+        Ok(())
+    }
+
+    /// Wait for the rate limiter, if any, to allow a request to `url`. Relative
+    /// URLs count against the host they resolve to from the current page.
+    async fn rate_limit(&self, url: &str) -> Option<RatePermit> {
+        let limiter = self.rate_limiter.as_ref()?;
+        if url.contains("://") {
+            return Some(limiter.acquire(url).await);
+        }
+        let resolved: Result<String> = self.evaluate_with_args(RESOLVE_URL_JS, url).await;
+        Some(limiter.acquire(resolved.as_deref().unwrap_or(url)).await)
     }
 
     /// Returns a reference to the underlying chromiumoxide Page.
@@ -48,61 +279,264 @@ impl Page {
 
     /// Navigate to the given URL and wait for the page to load.
     pub async fn goto(&self, url: &str) -> Result<()> {
-        self.inner
-            .goto(url)
-            .await
-            .map_err(|e| Error::NavigationError(e.to_string()))?;
-        Ok(())
+        self.goto_with(url, GotoOptions::default()).await
     }
 
     /// Navigate to the given URL, waiting only for DOMContentLoaded instead of the
     /// full load event. Typically 2-5x faster than `goto()` for content-heavy pages.
     pub async fn goto_fast(&self, url: &str) -> Result<()> {
-        use chromiumoxide::cdp::browser_protocol::page::NavigateParams;
+        self.goto_with(url, GotoOptions::new(WaitUntil::DomContentLoaded)).await
+    }
 
-        let params = NavigateParams::new(url);
-        self.inner
-            .execute(params)
-            .await
-            .map_err(|e| Error::NavigationError(e.to_string()))?;
+    /// Navigate to the given URL and wait as `options.wait_until` says: for the
+    /// load event, DOMContentLoaded, the network going idle or the DOM settling.
+    /// Fails with `Error::Timeout` after `options.timeout` (default: the page
+    /// timeout).
+    pub async fn goto_with(&self, url: &str, options: GotoOptions) -> Result<()> {
+        self.interceptor.check()?;
+        self.interceptor.check_navigation(url)?;
+        let _permit = self.rate_limit(url).await;
+        let timeout = options.timeout.unwrap_or(self.default_timeout);
+        let guard = StopOnDrop::new(&self.inner);
+        let result = match tokio::time::timeout(timeout, self.navigate(url, &options)).await {
+            Ok(result) => {
+                guard.disarm();
+                self.interceptor.check().and(result)
+            }
+            // Dropping the guard stops the page still loading
+            Err(_) => Err(Error::Timeout(format!(
+                "Navigation to {url} ({:?}) after {timeout:?}",
+                options.wait_until
+            ))),
+        };
+        telemetry::navigation(&result);
+        result
+    }
+
+    /// Stop loading the page (Page.stopLoading), like the browser's stop button:
+    /// pending navigations and resource loads are cancelled and a `goto` waiting
+    /// on them returns. Navigation methods also do this when their future is
+    /// dropped before they finish, so wrapping one in `tokio::time::timeout` or
+    /// `select!` leaves the tab idle rather than loading in the background.
+    pub async fn stop(&self) -> Result<()> {
+        use chromiumoxide::cdp::browser_protocol::page::StopLoadingParams;
 
-        // Wait for DOMContentLoaded (readyState becomes "interactive" or "complete")
-        let js = r#"new Promise(resolve => {
-            if (document.readyState !== 'loading') { resolve(); return; }
-            document.addEventListener('DOMContentLoaded', () => resolve(), { once: true });
-        })"#;
         self.inner
-            .evaluate(js)
+            .execute(StopLoadingParams::default())
             .await
             .map_err(|e| Error::NavigationError(e.to_string()))?;
         Ok(())
     }
 
-    /// Navigate back in the browser history.
-    pub async fn go_back(&self) -> Result<()> {
-        self.inner
-            .evaluate("window.history.back()")
-            .await
-            .map_err(|e| Error::NavigationError(e.to_string()))?;
+    async fn navigate(&self, url: &str, options: &GotoOptions) -> Result<()> {
+        use chromiumoxide::cdp::browser_protocol::page::NavigateParams;
+
+        let mut params = NavigateParams::new(url);
+        params.referrer = options.referrer.clone();
+        let nav_err = |e: chromiumoxide::error::CdpError| Error::NavigationError(e.to_string());
+        match options.wait_until {
+            WaitUntil::Load => {
+                self.inner.goto(params).await.map_err(nav_err)?;
+            }
+            WaitUntil::DomContentLoaded => {
+                self.inner.goto(params).await.map_err(nav_err)?;
+                // readyState becomes "interactive" or "complete"
+                self.inner
+                    .evaluate(navigation::DOM_CONTENT_LOADED_JS)
+                    .await
+                    .map_err(nav_err)?;
+            }
+            WaitUntil::NetworkIdle => {
+                let idle = NetworkIdle::listen(&self.inner).await?;
+                self.inner.goto(params).await.map_err(nav_err)?;
+                idle.wait(navigation::QUIET).await;
+            }
+            WaitUntil::Stable => {
+                self.inner.goto(params).await.map_err(nav_err)?;
+                let quiet_ms = navigation::QUIET.as_millis() as u64;
+                self.evaluate_with_args::<bool>(navigation::DOM_QUIET_JS, quiet_ms).await?;
+            }
+        }
         Ok(())
     }
 
-    /// Navigate forward in the browser history.
+    /// Navigate back in the browser history and wait for the page to load.
+    /// Fails if there is no previous entry.
+    pub async fn go_back(&self) -> Result<()> {
+        let history = self.history().await?;
+        if !history.can_go_back() {
+            return Err(Error::NavigationError("No previous page in history".into()));
+        }
+        self.navigate_to_entry(history.current_index - 1).await
+    }
+
+    /// Navigate forward in the browser history and wait for the page to load.
+    /// Fails if there is no next entry.
     pub async fn go_forward(&self) -> Result<()> {
-        self.inner
-            .evaluate("window.history.forward()")
+        let history = self.history().await?;
+        if !history.can_go_forward() {
+            return Err(Error::NavigationError("No next page in history".into()));
+        }
+        self.navigate_to_entry(history.current_index + 1).await
+    }
+
+    /// The tab's session history (Page.getNavigationHistory).
+    pub async fn history(&self) -> Result<History> {
+        use chromiumoxide::cdp::browser_protocol::page::GetNavigationHistoryParams;
+
+        let history = self
+            .inner
+            .execute(GetNavigationHistoryParams::default())
             .await
-            .map_err(|e| Error::NavigationError(e.to_string()))?;
-        Ok(())
+            .map_err(|e| Error::NavigationError(format!("Failed to read history: {e}")))?
+            .result;
+        Ok(History {
+            current_index: history.current_index.max(0) as usize,
+            entries: history
+                .entries
+                .into_iter()
+                .map(|entry| HistoryEntry { id: entry.id, url: entry.url, title: entry.title })
+                .collect(),
+        })
+    }
+
+    /// Navigate to `history().entries[index]` and wait for it to load, like
+    /// picking it from the back button's menu. Entries within the same document
+    /// (`pushState`, anchors) return once the URL has changed.
+    pub async fn navigate_to_entry(&self, index: usize) -> Result<()> {
+        use chromiumoxide::cdp::browser_protocol::page::{
+            EventFrameNavigated, EventNavigatedWithinDocument, NavigateToHistoryEntryParams,
+        };
+        use futures::StreamExt;
+
+        let history = self.history().await?;
+        let entry = history.entries.get(index).ok_or_else(|| {
+            Error::NavigationError(format!(
+                "No history entry {index} ({} entries)",
+                history.entries.len()
+            ))
+        })?;
+        if index == history.current_index {
+            return Ok(());
+        }
+        self.interceptor.check()?;
+        let _permit = self.rate_limit(&entry.url).await;
+
+        let listen_err = |e: chromiumoxide::error::CdpError| {
+            Error::NavigationError(format!("Failed to listen for navigation events: {e}"))
+        };
+        let main_frame = self.inner.mainframe().await.map_err(listen_err)?;
+        let mut new_document = self
+            .inner
+            .event_listener::<EventFrameNavigated>()
+            .await
+            .map_err(listen_err)?
+            .filter(|e| std::future::ready(e.frame.parent_id.is_none()));
+        let mut same_document = self
+            .inner
+            .event_listener::<EventNavigatedWithinDocument>()
+            .await
+            .map_err(listen_err)?
+            .filter(|e| std::future::ready(Some(&e.frame_id) == main_frame.as_ref()));
+
+        let navigation = async {
+            self.inner
+                .execute(NavigateToHistoryEntryParams::new(entry.id))
+                .await
+                .map_err(|e| Error::NavigationError(e.to_string()))?;
+            tokio::select! {
+                Some(_) = new_document.next() => {
+                    self.inner
+                        .evaluate(navigation::LOAD_JS)
+                        .await
+                        .map_err(|e| Error::NavigationError(e.to_string()))?;
+                }
+                Some(_) = same_document.next() => {}
+            }
+            Ok(())
+        };
+        let guard = StopOnDrop::new(&self.inner);
+        let result = match tokio::time::timeout(self.default_timeout, navigation).await {
+            Ok(result) => {
+                guard.disarm();
+                self.interceptor.check().and(result)
+            }
+            Err(_) => Err(Error::Timeout(format!(
+                "Navigation to history entry {index} ({}) after {:?}",
+                entry.url, self.default_timeout
+            ))),
+        };
+        telemetry::navigation(&result);
+        result
     }
 
     /// Reload the current page.
     pub async fn reload(&self) -> Result<()> {
+        self.reload_with(false).await
+    }
+
+    /// Reload the current page, revalidating every resource with the server
+    /// instead of using the HTTP cache (like Shift+Reload). Service workers may
+    /// still answer; `clear_cache_and_reload` doesn't leave that to chance.
+    pub async fn reload_hard(&self) -> Result<()> {
+        self.reload_with(true).await
+    }
+
+    /// Empty the browser's HTTP cache (for every site in this browser), then
+    /// `reload_hard`.
+    pub async fn clear_cache_and_reload(&self) -> Result<()> {
+        use chromiumoxide::cdp::browser_protocol::network::ClearBrowserCacheParams;
+
         self.inner
-            .reload()
+            .execute(ClearBrowserCacheParams::default())
             .await
-            .map_err(|e| Error::NavigationError(e.to_string()))?;
-        Ok(())
+            .map_err(|e| Error::NavigationError(format!("Failed to clear the browser cache: {e}")))?;
+        self.reload_with(true).await
+    }
+
+    async fn reload_with(&self, ignore_cache: bool) -> Result<()> {
+        use chromiumoxide::cdp::browser_protocol::page::ReloadParams;
+
+        self.interceptor.check()?;
+        let _permit = match self.rate_limiter {
+            Some(_) => self.rate_limit(&self.url().await?).await,
+            None => None,
+        };
+        let params = ReloadParams::builder().ignore_cache(ignore_cache).build();
+        let guard = StopOnDrop::new(&self.inner);
+        let result = async {
+            self.inner.execute(params).await?;
+            self.inner.wait_for_navigation().await?;
+            Ok(())
+        }
+        .await
+        .map_err(|e: chromiumoxide::error::CdpError| Error::NavigationError(e.to_string()));
+        // An interception failure explains a failed navigation better than the abort
+        let result = self.interceptor.check().and(result);
+        guard.disarm();
+        telemetry::navigation(&result);
+        result
+    }
+
+    /// Make the tab reusable for an unrelated task: clear the current origin's
+    /// storage (cookies, localStorage, sessionStorage, IndexedDB, caches,
+    /// service workers) and navigate to about:blank.
+    /// Init scripts (stealth, `expose_function`) and request rules belong to the
+    /// tab, not the document, so they stay active for the next `goto`.
+    pub async fn reset(&self) -> Result<()> {
+        use chromiumoxide::cdp::browser_protocol::storage::ClearDataForOriginParams;
+
+        // sessionStorage is not covered by Storage.clearDataForOrigin
+        let origin: String = self
+            .evaluate_typed("(() => { try { sessionStorage.clear(); } catch (e) {} return location.origin; })()")
+            .await?;
+        if origin.starts_with("http") {
+            self.inner
+                .execute(ClearDataForOriginParams::new(origin, "all"))
+                .await
+                .map_err(|e| Error::JsError(format!("Failed to clear storage: {e}")))?;
+        }
+        self.goto("about:blank").await
     }
 
     /// Get the current page URL.
@@ -142,6 +576,13 @@ impl Page {
         el.type_text(text).await
     }
 
+    /// Replace the value of an element matching the given CSS selector with `text`,
+    /// clearing any existing content first (see `Element::fill`).
+    pub async fn fill(&self, selector: &str, text: &str) -> Result<()> {
+        let el = self.find_element(selector).await?;
+        el.fill(text).await
+    }
+
     /// Press a key (e.g., "Enter", "Tab", "Escape"). Uses CDP keyboard events.
     pub async fn press_key(&self, key: &str) -> Result<()> {
         // Focus on the active element / body, then press
@@ -149,12 +590,148 @@ impl Page {
         el.press_key(key).await
     }
 
+    /// Move keyboard focus forward with Tab key presses, as a keyboard user would,
+    /// until it lands on an element matching `by`, and return that element. For
+    /// sites that ignore synthetic mouse events. Fails with `ElementNotFound` if
+    /// nothing matches or focus goes once around the page without reaching a
+    /// match. Focus starts from wherever it is (see `focused_element`).
+    pub async fn tab_to(&self, by: By) -> Result<Element> {
+        let tabbable: u64 = self.evaluate_typed(locator::TABBABLE_COUNT_JS).await?;
+        let targets = locator::find_all(&self.inner, &by).await?;
+        let reached: Result<bool> = async {
+            let Some(first) = targets.first() else {
+                return Ok(false);
+            };
+            let function = format!("function(...targets) {{ return targets.includes({}); }}", locator::FOCUSED_JS);
+            let args: Vec<_> = targets.iter().map(Element::object_arg).collect();
+            for _ in 0..tabbable + 2 {
+                self.press_key("Tab").await?;
+                if first.call_js_with(&function, args.clone()).await?.as_bool() == Some(true) {
+                    return Ok(true);
+                }
+                tokio::time::sleep(locator::TAB_PAUSE + random_up_to(locator::TAB_PAUSE)).await;
+            }
+            Ok(false)
+        }
+        .await;
+        if !reached? {
+            return Err(Error::ElementNotFound(format!("{} (not reachable with Tab)", by.describe())));
+        }
+        self.focused_element()
+            .await?
+            .ok_or_else(|| Error::ElementNotFound(by.describe()))
+    }
+
+    /// The element that has keyboard focus (a web component's host element when
+    /// focus is inside its shadow root), or `None` if nothing but the page itself
+    /// is focused.
+    pub async fn focused_element(&self) -> Result<Option<Element>> {
+        let focused = locator::evaluate_elements(&self.inner, format!("[{}]", locator::FOCUSED_JS)).await?;
+        Ok(focused.into_iter().next())
+    }
+
     /// Hover over an element matching the given CSS selector.
     pub async fn hover(&self, selector: &str) -> Result<()> {
         let el = self.find_element(selector).await?;
         el.hover().await
     }
 
+    /// Hover over `selector` and wait until `appear_selector` is visible, moving
+    /// the pointer slightly within the element meanwhile. Menus and tooltips
+    /// that open after a CSS transition delay or a hover-intent check render
+    /// before this returns, so the next click doesn't race them. Returns the
+    /// element that appeared; fails with `Error::Timeout` after the page timeout.
+    pub async fn hover_and_wait(&self, selector: &str, appear_selector: &str) -> Result<Element> {
+        use chromiumoxide::layout::Point;
+
+        let target = self.find_element(selector).await?;
+        target.hover().await?;
+        let bounds = target.bounding_box().await?;
+        let (center_x, center_y) = (bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0);
+        // Small enough to stay inside the element
+        let reach_x = (bounds.width / 4.0).min(3.0);
+        let reach_y = (bounds.height / 4.0).min(3.0);
+        let keep_hovering = async {
+            loop {
+                tokio::time::sleep(Duration::from_millis(120) + random_up_to(Duration::from_millis(80))).await;
+                let point = Point::new(center_x + random_signed() * reach_x, center_y + random_signed() * reach_y);
+                let _ = self.inner.move_mouse(point).await;
+            }
+        };
+        let appeared = self.wait_for_selector_with(appear_selector, WaitOptions::new(ElementState::Visible));
+        tokio::select! {
+            result = appeared => result?.ok_or_else(|| Error::ElementNotFound(appear_selector.to_string())),
+            _ = keep_hovering => unreachable!("keeps hovering until the element appears"),
+        }
+    }
+
+    /// Drag a slider handle horizontally with a hand-like motion: press, speed up,
+    /// slow down, wobble and settle, then release. For slider widgets (and slide-
+    /// to-verify checks) that reject the straight, constant-speed drag a script
+    /// would do. `target` is a distance in CSS pixels (`180.0`) or the selector of
+    /// the element to drag onto (`".slot"`).
+    pub async fn drag_slider(&self, handle_selector: &str, target: impl Into<DragTarget>) -> Result<()> {
+        use chromiumoxide::cdp::browser_protocol::input::{
+            DispatchMouseEventParams, DispatchMouseEventType, MouseButton,
+        };
+        use chromiumoxide::layout::Point;
+
+        let handle = self.find_element(handle_selector).await?;
+        handle.scroll_into_view().await?;
+        let bounds = handle.bounding_box().await?;
+        let (x, y) = (bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0);
+        let distance = match target.into() {
+            DragTarget::Distance(distance) => distance,
+            DragTarget::Element(selector) => {
+                let slot = self.find_element(&selector).await?;
+                let slot = slot.bounding_box().await?;
+                slot.x + slot.width / 2.0 - x
+            }
+        };
+
+        let mouse = |kind: DispatchMouseEventType, x: f64, y: f64| {
+            DispatchMouseEventParams::builder()
+                .r#type(kind)
+                .x(x)
+                .y(y)
+                .button(MouseButton::Left)
+                .buttons(1)
+                .click_count(1)
+                .build()
+                .map_err(Error::JsError)
+        };
+        let mouse_err = |e: chromiumoxide::error::CdpError| Error::JsError(format!("Drag failed: {e}"));
+
+        // Approach from slightly off the handle, as a hand would
+        self.inner
+            .move_mouse(Point::new(x - 6.0 + random_signed() * 3.0, y + 4.0 + random_signed() * 3.0))
+            .await
+            .map_err(mouse_err)?;
+        tokio::time::sleep(Duration::from_millis(60) + random_up_to(Duration::from_millis(60))).await;
+        self.inner.move_mouse(Point::new(x, y)).await.map_err(mouse_err)?;
+        tokio::time::sleep(Duration::from_millis(80) + random_up_to(Duration::from_millis(80))).await;
+        self.inner
+            .execute(mouse(DispatchMouseEventType::MousePressed, x, y)?)
+            .await
+            .map_err(mouse_err)?;
+
+        let mut end = (x, y);
+        for step in drag::path(x, y, distance) {
+            tokio::time::sleep(step.delay).await;
+            self.inner
+                .execute(mouse(DispatchMouseEventType::MouseMoved, step.x, step.y)?)
+                .await
+                .map_err(mouse_err)?;
+            end = (step.x, step.y);
+        }
+        tokio::time::sleep(Duration::from_millis(80) + random_up_to(Duration::from_millis(100))).await;
+        self.inner
+            .execute(mouse(DispatchMouseEventType::MouseReleased, end.0, end.1)?)
+            .await
+            .map_err(mouse_err)?;
+        Ok(())
+    }
+
     /// Scroll down by the specified number of pixels.
     pub async fn scroll_down(&self, pixels: u32) -> Result<()> {
         let js = format!("window.scrollBy(0, {})", pixels);
@@ -175,6 +752,20 @@ impl Page {
         Ok(())
     }
 
+    /// Scroll to an absolute position in the document, in CSS pixels. The browser
+    /// clamps it to the scrollable range; check `viewport()` for where it landed.
+    pub async fn scroll_to_position(&self, x: f64, y: f64) -> Result<()> {
+        if !x.is_finite() || !y.is_finite() {
+            return Err(Error::ConfigError(format!("Scroll position must be finite, got ({x}, {y})")));
+        }
+        let js = format!("window.scrollTo({{ left: {x}, top: {y}, behavior: 'instant' }})");
+        self.inner
+            .evaluate(js)
+            .await
+            .map_err(|e| Error::JsError(e.to_string()))?;
+        Ok(())
+    }
+
     /// Select an option in a `<select>` element by its value attribute.
     pub async fn select_option(&self, selector: &str, value: &str) -> Result<()> {
         let selector_js = serde_json::to_string(selector)
@@ -198,42 +789,26 @@ impl Page {
         Ok(())
     }
 
-    /// Fill multiple form fields in a single operation.
-    /// Each entry is (css_selector, value). Much faster than calling `type_text`
-    /// repeatedly because it batches everything into one JS evaluation.
-    /// Dispatches `input`, `change`, and `blur` events for framework compatibility.
-    pub async fn fill_form(&self, fields: &[(&str, &str)]) -> Result<()> {
-        let fields_json = serde_json::to_string(
-            &fields.iter().map(|(s, v)| serde_json::json!({"selector": s, "value": v}))
-                .collect::<Vec<_>>()
-        ).map_err(|e| Error::JsError(e.to_string()))?;
-
+    /// Select an option in a `<select>` element by its visible label text
+    /// (exact match after trimming whitespace).
+    pub async fn select_by_label(&self, selector: &str, label: &str) -> Result<()> {
+        let selector_js = serde_json::to_string(selector)
+            .map_err(|e| Error::JsError(e.to_string()))?;
+        let label_js = serde_json::to_string(label)
+            .map_err(|e| Error::JsError(e.to_string()))?;
         let js = format!(
-            r#"(() => {{
-                const fields = {fields_json};
-                const errors = [];
-                for (const f of fields) {{
-                    const el = document.querySelector(f.selector);
-                    if (!el) {{ errors.push('Not found: ' + f.selector); continue; }}
-                    el.focus();
-                    const nativeInputValueSetter = Object.getOwnPropertyDescriptor(
-                        window.HTMLInputElement.prototype, 'value'
-                    )?.set || Object.getOwnPropertyDescriptor(
-                        window.HTMLTextAreaElement.prototype, 'value'
-                    )?.set;
-                    if (nativeInputValueSetter) {{
-                        nativeInputValueSetter.call(el, f.value);
-                    }} else {{
-                        el.value = f.value;
-                    }}
-                    el.dispatchEvent(new Event('input', {{ bubbles: true }}));
-                    el.dispatchEvent(new Event('change', {{ bubbles: true }}));
-                    el.blur();
-                }}
-                if (errors.length > 0) throw new Error(errors.join('; '));
-            }})()"#,
+            r#"
+            (() => {{
+                const el = document.querySelector({selector_js});
+                if (!el) throw new Error('Element not found: ' + {selector_js});
+                const opt = Array.from(el.options).find(o => o.label.trim() === {label_js}.trim());
+                if (!opt) throw new Error('No option with label: ' + {label_js});
+                el.value = opt.value;
+                el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+                el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+            }})()
+            "#,
         );
-
         self.inner
             .evaluate(js)
             .await
@@ -241,143 +816,1051 @@ impl Page {
         Ok(())
     }
 
-    /// Wait for an element matching the given CSS selector to appear in the DOM.
-    /// Uses a MutationObserver for near-instant detection instead of polling.
-    pub async fn wait_for_selector(&self, selector: &str) -> Result<Element> {
+    /// Select several options of a `<select multiple>` by value; all other
+    /// options are deselected.
+    pub async fn select_multiple(&self, selector: &str, values: &[&str]) -> Result<()> {
         let selector_js = serde_json::to_string(selector)
             .map_err(|e| Error::JsError(e.to_string()))?;
-        let timeout_ms = self.default_timeout.as_millis() as u64;
-
+        let values_js = serde_json::to_string(values)
+            .map_err(|e| Error::JsError(e.to_string()))?;
         let js = format!(
-            r#"new Promise((resolve, reject) => {{
-                const sel = {selector_js};
-                const existing = document.querySelector(sel);
-                if (existing) {{ resolve(true); return; }}
-                const timer = setTimeout(() => {{
-                    observer.disconnect();
-                    reject(new Error('Timeout waiting for selector: ' + sel));
-                }}, {timeout_ms});
-                const observer = new MutationObserver(() => {{
-                    if (document.querySelector(sel)) {{
-                        observer.disconnect();
-                        clearTimeout(timer);
-                        resolve(true);
-                    }}
-                }});
-                observer.observe(document.documentElement, {{
-                    childList: true,
-                    subtree: true,
-                    attributes: true,
-                    attributeFilter: ['class', 'id', 'style', 'hidden']
-                }});
-            }})"#,
+            r#"
+            (() => {{
+                const el = document.querySelector({selector_js});
+                if (!el) throw new Error('Element not found: ' + {selector_js});
+                if (!el.multiple) throw new Error('Not a multi-select: ' + {selector_js});
+                const wanted = new Set({values_js});
+                for (const opt of el.options) opt.selected = wanted.has(opt.value);
+                el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+                el.dispatchEvent(new Event('change', {{ bubbles: true }}));
+            }})()
+            "#,
         );
-
         self.inner
             .evaluate(js)
             .await
-            .map_err(|e| {
-                let msg = e.to_string();
-                if msg.contains("Timeout waiting for selector") {
-                    Error::Timeout(format!("Timed out waiting for selector: {}", selector))
-                } else {
-                    Error::JsError(msg)
-                }
-            })?;
-
-        // Element is now in the DOM — get a proper Element handle
-        self.find_element(selector).await
+            .map_err(|e| Error::JsError(e.to_string()))?;
+        Ok(())
     }
 
-    /// Wait for a navigation to complete.
-    pub async fn wait_for_navigation(&self) -> Result<()> {
-        self.inner
-            .wait_for_navigation()
-            .await
-            .map_err(|e| Error::NavigationError(e.to_string()))?;
+    /// Fill multiple form fields in a single operation.
+    /// Each entry is (css_selector, value). Much faster than calling `type_text`
+    /// repeatedly because it batches everything into one JS evaluation.
+    /// Dispatches `input`, `change`, and `blur` events for framework compatibility.
+    pub async fn fill_form(&self, fields: &[(&str, &str)]) -> Result<()> {
+        self.evaluate_with_args::<FillFormReport>(honeypot::FILL_FORM_JS, (honeypot::fill_args(fields), false))
+            .await?;
         Ok(())
     }
 
-    /// Block specified resource types from loading on this page.
-    /// Useful for speeding up page loads when images/CSS/fonts aren't needed.
-    /// Valid types: "image", "stylesheet", "font", "media", "script".
-    /// Call this BEFORE navigating to the target URL.
-    pub async fn block_resources(&self, resource_types: &[&str]) -> Result<()> {
-        use chromiumoxide::cdp::browser_protocol::fetch::{
-            EnableParams, EventRequestPaused, FailRequestParams, RequestPattern,
-        };
-        use chromiumoxide::cdp::browser_protocol::network::{ErrorReason, ResourceType};
-        use futures::StreamExt;
+    /// `fill_form` that leaves alone the fields a person couldn't see or is told
+    /// not to fill: hidden, transparent, tiny or off-screen fields and fields
+    /// named or labeled like bot traps. Filling such a honeypot gets a submission
+    /// flagged as a bot. Returns which fields were filled and which skipped.
+    pub async fn fill_form_safe(&self, fields: &[(&str, &str)]) -> Result<FillFormReport> {
+        self.evaluate_with_args(honeypot::FILL_FORM_JS, (honeypot::fill_args(fields), true)).await
+    }
+
+    /// Gather the validation messages of the form matching `form_selector`: the
+    /// browser's HTML5 constraint messages for every field that is currently
+    /// invalid (whether or not the page shows them yet), and visible error texts
+    /// tied to a field (`aria-errormessage`/`aria-describedby`, `.error`-like
+    /// elements next to it) or to the whole form. Lets a form-filling agent see
+    /// what to fix after a submit.
+    pub async fn collect_validation_errors(&self, form_selector: &str) -> Result<Vec<ValidationError>> {
+        self.evaluate_with_args::<Option<Vec<ValidationError>>>(validation::VALIDATION_ERRORS_JS, form_selector)
+            .await?
+            .ok_or_else(|| Error::ElementNotFound(format!("No form matches {form_selector}")))
+    }
 
-        let patterns: Vec<RequestPattern> = resource_types
+    /// Fill the form fields on the page from `profile` in one batch. Fields are
+    /// matched by type, label, name, id and placeholder; payment, password and
+    /// identity-number fields are never filled, nor are fields that already have a
+    /// value. Returns what was filled and what was skipped, and why.
+    #[cfg(feature = "extraction")]
+    pub async fn autofill(&self, profile: &FillProfile) -> Result<AutofillReport> {
+        let (fills, mut skipped) = autofill::plan(self.get_form_fields().await?, profile);
+        let args: Vec<serde_json::Value> = fills
             .iter()
-            .filter_map(|rt| {
-                let resource_type = match *rt {
-                    "image" => Some(ResourceType::Image),
-                    "stylesheet" => Some(ResourceType::Stylesheet),
-                    "font" => Some(ResourceType::Font),
-                    "media" => Some(ResourceType::Media),
-                    "script" => Some(ResourceType::Script),
-                    _ => None,
-                };
-                resource_type.map(|rt| {
-                    RequestPattern::builder()
-                        .resource_type(rt)
-                        .build()
+            .map(|f| {
+                serde_json::json!({
+                    "id": f.filled.field.id,
+                    "name": f.filled.field.name,
+                    "tag": f.filled.field.tag,
+                    "value": f.value,
                 })
             })
             .collect();
+        let failures: Vec<FillFailure> = if args.is_empty() {
+            Vec::new()
+        } else {
+            self.evaluate_with_args(autofill::AUTOFILL_JS, (args,)).await?
+        };
 
-        if patterns.is_empty() {
-            return Ok(());
+        let mut filled = Vec::new();
+        for (index, fill) in fills.into_iter().enumerate() {
+            match failures.iter().find(|f| f.index == index) {
+                Some(failure) => skipped.push(SkippedField {
+                    field: fill.filled.field,
+                    reason: failure.reason,
+                }),
+                None => filled.push(fill.filled),
+            }
         }
+        Ok(AutofillReport { filled, skipped })
+    }
 
-        // Set up event listener BEFORE enabling fetch to avoid race condition
-        let mut pause_events = self
-            .inner
-            .event_listener::<EventRequestPaused>()
-            .await
-            .map_err(|e| Error::JsError(format!("Failed to listen for request paused events: {e}")))?;
+    /// Log in with `spec` on the current page, which should show a login form.
+    /// Handles forms where the password is on the same screen as the username and
+    /// multi-step flows that ask for the username (or an extra identifier) first.
+    /// A page without a recognizable login form fails with `ElementNotFound`.
+    pub async fn login(&self, spec: &LoginSpec) -> Result<LoginOutcome> {
+        let hints = &spec.hints;
+        let timeout = hints.timeout.unwrap_or(self.default_timeout);
+        let start_url = self.url().await?;
+        let mut deadline = tokio::time::Instant::now() + timeout;
+        let mut found_form = false;
+        let mut submitted_password = false;
+
+        loop {
+            // Fails, or sees a half-loaded document, while the page navigates
+            // between steps; look again on the next round.
+            let probe = self.login_probe(hints).await;
+            found_form |= probe.username || probe.password;
+            if !probe.ready {
+                if tokio::time::Instant::now() >= deadline {
+                    return Err(Error::Timeout("login page to load".to_string()));
+                }
+                tokio::time::sleep(Duration::from_millis(250)).await;
+                continue;
+            }
 
-        let enable = EnableParams::builder()
-            .patterns(patterns)
-            .build();
-        self.inner
-            .execute(enable)
-            .await
-            .map_err(|e| Error::JsError(format!("Failed to enable fetch for resource blocking: {e}")))?;
+            if probe.success {
+                return Ok(LoginOutcome::Success);
+            }
+            if probe.captcha {
+                return Ok(LoginOutcome::Challenge(LoginChallenge::Captcha));
+            }
+            if probe.otp {
+                return Ok(LoginOutcome::Challenge(LoginChallenge::OneTimeCode));
+            }
 
-        let page = self.inner.clone();
-        tokio::spawn(async move {
-            while let Some(event) = pause_events.next().await {
-                let params = FailRequestParams::new(
-                    event.request_id.clone(),
-                    ErrorReason::BlockedByClient,
-                );
-                let _ = page.execute(params).await;
+            if submitted_password {
+                if let (true, Some(error)) = (probe.password, &probe.error) {
+                    return Ok(LoginOutcome::Failure(error.clone()));
+                }
+                if hints.success_selector.is_none() && !probe.password && !probe.username {
+                    let url = self.url().await.unwrap_or_default();
+                    if url != start_url || probe.error.is_none() {
+                        return Ok(LoginOutcome::Success);
+                    }
+                }
+            } else if probe.password {
+                if probe.username && probe.username_value != spec.username {
+                    self.locator(probe.selector("username")?).fill(&spec.username).await?;
+                }
+                self.locator(probe.selector("password")?).fill(&spec.password).await?;
+                self.submit_login_step(&probe, "password").await?;
+                submitted_password = true;
+                deadline = tokio::time::Instant::now() + timeout;
+            } else if probe.username && probe.username_value != spec.username {
+                self.locator(probe.selector("username")?).fill(&spec.username).await?;
+                self.submit_login_step(&probe, "username").await?;
+                deadline = tokio::time::Instant::now() + timeout;
+            } else if let (true, Some(error)) = (probe.username, &probe.error) {
+                return Ok(LoginOutcome::Failure(error.clone()));
             }
-        });
 
-        Ok(())
+            if tokio::time::Instant::now() >= deadline {
+                return match (found_form, submitted_password) {
+                    (false, _) => Err(Error::ElementNotFound("login form".to_string())),
+                    (true, false) => Ok(LoginOutcome::Failure(
+                        "password field did not appear".to_string(),
+                    )),
+                    (true, true) => Ok(LoginOutcome::Failure(
+                        "still on the login form after submitting".to_string(),
+                    )),
+                };
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
     }
 
-    // ── Observations ────────────────────────────────────────────────
+    /// Fill in the one-time code for `secret` (a base32 TOTP secret or an
+    /// `otpauth://` URI) and submit it, e.g. after `login` returned
+    /// `LoginChallenge::OneTimeCode`. The field is found by `selector_hint` or
+    /// detected; codes split over one box per digit are typed. A code about to
+    /// expire is not used; the next one is waited for instead.
+    #[cfg(feature = "totp")]
+    pub async fn submit_otp(&self, selector_hint: Option<&str>, secret: &str) -> Result<LoginOutcome> {
+        let totp = if secret.starts_with("otpauth://") {
+            crate::totp::Totp::from_uri(secret)?
+        } else {
+            crate::totp::Totp::new(secret)?
+        };
+        let hints = login::LoginHints {
+            otp_selector: selector_hint.map(str::to_string),
+            ..Default::default()
+        };
 
-    /// Take a screenshot of the visible viewport (PNG format).
-    pub async fn screenshot(&self) -> Result<Vec<u8>> {
-        let params = ScreenshotParams::builder()
-            .format(CaptureScreenshotFormat::Png)
-            .build();
-        self.inner
-            .screenshot(params)
-            .await
-            .map_err(|e| Error::ScreenshotError(e.to_string()))
-    }
+        let deadline = tokio::time::Instant::now() + self.default_timeout;
+        let probe = loop {
+            let probe = self.login_probe(&hints).await;
+            if probe.ready && probe.otp {
+                break probe;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(Error::ElementNotFound("one-time code field".to_string()));
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        };
 
-    /// Take a screenshot and save it to a file.
-    pub async fn screenshot_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
-        let params = ScreenshotParams::builder()
-            .format(CaptureScreenshotFormat::Png)
+        if totp.remaining() < 3 {
+            tokio::time::sleep(Duration::from_secs(totp.remaining())).await;
+        }
+        let field = self.locator(probe.selector("otp")?);
+        if probe.otp_split {
+            field.type_text(&totp.now()).await?;
+        } else {
+            field.fill(&totp.now()).await?;
+        }
+        // Split-digit inputs often submit on the last digit.
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let probe = self.login_probe(&hints).await;
+        if probe.otp {
+            self.submit_login_step(&probe, "otp").await?;
+        }
+
+        let deadline = tokio::time::Instant::now() + self.default_timeout;
+        loop {
+            let probe = self.login_probe(&hints).await;
+            if probe.ready {
+                if probe.captcha {
+                    return Ok(LoginOutcome::Challenge(LoginChallenge::Captcha));
+                }
+                if !probe.otp {
+                    return Ok(LoginOutcome::Success);
+                }
+                if let Some(error) = probe.error {
+                    return Ok(LoginOutcome::Failure(error));
+                }
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Ok(LoginOutcome::Failure("still asking for a code".to_string()));
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+    }
+
+    /// Look for login controls; a page that can't be evaluated (e.g. mid-navigation)
+    /// reads as not ready.
+    async fn login_probe(&self, hints: &login::LoginHints) -> LoginProbe {
+        self.evaluate_with_args(login::LOGIN_PROBE_JS, (hints,))
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Click the step's submit button, or press Enter in `field`.
+    async fn submit_login_step(&self, probe: &LoginProbe, field: &str) -> Result<()> {
+        if probe.submit {
+            self.locator(probe.selector("submit")?).click().await
+        } else {
+            self.find_element(probe.selector(field)?).await?.press_key("Enter").await
+        }
+    }
+
+    /// Close modals, newsletter popups, app-install banners and other overlays
+    /// covering the page, and undo the scroll lock they set, so screenshots and
+    /// extraction see the content underneath. Only elements with dialog semantics
+    /// (`<dialog>`, `role=dialog`, `aria-modal`) or a fixed element named like an
+    /// overlay (`modal`, `popup`, `app-banner`...) that has a close control are
+    /// touched, so fixed app shells and headers stay. Clicks the close control,
+    /// and hides the overlay if it is still shown. Cookie and consent banners are
+    /// left alone, since closing them is a consent decision. Returns how many
+    /// overlays were dismissed.
+    pub async fn dismiss_overlays(&self) -> Result<usize> {
+        let js = r#"(async () => {
+            const keywords = /newsletter|subscribe|popup|pop-up|modal|overlay|lightbox|interstitial|app-?banner|smart-?banner/i;
+            const shown = (el) => {
+                const rect = el.getBoundingClientRect();
+                if (rect.width < 1 || rect.height < 1) return false;
+                const style = getComputedStyle(el);
+                return style.visibility !== 'hidden' && style.display !== 'none' && parseFloat(style.opacity) > 0.05;
+            };
+            const closeButton = (el) => {
+                const byLabel = el.querySelector('[aria-label*="close" i], [aria-label*="dismiss" i], [data-dismiss], [data-action="close"]');
+                if (byLabel && shown(byLabel)) return byLabel;
+                return [...el.querySelectorAll('button, a, [role=button], [class*="close" i]')].filter(shown)
+                    .find(b => /^(×|✕|✖|x|close|no,? thanks|not now|dismiss|maybe later|skip|continue to (the )?site)$/i
+                        .test((b.innerText || b.getAttribute('title') || '').trim())
+                        || /(^|[-_ ])close([-_ ]|$)/i.test(typeof b.className === 'string' ? b.className : ''));
+            };
+            const isOverlay = (el) => {
+                if (el === document.body || el === document.documentElement || !shown(el)) return false;
+                if (el.matches('dialog[open]')) return true;
+                const dialog = el.matches('[role=dialog], [role=alertdialog], [aria-modal=true]');
+                if (!dialog) {
+                    if (getComputedStyle(el).position !== 'fixed') return false;
+                    const named = (el.id || '') + ' ' + (typeof el.className === 'string' ? el.className : '')
+                        + ' ' + (el.getAttribute('aria-label') || '');
+                    if (!keywords.test(named)) return false;
+                }
+                return !!closeButton(el);
+            };
+
+            const overlays = [...document.querySelectorAll('body *')].filter(isOverlay);
+            const outermost = overlays.filter(el => !overlays.some(o => o !== el && o.contains(el)));
+            for (const el of outermost) {
+                if (el.tagName === 'DIALOG' && typeof el.close === 'function') { el.close(); continue; }
+                const button = closeButton(el);
+                if (button) {
+                    button.click();
+                    await new Promise(r => setTimeout(r, 300));
+                }
+                if (el.isConnected && shown(el)) el.style.setProperty('display', 'none', 'important');
+            }
+            if (outermost.length > 0) {
+                for (const root of [document.documentElement, document.body]) {
+                    if (!root) continue;
+                    const style = getComputedStyle(root);
+                    if (style.overflow === 'hidden' || style.overflowY === 'hidden') {
+                        root.style.setProperty('overflow', 'auto', 'important');
+                    }
+                    if (root === document.body && style.position === 'fixed') {
+                        root.style.setProperty('position', 'static', 'important');
+                    }
+                }
+            }
+            return outermost.length;
+        })()"#;
+        self.evaluate_typed(js).await
+    }
+
+    /// Wait for an element matching the given CSS selector to be visible: in the
+    /// DOM and rendered, so it can be clicked. Uses a MutationObserver for
+    /// near-instant detection. See `wait_for_selector_with` for other states.
+    pub async fn wait_for_selector(&self, selector: &str) -> Result<Element> {
+        self.wait_for_selector_with(selector, WaitOptions::default())
+            .await?
+            .ok_or_else(|| Error::ElementNotFound(selector.to_string()))
+    }
+
+    /// Wait for the first element matching `selector` to be attached, visible or
+    /// hidden (`options.state`). Returns the element, or `None` if it is hidden
+    /// by not being in the DOM at all.
+    pub async fn wait_for_selector_with(
+        &self,
+        selector: &str,
+        options: WaitOptions,
+    ) -> Result<Option<Element>> {
+        self.evaluate_with_args::<bool>(locator::WAIT_FOR_JS, options.js_args(selector, self.default_timeout))
+            .await
+            .map_err(|e| match e {
+                Error::JsError(msg) if msg.contains("Timeout waiting for selector") => {
+                    Error::Timeout(format!("Timed out waiting for selector: {}", selector))
+                }
+                e => e,
+            })?;
+
+        // The element is in the wanted state — get a proper Element handle
+        match options.state {
+            ElementState::Hidden => Ok(self.find_element(selector).await.ok()),
+            _ => self.find_element(selector).await.map(Some),
+        }
+    }
+
+    /// Wait for a navigation to complete.
+    pub async fn wait_for_navigation(&self) -> Result<()> {
+        self.inner
+            .wait_for_navigation()
+            .await
+            .map_err(|e| Error::NavigationError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Start recording the user's clicks, typing, `<select>` changes, Enter presses
+    /// and address-bar navigations on this page as an `ActionScript`. Record a flow
+    /// once headful, save the script as JSON, then run it headless with `replay`.
+    /// Text typed into password and one-time-code fields, and fields matching
+    /// `set_redaction`'s selectors, is recorded as the mask, so the script won't
+    /// replay those fields.
+    pub async fn start_action_recording(&self) -> Result<ActionRecorder> {
+        recorder::start(&self.inner, self.redaction.clone().unwrap_or_default()).await
+    }
+
+    /// Run a recorded `ActionScript`, one `execute_action` per step.
+    pub async fn replay(&self, script: &ActionScript) -> Result<()> {
+        for step in &script.steps {
+            self.execute_action(step).await?;
+        }
+        Ok(())
+    }
+
+    /// Run one `Action`. Clicks, typing and extraction wait for their element
+    /// (see `Locator`), so actions after a navigation don't need explicit waits.
+    pub async fn execute_action(&self, action: &Action) -> Result<ActionResult> {
+        let started = Instant::now();
+        let result = self.run_action(action).await;
+        telemetry::action(action.name(), started, &result);
+        result
+    }
+
+    /// Whether `action` may change what the site stores: typing, choosing an
+    /// option, pressing Enter, or clicking a form's submit button.
+    async fn mutates(&self, action: &Action) -> Result<bool> {
+        match action {
+            Action::Type { .. } | Action::Select { .. } => Ok(true),
+            Action::Press { key } => Ok(key.eq_ignore_ascii_case("enter")),
+            Action::Click { selector } => {
+                self.wait_for_selector_with(selector, WaitOptions::new(ElementState::Attached))
+                    .await?;
+                self.evaluate_with_args(readonly::SUBMITS_FORM_JS, selector).await
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// `action` as it should appear in a log: text typed into a password,
+    /// one-time-code or payment field, or one matching the redaction selectors,
+    /// is replaced by the mask. The field is looked up now, so check before the
+    /// action runs if it may navigate away; text for a field that can't be found
+    /// or checked is masked too.
+    pub async fn redact_action(&self, action: &Action) -> Action {
+        let Action::Type { selector, .. } = action else {
+            return action.clone();
+        };
+        let redaction = self.redaction.clone().unwrap_or_default();
+        let function = format!(
+            "(selector, selectors) => {{ const el = document.querySelector(selector); \
+             return el ? ({})(el, selectors) : true; }}",
+            redact::SENSITIVE_FIELD_JS
+        );
+        let sensitive: bool = self
+            .evaluate_with_args(&function, (selector, &redaction.selectors))
+            .await
+            .unwrap_or(true);
+        if !sensitive {
+            return action.clone();
+        }
+        Action::Type { selector: selector.clone(), text: redaction.mask }
+    }
+
+    async fn run_action(&self, action: &Action) -> Result<ActionResult> {
+        if let Some(ref read_only) = self.read_only {
+            if self.mutates(action).await? && !read_only.allows(action) {
+                return Err(Error::ActionRejected(format!("{} in read-only mode", action.name())));
+            }
+        }
+        match action {
+            Action::Goto { url } => self.goto(url).await?,
+            Action::Click { selector } => self.locator(selector).click().await?,
+            Action::Type { selector, text } => self.locator(selector).fill(text).await?,
+            Action::Press { key } => self.press_key(key).await?,
+            Action::Select { selector, value } => {
+                // Styled dropdowns often hide the native <select>
+                self.wait_for_selector_with(selector, WaitOptions::new(ElementState::Attached))
+                    .await?;
+                self.select_option(selector, value).await?;
+            }
+            Action::Scroll { selector: Some(selector), .. } => {
+                self.locator(selector).element().await?.scroll_into_view().await?;
+            }
+            Action::Scroll { selector: None, pixels } => {
+                let amount = u32::try_from(pixels.unsigned_abs()).unwrap_or(u32::MAX);
+                if *pixels < 0 {
+                    self.scroll_up(amount).await?;
+                } else {
+                    self.scroll_down(amount).await?;
+                }
+            }
+            Action::Wait { selector: Some(selector), .. } => {
+                self.wait_for_selector(selector).await?;
+            }
+            Action::Wait { selector: None, ms } => {
+                tokio::time::sleep(Duration::from_millis(ms.unwrap_or(0))).await;
+            }
+            Action::Extract { selector, attribute } => {
+                let element = self.locator(selector).element().await?;
+                let value = match attribute {
+                    Some(name) => element.get_attribute(name).await?.unwrap_or_default(),
+                    None => element.inner_text().await?,
+                };
+                return Ok(ActionResult::Extracted(value));
+            }
+        }
+        Ok(ActionResult::Done)
+    }
+
+    /// Block specified resource types from loading on this page.
+    /// Useful for speeding up page loads when images/CSS/fonts aren't needed.
+    /// Valid types: "image", "stylesheet", "font", "media", "script".
+    /// Adds to any types already blocked. Call this BEFORE navigating to the target URL.
+    pub async fn block_resources(&self, resource_types: &[&str]) -> Result<()> {
+        let types: Vec<_> = resource_types
+            .iter()
+            .filter_map(|rt| parse_resource_type(rt))
+            .collect();
+        if types.is_empty() {
+            return Ok(());
+        }
+
+        self.interceptor.update(|rules| {
+            for rt in types {
+                if !rules.blocked_resources.contains(&rt) {
+                    rules.blocked_resources.push(rt);
+                }
+            }
+        });
+        self.interceptor.apply().await
+    }
+
+    /// Answer HTTP basic/digest auth challenges (401) from `origin` with the given
+    /// credentials, e.g. `page.authenticate("https://intranet.example.com", "user", "pass")`.
+    /// The origin is scheme + host (+ port if non-default). If the server rejects the
+    /// credentials the challenge is cancelled rather than retried.
+    /// Call this BEFORE navigating to the target URL.
+    pub async fn authenticate(&self, origin: &str, username: &str, password: &str) -> Result<()> {
+        let credentials = (Arc::from(username), Arc::from(password));
+        self.interceptor.update(|rules| {
+            rules.origin_auth.insert(normalize_origin(origin), credentials);
+        });
+        self.interceptor.apply().await
+    }
+
+    /// Block requests whose URL matches any of the given patterns (`*` wildcards,
+    /// e.g. `"*.doubleclick.net/*"`). Replaces any patterns set by a previous call;
+    /// combine with `blocklist::ADS_AND_TRACKERS` to block both.
+    /// Call this BEFORE navigating to the target URL.
+    pub async fn block_urls(&self, patterns: &[&str]) -> Result<()> {
+        use chromiumoxide::cdp::browser_protocol::network::{EnableParams, SetBlockedUrLsParams};
+
+        let urls = patterns.iter().map(|p| p.to_string()).collect();
+        self.inner
+            .execute(EnableParams::default())
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to enable network domain: {e}")))?;
+        self.inner
+            .execute(SetBlockedUrLsParams::new(urls))
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to set blocked URLs: {e}")))?;
+        Ok(())
+    }
+
+    #[cfg(feature = "low-resource")]
+    /// Block common ad, analytics and tracker hosts (see `blocklist::ADS_AND_TRACKERS`).
+    /// Replaces any patterns set by `block_urls`.
+    pub async fn block_ads(&self) -> Result<()> {
+        self.block_urls(ADS_AND_TRACKERS).await
+    }
+
+    /// Send the given HTTP headers with every request from this page.
+    /// Replaces any headers set by a previous call.
+    pub async fn set_extra_headers(&self, headers: &HashMap<String, String>) -> Result<()> {
+        use chromiumoxide::cdp::browser_protocol::network::{
+            EnableParams, Headers, SetExtraHttpHeadersParams,
+        };
+
+        let headers_json = serde_json::to_value(headers)
+            .map_err(|e| Error::JsError(e.to_string()))?;
+        self.inner
+            .execute(EnableParams::default())
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to enable network domain: {e}")))?;
+        self.inner
+            .execute(SetExtraHttpHeadersParams::new(Headers::new(headers_json)))
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to set extra headers: {e}")))?;
+        Ok(())
+    }
+
+    /// Throttle this page's network connection.
+    pub async fn set_network_throttle(&self, throttle: &NetworkThrottle) -> Result<()> {
+        use chromiumoxide::cdp::browser_protocol::network::{
+            EmulateNetworkConditionsByRuleParams, EnableParams, NetworkConditions,
+        };
+
+        // -1 disables throttling for that direction; an empty URL pattern matches all requests
+        let download = throttle.download_throughput.map_or(-1.0, |b| b as f64);
+        let upload = throttle.upload_throughput.map_or(-1.0, |b| b as f64);
+        let conditions = NetworkConditions::new(
+            "",
+            throttle.latency.as_millis() as f64,
+            download,
+            upload,
+        );
+        let params = EmulateNetworkConditionsByRuleParams::new(false, vec![conditions]);
+        self.inner
+            .execute(EnableParams::default())
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to enable network domain: {e}")))?;
+        self.inner
+            .execute(params)
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to set network throttle: {e}")))?;
+        Ok(())
+    }
+
+    /// Present `identity` from now on: user agent, client hints, `navigator.platform`,
+    /// `Accept-Language`, viewport and screen size and time zone. Overrides the
+    /// stealth script's fixed values; requests already sent keep the old headers.
+    pub async fn set_identity(&self, identity: &Identity) -> Result<()> {
+        use chromiumoxide::cdp::browser_protocol::emulation::{
+            SetTimezoneOverrideParams, SetUserAgentOverrideParams, UserAgentBrandVersion,
+            UserAgentMetadata,
+        };
+        use chromiumoxide::cdp::browser_protocol::page::{
+            AddScriptToEvaluateOnNewDocumentParams, RemoveScriptToEvaluateOnNewDocumentParams,
+        };
+
+        let major = identity.chrome_version.split('.').next().unwrap_or_default();
+        let brands = [("Google Chrome", major), ("Chromium", major), ("Not?A_Brand", "24")];
+        let full_brands = [
+            ("Google Chrome", identity.chrome_version.as_str()),
+            ("Chromium", identity.chrome_version.as_str()),
+            ("Not?A_Brand", "24.0.0.0"),
+        ];
+        let metadata = UserAgentMetadata {
+            brands: Some(brands.iter().map(|(b, v)| UserAgentBrandVersion::new(*b, *v)).collect()),
+            full_version_list: Some(full_brands.iter().map(|(b, v)| UserAgentBrandVersion::new(*b, *v)).collect()),
+            platform: identity.hints_platform.clone(),
+            platform_version: identity.hints_platform_version.clone(),
+            architecture: identity.architecture.clone(),
+            model: String::new(),
+            mobile: identity.mobile,
+            bitness: Some("64".to_string()),
+            wow64: Some(false),
+            form_factors: None,
+        };
+        let params = SetUserAgentOverrideParams {
+            user_agent: identity.user_agent.clone(),
+            accept_language: Some(identity.accept_language.clone()),
+            platform: Some(identity.platform.clone()),
+            user_agent_metadata: Some(metadata),
+        };
+        self.inner
+            .execute(params)
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to set user agent: {e}")))?;
+
+        let dpr = self.viewport().await?.dpr;
+        let (width, height) = (identity.viewport_width as i64, identity.viewport_height as i64);
+        let mut metrics = SetDeviceMetricsOverrideParams::new(width, height, dpr, identity.mobile);
+        // `screen` matches the window, as in a maximized browser
+        metrics.screen_width = Some(width);
+        metrics.screen_height = Some(height);
+        self.inner
+            .execute(metrics.clone())
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to set viewport: {e}")))?;
+        *self.device_metrics.lock().unwrap_or_else(|e| e.into_inner()) = Some(metrics);
+        if let Some(ref timezone) = identity.timezone {
+            self.inner
+                .execute(SetTimezoneOverrideParams::new(timezone.clone()))
+                .await
+                .map_err(|e| Error::JsError(format!("Failed to set time zone {timezone:?}: {e}")))?;
+        }
+        let installed = self.identity_script.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(identifier) = installed {
+            self.inner
+                .execute(RemoveScriptToEvaluateOnNewDocumentParams::new(identifier))
+                .await
+                .map_err(|e| Error::JsError(format!("Failed to replace identity script: {e}")))?;
+        }
+        let identifier = self
+            .inner
+            .execute(AddScriptToEvaluateOnNewDocumentParams::new(identity::UNPIN_NAVIGATOR_JS))
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to install identity script: {e}")))?
+            .result
+            .identifier;
+        *self.identity_script.lock().unwrap_or_else(|e| e.into_inner()) = Some(identifier);
+        self.inner
+            .evaluate(identity::UNPIN_NAVIGATOR_JS)
+            .await
+            .map_err(|e| Error::JsError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Render this page at `factor` device pixels per CSS pixel, keeping the current
+    /// viewport size and layout. Screenshots scale with it: below 1 for smaller
+    /// images to send to a vision model, 2 or more for crisper text to OCR.
+    /// Keeps the mobile emulation of the last `set_identity`.
+    pub async fn set_zoom(&self, factor: f64) -> Result<()> {
+        if !factor.is_finite() || factor <= 0.0 {
+            return Err(Error::ConfigError(format!("zoom factor must be positive, got {factor}")));
+        }
+        // A mobile layout viewport is wider than the screen; reuse the size set
+        let metrics = self.device_metrics.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let params = match metrics {
+            Some(metrics) => SetDeviceMetricsOverrideParams { device_scale_factor: factor, ..metrics },
+            None => {
+                let viewport = self.viewport().await?;
+                let (width, height) = (viewport.width.round() as i64, viewport.height.round() as i64);
+                SetDeviceMetricsOverrideParams::new(width, height, factor, false)
+            }
+        };
+        self.inner
+            .execute(params)
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to set zoom: {e}")))?;
+        Ok(())
+    }
+
+    /// Make this the browser's active tab, in front of the others. Headful
+    /// Chrome only paints the tab in front.
+    pub async fn bring_to_front(&self) -> Result<()> {
+        self.inner
+            .bring_to_front()
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to bring page to front: {e}")))?;
+        Ok(())
+    }
+
+    /// Whether this tab is being shown (`document.visibilityState` is
+    /// `visible`), rather than sitting behind another tab or in a minimized
+    /// window.
+    pub async fn is_active(&self) -> Result<bool> {
+        // In an isolated world, past `keep_active`'s spoofing
+        self.evaluate_isolated("document.visibilityState === 'visible'").await
+    }
+
+    /// Keep this tab working as if it were in front while it sits in the
+    /// background, for scraping in several tabs at once: emulates focus, wakes
+    /// the page if Chrome froze it, and has it report itself visible so sites
+    /// don't pause their polling. Chrome's own background timer throttling is
+    /// already off at launch. `false` undoes it.
+    pub async fn keep_active(&self, enabled: bool) -> Result<()> {
+        use chromiumoxide::cdp::browser_protocol::emulation::SetFocusEmulationEnabledParams;
+        use chromiumoxide::cdp::browser_protocol::page::{
+            AddScriptToEvaluateOnNewDocumentParams, RemoveScriptToEvaluateOnNewDocumentParams,
+            SetWebLifecycleStateParams, SetWebLifecycleStateState,
+        };
+
+        let failed = |e: chromiumoxide::error::CdpError| Error::JsError(format!("Failed to keep page active: {e}"));
+        self.inner
+            .execute(SetFocusEmulationEnabledParams::new(enabled))
+            .await
+            .map_err(failed)?;
+        let installed = self.keep_active_script.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(identifier) = installed {
+            self.inner
+                .execute(RemoveScriptToEvaluateOnNewDocumentParams::new(identifier))
+                .await
+                .map_err(failed)?;
+        }
+        if !enabled {
+            return self.evaluate_void(lifecycle::RELEASE_VISIBLE_JS).await;
+        }
+
+        self.inner
+            .execute(SetWebLifecycleStateParams::new(SetWebLifecycleStateState::Active))
+            .await
+            .map_err(failed)?;
+        let identifier = self
+            .inner
+            .execute(AddScriptToEvaluateOnNewDocumentParams::new(lifecycle::KEEP_VISIBLE_JS))
+            .await
+            .map_err(failed)?
+            .result
+            .identifier;
+        *self.keep_active_script.lock().unwrap_or_else(|e| e.into_inner()) = Some(identifier);
+        self.evaluate_void(lifecycle::KEEP_VISIBLE_JS).await
+    }
+
+    /// With `set_screenshot_background`, bring a background tab to the front and
+    /// wait for it to paint, so a screenshot of it isn't blank or stale.
+    async fn ensure_painted(&self) -> Result<()> {
+        if !self.screenshot_background || self.is_active().await? {
+            return Ok(());
+        }
+        self.bring_to_front().await?;
+        // A minimized window never paints; capture whatever there is then
+        let frame = self.evaluate_void(navigation::NEXT_FRAME_JS);
+        match tokio::time::timeout(Duration::from_secs(1), frame).await {
+            Ok(result) => result,
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Add a stylesheet to the current document and to every document this page
+    /// loads afterwards.
+    pub async fn add_style(&self, css: &str) -> Result<()> {
+        use chromiumoxide::cdp::browser_protocol::page::AddScriptToEvaluateOnNewDocumentParams;
+
+        let script = style::inject_js(css)?;
+        self.inner
+            .execute(AddScriptToEvaluateOnNewDocumentParams::new(script.clone()))
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to install style: {e}")))?;
+        self.inner
+            .evaluate(script)
+            .await
+            .map_err(|e| Error::JsError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Finish CSS animations and transitions instantly and turn off smooth scrolling,
+    /// from now on and on later documents, so pages settle sooner after actions and
+    /// screenshots are repeatable. Animations driven from JavaScript are not affected.
+    pub async fn disable_animations(&self) -> Result<()> {
+        self.add_style(style::DISABLE_ANIMATIONS_CSS).await
+    }
+
+    /// Outline every visible element matching `selector` with a box in `color` (any
+    /// CSS color), e.g. to show in a screenshot which element an agent acted on.
+    /// Boxes stay until `clear_highlights` or the next navigation, and don't block
+    /// clicks.
+    pub async fn highlight(&self, selector: &str, color: &str) -> Result<()> {
+        let drawn: usize = self.evaluate_with_args(style::HIGHLIGHT_JS, (selector, color)).await?;
+        if drawn == 0 {
+            return Err(Error::ElementNotFound(selector.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Remove all boxes drawn by `highlight`.
+    pub async fn clear_highlights(&self) -> Result<()> {
+        self.inner
+            .evaluate(style::CLEAR_HIGHLIGHTS_JS)
+            .await
+            .map_err(|e| Error::JsError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Make an HTTP request from inside the page with `fetch()`, so it carries the
+    /// page's cookies, user agent and proxy. Useful for calling a site's JSON API
+    /// once logged in. Subject to the page's CORS rules for cross-origin URLs.
+    /// e.g. `page.fetch(FetchRequest::get("/api/me")).await?.json::<Me>()`
+    /// File parts of multipart requests must be on the machine running Chrome.
+    pub async fn fetch(&self, request: impl Into<FetchRequest>) -> Result<FetchResponse> {
+        use chromiumoxide::cdp::js_protocol::runtime::ReleaseObjectParams;
+
+        let request = request.into();
+        let _permit = self.rate_limit(&request.url).await;
+        let mut inputs = Vec::new();
+        let attached = self.attach_files(&request.parts, &mut inputs).await;
+        let response = match attached {
+            Ok(()) => self
+                .call_fetch(&request, &inputs)
+                .await
+                .map_err(|e| Error::JsError(format!("fetch failed: {e}"))),
+            Err(e) => Err(e),
+        };
+        for input in inputs {
+            let _ = self.inner.execute(ReleaseObjectParams::new(input)).await;
+        }
+        response
+    }
+
+    /// Put each file part's file in a file input of its own (DOM.setFileInputFiles),
+    /// pushing the inputs to `inputs`. The inputs stay out of the document.
+    async fn attach_files(&self, parts: &[FormPart], inputs: &mut Vec<RemoteObjectId>) -> Result<()> {
+        use chromiumoxide::cdp::browser_protocol::dom::SetFileInputFilesParams;
+        use chromiumoxide::cdp::js_protocol::runtime::EvaluateParams;
+
+        for part in parts {
+            let FormPart::File { path, .. } = part else {
+                continue;
+            };
+            let path = std::fs::canonicalize(path)?;
+            let params = EvaluateParams::builder()
+                .expression(NEW_FILE_INPUT_JS)
+                .return_by_value(false)
+                .build()
+                .map_err(Error::JsError)?;
+            let input = self
+                .inner
+                .execute(params)
+                .await?
+                .result
+                .result
+                .object_id
+                .ok_or_else(|| Error::JsError("could not create a file input".to_string()))?;
+            inputs.push(input.clone());
+            let params = SetFileInputFilesParams::builder()
+                .file(path.to_string_lossy())
+                .object_id(input)
+                .build()
+                .map_err(Error::JsError)?;
+            self.inner.execute(params).await?;
+        }
+        Ok(())
+    }
+
+    /// Run `FETCH_JS` on `request`, passing the file `inputs` by reference.
+    async fn call_fetch(&self, request: &FetchRequest, inputs: &[RemoteObjectId]) -> Result<FetchResponse> {
+        use chromiumoxide::cdp::js_protocol::runtime::{CallArgument, CallFunctionOnParams};
+
+        let request = serde_json::to_value(request).map_err(|e| Error::JsError(e.to_string()))?;
+        let args = std::iter::once(CallArgument::builder().value(request).build())
+            .chain(inputs.iter().map(|id| CallArgument::builder().object_id(id.clone()).build()));
+        let params = CallFunctionOnParams::builder()
+            .function_declaration(FETCH_JS)
+            .arguments(args)
+            .build()
+            .map_err(Error::JsError)?;
+        self.inner
+            .evaluate_function(params)
+            .await
+            .map_err(|e| Error::JsError(e.to_string()))?
+            .into_value()
+            .map_err(|e| Error::JsError(e.to_string()))
+    }
+
+    /// Download `url` (relative to the page) to `path` with the page's cookies,
+    /// through Chrome's own network stack and so its proxy and client
+    /// certificates: for export links behind a login, where a plain HTTP client
+    /// would lack the session. Not subject to CORS. Returns the bytes written;
+    /// `path` only appears once the download is complete.
+    pub async fn download_url(&self, url: &str, path: impl AsRef<Path>) -> Result<u64> {
+        self.download_url_with(url, path, |_| {}).await
+    }
+
+    /// `download_url`, calling `progress` after each chunk (up to 1 MiB) is written.
+    pub async fn download_url_with(
+        &self,
+        url: &str,
+        path: impl AsRef<Path>,
+        progress: impl FnMut(DownloadProgress) + Send,
+    ) -> Result<u64> {
+        let url: String = self
+            .evaluate_with_args(RESOLVE_URL_JS, url)
+            .await?;
+        let _permit = self.rate_limit(&url).await;
+        download::download(&self.inner, &url, path.as_ref(), progress).await
+    }
+
+    /// Capture XHR/fetch responses (including GraphQL calls) whose URL matches
+    /// `url_pattern` (`*` wildcards, e.g. `"*/api/graphql*"`), to read the site's
+    /// own API payloads instead of scraping the DOM. Call this BEFORE triggering the
+    /// requests; capturing stops when the returned stream is dropped.
+    pub async fn capture_responses(
+        &self,
+        url_pattern: &str,
+    ) -> Result<impl Stream<Item = CapturedResponse> + Send + Unpin> {
+        capture::capture(&self.inner, url_pattern).await
+    }
+
+    /// Capture the GraphQL operations the page sends from now on (POSTed JSON,
+    /// batches, and persisted queries over GET), each with its variables and
+    /// response once loaded. Replay one with other variables through `fetch`:
+    /// `page.fetch(op.with_variables(json!({"first": 100})).to_request())`.
+    /// Capturing stops when the returned stream is dropped.
+    pub async fn capture_graphql(
+        &self,
+    ) -> Result<impl Stream<Item = GraphqlOperation> + Send + Unpin> {
+        graphql::capture(&self.inner).await
+    }
+
+    /// Failures of this page's request interception from now on: CDP commands
+    /// that should have continued, failed, fulfilled or authenticated a paused
+    /// request (which then never loads). What else happens to them is set with
+    /// `BrowserBuilder::background_error_policy`.
+    pub fn background_errors(&self) -> impl Stream<Item = BackgroundError> + Send + Unpin {
+        self.interceptor.subscribe()
+    }
+
+    // ── Observations ────────────────────────────────────────────────
+
+    /// Get url, title, ready state, scroll position and viewport size in a single
+    /// CDP round trip, instead of one call per value.
+    pub async fn observe(&self) -> Result<Observation> {
+        let js = r#"({
+            url: location.href,
+            title: document.title,
+            ready_state: document.readyState,
+            scroll_x: window.scrollX,
+            scroll_y: window.scrollY,
+            viewport_width: window.innerWidth,
+            viewport_height: window.innerHeight,
+        })"#;
+        self.evaluate_typed(js).await
+    }
+
+    /// Get viewport size, device pixel ratio, scroll offset and document height,
+    /// for mapping screenshot coordinates onto the page.
+    pub async fn viewport(&self) -> Result<ViewportInfo> {
+        let js = r#"({
+            width: window.innerWidth,
+            height: window.innerHeight,
+            dpr: window.devicePixelRatio,
+            scroll_x: window.scrollX,
+            scroll_y: window.scrollY,
+            page_height: Math.max(
+                document.documentElement.scrollHeight,
+                document.body ? document.body.scrollHeight : 0,
+            ),
+        })"#;
+        self.evaluate_typed(js).await
+    }
+
+    /// Check whether the page is a bot wall or shows a CAPTCHA, from the URL, the
+    /// DOM and the document's response status, so agents can back off or hand over
+    /// instead of acting on a block page.
+    pub async fn detect_blocking(&self) -> Result<Blocking> {
+        self.evaluate_typed(blocking::DETECT_BLOCKING_JS).await
+    }
+
+    /// Get renderer metrics for this page: JS heap size, DOM node, document and
+    /// frame counts, event listeners and layout count (Performance.getMetrics).
+    pub async fn metrics(&self) -> Result<PageMetrics> {
+        metrics::page_metrics(&self.inner).await
+    }
+
+    /// Get navigation timing (DNS, connect, TTFB, DOMContentLoaded, load) and Core
+    /// Web Vitals (FCP, LCP, CLS) for the current document. Call after the page has
+    /// loaded; LCP and CLS keep changing until the user interacts with the page.
+    pub async fn performance_timings(&self) -> Result<PerformanceTimings> {
+        self.evaluate_typed(metrics::PERFORMANCE_TIMINGS_JS).await
+    }
+
+    /// Get the timing of every request made by the current document (the document
+    /// itself first), with DNS/connect/TTFB phases, sizes and cache hits, from the
+    /// Resource Timing API. Chrome keeps the first 250 resource entries per document.
+    pub async fn request_waterfall(&self) -> Result<Vec<RequestTiming>> {
+        self.evaluate_typed(metrics::REQUEST_WATERFALL_JS).await
+    }
+
+    /// Take a screenshot of the visible viewport (PNG format).
+    pub async fn screenshot(&self) -> Result<Vec<u8>> {
+        self.ensure_painted().await?;
+        let params = ScreenshotParams::builder()
+            .format(CaptureScreenshotFormat::Png)
+            .build();
+        self.inner
+            .screenshot(params)
+            .await
+            .map(telemetry::screenshot)
+            .map_err(|e| Error::ScreenshotError(e.to_string()))
+    }
+
+    /// `screenshot` with password and one-time-code fields and the elements of
+    /// `set_redaction`'s selectors blurred, for transcripts stored for review.
+    /// Open shadow roots and same-origin frames are searched too; frames of other
+    /// origins (e.g. hosted payment fields) are blurred whole, since their content
+    /// can't be searched. Fields in closed shadow roots are not covered.
+    pub async fn screenshot_redacted(&self) -> Result<Vec<u8>> {
+        let redaction = self.redaction.clone().unwrap_or_default();
+        let redact = format!(
+            "(selectors, blur) => ({})({}, selectors, blur)",
+            redact::REDACT_JS,
+            redact::SENSITIVE_FIELD_JS
+        );
+        let _: u32 = self
+            .evaluate_with_args(&redact, (&redaction.selectors, redaction.blur))
+            .await?;
+        let shot = self.screenshot().await;
+        let restored: Result<()> = self.evaluate_with_args(redact::UNREDACT_JS, ()).await;
+        let shot = shot?;
+        restored?;
+        Ok(shot)
+    }
+
+    /// Take a screenshot and save it to a file.
+    pub async fn screenshot_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.ensure_painted().await?;
+        let params = ScreenshotParams::builder()
+            .format(CaptureScreenshotFormat::Png)
             .build();
         self.inner
             .save_screenshot(params, path)
@@ -388,6 +1871,7 @@ impl Page {
 
     /// Take a full-page screenshot (PNG format).
     pub async fn screenshot_full_page(&self) -> Result<Vec<u8>> {
+        self.ensure_painted().await?;
         let params = ScreenshotParams::builder()
             .format(CaptureScreenshotFormat::Png)
             .full_page(true)
@@ -395,12 +1879,14 @@ impl Page {
         self.inner
             .screenshot(params)
             .await
+            .map(telemetry::screenshot)
             .map_err(|e| Error::ScreenshotError(e.to_string()))
     }
 
     /// Take a screenshot as JPEG with the given quality (0-100).
     /// JPEG screenshots are typically 3-10x smaller than PNG.
     pub async fn screenshot_jpeg(&self, quality: u8) -> Result<Vec<u8>> {
+        self.ensure_painted().await?;
         let params = ScreenshotParams::builder()
             .format(CaptureScreenshotFormat::Jpeg)
             .quality(quality as i64)
@@ -408,11 +1894,13 @@ impl Page {
         self.inner
             .screenshot(params)
             .await
+            .map(telemetry::screenshot)
             .map_err(|e| Error::ScreenshotError(e.to_string()))
     }
 
     /// Take a full-page screenshot as JPEG with the given quality (0-100).
     pub async fn screenshot_full_page_jpeg(&self, quality: u8) -> Result<Vec<u8>> {
+        self.ensure_painted().await?;
         let params = ScreenshotParams::builder()
             .format(CaptureScreenshotFormat::Jpeg)
             .quality(quality as i64)
@@ -421,9 +1909,133 @@ impl Page {
         self.inner
             .screenshot(params)
             .await
+            .map(telemetry::screenshot)
             .map_err(|e| Error::ScreenshotError(e.to_string()))
     }
 
+    /// Screenshot the viewport as the largest image no bigger than `max_bytes`,
+    /// for vision APIs that reject oversized payloads: PNG if it fits, else the
+    /// best JPEG quality that does (down to 40), else the same at ever smaller
+    /// sizes, down to a quarter. Fails with `ScreenshotError` if even that is too
+    /// big.
+    pub async fn screenshot_within(&self, max_bytes: usize) -> Result<FittedScreenshot> {
+        const MIN_QUALITY: u8 = 40;
+        const MAX_QUALITY: u8 = 90;
+
+        self.ensure_painted().await?;
+        let view = self.viewport().await?;
+        let fitted = |data: Vec<u8>, quality: Option<u8>, scale: f64| FittedScreenshot {
+            data: telemetry::screenshot(data),
+            mime_type: if quality.is_some() { "image/jpeg" } else { "image/png" }.to_string(),
+            quality,
+            scale,
+        };
+        for scale in [1.0, 0.75, 0.5, 0.35, 0.25] {
+            let png = self.screenshot_scaled(&view, scale, None).await?;
+            if png.len() <= max_bytes {
+                return Ok(fitted(png, None, scale));
+            }
+            let best = self.screenshot_scaled(&view, scale, Some(MAX_QUALITY)).await?;
+            if best.len() <= max_bytes {
+                return Ok(fitted(best, Some(MAX_QUALITY), scale));
+            }
+            let worst = self.screenshot_scaled(&view, scale, Some(MIN_QUALITY)).await?;
+            if worst.len() > max_bytes {
+                continue;
+            }
+            // Highest quality that fits, to within 5
+            let (mut fits, mut too_big) = ((MIN_QUALITY, worst), MAX_QUALITY);
+            while too_big - fits.0 > 5 {
+                let quality = (fits.0 + too_big) / 2;
+                let jpeg = self.screenshot_scaled(&view, scale, Some(quality)).await?;
+                if jpeg.len() <= max_bytes {
+                    fits = (quality, jpeg);
+                } else {
+                    too_big = quality;
+                }
+            }
+            return Ok(fitted(fits.1, Some(fits.0), scale));
+        }
+        Err(Error::ScreenshotError(format!(
+            "no screenshot fits in {max_bytes} bytes, even a quarter-size JPEG at quality {MIN_QUALITY}"
+        )))
+    }
+
+    /// The viewport at `scale`, as PNG or as JPEG of `quality`.
+    async fn screenshot_scaled(&self, view: &ViewportInfo, scale: f64, quality: Option<u8>) -> Result<Vec<u8>> {
+        use chromiumoxide::cdp::browser_protocol::page::Viewport;
+
+        let clip = Viewport {
+            x: view.scroll_x,
+            y: view.scroll_y,
+            width: view.width,
+            height: view.height,
+            scale,
+        };
+        let builder = ScreenshotParams::builder().clip(clip);
+        let params = match quality {
+            Some(quality) => builder
+                .format(CaptureScreenshotFormat::Jpeg)
+                .quality(quality as i64)
+                .build(),
+            None => builder.format(CaptureScreenshotFormat::Png).build(),
+        };
+        self.inner
+            .screenshot(params)
+            .await
+            .map_err(|e| Error::ScreenshotError(e.to_string()))
+    }
+
+    /// Screenshot the viewport into a `Screenshot` to crop, resize, gray out or
+    /// annotate before encoding.
+    #[cfg(feature = "image")]
+    pub async fn screenshot_image(&self) -> Result<Screenshot> {
+        let view = self.viewport().await?;
+        Screenshot::decode(&self.screenshot().await?, view.dpr)
+    }
+
+    /// Read the text in `region` of the viewport (the whole viewport if `None`)
+    /// off a screenshot with Tesseract, for text the DOM doesn't hold: canvas
+    /// charts, menus as images, glyph-scrambled anti-scraping fonts. Needs the
+    /// `tesseract` binary installed.
+    #[cfg(feature = "ocr")]
+    pub async fn ocr(&self, region: Option<BoundingBox>) -> Result<Vec<OcrText>> {
+        self.ocr_with(region, &OcrOptions::default()).await
+    }
+
+    /// `ocr` with the language, confidence threshold, zoom or binary set by
+    /// `options`.
+    #[cfg(feature = "ocr")]
+    pub async fn ocr_with(&self, region: Option<BoundingBox>, options: &OcrOptions) -> Result<Vec<OcrText>> {
+        use chromiumoxide::cdp::browser_protocol::page::Viewport;
+
+        self.ensure_painted().await?;
+        let view = self.viewport().await?;
+        let region = region.unwrap_or(BoundingBox { x: 0.0, y: 0.0, width: view.width, height: view.height });
+        if region.width <= 0.0 || region.height <= 0.0 {
+            return Err(Error::OcrError(format!("empty region {region:?}")));
+        }
+        // The clip is in document coordinates
+        let clip = Viewport {
+            x: view.scroll_x + region.x,
+            y: view.scroll_y + region.y,
+            width: region.width,
+            height: region.height,
+            scale: options.scale,
+        };
+        let params = ScreenshotParams::builder()
+            .format(CaptureScreenshotFormat::Png)
+            .clip(clip)
+            .build();
+        let png = self
+            .inner
+            .screenshot(params)
+            .await
+            .map(telemetry::screenshot)
+            .map_err(|e| Error::ScreenshotError(e.to_string()))?;
+        ocr::recognize(&png, region, options).await
+    }
+
     /// Get the full HTML content of the page.
     pub async fn html(&self) -> Result<String> {
         self.inner
@@ -432,6 +2044,66 @@ impl Page {
             .map_err(|e| Error::JsError(e.to_string()))
     }
 
+    /// Capture the page as MHTML (Page.captureSnapshot): the DOM with its images,
+    /// stylesheets and frames inlined in one self-contained document.
+    pub async fn mhtml(&self) -> Result<String> {
+        use chromiumoxide::cdp::browser_protocol::page::{CaptureSnapshotFormat, CaptureSnapshotParams};
+
+        let params = CaptureSnapshotParams::builder()
+            .format(CaptureSnapshotFormat::Mhtml)
+            .build();
+        let snapshot = self
+            .inner
+            .execute(params)
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to capture MHTML: {e}")))?;
+        Ok(snapshot.result.data)
+    }
+
+    /// Save the page as an `.mhtml` archive (see `mhtml`), viewable offline in
+    /// Chrome exactly as it was rendered.
+    pub async fn save_mhtml(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.mhtml().await?)?;
+        Ok(())
+    }
+
+    /// Save the page as a browsable offline copy in `dir`: `index.html` with the
+    /// current DOM, and the images, stylesheets and fonts it loaded under
+    /// `resources/`, with links rewritten to the local files. Resources come from
+    /// the browser's cache, so nothing is downloaded again; scripts are left out.
+    /// Returns the path of `index.html`.
+    pub async fn save_complete(&self, dir: impl AsRef<Path>) -> Result<PathBuf> {
+        let dir = dir.as_ref();
+        let resources = archive::collect(&self.inner).await?;
+        let files: HashMap<&str, &str> = resources
+            .iter()
+            .map(|r| (r.url.as_str(), r.file_name.as_str()))
+            .collect();
+        let stylesheets: Vec<serde_json::Value> = resources
+            .iter()
+            .filter(|r| r.is_stylesheet)
+            .map(|r| serde_json::json!({ "url": r.url, "text": String::from_utf8_lossy(&r.body) }))
+            .collect();
+        let rewritten: Rewritten = self
+            .evaluate_with_args(archive::REWRITE_JS, (files, stylesheets, RESOURCE_DIR))
+            .await?;
+
+        let resource_dir = dir.join(RESOURCE_DIR);
+        std::fs::create_dir_all(&resource_dir)?;
+        let mut stylesheets = rewritten.stylesheets.into_iter();
+        for resource in &resources {
+            let path = resource_dir.join(&resource.file_name);
+            if resource.is_stylesheet {
+                std::fs::write(path, stylesheets.next().unwrap_or_default())?;
+            } else {
+                std::fs::write(path, &resource.body)?;
+            }
+        }
+        let index = dir.join("index.html");
+        std::fs::write(&index, rewritten.html)?;
+        Ok(index)
+    }
+
     /// Get the inner HTML of an element matching the given CSS selector.
     pub async fn inner_html(&self, selector: &str) -> Result<String> {
         let el = self.find_element(selector).await?;
@@ -446,156 +2118,120 @@ impl Page {
 
     /// Get all links on the page as (text, href) tuples.
     pub async fn get_links(&self) -> Result<Vec<(String, String)>> {
-        let js = r#"
-            JSON.stringify(
-                Array.from(document.querySelectorAll('a[href]')).map(a => ({
-                    text: (a.innerText || '').trim(),
-                    href: a.href
-                }))
-            )
-        "#;
-        let result = self
-            .inner
-            .evaluate(js)
-            .await
-            .map_err(|e| Error::JsError(e.to_string()))?;
-        let json_str: String = result
-            .into_value()
-            .map_err(|e| Error::JsError(e.to_string()))?;
-
-        #[derive(serde::Deserialize)]
-        struct Link {
-            text: String,
-            href: String,
+        if self.extraction == ExtractionBackend::DomSnapshot {
+            return Ok(DomSnapshot::capture(&self.inner).await?.links());
         }
-
-        let links: Vec<Link> =
-            serde_json::from_str(&json_str).map_err(|e| Error::JsError(e.to_string()))?;
-        Ok(links.into_iter().map(|l| (l.text, l.href)).collect())
+        let links = format!("({})(document)", snapshot::LINKS_JS);
+        self.evaluate_typed(&links).await
     }
 
     /// Get all form fields on the page.
     pub async fn get_form_fields(&self) -> Result<Vec<FormField>> {
-        let js = r#"
-            JSON.stringify(
-                Array.from(document.querySelectorAll('input, select, textarea')).map(el => {
-                    let label = '';
-                    if (el.id) {
-                        const labelEl = document.querySelector(`label[for="${el.id}"]`);
-                        if (labelEl) label = (labelEl.innerText || '').trim();
-                    }
-                    if (!label && el.closest('label')) {
-                        label = (el.closest('label').innerText || '').trim();
-                    }
-                    return {
-                        tag: el.tagName.toLowerCase(),
-                        type: el.type || '',
-                        name: el.name || '',
-                        id: el.id || '',
-                        value: el.value || '',
-                        placeholder: el.placeholder || '',
-                        label: label
-                    };
-                })
-            )
-        "#;
-        let result = self
-            .inner
-            .evaluate(js)
-            .await
-            .map_err(|e| Error::JsError(e.to_string()))?;
-        let json_str: String = result
-            .into_value()
-            .map_err(|e| Error::JsError(e.to_string()))?;
-        let fields: Vec<FormField> =
-            serde_json::from_str(&json_str).map_err(|e| Error::JsError(e.to_string()))?;
-        Ok(fields)
+        if self.extraction == ExtractionBackend::DomSnapshot {
+            return Ok(DomSnapshot::capture(&self.inner).await?.form_fields());
+        }
+        self.evaluate_typed(snapshot::FORM_FIELDS_JS).await
     }
 
     /// Build a compact accessibility tree representation of the page DOM,
     /// suitable for LLM consumption. Shows roles, labels, links, form elements.
+    /// Covers the main document only; see `accessibility_tree_with`.
     pub async fn accessibility_tree(&self) -> Result<String> {
-        let js = r#"
-            JSON.stringify((function() {
-                function getRole(el) {
-                    return el.getAttribute('role') || el.tagName.toLowerCase();
-                }
-                function getLabel(el) {
-                    if (el.getAttribute('aria-label')) return el.getAttribute('aria-label');
-                    if (el.id) {
-                        const label = document.querySelector('label[for="' + el.id + '"]');
-                        if (label) return (label.innerText || '').trim();
-                    }
-                    if (el.getAttribute('alt')) return el.getAttribute('alt');
-                    if (el.getAttribute('title')) return el.getAttribute('title');
-                    if (el.getAttribute('placeholder')) return el.getAttribute('placeholder');
-                    return '';
-                }
-                function walk(node, depth) {
-                    const lines = [];
-                    const indent = '  '.repeat(depth);
-                    if (node.nodeType === Node.TEXT_NODE) {
-                        const text = (node.textContent || '').trim();
-                        if (text && text.length < 200) {
-                            lines.push(indent + '[text] "' + text.substring(0, 100) + '"');
-                        }
-                        return lines;
-                    }
-                    if (node.nodeType !== Node.ELEMENT_NODE) return lines;
-                    const el = node;
-                    const tag = el.tagName.toLowerCase();
-
-                    // Skip invisible elements
-                    if (['script','style','noscript','meta','link','head'].includes(tag)) return lines;
-                    if (typeof el.checkVisibility === 'function') {
-                        if (!el.checkVisibility({checkOpacity: false, checkVisibilityCSS: true})) return lines;
-                    } else if (el.offsetParent === null && tag !== 'body' && tag !== 'html') {
-                        return lines;
-                    }
+        self.accessibility_tree_with(TreeOptions::default()).await
+    }
 
-                    const role = getRole(el);
-                    const label = getLabel(el);
-                    const interactable = ['a','button','input','select','textarea'].includes(tag);
-                    const isLandmark = ['main','nav','header','footer','aside','section','article','form'].includes(tag)
-                        || el.getAttribute('role');
-
-                    if (interactable || isLandmark) {
-                        let desc = indent + '[' + role + ']';
-                        if (label) desc += ' "' + label + '"';
-                        if (tag === 'a' && el.href) desc += ' href=' + el.href;
-                        if (tag === 'input') {
-                            desc += ' type=' + (el.type || 'text');
-                            if (el.name) desc += ' name=' + el.name;
-                            if (el.value) desc += ' value="' + el.value.substring(0, 50) + '"';
-                        }
-                        if (tag === 'select') {
-                            if (el.name) desc += ' name=' + el.name;
-                        }
-                        if (tag === 'button' || (tag === 'input' && ['submit','button'].includes(el.type))) {
-                            const btnText = (el.innerText || el.value || '').trim();
-                            if (btnText && !label) desc += ' "' + btnText + '"';
-                        }
-                        lines.push(desc);
-                    }
+    /// `accessibility_tree`, also walking into iframes and open shadow roots as
+    /// `options` say, so embedded login or checkout widgets show up. Frame
+    /// content is indented under a `[frame] src=...` line.
+    pub async fn accessibility_tree_with(&self, options: TreeOptions) -> Result<String> {
+        if self.extraction == ExtractionBackend::DomSnapshot {
+            return Ok(DomSnapshot::capture(&self.inner).await?.accessibility_tree(options));
+        }
+        let lines: Vec<String> = self
+            .evaluate_with_args(snapshot::ACCESSIBILITY_TREE_JS, options.js_args())
+            .await?;
+        Ok(lines.join("\n"))
+    }
 
-                    for (const child of el.childNodes) {
-                        const childLines = walk(child, interactable || isLandmark ? depth + 1 : depth);
-                        lines.push(...childLines);
-                    }
-                    return lines;
-                }
-                return walk(document.body || document.documentElement, 0);
-            })())
-        "#;
-        let result = self
+    /// The page's links, buttons and form fields with their role, name, a
+    /// selector, box and state: enough to plan actions without the cost of a
+    /// full `accessibility_tree`. Hidden and disabled elements are included;
+    /// filter on `visible` and `enabled`.
+    pub async fn interactive_elements(&self) -> Result<Vec<InteractiveElement>> {
+        let script = format!("({})({})", interactive::INTERACTIVE_ELEMENTS_JS, interactive::SELECTOR_FOR_JS);
+        self.evaluate_typed(&script).await
+    }
+
+    /// A screenshot of the viewport and the visible interactive elements on it,
+    /// for vision models that act on "element 12": an element's label is its
+    /// index in `elements` (the screenshot itself is not annotated). CSS
+    /// animations and transitions are paused for the capture. Chrome can't take
+    /// a screenshot and run script in one step, and script can still change the
+    /// page, so the elements are read right before and right after the
+    /// screenshot, which is retaken (up to three times) until both reads agree;
+    /// `consistent` says whether they did.
+    pub async fn observe_visual(&self) -> Result<VisualObservation> {
+        use chromiumoxide::cdp::browser_protocol::animation::{
+            EnableParams, GetPlaybackRateParams, SetPlaybackRateParams,
+        };
+
+        let failed = |e| Error::ScreenshotError(format!("Failed to pause animations: {e}"));
+        self.inner.execute(EnableParams::default()).await.map_err(failed)?;
+        let rate = self
             .inner
-            .evaluate(js)
+            .execute(GetPlaybackRateParams::default())
             .await
-            .map_err(|e| Error::JsError(e.to_string()))?;
-        let lines: Vec<String> = result
-            .into_value()
-            .map_err(|e| Error::JsError(e.to_string()))?;
-        Ok(lines.join("\n"))
+            .map_err(failed)?
+            .result
+            .playback_rate;
+        self.inner.execute(SetPlaybackRateParams::new(0.0)).await.map_err(failed)?;
+        let observed = self.observe_visual_paused().await;
+        let _ = self.inner.execute(SetPlaybackRateParams::new(rate)).await;
+        observed
+    }
+
+    async fn observe_visual_paused(&self) -> Result<VisualObservation> {
+        let script = format!(
+            "({})({}, {})",
+            interactive::SCREEN_STATE_JS,
+            interactive::INTERACTIVE_ELEMENTS_JS,
+            interactive::SELECTOR_FOR_JS
+        );
+        let mut before: ScreenState = self.evaluate_typed(&script).await?;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let screenshot = self.screenshot().await?;
+            let after: ScreenState = self.evaluate_typed(&script).await?;
+            let consistent = after == before;
+            if consistent || attempts == 3 {
+                return Ok(VisualObservation {
+                    screenshot,
+                    elements: after.elements,
+                    viewport: after.viewport,
+                    consistent,
+                });
+            }
+            before = after;
+        }
+    }
+
+    /// Split the page into labeled regions (header, navigation, main content,
+    /// sidebars, footer, cookie banner, modals) with their boxes and the start of
+    /// their text, so actions and screenshots can be scoped to one of them.
+    /// Only visible regions are listed.
+    pub async fn regions(&self) -> Result<Vec<PageRegion>> {
+        let script = format!("({})({})", regions::REGIONS_JS, interactive::SELECTOR_FOR_JS);
+        self.evaluate_typed(&script).await
+    }
+
+    /// Describe the page for an LLM in at most `budget_tokens` tokens: title and
+    /// URL, landmarks and headings, visible interactive elements, and the main
+    /// text, each cut short (with a count of what was left out) to fit.
+    #[cfg(feature = "extraction")]
+    pub async fn describe(&self, budget_tokens: usize) -> Result<String> {
+        let outline: PageOutline = self.evaluate_typed(describe::OUTLINE_JS).await?;
+        Ok(describe::render(&outline, budget_tokens, &self.token_estimator))
     }
 
     /// Evaluate a JavaScript expression and return the result as a string.
@@ -620,6 +2256,259 @@ impl Page {
         Ok(())
     }
 
+    /// Evaluate a JavaScript expression and deserialize its result into `T`.
+    /// Promises are awaited. Objects are returned by value, so there's no need to
+    /// `JSON.stringify` in the page and parse again in Rust.
+    pub async fn evaluate_typed<T: DeserializeOwned>(&self, expression: &str) -> Result<T> {
+        self.inner
+            .evaluate(expression)
+            .await
+            .map_err(|e| Error::JsError(e.to_string()))?
+            .into_value()
+            .map_err(|e| Error::JsError(e.to_string()))
+    }
+
+    /// Call a JavaScript function with arguments passed as data (Runtime.callFunctionOn)
+    /// and deserialize its result into `T`. No string interpolation or escaping needed.
+    /// If `args` serializes to a JSON array, each element becomes one argument;
+    /// anything else is passed as a single argument.
+    ///
+    /// e.g. `page.evaluate_with_args::<i64>("(a, b) => a + b", (1, 2))`
+    pub async fn evaluate_with_args<T: DeserializeOwned>(
+        &self,
+        function: &str,
+        args: impl Serialize,
+    ) -> Result<T> {
+        use chromiumoxide::cdp::js_protocol::runtime::{CallArgument, CallFunctionOnParams};
+
+        let args = match serde_json::to_value(args).map_err(|e| Error::JsError(e.to_string()))? {
+            serde_json::Value::Array(values) => values,
+            value => vec![value],
+        };
+        let params = CallFunctionOnParams::builder()
+            .function_declaration(function)
+            .arguments(args.into_iter().map(|v| CallArgument::builder().value(v).build()))
+            .build()
+            .map_err(Error::JsError)?;
+        self.inner
+            .evaluate_function(params)
+            .await
+            .map_err(|e| Error::JsError(e.to_string()))?
+            .into_value()
+            .map_err(|e| Error::JsError(e.to_string()))
+    }
+
+    /// Evaluate a JavaScript expression in an isolated world (Page.createIsolatedWorld)
+    /// and deserialize the result into `T`. The isolated world shares the DOM with the
+    /// page but not its JS globals, so site scripts can't observe or tamper with the
+    /// evaluation (e.g. by patching `Array.prototype` or `querySelector`), and helper
+    /// variables can't collide with site globals. The world is created once per
+    /// document, so its globals last until the page navigates.
+    pub async fn evaluate_isolated<T: DeserializeOwned>(&self, expression: &str) -> Result<T> {
+        let (context_id, cached) = self.isolated_context().await?;
+        match self.evaluate_in_context(expression, context_id).await {
+            // The world may have gone with a navigation its events haven't reported yet
+            Err(Error::JsError(message)) if cached && message.contains("Cannot find context") => {
+                self.isolated_world.lock().unwrap_or_else(|e| e.into_inner()).take();
+                let (context_id, _) = self.isolated_context().await?;
+                self.evaluate_in_context(expression, context_id).await
+            }
+            result => result,
+        }
+    }
+
+    async fn evaluate_in_context<T: DeserializeOwned>(
+        &self,
+        expression: &str,
+        context_id: ExecutionContextId,
+    ) -> Result<T> {
+        use chromiumoxide::cdp::js_protocol::runtime::EvaluateParams;
+
+        let params = EvaluateParams::builder()
+            .expression(expression)
+            .context_id(context_id)
+            .await_promise(true)
+            .return_by_value(true)
+            .build()
+            .map_err(Error::JsError)?;
+        self.inner
+            .evaluate_expression(params)
+            .await
+            .map_err(|e| Error::JsError(e.to_string()))?
+            .into_value()
+            .map_err(|e| Error::JsError(e.to_string()))
+    }
+
+    /// The execution context of the isolated world in the main frame, and whether
+    /// it came from the cache. The world is created once per document: the cache
+    /// is cleared when the main frame navigates or its contexts are cleared.
+    async fn isolated_context(&self) -> Result<(ExecutionContextId, bool)> {
+        use chromiumoxide::cdp::browser_protocol::page::{CreateIsolatedWorldParams, EventFrameNavigated};
+        use chromiumoxide::cdp::js_protocol::runtime::EventExecutionContextsCleared;
+
+        let frame_id = self
+            .inner
+            .mainframe()
+            .await
+            .map_err(|e| Error::JsError(e.to_string()))?
+            .ok_or_else(|| Error::JsError("Page has no main frame".into()))?;
+        if let Some((frame, context_id)) = self.isolated_world.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            if *frame == frame_id {
+                return Ok((*context_id, true));
+            }
+        }
+
+        // Listen before creating the world, so its end can't slip through
+        let listen_err = |e: chromiumoxide::error::CdpError| {
+            Error::JsError(format!("Failed to listen for execution context events: {e}"))
+        };
+        let cleared = self
+            .inner
+            .event_listener::<EventExecutionContextsCleared>()
+            .await
+            .map_err(listen_err)?
+            .map(|_| ());
+        let main_frame = frame_id.clone();
+        let navigated = self
+            .inner
+            .event_listener::<EventFrameNavigated>()
+            .await
+            .map_err(listen_err)?
+            .filter(move |e| std::future::ready(e.frame.id == main_frame))
+            .map(|_| ());
+        let world = CreateIsolatedWorldParams::builder()
+            .frame_id(frame_id.clone())
+            .world_name("agentic_browser")
+            .build()
+            .map_err(Error::JsError)?;
+        let context_id = self
+            .inner
+            .execute(world)
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to create isolated world: {e}")))?
+            .result
+            .execution_context_id;
+        *self.isolated_world.lock().unwrap_or_else(|e| e.into_inner()) = Some((frame_id, context_id));
+
+        let cache = Arc::downgrade(&self.isolated_world);
+        tokio::spawn(async move {
+            let mut gone = futures::stream::select(cleared, navigated);
+            if gone.next().await.is_none() {
+                return;
+            }
+            if let Some(cache) = cache.upgrade() {
+                let mut world = cache.lock().unwrap_or_else(|e| e.into_inner());
+                if world.as_ref().is_some_and(|(_, id)| *id == context_id) {
+                    *world = None;
+                }
+            }
+        });
+        Ok((context_id, false))
+    }
+
+    /// Expose an async Rust callback to page JavaScript as `window[name]`.
+    /// Calling it from the page returns a Promise resolving to the callback's result;
+    /// arguments arrive as JSON values. Survives navigations (installed on every new
+    /// document). Errors returned by the callback reject the Promise.
+    pub async fn expose_function<F, Fut>(&self, name: &str, callback: F) -> Result<()>
+    where
+        F: Fn(Vec<serde_json::Value>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<serde_json::Value>> + Send + 'static,
+    {
+        use chromiumoxide::cdp::browser_protocol::page::AddScriptToEvaluateOnNewDocumentParams;
+        use chromiumoxide::cdp::js_protocol::runtime::{
+            AddBindingParams, EvaluateParams, EventBindingCalled,
+        };
+        use futures::StreamExt;
+
+        let binding = format!("__agentic_binding_{name}");
+        let name_js = serde_json::to_string(name).map_err(|e| Error::JsError(e.to_string()))?;
+        let binding_js = serde_json::to_string(&binding).map_err(|e| Error::JsError(e.to_string()))?;
+
+        // Listen before adding the binding so no call is missed
+        let mut calls = self
+            .inner
+            .event_listener::<EventBindingCalled>()
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to listen for binding calls: {e}")))?;
+        self.inner
+            .execute(AddBindingParams::new(binding.clone()))
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to add binding: {e}")))?;
+
+        // Promise-returning wrapper around the raw (fire-and-forget) binding
+        let wrapper = format!(
+            r#"(() => {{
+                const raw = window[{binding_js}];
+                if (!raw || window[{name_js}]) return;
+                const pending = new Map();
+                let seq = 0;
+                const fn = (...args) => new Promise((resolve, reject) => {{
+                    const id = ++seq;
+                    pending.set(id, {{ resolve, reject }});
+                    raw(JSON.stringify({{ id, args }}));
+                }});
+                fn.__deliver = (id, ok, value) => {{
+                    const p = pending.get(id);
+                    if (!p) return;
+                    pending.delete(id);
+                    ok ? p.resolve(value) : p.reject(new Error(value));
+                }};
+                window[{name_js}] = fn;
+            }})()"#
+        );
+        self.inner
+            .execute(AddScriptToEvaluateOnNewDocumentParams::new(wrapper.clone()))
+            .await
+            .map_err(|e| Error::JsError(format!("Failed to install function wrapper: {e}")))?;
+        self.inner
+            .evaluate(wrapper)
+            .await
+            .map_err(|e| Error::JsError(e.to_string()))?;
+
+        #[derive(serde::Deserialize)]
+        struct Call {
+            id: u64,
+            args: Vec<serde_json::Value>,
+        }
+
+        let callback = Arc::new(callback);
+        let page = self.inner.clone();
+        tokio::spawn(async move {
+            while let Some(event) = calls.next().await {
+                if event.name != binding {
+                    continue;
+                }
+                let Ok(call) = serde_json::from_str::<Call>(&event.payload) else {
+                    continue;
+                };
+                let callback = Arc::clone(&callback);
+                let page = page.clone();
+                let name_js = name_js.clone();
+                let context_id = event.execution_context_id;
+                tokio::spawn(async move {
+                    let (ok, value) = match callback(call.args).await {
+                        Ok(value) => (true, value),
+                        Err(e) => (false, serde_json::Value::String(e.to_string())),
+                    };
+                    let deliver = format!(
+                        "window[{name_js}].__deliver({}, {ok}, {value})",
+                        call.id
+                    );
+                    if let Ok(params) = EvaluateParams::builder()
+                        .expression(deliver)
+                        .context_id(context_id)
+                        .build()
+                    {
+                        let _ = page.execute(params).await;
+                    }
+                });
+            }
+        });
+
+        Ok(())
+    }
+
     // ── Batch Queries ─────────────────────────────────────────────
 
     /// Query all elements matching a CSS selector and extract their text content
@@ -629,39 +2518,55 @@ impl Page {
         selector: &str,
         attributes: &[&str],
     ) -> Result<Vec<ElementData>> {
-        let selector_js = serde_json::to_string(selector)
-            .map_err(|e| Error::JsError(e.to_string()))?;
-        let attrs_js = serde_json::to_string(attributes)
-            .map_err(|e| Error::JsError(e.to_string()))?;
+        self.query_selector_all_with(selector, attributes, ExtractOptions::default()).await
+    }
 
-        let js = format!(
-            r#"JSON.stringify(
-                Array.from(document.querySelectorAll({selector_js})).map(el => {{
-                    const attrs = {{}};
-                    for (const name of {attrs_js}) {{
-                        const val = el.getAttribute(name);
-                        if (val !== null) attrs[name] = val;
-                    }}
-                    return {{
-                        tag: el.tagName.toLowerCase(),
-                        text: (el.innerText || '').trim().substring(0, 500),
-                        attributes: attrs
-                    }};
-                }})
-            )"#
+    /// `query_selector_all_with_data` with text truncation set by `options`;
+    /// `ExtractOptions::full_text()` for whole articles.
+    pub async fn query_selector_all_with(
+        &self,
+        selector: &str,
+        attributes: &[&str],
+        options: ExtractOptions,
+    ) -> Result<Vec<ElementData>> {
+        let query = format!(
+            "(selector, attributes, options) => ({})(document, selector, attributes, options)",
+            snapshot::QUERY_WITH_DATA_JS
         );
+        self.evaluate_with_args(&query, (selector, attributes, options.js_args())).await
+    }
 
-        let result = self
-            .inner
-            .evaluate(js)
-            .await
-            .map_err(|e| Error::JsError(e.to_string()))?;
-        let json_str: String = result
-            .into_value()
-            .map_err(|e| Error::JsError(e.to_string()))?;
-        let elements: Vec<ElementData> =
-            serde_json::from_str(&json_str).map_err(|e| Error::JsError(e.to_string()))?;
-        Ok(elements)
+    /// `query_selector_all_with_data` for result sets too large to extract at
+    /// once: the matches are fetched `chunk_size` at a time, so neither the page
+    /// nor this process ever holds more than one chunk of serialized data. The
+    /// matches are found once, when the first chunk is read, and kept in the
+    /// page until the stream ends or is dropped; elements added later are not
+    /// included.
+    pub fn query_stream<'a>(
+        &'a self,
+        selector: &'a str,
+        attributes: &'a [&'a str],
+        chunk_size: usize,
+    ) -> BoxStream<'a, Result<ElementData>> {
+        let chunk_size = chunk_size.max(1);
+        // `None` once done; `Some(None)` before the query ran
+        let state: Option<Option<(RemoteNodeList, usize)>> = Some(None);
+        futures::stream::try_unfold(state, move |state| async move {
+            let (matches, start) = match state {
+                None => return Ok::<_, Error>(None),
+                Some(Some(queried)) => queried,
+                Some(None) => (RemoteNodeList::query(&self.inner, selector).await?, 0),
+            };
+            let chunk = matches.chunk(attributes, start, chunk_size).await?;
+            if chunk.is_empty() {
+                return Ok(None);
+            }
+            let next = (chunk.len() == chunk_size).then_some((matches, start + chunk_size));
+            Ok(Some((chunk, next.map(Some))))
+        })
+        .map_ok(|chunk| futures::stream::iter(chunk.into_iter().map(Ok)))
+        .try_flatten()
+        .boxed()
     }
 
     // ── Element Queries ─────────────────────────────────────────────
@@ -673,7 +2578,7 @@ impl Page {
             .find_element(selector)
             .await
             .map_err(|e| Error::ElementNotFound(e.to_string()))?;
-        Ok(Element::new(el))
+        Ok(Element::new(el, self.inner.clone()))
     }
 
     /// Find all elements matching the given CSS selector.
@@ -683,6 +2588,109 @@ impl Page {
             .find_elements(selector)
             .await
             .map_err(|e| Error::ElementNotFound(e.to_string()))?;
-        Ok(els.into_iter().map(Element::new).collect())
+        Ok(els.into_iter().map(|el| Element::new(el, self.inner.clone())).collect())
+    }
+
+    // ── Semantic Locators ───────────────────────────────────────────
+
+    /// Create an auto-waiting `Locator` for the given CSS selector.
+    /// Nothing is queried until an action is performed on it.
+    pub fn locator(&self, selector: &str) -> Locator {
+        self.locator_by(By::Css(selector.to_string()))
+    }
+
+    /// Create an auto-waiting `Locator` for any `By` query (role, label, ...).
+    pub fn locator_by(&self, by: By) -> Locator {
+        Locator::new(self.inner.clone(), by, self.default_timeout)
+    }
+
+    /// Find the first visible element with the given ARIA role (explicit or implicit),
+    /// optionally filtered by accessible name (case-insensitive substring match).
+    pub async fn get_by_role(&self, role: Role, name: Option<&str>) -> Result<Element> {
+        let by = By::Role { role, name: name.map(str::to_string) };
+        locator::find_first(&self.inner, &by).await
+    }
+
+    /// Find the first form control whose label (`<label>`, `aria-label` or
+    /// `aria-labelledby`) contains the given text.
+    pub async fn get_by_label(&self, text: &str) -> Result<Element> {
+        locator::find_first(&self.inner, &By::Label(text.to_string())).await
+    }
+
+    /// Find the first element whose placeholder contains the given text.
+    pub async fn get_by_placeholder(&self, text: &str) -> Result<Element> {
+        locator::find_first(&self.inner, &By::Placeholder(text.to_string())).await
+    }
+
+    /// Find the first element with the given `data-testid` attribute.
+    pub async fn get_by_test_id(&self, test_id: &str) -> Result<Element> {
+        locator::find_first(&self.inner, &By::TestId(test_id.to_string())).await
+    }
+}
+
+/// The matches of a `query_stream` selector, held in the page between chunks
+/// and released on drop.
+struct RemoteNodeList {
+    page: CrPage,
+    id: RemoteObjectId,
+}
+
+impl RemoteNodeList {
+    async fn query(page: &CrPage, selector: &str) -> Result<Self> {
+        use chromiumoxide::cdp::js_protocol::runtime::EvaluateParams;
+
+        let selector = serde_json::to_string(selector).map_err(|e| Error::JsError(e.to_string()))?;
+        let params = EvaluateParams::builder()
+            .expression(format!("document.querySelectorAll({selector})"))
+            .return_by_value(false)
+            .build()
+            .map_err(Error::JsError)?;
+        let evaluated = page.execute(params).await?.result;
+        if let Some(details) = evaluated.exception_details {
+            let message = details.exception.and_then(|e| e.description).unwrap_or(details.text);
+            return Err(Error::JsError(message));
+        }
+        let id = evaluated
+            .result
+            .object_id
+            .ok_or_else(|| Error::JsError("querySelectorAll returned no object".to_string()))?;
+        Ok(Self { page: page.clone(), id })
+    }
+
+    /// `ElementData` of up to `count` matches from the `start`th on.
+    async fn chunk(&self, attributes: &[&str], start: usize, count: usize) -> Result<Vec<ElementData>> {
+        use chromiumoxide::cdp::js_protocol::runtime::{CallArgument, CallFunctionOnParams};
+
+        let function = format!(
+            "function(attributes, start, count) {{ return ({})(this, null, attributes, {{ start, count }}); }}",
+            snapshot::QUERY_WITH_DATA_JS
+        );
+        let args = [serde_json::json!(attributes), serde_json::json!(start), serde_json::json!(count)];
+        let params = CallFunctionOnParams::builder()
+            .function_declaration(function)
+            .object_id(self.id.clone())
+            .arguments(args.into_iter().map(|v| CallArgument::builder().value(v).build()))
+            .return_by_value(true)
+            .build()
+            .map_err(Error::JsError)?;
+        let called = self.page.execute(params).await?.result;
+        if let Some(details) = called.exception_details {
+            let message = details.exception.and_then(|e| e.description).unwrap_or(details.text);
+            return Err(Error::JsError(message));
+        }
+        serde_json::from_value(called.result.value.unwrap_or_default()).map_err(|e| Error::JsError(e.to_string()))
+    }
+}
+
+impl Drop for RemoteNodeList {
+    fn drop(&mut self) {
+        use chromiumoxide::cdp::js_protocol::runtime::ReleaseObjectParams;
+
+        let (page, params) = (self.page.clone(), ReleaseObjectParams::new(self.id.clone()));
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                let _ = page.execute(params).await;
+            });
+        }
     }
 }
@@ -0,0 +1,116 @@
+/// Restricts which domains pages may navigate to or load resources from.
+///
+/// Enforced for every page of a browser through request interception, so it
+/// also covers link clicks, redirects, `window.location` changes and
+/// subresources — not just `goto`. Tabs opened by pages (popups,
+/// `window.open`) get it as soon as Chrome creates them. Blocked requests fail
+/// with `net::ERR_BLOCKED_BY_CLIENT`, which surfaces as a `NavigationError`;
+/// `goto` to a denied URL fails without navigating.
+///
+/// Domain patterns match the domain itself and all of its subdomains:
+/// `"example.com"` allows `example.com` and `www.example.com`.
+/// `http(s)` URLs are checked against the domains; URLs of other schemes
+/// (`file:`, `data:`, `blob:`, ...) are denied unless their scheme is allowed
+/// with `allow_schemes`. `about:` URLs (new and blank tabs) are always allowed.
+///
+/// WebSocket connections don't go through request interception and are not
+/// checked.
+#[derive(Debug, Clone, Default)]
+pub struct NavigationPolicy {
+    allowed: Vec<String>,
+    denied: Vec<String>,
+    schemes: Vec<String>,
+    documents_only: bool,
+}
+
+impl NavigationPolicy {
+    /// A policy that only allows the given domains (and their subdomains).
+    pub fn allow_domains(domains: &[&str]) -> Self {
+        Self {
+            allowed: domains.iter().map(|d| normalize(d)).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// A policy that allows everything except the given domains.
+    pub fn deny_domains(domains: &[&str]) -> Self {
+        Self::default().deny(domains)
+    }
+
+    /// Additionally deny the given domains (takes precedence over the allowlist).
+    pub fn deny(mut self, domains: &[&str]) -> Self {
+        self.denied.extend(domains.iter().map(|d| normalize(d)));
+        self
+    }
+
+    /// Additionally allow URLs of the given schemes (`"data"`, `"blob"`,
+    /// `"file"`), whatever the domain lists say.
+    pub fn allow_schemes(mut self, schemes: &[&str]) -> Self {
+        self.schemes.extend(schemes.iter().map(|s| s.trim().trim_end_matches(':').to_ascii_lowercase()));
+        self
+    }
+
+    /// Only restrict top-level and frame navigations; let subresources (scripts,
+    /// images, XHR) load from any domain.
+    pub fn documents_only(mut self) -> Self {
+        self.documents_only = true;
+        self
+    }
+
+    /// Whether the policy applies to subresource requests as well as documents.
+    pub(crate) fn restricts_subresources(&self) -> bool {
+        !self.documents_only
+    }
+
+    /// Whether a request to `url` is permitted.
+    pub fn is_allowed(&self, url: &str) -> bool {
+        let Some(scheme) = scheme_of(url) else {
+            return false;
+        };
+        if scheme == "about" {
+            return true;
+        }
+        if self.schemes.contains(&scheme) {
+            return true;
+        }
+        let Some(host) = host_of(url) else {
+            return false;
+        };
+        if self.denied.iter().any(|d| domain_matches(&host, d)) {
+            return false;
+        }
+        self.allowed.is_empty() || self.allowed.iter().any(|d| domain_matches(&host, d))
+    }
+}
+
+fn normalize(domain: &str) -> String {
+    domain.trim().trim_start_matches("*.").trim_end_matches('.').to_ascii_lowercase()
+}
+
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain || host.strip_suffix(domain).is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// The lowercase scheme of `url`, without the colon.
+fn scheme_of(url: &str) -> Option<String> {
+    let (scheme, _) = url.trim_start().split_once(':')?;
+    let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then(|| scheme.to_ascii_lowercase())
+}
+
+/// Extract the lowercase host of an http(s) URL. Other schemes return `None`.
+fn host_of(url: &str) -> Option<String> {
+    let (scheme, rest) = url.trim_start().split_once("://")?;
+    if !matches!(scheme.to_ascii_lowercase().as_str(), "http" | "https") {
+        return None;
+    }
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+    let host_port = authority.rsplit('@').next().unwrap_or(authority);
+    let host = if let Some(ipv6) = host_port.strip_prefix('[') {
+        ipv6.split(']').next().unwrap_or(ipv6)
+    } else {
+        host_port.split(':').next().unwrap_or(host_port)
+    };
+    Some(host.trim_end_matches('.').to_ascii_lowercase())
+}
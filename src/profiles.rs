@@ -0,0 +1,425 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chromiumoxide::cdp::browser_protocol::browser::BrowserContextId;
+use chromiumoxide::cdp::browser_protocol::network::{Cookie, CookieParam, CookieSameSite, TimeSinceEpoch};
+use chromiumoxide::cdp::browser_protocol::page::AddScriptToEvaluateOnNewDocumentParams;
+use chromiumoxide::cdp::browser_protocol::storage::{GetCookiesParams, SetCookiesParams};
+use chromiumoxide::cdp::browser_protocol::target::{CreateBrowserContextParams, DisposeBrowserContextParams};
+
+use crate::browser::AgenticBrowser;
+use crate::config::ProxyConfig;
+use crate::error::{Error, Result};
+use crate::identity::{Identity, RequestIdentity};
+use crate::page::Page;
+
+/// A cookie saved with a `Profile`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct StoredCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    /// Seconds since the Unix epoch; `None` for a session cookie.
+    pub expires: Option<f64>,
+    pub http_only: bool,
+    pub secure: bool,
+    /// `Strict`, `Lax` or `None`.
+    pub same_site: Option<String>,
+}
+
+impl StoredCookie {
+    fn from_cdp(cookie: Cookie) -> Self {
+        Self {
+            name: cookie.name,
+            value: cookie.value,
+            domain: cookie.domain,
+            path: cookie.path,
+            expires: (!cookie.session).then_some(cookie.expires),
+            http_only: cookie.http_only,
+            secure: cookie.secure,
+            same_site: cookie.same_site.map(|s| s.as_ref().to_string()),
+        }
+    }
+
+    fn to_cdp(&self) -> CookieParam {
+        let mut param = CookieParam::new(self.name.clone(), self.value.clone());
+        param.domain = Some(self.domain.clone());
+        param.path = Some(self.path.clone());
+        param.secure = Some(self.secure);
+        param.http_only = Some(self.http_only);
+        param.expires = self.expires.map(TimeSinceEpoch::new);
+        param.same_site = match self.same_site.as_deref() {
+            Some("Strict") => Some(CookieSameSite::Strict),
+            Some("Lax") => Some(CookieSameSite::Lax),
+            Some("None") => Some(CookieSameSite::None),
+            _ => None,
+        };
+        param
+    }
+}
+
+/// A named identity kept by `ProfileManager`: what the browser presents
+/// (`identity`), where its traffic goes (`proxy`) and the state it built up
+/// (cookies and localStorage).
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub struct Profile {
+    pub name: String,
+    pub identity: Identity,
+    /// Proxy for this profile's pages only, instead of the browser's; its pages
+    /// answer proxy challenges with its credentials alone.
+    #[serde(default)]
+    pub proxy: Option<ProxyConfig>,
+    #[serde(default)]
+    pub cookies: Vec<StoredCookie>,
+    /// localStorage items by origin (`https://example.com`).
+    #[serde(default)]
+    pub local_storage: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Profile {
+    /// An empty profile presenting `identity`.
+    pub fn new(name: impl Into<String>, identity: Identity) -> Self {
+        Self {
+            name: name.into(),
+            identity,
+            proxy: None,
+            cookies: Vec::new(),
+            local_storage: BTreeMap::new(),
+        }
+    }
+
+    pub fn proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+}
+
+/// The version of `ProfileBundle` this build writes and the newest it reads.
+pub const PROFILE_BUNDLE_VERSION: u32 = 1;
+
+/// A profile in its portable form, as stored by `ProfileManager` and moved
+/// between machines with `export_profile`/`import_profile`. Holds the proxy
+/// credentials and session cookies in clear text: keep it with other secrets.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub struct ProfileBundle {
+    /// Format version, raised when a field changes meaning.
+    pub version: u32,
+    pub profile: Profile,
+}
+
+impl ProfileBundle {
+    pub fn new(profile: Profile) -> Self {
+        Self { version: PROFILE_BUNDLE_VERSION, profile }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| Error::ConfigError(format!("Failed to serialize profile: {e}")))
+    }
+
+    /// Parse a bundle, refusing ones written by a newer version of the format.
+    pub fn from_json(json: &str) -> Result<Self> {
+        #[derive(serde::Deserialize)]
+        struct Version {
+            version: u32,
+        }
+
+        let Version { version } = serde_json::from_str(json)
+            .map_err(|e| Error::ConfigError(format!("Not a profile bundle: {e}")))?;
+        if version > PROFILE_BUNDLE_VERSION {
+            return Err(Error::ConfigError(format!(
+                "profile bundle version {version} is newer than the supported {PROFILE_BUNDLE_VERSION}"
+            )));
+        }
+        serde_json::from_str(json).map_err(|e| Error::ConfigError(format!("Invalid profile bundle: {e}")))
+    }
+}
+
+/// Named profiles stored as `<name>.json` bundles in a directory, for operators
+/// running several accounts side by side. Open one with
+/// `AgenticBrowser::open_profile`.
+#[derive(Debug, Clone)]
+pub struct ProfileManager {
+    dir: PathBuf,
+    /// Where new profiles get their identity.
+    identities: RequestIdentity,
+}
+
+impl ProfileManager {
+    /// Profiles in `dir`, created on the first save.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into(), identities: RequestIdentity::new() }
+    }
+
+    /// Draw the identities of new profiles from `pool` instead of the default.
+    pub fn identities(mut self, pool: RequestIdentity) -> Self {
+        self.identities = pool;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Names of the stored profiles, sorted.
+    pub fn list(&self) -> Result<Vec<String>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    names.push(stem.to_string());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    pub fn exists(&self, name: &str) -> bool {
+        self.path(name).is_ok_and(|p| p.is_file())
+    }
+
+    pub fn load(&self, name: &str) -> Result<Profile> {
+        let path = self.path(name)?;
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| Error::ConfigError(format!("Failed to read profile {name:?}: {e}")))?;
+        Ok(ProfileBundle::from_json(&text)?.profile)
+    }
+
+    /// Load `name`, or create and save it with a fresh identity.
+    pub fn load_or_create(&self, name: &str) -> Result<Profile> {
+        if self.exists(name) {
+            return self.load(name);
+        }
+        let profile = Profile::new(name, self.identities.next());
+        self.save(&profile)?;
+        Ok(profile)
+    }
+
+    /// Write `profile`, replacing any stored one of the same name.
+    pub fn save(&self, profile: &Profile) -> Result<()> {
+        let path = self.path(&profile.name)?;
+        std::fs::create_dir_all(&self.dir)?;
+        let json = ProfileBundle::new(profile.clone()).to_json()?;
+        // Write then rename, so a crash never leaves half a profile behind.
+        // Profiles hold session cookies and proxy passwords: owner-only
+        let tmp = path.with_extension("json.tmp");
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp)?;
+        #[cfg(unix)]
+        file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+        file.write_all(json.as_bytes())?;
+        drop(file);
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// The stored profile `name` as a bundle to copy to another machine or
+    /// into secrets storage.
+    pub fn export_profile(&self, name: &str) -> Result<String> {
+        ProfileBundle::new(self.load(name)?).to_json()
+    }
+
+    /// Store a bundle from `export_profile`, replacing any profile of the same
+    /// name, and return the profile.
+    pub fn import_profile(&self, bundle: &str) -> Result<Profile> {
+        let profile = ProfileBundle::from_json(bundle)?.profile;
+        self.save(&profile)?;
+        Ok(profile)
+    }
+
+    pub fn delete(&self, name: &str) -> Result<()> {
+        std::fs::remove_file(self.path(name)?)?;
+        Ok(())
+    }
+
+    /// The file of `name`, which must be letters, digits, `-`, `_` and `.`.
+    fn path(&self, name: &str) -> Result<PathBuf> {
+        let valid = !name.is_empty()
+            && !name.starts_with('.')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid {
+            return Err(Error::ConfigError(format!("invalid profile name {name:?}")));
+        }
+        Ok(self.dir.join(format!("{name}.json")))
+    }
+}
+
+/// An open profile: a separate browser context (its own cookies, storage,
+/// cache and proxy) whose pages present the profile's identity. From
+/// `AgenticBrowser::open_profile`.
+pub struct ProfileContext<'a> {
+    browser: &'a AgenticBrowser,
+    manager: &'a ProfileManager,
+    profile: Mutex<Profile>,
+    context_id: BrowserContextId,
+}
+
+impl<'a> ProfileContext<'a> {
+    pub(crate) async fn open(browser: &'a AgenticBrowser, manager: &'a ProfileManager, profile: Profile) -> Result<Self> {
+        let mut params = CreateBrowserContextParams::default();
+        #[cfg_attr(not(feature = "proxy-auth"), allow(unused_mut))]
+        let mut intercept = browser.intercept_rules().clone();
+        if let Some(ref proxy) = profile.proxy {
+            // The browser's proxy credentials must not reach the profile's proxy
+            let credentials = match (&proxy.username, &proxy.password) {
+                (Some(username), Some(password)) => Some((Arc::from(username.as_str()), Arc::from(password.as_str()))),
+                (None, None) => None,
+                _ => {
+                    return Err(Error::ConfigError(
+                        "profile proxy needs both a username and a password".to_string(),
+                    ))
+                }
+            };
+            #[cfg(feature = "proxy-auth")]
+            {
+                intercept.proxy_auth = credentials;
+            }
+            #[cfg(not(feature = "proxy-auth"))]
+            if credentials.is_some() {
+                return Err(Error::ConfigError(
+                    "profile proxy authentication needs the `proxy-auth` cargo feature".to_string(),
+                ));
+            }
+            params.proxy_server = Some(proxy.server.clone());
+            if !proxy.bypass.is_empty() {
+                params.proxy_bypass_list = Some(proxy.bypass.join(";"));
+            }
+        }
+        let context_id = browser.cdp().create_browser_context(params).await?;
+        // Every tab of the context, popups included, gets the profile's rules and identity
+        browser.register_context(context_id.clone(), intercept, profile.identity.clone());
+        let context = Self {
+            browser,
+            manager,
+            profile: Mutex::new(profile),
+            context_id,
+        };
+        if let Err(e) = browser.enable_download_events(Some(context.context_id.clone())).await {
+            context.dispose().await;
+            return Err(e);
+        }
+
+        let cookies: Vec<CookieParam> = context.profile().cookies.iter().map(StoredCookie::to_cdp).collect();
+        if !cookies.is_empty() {
+            let mut params = SetCookiesParams::new(cookies);
+            params.browser_context_id = Some(context.context_id.clone());
+            if let Err(e) = browser.cdp().execute(params).await {
+                context.dispose().await;
+                return Err(e.into());
+            }
+        }
+        Ok(context)
+    }
+
+    pub fn name(&self) -> String {
+        self.profile().name
+    }
+
+    /// A copy of the profile as last loaded or saved.
+    pub fn profile(&self) -> Profile {
+        self.profile.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Open a page in this profile's context, with its identity and stored
+    /// localStorage, navigated to `url`.
+    pub async fn new_page(&self, url: &str) -> Result<Page> {
+        let page = self.browser.open_context_page(&self.context_id).await?;
+        let profile = self.profile();
+        if !profile.local_storage.is_empty() {
+            let stored = serde_json::to_string(&profile.local_storage)
+                .map_err(|e| Error::JsError(e.to_string()))?;
+            page.inner()
+                .execute(AddScriptToEvaluateOnNewDocumentParams::new(format!(
+                    "({RESTORE_STORAGE_JS})({stored})"
+                )))
+                .await
+                .map_err(|e| Error::JsError(format!("Failed to install storage script: {e}")))?;
+        }
+        page.goto(url).await?;
+        Ok(page)
+    }
+
+    /// Store the context's cookies and the localStorage of its open tabs,
+    /// popups included, in the profile file.
+    pub async fn save(&self) -> Result<()> {
+        let params = GetCookiesParams {
+            browser_context_id: Some(self.context_id.clone()),
+        };
+        let cookies = self.browser.cdp().execute(params).await?.result.cookies;
+
+        let pages = self.browser.context_tabs(&self.context_id).await?;
+        let mut storage = Vec::new();
+        for page in pages {
+            // Pages closed meanwhile fail; their storage was saved earlier or is lost
+            let Ok(read) = page.evaluate(format!("({READ_STORAGE_JS})()")).await else {
+                continue;
+            };
+            if let Ok((origin, items)) = read.into_value::<(String, BTreeMap<String, String>)>() {
+                storage.push((origin, items));
+            }
+        }
+
+        let mut profile = self.profile.lock().unwrap_or_else(|e| e.into_inner());
+        profile.cookies = cookies.into_iter().map(StoredCookie::from_cdp).collect();
+        for (origin, items) in storage {
+            if origin == "null" {
+                continue;
+            }
+            if items.is_empty() {
+                profile.local_storage.remove(&origin);
+            } else {
+                profile.local_storage.insert(origin, items);
+            }
+        }
+        self.manager.save(&profile)
+    }
+
+    /// Save the profile, then close its pages and context.
+    pub async fn close(self) -> Result<()> {
+        let saved = self.save().await;
+        self.dispose().await;
+        saved
+    }
+
+    async fn dispose(&self) {
+        self.browser.forget_context(&self.context_id);
+        let _ = self
+            .browser
+            .cdp()
+            .execute(DisposeBrowserContextParams::new(self.context_id.clone()))
+            .await;
+    }
+}
+
+/// `(stored) => void`: fill localStorage from `stored` (items by origin) when
+/// the origin has none yet, so the site's own later changes stand.
+const RESTORE_STORAGE_JS: &str = r#"(stored) => {
+    try {
+        const items = stored[location.origin];
+        if (!items || localStorage.length > 0) return;
+        for (const [key, value] of Object.entries(items)) localStorage.setItem(key, value);
+    } catch (e) {}
+}"#;
+
+/// `() => [origin, items]` for the page's localStorage.
+const READ_STORAGE_JS: &str = r#"() => {
+    const items = {};
+    for (let i = 0; i < localStorage.length; i++) {
+        const key = localStorage.key(i);
+        items[key] = localStorage.getItem(key);
+    }
+    return [location.origin, items];
+}"#;
@@ -0,0 +1,185 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+/// Politeness settings for one domain.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Minimum time between the starts of two requests.
+    pub min_delay: Duration,
+    /// Requests in flight at once; 0 for no limit.
+    pub max_concurrent: usize,
+    /// Random extra delay, up to this much, added to each gap so the timing
+    /// doesn't look machine-made.
+    pub jitter: Duration,
+}
+
+impl RateLimit {
+    /// At most one request every `min_delay`, with no concurrency limit or jitter.
+    pub fn every(min_delay: Duration) -> Self {
+        Self {
+            min_delay,
+            ..Default::default()
+        }
+    }
+
+    pub fn max_concurrent(mut self, n: usize) -> Self {
+        self.max_concurrent = n;
+        self
+    }
+
+    pub fn jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+}
+
+struct DomainState {
+    slots: Arc<Semaphore>,
+    /// Earliest time the next request may start.
+    next_start: Mutex<Instant>,
+}
+
+/// Spaces out and caps concurrent requests per domain. Clones share their state,
+/// so one limiter passed to several browsers (`BrowserBuilder::rate_limiter`)
+/// throttles the whole fleet. A domain's limit also covers its subdomains.
+#[derive(Clone, Default)]
+pub struct RateLimiter {
+    default: Option<RateLimit>,
+    domains: Vec<(String, RateLimit)>,
+    state: Arc<Mutex<HashMap<String, Arc<DomainState>>>>,
+}
+
+impl std::fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("default", &self.default)
+            .field("domains", &self.domains)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Held while a request is in flight; frees its concurrency slot on drop.
+#[must_use = "the concurrency slot is released when the permit is dropped"]
+pub struct RatePermit {
+    _slot: Option<OwnedSemaphorePermit>,
+}
+
+/// Lowercase host of an http(s) URL.
+fn host(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+        return None;
+    }
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host_port = authority.rsplit('@').next()?;
+    let host = if host_port.starts_with('[') {
+        host_port.split_inclusive(']').next()?
+    } else {
+        host_port.split(':').next()?
+    };
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+/// Random 64 bits, good enough for jitter (not for cryptography).
+pub(crate) fn random_u64() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    hasher.finish()
+}
+
+/// Random duration in `0..=max`.
+pub(crate) fn random_up_to(max: Duration) -> Duration {
+    if max.is_zero() {
+        return Duration::ZERO;
+    }
+    let nanos = max.as_nanos().min(u64::MAX as u128) as u64;
+    Duration::from_nanos(random_u64() % (nanos + 1))
+}
+
+/// Random number in `-1.0..1.0`.
+pub(crate) fn random_signed() -> f64 {
+    (random_u64() >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+}
+
+impl RateLimiter {
+    /// A limiter that limits nothing until limits are added.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit for every host without a domain-specific limit. Each host is
+    /// limited separately.
+    pub fn default_limit(mut self, limit: RateLimit) -> Self {
+        self.default = Some(limit);
+        self
+    }
+
+    /// Limit for `domain` and its subdomains, shared between them. The most
+    /// specific matching domain wins.
+    pub fn domain(mut self, domain: impl Into<String>, limit: RateLimit) -> Self {
+        let domain = domain.into().trim_start_matches('.').to_ascii_lowercase();
+        self.domains.retain(|(d, _)| *d != domain);
+        self.domains.push((domain, limit));
+        self
+    }
+
+    /// The key requests to `host` are counted under, and its limit.
+    fn limit_for(&self, host: &str) -> Option<(String, RateLimit)> {
+        self.domains
+            .iter()
+            .filter(|(domain, _)| {
+                host == domain
+                    || host.strip_suffix(domain.as_str()).is_some_and(|rest| rest.ends_with('.'))
+            })
+            .max_by_key(|(domain, _)| domain.len())
+            .map(|(domain, limit)| (domain.clone(), *limit))
+            .or_else(|| self.default.map(|limit| (host.to_string(), limit)))
+    }
+
+    /// Wait until a request to `url` may start under its domain's limit. Hold the
+    /// permit until the request is done. URLs that aren't http(s), or whose domain
+    /// has no limit, pass straight through.
+    pub async fn acquire(&self, url: &str) -> RatePermit {
+        let Some((key, limit)) = host(url).and_then(|host| self.limit_for(&host)) else {
+            return RatePermit { _slot: None };
+        };
+        let state = Arc::clone(
+            self.state
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(key)
+                .or_insert_with(|| {
+                    let slots = match limit.max_concurrent {
+                        0 => Semaphore::MAX_PERMITS,
+                        n => n,
+                    };
+                    Arc::new(DomainState {
+                        slots: Arc::new(Semaphore::new(slots)),
+                        next_start: Mutex::new(Instant::now()),
+                    })
+                }),
+        );
+
+        // The semaphore is never closed.
+        let slot = Arc::clone(&state.slots).acquire_owned().await.ok();
+        let start = {
+            let mut next_start = state.next_start.lock().unwrap_or_else(|e| e.into_inner());
+            let start = (*next_start).max(Instant::now());
+            *next_start = start + limit.min_delay + random_up_to(limit.jitter);
+            start
+        };
+        tokio::time::sleep_until(start).await;
+        RatePermit { _slot: slot }
+    }
+}
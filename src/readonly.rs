@@ -0,0 +1,74 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::action::Action;
+
+type Confirm = Arc<dyn Fn(&Action) -> bool + Send + Sync>;
+
+/// Lets pages navigate and be observed but not change anything, for trying a
+/// new agent policy against a production site. `Page::execute_action` (and so
+/// `AgentRunner` and `replay`) fails typing, `<select>` changes, Enter and clicks
+/// on form submit buttons with `Error::ActionRejected`, unless `confirm` lets
+/// them through. Since any click can start a write from script, every request
+/// that isn't `GET`, `HEAD` or `OPTIONS` is failed in the network as well,
+/// unless `allow_writes` is set. Set with `BrowserBuilder::read_only` or
+/// `Page::set_read_only`.
+///
+/// Direct calls like `Page::click`, `Page::type_text` and `Page::fill` bypass
+/// the action check; only the network block applies to them.
+#[derive(Clone, Default)]
+pub struct ReadOnly {
+    confirm: Option<Confirm>,
+    allow_writes: bool,
+}
+
+impl fmt::Debug for ReadOnly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadOnly")
+            .field("confirm", &self.confirm.as_ref().map(|_| ".."))
+            .field("allow_writes", &self.allow_writes)
+            .finish()
+    }
+}
+
+impl ReadOnly {
+    /// Reject every mutating action and block writes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask `confirm` about each mutating action instead, and run it if it
+    /// returns true. It is called on the runtime's thread; wrap a blocking
+    /// prompt in `tokio::task::block_in_place`.
+    pub fn confirm(mut self, confirm: impl Fn(&Action) -> bool + Send + Sync + 'static) -> Self {
+        self.confirm = Some(Arc::new(confirm));
+        self
+    }
+
+    /// Let requests that aren't `GET`, `HEAD` or `OPTIONS` (form posts, `fetch`
+    /// and XHR writes) through, for sites that post while only reading, e.g.
+    /// search or analytics. Only the action check then stands between the
+    /// agent and a write.
+    pub fn allow_writes(mut self) -> Self {
+        self.allow_writes = true;
+        self
+    }
+
+    pub(crate) fn blocks_writes(&self) -> bool {
+        !self.allow_writes
+    }
+
+    /// Whether a mutating `action` may run.
+    pub(crate) fn allows(&self, action: &Action) -> bool {
+        self.confirm.as_ref().is_some_and(|confirm| confirm(action))
+    }
+}
+
+/// `(selector) => bool`: whether the first element matching `selector` is, or is
+/// inside, a button that submits a form.
+pub(crate) const SUBMITS_FORM_JS: &str = r#"(selector) => {
+    const el = document.querySelector(selector);
+    const control = el && el.closest('button, input[type=submit], input[type=image]');
+    if (!control || !control.form) return false;
+    return control.tagName !== 'BUTTON' || control.type === 'submit';
+}"#;
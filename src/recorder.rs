@@ -0,0 +1,247 @@
+use std::sync::{Arc, Mutex};
+
+use chromiumoxide::cdp::browser_protocol::page::{
+    AddScriptToEvaluateOnNewDocumentParams, EventFrameNavigated, GetNavigationHistoryParams,
+    RemoveScriptToEvaluateOnNewDocumentParams, ScriptIdentifier, TransitionType,
+};
+use chromiumoxide::cdp::js_protocol::runtime::{AddBindingParams, EventBindingCalled, RemoveBindingParams};
+use chromiumoxide::page::Page as CrPage;
+use futures::StreamExt;
+use tokio::task::JoinHandle;
+
+use crate::action::Action;
+use crate::error::{Error, Result};
+use crate::redact::{self, Redaction};
+
+/// A recorded flow, serializable as JSON (or any serde format) and replayable
+/// with `Page::replay`.
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ActionScript {
+    pub steps: Vec<Action>,
+}
+
+/// Records actions on a page until stopped or dropped, which removes the
+/// recorder from the page. Returned by `Page::start_action_recording`.
+pub struct ActionRecorder {
+    steps: Arc<Mutex<Vec<Action>>>,
+    tasks: Vec<JoinHandle<()>>,
+    page: CrPage,
+    script: Option<ScriptIdentifier>,
+}
+
+impl ActionRecorder {
+    /// The steps recorded so far.
+    pub fn script(&self) -> ActionScript {
+        ActionScript {
+            steps: self.steps.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+        }
+    }
+
+    /// Stop recording and return the script.
+    pub fn stop(self) -> ActionScript {
+        self.script()
+    }
+}
+
+impl Drop for ActionRecorder {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+        let Some(script) = self.script.take() else {
+            return;
+        };
+        let page = self.page.clone();
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                let _ = page.execute(RemoveScriptToEvaluateOnNewDocumentParams::new(script)).await;
+                let _ = page.execute(RemoveBindingParams::new(BINDING)).await;
+                let _ = page.evaluate(UNINSTALL_JS).await;
+            });
+        }
+    }
+}
+
+const BINDING: &str = "__agentic_record";
+
+/// `(isSensitive, selectors) => void`: reports clicks, field edits, `<select>`
+/// changes and Enter presses through the recording binding, identifying elements
+/// by the most stable selector available. Edits of fields `isSensitive` matches
+/// are flagged `sensitive`.
+const RECORDER_JS: &str = r#"(isSensitive, selectors) => {
+    const key = Symbol.for('agentic-browser.recorder');
+    if (window[key]) return;
+    const listeners = [];
+    const on = (type, listener) => {
+        document.addEventListener(type, listener, true);
+        listeners.push([type, listener]);
+    };
+    window[key] = () => {
+        for (const [type, listener] of listeners) document.removeEventListener(type, listener, true);
+        delete window[key];
+    };
+    const send = (step) => {
+        const binding = window.__agentic_record;
+        if (typeof binding === 'function') binding(JSON.stringify(step));
+    };
+    const unique = (sel) => {
+        try { return document.querySelectorAll(sel).length === 1; } catch (e) { return false; }
+    };
+    const selectorFor = (el) => {
+        if (el.id && unique('#' + CSS.escape(el.id))) return '#' + CSS.escape(el.id);
+        for (const attr of ['data-testid', 'data-test', 'name', 'aria-label']) {
+            const v = el.getAttribute(attr);
+            if (!v) continue;
+            const sel = el.tagName.toLowerCase() + '[' + attr + '=' + JSON.stringify(v) + ']';
+            if (unique(sel)) return sel;
+        }
+        const parts = [];
+        for (let node = el; node && node.nodeType === 1 && node !== document.body; node = node.parentElement) {
+            if (node.id && unique('#' + CSS.escape(node.id))) {
+                parts.unshift('#' + CSS.escape(node.id));
+                return parts.join(' > ');
+            }
+            let part = node.tagName.toLowerCase();
+            const siblings = node.parentElement
+                ? [...node.parentElement.children].filter(c => c.tagName === node.tagName)
+                : [];
+            if (siblings.length > 1) part += ':nth-of-type(' + (siblings.indexOf(node) + 1) + ')';
+            parts.unshift(part);
+        }
+        return 'body > ' + parts.join(' > ');
+    };
+    const isTextField = (el) =>
+        el.tagName === 'TEXTAREA' || el.isContentEditable ||
+        (el.tagName === 'INPUT' && !['checkbox', 'radio', 'submit', 'button', 'reset', 'file', 'image']
+            .includes((el.type || 'text').toLowerCase()));
+    const lastValues = new Map();
+    const recordValue = (el) => {
+        const selector = selectorFor(el);
+        const text = el.isContentEditable ? el.innerText : el.value;
+        if (lastValues.get(selector) === text) return;
+        lastValues.set(selector, text);
+        send({ action: 'type', selector, text, sensitive: isSensitive(el, selectors) });
+    };
+
+    on('click', (e) => {
+        const el = e.target instanceof Element ? e.target : null;
+        if (!el || !e.isTrusted || isTextField(el) || el.tagName === 'SELECT' || el.tagName === 'OPTION') return;
+        const target = el.closest('a, button, input, label, [role], [onclick]') || el;
+        send({ action: 'click', selector: selectorFor(target) });
+    });
+    on('change', (e) => {
+        const el = e.target;
+        if (!e.isTrusted || !(el instanceof Element)) return;
+        if (el.tagName === 'SELECT') send({ action: 'select', selector: selectorFor(el), value: el.value });
+        else if (isTextField(el)) recordValue(el);
+    });
+    on('keydown', (e) => {
+        const el = e.target;
+        if (!e.isTrusted || e.key !== 'Enter' || !(el instanceof Element) || !isTextField(el)) return;
+        recordValue(el);
+        send({ action: 'press', key: 'Enter' });
+    });
+}"#;
+
+/// Removes the listeners `RECORDER_JS` added to the current document.
+const UNINSTALL_JS: &str = "window[Symbol.for('agentic-browser.recorder')]?.()";
+
+/// Whether a recorded step was flagged `sensitive` by `RECORDER_JS`.
+#[derive(serde::Deserialize)]
+struct Flags {
+    #[serde(default)]
+    sensitive: bool,
+}
+
+/// Start recording actions on `page`. The current URL becomes the first `goto`.
+/// Text typed into fields `redaction` covers is recorded as its mask.
+pub(crate) async fn start(page: &CrPage, redaction: Redaction) -> Result<ActionRecorder> {
+    let script = format!(
+        "({})({}, {})",
+        RECORDER_JS,
+        redact::SENSITIVE_FIELD_JS,
+        serde_json::to_string(&redaction.selectors).map_err(|e| Error::JsError(e.to_string()))?
+    );
+    let mut calls = page
+        .event_listener::<EventBindingCalled>()
+        .await
+        .map_err(|e| Error::JsError(format!("Failed to listen for binding calls: {e}")))?;
+    let mut navigations = page
+        .event_listener::<EventFrameNavigated>()
+        .await
+        .map_err(|e| Error::JsError(format!("Failed to listen for navigations: {e}")))?;
+    page.execute(AddBindingParams::new(BINDING))
+        .await
+        .map_err(|e| Error::JsError(format!("Failed to add binding: {e}")))?;
+    let identifier = page
+        .execute(AddScriptToEvaluateOnNewDocumentParams::new(script.clone()))
+        .await
+        .map_err(|e| Error::JsError(format!("Failed to install recorder: {e}")))?
+        .result
+        .identifier;
+    page.evaluate(script.as_str())
+        .await
+        .map_err(|e| Error::JsError(e.to_string()))?;
+
+    let mut initial = Vec::new();
+    if let Some(url) = page.url().await?.filter(|u| u.starts_with("http")) {
+        initial.push(Action::Goto { url });
+    }
+    let steps = Arc::new(Mutex::new(initial));
+
+    let recorded = Arc::clone(&steps);
+    let actions = tokio::spawn(async move {
+        while let Some(event) = calls.next().await {
+            if event.name != BINDING {
+                continue;
+            }
+            let Ok(mut step) = serde_json::from_str::<Action>(&event.payload) else {
+                continue;
+            };
+            let flags = serde_json::from_str::<Flags>(&event.payload);
+            if let Action::Type { text, .. } = &mut step {
+                if flags.map_or(true, |f| f.sensitive) {
+                    *text = redaction.mask.clone();
+                }
+            }
+            recorded.lock().unwrap_or_else(|e| e.into_inner()).push(step);
+        }
+    });
+
+    // Navigations the user started (address bar, bookmarks) become `goto` steps;
+    // those caused by clicks and form submissions replay on their own.
+    let recorded = Arc::clone(&steps);
+    let navigator = page.clone();
+    let gotos = tokio::spawn(async move {
+        while let Some(event) = navigations.next().await {
+            if event.frame.parent_id.is_some() || !event.frame.url.starts_with("http") {
+                continue;
+            }
+            let Ok(history) = navigator.execute(GetNavigationHistoryParams::default()).await else {
+                continue;
+            };
+            let current = usize::try_from(history.result.current_index)
+                .ok()
+                .and_then(|i| history.result.entries.get(i));
+            let typed = current.is_some_and(|entry| {
+                matches!(
+                    entry.transition_type,
+                    TransitionType::Typed | TransitionType::AutoBookmark | TransitionType::Generated
+                )
+            });
+            if typed {
+                recorded
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(Action::Goto { url: event.frame.url.clone() });
+            }
+        }
+    });
+
+    Ok(ActionRecorder {
+        steps,
+        tasks: vec![actions, gotos],
+        page: page.clone(),
+        script: Some(identifier),
+    })
+}
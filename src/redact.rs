@@ -0,0 +1,107 @@
+/// What to keep out of transcripts, recordings and `Page::screenshot_redacted`.
+/// Password and one-time-code fields are always covered; `selectors` adds
+/// anything else sensitive (account numbers, addresses, chat messages).
+#[derive(Debug, Clone)]
+pub struct Redaction {
+    /// CSS selectors of more elements to blur and of fields whose typed text
+    /// is masked.
+    pub selectors: Vec<String>,
+    /// Replaces typed text in logs (default `[REDACTED]`).
+    pub mask: String,
+    /// Blur radius in CSS pixels (default 12).
+    pub blur: u32,
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Self {
+            selectors: Vec::new(),
+            mask: "[REDACTED]".to_string(),
+            blur: 12,
+        }
+    }
+}
+
+impl Redaction {
+    /// Password and one-time-code fields only.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn selector(mut self, selector: impl Into<String>) -> Self {
+        self.selectors.push(selector.into());
+        self
+    }
+
+    pub fn selectors(mut self, selectors: &[&str]) -> Self {
+        self.selectors.extend(selectors.iter().map(|s| s.to_string()));
+        self
+    }
+
+    pub fn mask(mut self, mask: impl Into<String>) -> Self {
+        self.mask = mask.into();
+        self
+    }
+
+    pub fn blur(mut self, pixels: u32) -> Self {
+        self.blur = pixels;
+        self
+    }
+}
+
+/// `(el, selectors) => bool`: whether `el` is a password, one-time-code or
+/// payment field, or matches one of `selectors`.
+pub(crate) const SENSITIVE_FIELD_JS: &str = r#"(el, selectors = []) => {
+    if (!el || el.nodeType !== 1) return false;
+    if (selectors.some(sel => { try { return el.matches(sel); } catch (e) { return false; } })) return true;
+    if ((el.getAttribute('type') || '').toLowerCase() === 'password') return true;
+    const autocomplete = (el.getAttribute('autocomplete') || '').toLowerCase();
+    if (/one-time-code|current-password|new-password|cc-number|cc-csc/.test(autocomplete)) return true;
+    const names = [el.name, el.id, el.getAttribute('aria-label'), el.getAttribute('placeholder')].join(' ');
+    return /(^|[\W_])(password|passwd|passcode|otp|totp|2fa|mfa|one-?time|pin|cvv|cvc|security-?code|verification-?code)($|[\W_])/i
+        .test(names);
+}"#;
+
+/// `(isSensitive, selectors, blur) => number`: blur every sensitive field and
+/// element matching `selectors` in the document, its open shadow roots and its
+/// same-origin frames, and every frame of another origin whole, remembering
+/// their inline filters for `UNREDACT_JS`; returns how many were blurred.
+pub(crate) const REDACT_JS: &str = r#"(isSensitive, selectors, blur) => {
+    const KEY = Symbol.for('agentic.redacted');
+    const targets = new Set();
+    const roots = [document];
+    for (let i = 0; i < roots.length; i++) {
+        const root = roots[i];
+        for (const el of root.querySelectorAll('*')) {
+            if (el.shadowRoot) roots.push(el.shadowRoot);
+            if (el.tagName === 'IFRAME' || el.tagName === 'FRAME') {
+                // null for frames of other origins: their content can't be searched
+                const doc = el.contentDocument;
+                if (doc) roots.push(doc);
+                else targets.add(el);
+            }
+        }
+        for (const el of root.querySelectorAll('input, textarea, [contenteditable]')) {
+            if (isSensitive(el)) targets.add(el);
+        }
+        for (const sel of selectors) {
+            try { root.querySelectorAll(sel).forEach(el => targets.add(el)); } catch (e) {}
+        }
+    }
+    const saved = window[KEY] || (window[KEY] = []);
+    for (const el of targets) {
+        saved.push([el, el.style.getPropertyValue('filter'), el.style.getPropertyPriority('filter')]);
+        el.style.setProperty('filter', 'blur(' + blur + 'px)', 'important');
+    }
+    return targets.size;
+}"#;
+
+/// `() => void`: put back the inline filters `REDACT_JS` replaced.
+pub(crate) const UNREDACT_JS: &str = r#"() => {
+    const KEY = Symbol.for('agentic.redacted');
+    for (const [el, value, priority] of (window[KEY] || []).reverse()) {
+        if (value) el.style.setProperty('filter', value, priority);
+        else el.style.removeProperty('filter');
+    }
+    delete window[KEY];
+}"#;
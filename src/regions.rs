@@ -0,0 +1,149 @@
+use crate::page::BoundingBox;
+
+/// What part of a page a `PageRegion` is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionKind {
+    /// The site header or banner at the top.
+    Header,
+    /// A navigation menu.
+    Nav,
+    /// The page's own content: `<main>`, else the article or the block with the
+    /// most paragraph text.
+    Main,
+    /// Content beside the main region (`<aside>`, `.sidebar`).
+    Sidebar,
+    Footer,
+    /// A cookie or privacy consent notice.
+    CookieBanner,
+    /// A dialog or an overlay covering much of the viewport.
+    Modal,
+}
+
+/// A labeled region of the page, from `Page::regions`.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct PageRegion {
+    pub kind: RegionKind,
+    /// `aria-label`, else the region's first heading, else empty.
+    pub label: String,
+    /// A CSS selector matching only the region's element, to scope queries
+    /// (`find_element(selector)`) and screenshots (`Element::screenshot`) to it.
+    pub selector: String,
+    pub bounds: BoundingBox,
+    /// The start of the region's text, at most 200 characters.
+    pub text: String,
+}
+
+/// `(selectorFor) => PageRegion[]` for the visible regions of the document,
+/// each element listed once, in page order; `selectorFor` is
+/// `interactive::SELECTOR_FOR_JS`.
+pub(crate) const REGIONS_JS: &str = r#"(selectorFor) => {
+    const CONSENT = /cookie|consent|gdpr|onetrust|cookiebot|didomi|truste|(^|[\W_])cmp($|[\W_])/i;
+    const SIDEBAR = /(^|[\W_])(side-?bar|side-?nav)($|[\W_])/i;
+    const HEADER = /(^|[\W_])(site-?header|page-?header|masthead|header)($|[\W_])/i;
+    const FOOTER = /(^|[\W_])(site-?footer|page-?footer|footer)($|[\W_])/i;
+    const clean = (s, max) => (s || '').replace(/\s+/g, ' ').trim().substring(0, max);
+    const visible = el => {
+        const rect = el.getBoundingClientRect();
+        if (rect.width === 0 || rect.height === 0) return false;
+        return typeof el.checkVisibility === 'function'
+            ? el.checkVisibility({ checkOpacity: true, checkVisibilityCSS: true })
+            : el.offsetParent !== null || getComputedStyle(el).position === 'fixed';
+    };
+    const names = el => (el.id || '') + ' ' + (el.getAttribute('class') || '') + ' '
+        + (el.getAttribute('aria-label') || '');
+    const floating = el => {
+        for (let node = el; node && node.nodeType === 1; node = node.parentElement) {
+            const position = getComputedStyle(node).position;
+            if (position === 'fixed' || position === 'sticky') return true;
+        }
+        return false;
+    };
+    // Headers and footers of articles and sections aren't the page's own
+    const pageLevel = el => !el.parentElement.closest('article, section, main, aside, nav, [role=main]');
+
+    const found = [];
+    const taken = new Set();
+    const add = (kind, el) => {
+        if (!el || taken.has(el) || !visible(el)) return;
+        // A region inside another of the same kind is part of it
+        if (found.some(r => r.kind === kind && (r.el.contains(el) || el.contains(r.el)))) return;
+        taken.add(el);
+        found.push({ kind, el });
+    };
+    const all = sel => Array.from(document.querySelectorAll(sel));
+
+    // Consent notices and dialogs first, so they aren't also taken for headers
+    // or footers
+    for (const el of all('div, section, aside, form, dialog, [role=dialog], [role=region], [role=banner]')) {
+        if (CONSENT.test(names(el)) && floating(el) && /cookie|consent|privacy/i.test(el.innerText || '')) {
+            add('cookie_banner', el);
+        }
+    }
+    const cookieOf = el => found.some(r => r.kind === 'cookie_banner' && (r.el.contains(el) || el.contains(r.el)));
+    for (const el of all('dialog[open], [role=dialog], [role=alertdialog], [aria-modal=true]')) {
+        if (!cookieOf(el)) add('modal', el);
+    }
+    const viewport = window.innerWidth * window.innerHeight;
+    for (const el of all('body > *, body > * > *')) {
+        if (cookieOf(el) || getComputedStyle(el).position !== 'fixed') continue;
+        const rect = el.getBoundingClientRect();
+        if (rect.width * rect.height >= viewport * 0.3 && parseInt(getComputedStyle(el).zIndex, 10) > 0) {
+            add('modal', el);
+        }
+    }
+    const overlays = found.map(r => r.el);
+    const inOverlay = el => overlays.some(o => o.contains(el));
+
+    for (const el of all('header, [role=banner]')) if (pageLevel(el) && !inOverlay(el)) add('header', el);
+    if (!found.some(r => r.kind === 'header')) {
+        for (const el of all('body > div, body > * > div')) {
+            if (HEADER.test(el.id + ' ' + (el.getAttribute('class') || '')) && !inOverlay(el)) add('header', el);
+        }
+    }
+    for (const el of all('nav, [role=navigation]')) if (!inOverlay(el)) add('nav', el);
+
+    let main = document.querySelector('main, [role=main]');
+    if (!main || !visible(main)) {
+        const articles = all('article').filter(visible);
+        main = articles.length === 1 ? articles[0] : null;
+    }
+    if (!main) {
+        // The block holding the most paragraph text
+        const scores = new Map();
+        for (const p of all('p')) {
+            const parent = p.parentElement;
+            if (!parent || inOverlay(parent) || parent.closest('header, footer, nav, aside')) continue;
+            scores.set(parent, (scores.get(parent) || 0) + (p.innerText || '').length);
+        }
+        let best = 0;
+        for (const [el, score] of scores) {
+            if (score > best) { best = score; main = el; }
+        }
+    }
+    if (main && !inOverlay(main)) add('main', main);
+
+    for (const el of all('aside, [role=complementary]')) if (!inOverlay(el)) add('sidebar', el);
+    for (const el of all('div, section')) {
+        if (SIDEBAR.test(el.id + ' ' + (el.getAttribute('class') || '')) && !inOverlay(el)) add('sidebar', el);
+    }
+    for (const el of all('footer, [role=contentinfo]')) if (pageLevel(el) && !inOverlay(el)) add('footer', el);
+    if (!found.some(r => r.kind === 'footer')) {
+        for (const el of all('body > div, body > * > div')) {
+            if (FOOTER.test(el.id + ' ' + (el.getAttribute('class') || '')) && !inOverlay(el)) add('footer', el);
+        }
+    }
+
+    found.sort((a, b) => a.el.compareDocumentPosition(b.el) & Node.DOCUMENT_POSITION_FOLLOWING ? -1 : 1);
+    return found.map(({ kind, el }) => {
+        const rect = el.getBoundingClientRect();
+        const heading = el.querySelector('h1, h2, h3, h4, h5, h6');
+        return {
+            kind,
+            label: clean(el.getAttribute('aria-label') || (heading && heading.innerText), 100),
+            selector: selectorFor(el),
+            bounds: { x: rect.x, y: rect.y, width: rect.width, height: rect.height },
+            text: clean(el.innerText, 200),
+        };
+    });
+}"#;
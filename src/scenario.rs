@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::page::Page;
+
+/// A declarative automation flow: a list of steps (`navigate`, `wait_for`, `fill`,
+/// `click`, `assert`, `extract`, `screenshot`) loaded from JSON, or YAML with the
+/// `yaml` feature, so flows can be written without Rust.
+/// `${name}` in any step is replaced by a variable, including values captured by
+/// earlier `extract` steps.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    /// Retries for every step that doesn't set its own.
+    #[serde(default)]
+    pub retries: u32,
+    pub steps: Vec<ScenarioStep>,
+}
+
+/// One step of a `Scenario`, with optional per-step retries.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ScenarioStep {
+    #[serde(flatten)]
+    pub action: StepAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+}
+
+/// What a `ScenarioStep` does. Selectors are CSS; clicks, fills, assertions and
+/// extractions wait for their element like `Locator`.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepAction {
+    Navigate(String),
+    /// Wait until the selector is visible.
+    WaitFor(String),
+    Fill {
+        selector: String,
+        value: String,
+    },
+    Click(String),
+    /// Fail unless the element exists and, if given, its text contains or equals
+    /// the expected value.
+    Assert {
+        selector: String,
+        #[serde(default)]
+        contains: Option<String>,
+        #[serde(default)]
+        equals: Option<String>,
+    },
+    /// Store the element's text (or attribute) in the variable `as`.
+    Extract {
+        selector: String,
+        #[serde(rename = "as")]
+        name: String,
+        #[serde(default)]
+        attribute: Option<String>,
+    },
+    /// Save a PNG screenshot to the given path.
+    Screenshot(String),
+}
+
+impl StepAction {
+    fn name(&self) -> &'static str {
+        match self {
+            StepAction::Navigate(_) => "navigate",
+            StepAction::WaitFor(_) => "wait_for",
+            StepAction::Fill { .. } => "fill",
+            StepAction::Click(_) => "click",
+            StepAction::Assert { .. } => "assert",
+            StepAction::Extract { .. } => "extract",
+            StepAction::Screenshot(_) => "screenshot",
+        }
+    }
+}
+
+/// Outcome of a successful `Scenario::run`.
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct ScenarioReport {
+    pub steps_run: usize,
+    /// Retries used across all steps.
+    pub retries_used: u32,
+    /// Values captured by `extract` steps.
+    pub extracted: HashMap<String, String>,
+}
+
+/// Delay between attempts of a failing step.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+impl Scenario {
+    pub fn from_json(text: &str) -> Result<Self> {
+        serde_json::from_str(text).map_err(|e| Error::ConfigError(format!("Invalid scenario: {e}")))
+    }
+
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        serde_yaml::from_str(text).map_err(|e| Error::ConfigError(format!("Invalid scenario: {e}")))
+    }
+
+    /// Load a `.json` file, or a `.yaml`/`.yml` file with the `yaml` feature.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => Self::from_yaml(&text),
+            _ => Self::from_json(&text),
+        }
+    }
+
+    /// Set a variable, overriding the value from the file.
+    pub fn var(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.variables.insert(name.into(), value.into());
+        self
+    }
+
+    /// Run the steps in order on `page`. A step that fails is retried after a short
+    /// delay; once its retries are used up the run stops with `Error::ScenarioError`.
+    pub async fn run(&self, page: &Page) -> Result<ScenarioReport> {
+        let mut variables = self.variables.clone();
+        let mut report = ScenarioReport::default();
+
+        for (index, step) in self.steps.iter().enumerate() {
+            let retries = step.retries.unwrap_or(self.retries);
+            let mut attempt = 0;
+            loop {
+                match run_step(page, &step.action, &mut variables, &mut report).await {
+                    Ok(()) => break,
+                    Err(_) if attempt < retries => {
+                        attempt += 1;
+                        report.retries_used += 1;
+                        tokio::time::sleep(RETRY_DELAY).await;
+                    }
+                    Err(e) => {
+                        let message = match e {
+                            Error::ScenarioError(message) => message,
+                            other => other.to_string(),
+                        };
+                        return Err(Error::ScenarioError(format!(
+                            "step {} ({}): {message}",
+                            index + 1,
+                            step.action.name()
+                        )));
+                    }
+                }
+            }
+            report.steps_run += 1;
+        }
+        Ok(report)
+    }
+}
+
+/// Replace `${name}` with the variable's value; unknown variables are left as is.
+fn substitute(text: &str, variables: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+                match variables.get(name) {
+                    Some(value) => out.push_str(value),
+                    None => out.push_str(&rest[start..start + 3 + end]),
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+async fn run_step(
+    page: &Page,
+    action: &StepAction,
+    variables: &mut HashMap<String, String>,
+    report: &mut ScenarioReport,
+) -> Result<()> {
+    let sub = |text: &str| substitute(text, variables);
+    match action {
+        StepAction::Navigate(url) => page.goto(&sub(url)).await,
+        StepAction::WaitFor(selector) => page.wait_for_selector(&sub(selector)).await.map(|_| ()),
+        StepAction::Fill { selector, value } => page.locator(&sub(selector)).fill(&sub(value)).await,
+        StepAction::Click(selector) => page.locator(&sub(selector)).click().await,
+        StepAction::Assert { selector, contains, equals } => {
+            let selector = sub(selector);
+            let text = page.locator(&selector).inner_text().await?;
+            if let Some(expected) = contains.as_deref().map(sub) {
+                if !text.contains(&expected) {
+                    return Err(Error::ScenarioError(format!(
+                        "{selector:?} text {text:?} does not contain {expected:?}"
+                    )));
+                }
+            }
+            if let Some(expected) = equals.as_deref().map(sub) {
+                if text.trim() != expected {
+                    return Err(Error::ScenarioError(format!(
+                        "{selector:?} text {text:?} is not {expected:?}"
+                    )));
+                }
+            }
+            Ok(())
+        }
+        StepAction::Extract { selector, name, attribute } => {
+            let selector = sub(selector);
+            let value = match attribute {
+                Some(attribute) => page
+                    .locator(&selector)
+                    .element()
+                    .await?
+                    .get_attribute(attribute)
+                    .await?
+                    .unwrap_or_default(),
+                None => page.locator(&selector).inner_text().await?,
+            };
+            variables.insert(name.clone(), value.clone());
+            report.extracted.insert(name.clone(), value);
+            Ok(())
+        }
+        StepAction::Screenshot(path) => page.screenshot_to_file(sub(path)).await,
+    }
+}
@@ -0,0 +1,887 @@
+use std::collections::HashMap;
+
+use chromiumoxide::cdp::browser_protocol::dom_snapshot::{
+    ArrayOfStrings, CaptureSnapshotParams, RareBooleanData, RareIntegerData, RareStringData,
+    StringIndex,
+};
+use chromiumoxide::page::Page as CrPage;
+
+use crate::error::{Error, Result};
+use crate::element::SelectOption;
+use crate::page::{FormField, FormTarget};
+
+/// Where `accessibility_tree`, `get_links` and `get_form_fields` get their data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExtractionBackend {
+    /// Walk the DOM with injected JavaScript (default).
+    #[default]
+    Js,
+    /// Read a `DOMSnapshot.captureSnapshot` taken by the browser. Runs no JS in
+    /// the page (invisible to the site, unaffected by CSP) and is much faster on
+    /// huge DOMs. Like the JS backend, reads the main document unless
+    /// `TreeOptions` ask for frames and shadow roots.
+    DomSnapshot,
+}
+
+/// What `Page::accessibility_tree_with` looks into besides the main document,
+/// and how much text it keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeOptions {
+    /// Include same-origin iframes, each under a `[frame]` line with its `src`.
+    /// Cross-origin frames show as `[frame] ... (cross-origin)` with nothing under
+    /// them.
+    pub frames: bool,
+    /// Include the content of open shadow roots (web components), in place of
+    /// the elements they render.
+    pub shadow_roots: bool,
+    /// Characters kept of each text (default 100); texts over twice as long are
+    /// left out, as body copy rather than labels. `None` keeps all text in full.
+    pub text_limit: Option<usize>,
+    /// Characters kept of input values (default 50); `None` for no limit.
+    pub value_limit: Option<usize>,
+}
+
+impl Default for TreeOptions {
+    fn default() -> Self {
+        Self {
+            frames: false,
+            shadow_roots: false,
+            text_limit: Some(100),
+            value_limit: Some(50),
+        }
+    }
+}
+
+impl TreeOptions {
+    /// Frames and shadow roots both.
+    pub fn all() -> Self {
+        Self { frames: true, shadow_roots: true, ..Self::default() }
+    }
+
+    /// Keep every text and value in full, for reading articles through the tree.
+    pub fn full_text(mut self) -> Self {
+        self.text_limit = None;
+        self.value_limit = None;
+        self
+    }
+
+    pub fn text_limit(mut self, limit: Option<usize>) -> Self {
+        self.text_limit = limit;
+        self
+    }
+
+    pub fn value_limit(mut self, limit: Option<usize>) -> Self {
+        self.value_limit = limit;
+        self
+    }
+
+    /// The `options` argument of `ACCESSIBILITY_TREE_JS`.
+    pub(crate) fn js_args(&self) -> serde_json::Value {
+        serde_json::json!({
+            "frames": self.frames,
+            "shadowRoots": self.shadow_roots,
+            "textLimit": self.text_limit,
+            "valueLimit": self.value_limit,
+        })
+    }
+
+    pub fn frames(mut self, frames: bool) -> Self {
+        self.frames = frames;
+        self
+    }
+
+    pub fn shadow_roots(mut self, shadow_roots: bool) -> Self {
+        self.shadow_roots = shadow_roots;
+        self
+    }
+}
+
+/// `(options, root) => string[]`: the lines of the accessibility tree of `root`
+/// (default: the body), walking into frames and open shadow roots as `options`
+/// say.
+pub(crate) const ACCESSIBILITY_TREE_JS: &str = r#"(options, root) => {
+    function getRole(el) {
+        return el.getAttribute('role') || el.tagName.toLowerCase();
+    }
+    function getLabel(el) {
+        if (el.getAttribute('aria-label')) return el.getAttribute('aria-label');
+        if (el.id) {
+            const label = el.getRootNode().querySelector('label[for="' + CSS.escape(el.id) + '"]');
+            if (label) return (label.innerText || '').trim();
+        }
+        if (el.getAttribute('alt')) return el.getAttribute('alt');
+        if (el.getAttribute('title')) return el.getAttribute('title');
+        if (el.getAttribute('placeholder')) return el.getAttribute('placeholder');
+        return '';
+    }
+    // What an element renders: its shadow tree or slot assignment if we look
+    // into shadow roots, else its children
+    function childrenOf(el) {
+        if (options.shadowRoots && el.shadowRoot) return el.shadowRoot.childNodes;
+        if (options.shadowRoots && el.tagName === 'SLOT') {
+            const assigned = el.assignedNodes({ flatten: true });
+            if (assigned.length) return assigned;
+        }
+        return el.childNodes;
+    }
+    function walk(node, depth) {
+        const lines = [];
+        const indent = '  '.repeat(depth);
+        if (node.nodeType === Node.TEXT_NODE) {
+            const text = (node.textContent || '').trim();
+            const limit = options.textLimit;
+            if (text && (limit == null || text.length < 2 * limit)) {
+                lines.push(indent + '[text] "' + (limit == null ? text : text.substring(0, limit)) + '"');
+            }
+            return lines;
+        }
+        if (node.nodeType !== Node.ELEMENT_NODE) return lines;
+        const el = node;
+        const tag = el.tagName.toLowerCase();
+
+        // Skip invisible elements
+        if (['script','style','noscript','meta','link','head'].includes(tag)) return lines;
+        if (typeof el.checkVisibility === 'function') {
+            if (!el.checkVisibility({checkOpacity: false, checkVisibilityCSS: true})) return lines;
+        } else if (el.offsetParent === null && tag !== 'body' && tag !== 'html') {
+            return lines;
+        }
+
+        if (options.frames && (tag === 'iframe' || tag === 'frame')) {
+            let doc = null;
+            try { doc = el.contentDocument; } catch (e) {}
+            const root = doc && (doc.body || doc.documentElement);
+            let desc = indent + '[frame]';
+            const name = el.getAttribute('title') || el.getAttribute('name');
+            if (name) desc += ' "' + name + '"';
+            desc += ' src=' + (el.src || 'about:blank');
+            if (!root) {
+                lines.push(desc + ' (cross-origin)');
+                return lines;
+            }
+            lines.push(desc);
+            lines.push(...walk(root, depth + 1));
+            return lines;
+        }
+
+        const role = getRole(el);
+        const label = getLabel(el);
+        const interactable = ['a','button','input','select','textarea'].includes(tag);
+        const isLandmark = ['main','nav','header','footer','aside','section','article','form'].includes(tag)
+            || el.getAttribute('role');
+
+        if (interactable || isLandmark) {
+            let desc = indent + '[' + role + ']';
+            if (label) desc += ' "' + label + '"';
+            if (tag === 'a' && el.href) desc += ' href=' + el.href;
+            if (tag === 'input') {
+                desc += ' type=' + (el.type || 'text');
+                if (el.name) desc += ' name=' + el.name;
+                const limit = options.valueLimit;
+                if (el.value) desc += ' value="' + (limit == null ? el.value : el.value.substring(0, limit)) + '"';
+            }
+            if (tag === 'select') {
+                if (el.name) desc += ' name=' + el.name;
+            }
+            if (tag === 'button' || (tag === 'input' && ['submit','button'].includes(el.type))) {
+                const btnText = (el.innerText || el.value || '').trim();
+                if (btnText && !label) desc += ' "' + btnText + '"';
+            }
+            lines.push(desc);
+        }
+
+        for (const child of childrenOf(el)) {
+            const childLines = walk(child, interactable || isLandmark ? depth + 1 : depth);
+            lines.push(...childLines);
+        }
+        return lines;
+    }
+    return walk(root || document.body || document.documentElement, 0);
+}"#;
+
+/// `(root, selector, attributes, {textLimit?, start?, count?}) => ElementData[]`
+/// for the elements matching `selector` under `root` (a document or an
+/// element), from the `start`th match on and at most `count` of them. `root`
+/// may also be the `NodeList` of an earlier query, whose matches are used as
+/// they are. Texts are cut to `textLimit` characters (500 if left out, none if
+/// null).
+pub(crate) const QUERY_WITH_DATA_JS: &str = r#"(root, selector, attributes, options = {}) => {
+    const { textLimit = 500, start = 0, count = Infinity } = options;
+    const matches = root instanceof NodeList ? root : root.querySelectorAll(selector);
+    return Array.prototype.slice.call(matches, start, start + count).map(el => {
+        const attrs = {};
+        for (const name of attributes) {
+            const val = el.getAttribute(name);
+            if (val !== null) attrs[name] = val;
+        }
+        const text = (el.innerText || '').trim();
+        return {
+            tag: el.tagName.toLowerCase(),
+            text: textLimit == null ? text : text.substring(0, textLimit),
+            attributes: attrs
+        };
+    });
+}"#;
+
+/// Settings for `Page::query_selector_all_with` and `Element::query_all_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractOptions {
+    /// Characters kept of each element's text (default 500); `None` keeps the
+    /// whole text, e.g. for scraping articles.
+    pub text_limit: Option<usize>,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        Self { text_limit: Some(500) }
+    }
+}
+
+impl ExtractOptions {
+    /// Keep every element's text in full.
+    pub fn full_text() -> Self {
+        Self { text_limit: None }
+    }
+
+    pub fn text_limit(mut self, limit: Option<usize>) -> Self {
+        self.text_limit = limit;
+        self
+    }
+
+    /// The `options` argument of `QUERY_WITH_DATA_JS`.
+    pub(crate) fn js_args(&self) -> serde_json::Value {
+        serde_json::json!({ "textLimit": self.text_limit })
+    }
+}
+
+/// `(root) => [text, href][]` for the links under `root`, hrefs absolute.
+pub(crate) const LINKS_JS: &str = r#"(root) =>
+    Array.from(root.querySelectorAll('a[href]')).map(a => [(a.innerText || '').trim(), a.href])"#;
+
+/// Every input, select and textarea of the document, as `FormField`s.
+pub(crate) const FORM_FIELDS_JS: &str = r#"(() => {
+    const fields = Array.from(document.querySelectorAll('input, select, textarea'));
+    // Values of each checkbox and radio group, by form, then type and name
+    const groups = new Map();
+    for (const el of fields) {
+        if ((el.type !== 'checkbox' && el.type !== 'radio') || !el.name) continue;
+        if (!groups.has(el.form)) groups.set(el.form, new Map());
+        const byName = groups.get(el.form);
+        const key = `${el.type} ${el.name}`;
+        if (!byName.has(key)) byName.set(key, []);
+        byName.get(key).push(el.value);
+    }
+    return fields.map(el => {
+        let label = '';
+        if (el.id) {
+            const labelEl = document.querySelector(`label[for="${CSS.escape(el.id)}"]`);
+            if (labelEl) label = (labelEl.innerText || '').trim();
+        }
+        if (!label && el.closest('label')) {
+            label = (el.closest('label').innerText || '').trim();
+        }
+        const checkable = el.type === 'checkbox' || el.type === 'radio';
+        const group = !checkable ? [] : !el.name ? [el.value] : groups.get(el.form).get(`${el.type} ${el.name}`);
+        return {
+            tag: el.tagName.toLowerCase(),
+            type: el.type || '',
+            name: el.name || '',
+            id: el.id || '',
+            value: el.value || '',
+            placeholder: el.placeholder || '',
+            label: label,
+            options: el.tagName === 'SELECT'
+                ? Array.from(el.options).map(o => ({
+                    value: o.value, label: o.label.trim(), selected: o.selected, disabled: o.disabled
+                  }))
+                : [],
+            checked: checkable && el.checked,
+            group: group,
+            required: el.required || false,
+            disabled: el.matches(':disabled'),
+            form: el.form
+                ? { id: el.form.id || '', action: el.form.action, method: el.form.method }
+                : null
+        };
+    });
+})()"#;
+
+const ELEMENT_NODE: i64 = 1;
+const TEXT_NODE: i64 = 3;
+const DOCUMENT_FRAGMENT_NODE: i64 = 11;
+
+/// Tags whose content is never shown.
+const HIDDEN_TAGS: &[&str] = &["script", "style", "noscript", "meta", "link", "head", "template"];
+
+struct Node {
+    /// Index of the document (0: the main one) the node belongs to.
+    document: usize,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    node_type: i64,
+    /// Lowercase tag name for elements, `#text` for text nodes.
+    name: String,
+    value: String,
+    attributes: Vec<(String, String)>,
+    input_value: Option<String>,
+    text_value: Option<String>,
+    selected: bool,
+    checked: bool,
+    /// Has a layout object and is not `visibility: hidden`.
+    rendered: bool,
+    /// For shadow roots: `open`, `closed` or `user-agent`.
+    shadow_root_type: Option<String>,
+    /// For frames: the document they show, if it was captured (same process).
+    content_document: Option<usize>,
+}
+
+/// The documents of a page (main document and same-process frames) as captured
+/// by `DOMSnapshot.captureSnapshot`, their nodes in one list.
+pub(crate) struct DomSnapshot {
+    nodes: Vec<Node>,
+    base_urls: Vec<String>,
+    /// First `<label for>` of each id, keyed by document and id.
+    labels: HashMap<(usize, String), usize>,
+    /// First `<form>` with each id, keyed by document and id.
+    forms: HashMap<(usize, String), usize>,
+}
+
+fn string(strings: &[String], index: &StringIndex) -> String {
+    usize::try_from(*index.inner())
+        .ok()
+        .and_then(|i| strings.get(i))
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn rare_strings(strings: &[String], data: &Option<RareStringData>, len: usize) -> Vec<Option<String>> {
+    let mut out = vec![None; len];
+    if let Some(data) = data {
+        for (node, value) in data.index.iter().zip(&data.value) {
+            if let Some(slot) = usize::try_from(*node).ok().and_then(|n| out.get_mut(n)) {
+                *slot = Some(string(strings, value));
+            }
+        }
+    }
+    out
+}
+
+fn rare_integers(data: &Option<RareIntegerData>, len: usize) -> Vec<Option<usize>> {
+    let mut out = vec![None; len];
+    if let Some(data) = data {
+        for (node, value) in data.index.iter().zip(&data.value) {
+            if let Some(slot) = usize::try_from(*node).ok().and_then(|n| out.get_mut(n)) {
+                *slot = usize::try_from(*value).ok();
+            }
+        }
+    }
+    out
+}
+
+fn rare_bools(data: &Option<RareBooleanData>, len: usize) -> Vec<bool> {
+    let mut out = vec![false; len];
+    if let Some(data) = data {
+        for node in &data.index {
+            if let Some(slot) = usize::try_from(*node).ok().and_then(|n| out.get_mut(n)) {
+                *slot = true;
+            }
+        }
+    }
+    out
+}
+
+/// Whether a URL starts with a scheme (`https:`, `mailto:`, `javascript:`, ...).
+fn has_scheme(url: &str) -> bool {
+    let Some((scheme, _)) = url.split_once(':') else {
+        return false;
+    };
+    scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl DomSnapshot {
+    pub async fn capture(page: &CrPage) -> Result<Self> {
+        let params = CaptureSnapshotParams::new(vec!["visibility".to_string()]);
+        let snapshot = page
+            .execute(params)
+            .await
+            .map_err(|e| Error::JsError(format!("DOMSnapshot.captureSnapshot failed: {e}")))?
+            .result;
+        if snapshot.documents.is_empty() {
+            return Err(Error::JsError("DOM snapshot contains no document".to_string()));
+        }
+        let strings = &snapshot.strings;
+        let mut nodes: Vec<Node> = Vec::new();
+        let mut base_urls = Vec::new();
+        for (index, document) in snapshot.documents.iter().enumerate() {
+            let offset = nodes.len();
+            base_urls.push(string(strings, &document.base_url));
+            let tree = &document.nodes;
+
+            let names = tree.node_name.clone().unwrap_or_default();
+            let len = names.len();
+            let parents = tree.parent_index.clone().unwrap_or_default();
+            let types = tree.node_type.clone().unwrap_or_default();
+            let values = tree.node_value.clone().unwrap_or_default();
+            let attributes = tree.attributes.clone().unwrap_or_default();
+            let mut input_values = rare_strings(strings, &tree.input_value, len);
+            let mut text_values = rare_strings(strings, &tree.text_value, len);
+            let mut shadow_root_types = rare_strings(strings, &tree.shadow_root_type, len);
+            let content_documents = rare_integers(&tree.content_document_index, len);
+            let selected = rare_bools(&tree.option_selected, len);
+            let checked = rare_bools(&tree.input_checked, len);
+
+            let mut rendered = vec![false; len];
+            let layout = &document.layout;
+            for (i, node) in layout.node_index.iter().enumerate() {
+                let hidden = layout
+                    .styles
+                    .get(i)
+                    .and_then(|styles: &ArrayOfStrings| styles.inner().first())
+                    .is_some_and(|v| string(strings, v) == "hidden");
+                if let Some(slot) = usize::try_from(*node).ok().and_then(|n| rendered.get_mut(n)) {
+                    *slot = !hidden;
+                }
+            }
+
+            nodes.extend((0..len).map(|i| {
+                let pairs = attributes.get(i).map(|a| a.inner().as_slice()).unwrap_or_default();
+                Node {
+                    document: index,
+                    parent: parents
+                        .get(i)
+                        .and_then(|p| usize::try_from(*p).ok())
+                        .filter(|&p| p < len)
+                        .map(|p| offset + p),
+                    children: Vec::new(),
+                    node_type: types.get(i).copied().unwrap_or(0),
+                    name: names.get(i).map(|n| string(strings, n).to_ascii_lowercase()).unwrap_or_default(),
+                    value: values.get(i).map(|v| string(strings, v)).unwrap_or_default(),
+                    attributes: pairs
+                        .chunks(2)
+                        .filter(|pair| pair.len() == 2)
+                        .map(|pair| (string(strings, &pair[0]), string(strings, &pair[1])))
+                        .collect(),
+                    input_value: input_values[i].take(),
+                    text_value: text_values[i].take(),
+                    selected: selected[i],
+                    checked: checked[i],
+                    rendered: rendered[i],
+                    shadow_root_type: shadow_root_types[i].take(),
+                    content_document: content_documents[i],
+                }
+            }));
+        }
+        for i in 0..nodes.len() {
+            if let Some(parent) = nodes[i].parent {
+                nodes[parent].children.push(i);
+            }
+        }
+
+        let mut snapshot = Self { nodes, base_urls, labels: HashMap::new(), forms: HashMap::new() };
+        snapshot.labels = snapshot.index("label", "for");
+        snapshot.forms = snapshot.index("form", "id");
+        Ok(snapshot)
+    }
+
+    /// The first `tag` element with each value of `attribute`, by document.
+    fn index(&self, tag: &str, attribute: &str) -> HashMap<(usize, String), usize> {
+        let mut index = HashMap::new();
+        for i in 0..self.nodes.len() {
+            let node = &self.nodes[i];
+            if node.node_type != ELEMENT_NODE || node.name != tag {
+                continue;
+            }
+            if let Some(value) = self.attr(i, attribute) {
+                index.entry((self.nodes[i].document, value.to_string())).or_insert(i);
+            }
+        }
+        index
+    }
+
+    fn attr(&self, node: usize, name: &str) -> Option<&str> {
+        self.nodes[node]
+            .attributes
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Elements of document `document` (0: the main one).
+    fn elements(&self, document: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len())
+            .filter(move |&i| self.nodes[i].document == document && self.nodes[i].node_type == ELEMENT_NODE)
+    }
+
+    fn find(&self, document: usize, name: &str) -> Option<usize> {
+        self.elements(document).find(|&i| self.nodes[i].name == name)
+    }
+
+    /// Visible text of a subtree, whitespace-collapsed (approximates `innerText`).
+    fn text(&self, node: usize) -> String {
+        let mut parts = Vec::new();
+        self.collect_text(node, &mut parts);
+        collapse_whitespace(&parts.concat())
+    }
+
+    fn collect_text(&self, node: usize, parts: &mut Vec<String>) {
+        let n = &self.nodes[node];
+        match n.node_type {
+            TEXT_NODE => parts.push(n.value.clone()),
+            ELEMENT_NODE if !n.rendered || HIDDEN_TAGS.contains(&n.name.as_str()) => {}
+            _ => {
+                for &child in &n.children {
+                    self.collect_text(child, parts);
+                }
+            }
+        }
+    }
+
+    fn ancestor(&self, node: usize, name: &str) -> Option<usize> {
+        let mut current = self.nodes[node].parent;
+        while let Some(i) = current {
+            if self.nodes[i].name == name {
+                return Some(i);
+            }
+            current = self.nodes[i].parent;
+        }
+        None
+    }
+
+    fn label_for(&self, node: usize) -> Option<String> {
+        let id = self.attr(node, "id")?;
+        self.labels.get(&(self.nodes[node].document, id.to_string())).map(|&i| self.text(i))
+    }
+
+    /// `el.type` as the DOM reports it.
+    fn input_type(&self, node: usize) -> String {
+        match self.nodes[node].name.as_str() {
+            "input" => self.attr(node, "type").unwrap_or("text").to_ascii_lowercase(),
+            "select" if self.attr(node, "multiple").is_some() => "select-multiple".to_string(),
+            "select" => "select-one".to_string(),
+            "textarea" => "textarea".to_string(),
+            "button" => self.attr(node, "type").unwrap_or("submit").to_ascii_lowercase(),
+            _ => String::new(),
+        }
+    }
+
+    fn form_value(&self, node: usize) -> String {
+        let n = &self.nodes[node];
+        match n.name.as_str() {
+            "input" => n
+                .input_value
+                .clone()
+                .or_else(|| self.attr(node, "value").map(String::from))
+                .unwrap_or_default(),
+            "textarea" => n.text_value.clone().unwrap_or_default(),
+            "select" => self
+                .descendants(node)
+                .into_iter()
+                .find(|&i| self.nodes[i].name == "option" && self.nodes[i].selected)
+                .map(|i| self.attr(i, "value").map(String::from).unwrap_or_else(|| self.text(i)))
+                .unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    fn descendants(&self, node: usize) -> Vec<usize> {
+        let mut out = Vec::new();
+        let mut stack: Vec<usize> = self.nodes[node].children.iter().rev().copied().collect();
+        while let Some(i) = stack.pop() {
+            out.push(i);
+            stack.extend(self.nodes[i].children.iter().rev());
+        }
+        out
+    }
+
+    /// Resolve a (possibly relative) href against the base URL of `node`'s document.
+    fn resolve(&self, node: usize, href: &str) -> String {
+        let base = self.base_urls[self.nodes[node].document].as_str();
+        let href = href.trim();
+        if has_scheme(href) {
+            return href.to_string();
+        }
+        let Some(scheme_end) = base.find("://").map(|i| i + 3) else {
+            return href.to_string();
+        };
+        if let Some(rest) = href.strip_prefix("//") {
+            return format!("{}{rest}", &base[..scheme_end]);
+        }
+        let origin_end = base[scheme_end..].find('/').map_or(base.len(), |i| scheme_end + i);
+        if href.starts_with('/') {
+            return format!("{}{href}", &base[..origin_end]);
+        }
+        let without_fragment = base.split('#').next().unwrap_or(base);
+        if href.starts_with('#') {
+            return format!("{without_fragment}{href}");
+        }
+        let without_query = without_fragment.split('?').next().unwrap_or(without_fragment);
+        if href.starts_with('?') {
+            return format!("{without_query}{href}");
+        }
+        let dir_end = without_query
+            .rfind('/')
+            .filter(|&i| i >= origin_end)
+            .map_or(without_query.len(), |i| i + 1);
+        let dir = &without_query[..dir_end];
+        if dir.ends_with('/') {
+            format!("{dir}{href}")
+        } else {
+            format!("{dir}/{href}")
+        }
+    }
+
+    /// Same output as the JS `get_links`.
+    pub fn links(&self) -> Vec<(String, String)> {
+        self.elements(0)
+            .filter(|&i| self.nodes[i].name == "a")
+            .filter_map(|i| Some((self.text(i), self.resolve(i, self.attr(i, "href")?))))
+            .collect()
+    }
+
+    /// Same output as the JS `get_form_fields`.
+    pub fn form_fields(&self) -> Vec<FormField> {
+        let fields: Vec<(usize, Option<usize>)> = self
+            .elements(0)
+            .filter(|&i| matches!(self.nodes[i].name.as_str(), "input" | "select" | "textarea"))
+            .map(|i| (i, self.form_of(i)))
+            .collect();
+        // Values of each checkbox and radio group, by form, type and name
+        let mut groups: HashMap<(Option<usize>, String, &str), Vec<String>> = HashMap::new();
+        for &(i, form) in &fields {
+            if let Some(name) = self.attr(i, "name").filter(|_| self.is_checkable(i)) {
+                groups.entry((form, self.input_type(i), name)).or_default().push(self.form_value(i));
+            }
+        }
+        fields
+            .into_iter()
+            .map(|(i, form)| FormField {
+                tag: self.nodes[i].name.clone(),
+                r#type: self.input_type(i),
+                name: self.attr(i, "name").unwrap_or_default().to_string(),
+                id: self.attr(i, "id").unwrap_or_default().to_string(),
+                value: self.form_value(i),
+                placeholder: self.attr(i, "placeholder").unwrap_or_default().to_string(),
+                label: self
+                    .label_for(i)
+                    .filter(|l| !l.is_empty())
+                    .or_else(|| self.ancestor(i, "label").map(|l| self.text(l)))
+                    .unwrap_or_default(),
+                options: self.select_options(i),
+                checked: self.is_checkable(i) && self.nodes[i].checked,
+                group: match self.attr(i, "name") {
+                    _ if !self.is_checkable(i) => Vec::new(),
+                    Some(name) => groups[&(form, self.input_type(i), name)].clone(),
+                    None => vec![self.form_value(i)],
+                },
+                required: self.attr_present(i, "required"),
+                disabled: self.is_disabled(i),
+                form: form.map(|form| self.form_target(form)),
+            })
+            .collect()
+    }
+
+    fn attr_present(&self, node: usize, name: &str) -> bool {
+        self.nodes[node].attributes.iter().any(|(n, _)| n.eq_ignore_ascii_case(name))
+    }
+
+    fn is_checkable(&self, node: usize) -> bool {
+        self.nodes[node].name == "input" && matches!(self.input_type(node).as_str(), "checkbox" | "radio")
+    }
+
+    /// All text under a node, rendered or not (`<option>`s have no layout).
+    fn raw_text(&self, node: usize) -> String {
+        let parts: Vec<&str> = self
+            .descendants(node)
+            .into_iter()
+            .filter(|&i| self.nodes[i].node_type == TEXT_NODE)
+            .map(|i| self.nodes[i].value.as_str())
+            .collect();
+        collapse_whitespace(&parts.concat())
+    }
+
+    fn select_options(&self, node: usize) -> Vec<SelectOption> {
+        if self.nodes[node].name != "select" {
+            return Vec::new();
+        }
+        self.descendants(node)
+            .into_iter()
+            .filter(|&i| self.nodes[i].name == "option")
+            .map(|i| {
+                let text = self.raw_text(i);
+                SelectOption {
+                    value: self.attr(i, "value").map_or_else(|| text.clone(), String::from),
+                    label: self.attr(i, "label").map_or(text, String::from),
+                    selected: self.nodes[i].selected,
+                    disabled: self.is_disabled(i),
+                }
+            })
+            .collect()
+    }
+
+    /// The form a field belongs to: its `form` attribute's target, else the
+    /// enclosing `<form>`.
+    fn form_of(&self, node: usize) -> Option<usize> {
+        match self.attr(node, "form") {
+            Some(id) => self.forms.get(&(self.nodes[node].document, id.to_string())).copied(),
+            None => self.ancestor(node, "form"),
+        }
+    }
+
+    fn form_target(&self, form: usize) -> FormTarget {
+        let method = self.attr(form, "method").unwrap_or("get").to_ascii_lowercase();
+        FormTarget {
+            id: self.attr(form, "id").unwrap_or_default().to_string(),
+            action: match self.attr(form, "action") {
+                Some(action) => self.resolve(form, action),
+                None => self.base_urls[self.nodes[form].document].clone(),
+            },
+            method: if matches!(method.as_str(), "post" | "dialog") { method } else { "get".to_string() },
+        }
+    }
+
+    /// `:disabled`: the attribute, or a disabled `<fieldset>` around the field
+    /// (outside its first `<legend>`).
+    fn is_disabled(&self, node: usize) -> bool {
+        if self.attr_present(node, "disabled") {
+            return true;
+        }
+        let mut child = node;
+        while let Some(parent) = self.nodes[child].parent {
+            if self.nodes[parent].name == "fieldset" && self.attr_present(parent, "disabled") {
+                let first_legend = self.nodes[parent]
+                    .children
+                    .iter()
+                    .copied()
+                    .find(|&c| self.nodes[c].name == "legend");
+                if first_legend != Some(child) {
+                    return true;
+                }
+            }
+            child = parent;
+        }
+        false
+    }
+
+    /// Same format as `ACCESSIBILITY_TREE_JS`.
+    pub fn accessibility_tree(&self, options: TreeOptions) -> String {
+        let mut lines = Vec::new();
+        if let Some(root) = self.root(0) {
+            self.walk(root, 0, options, &mut lines);
+        }
+        lines.join("\n")
+    }
+
+    fn root(&self, document: usize) -> Option<usize> {
+        self.find(document, "body").or_else(|| self.find(document, "html"))
+    }
+
+    fn walk(&self, node: usize, depth: usize, options: TreeOptions, lines: &mut Vec<String>) {
+        let n = &self.nodes[node];
+        let indent = "  ".repeat(depth);
+        if n.node_type == DOCUMENT_FRAGMENT_NODE {
+            if options.shadow_roots && n.shadow_root_type.as_deref() == Some("open") {
+                for &child in &n.children {
+                    self.walk(child, depth, options, lines);
+                }
+            }
+            return;
+        }
+        if n.node_type == TEXT_NODE {
+            let text = n.value.trim();
+            let fits = options.text_limit.is_none_or(|limit| text.chars().count() < limit.saturating_mul(2));
+            if !text.is_empty() && fits {
+                let short: String = text.chars().take(options.text_limit.unwrap_or(usize::MAX)).collect();
+                lines.push(format!("{indent}[text] \"{short}\""));
+            }
+            return;
+        }
+        if n.node_type != ELEMENT_NODE {
+            return;
+        }
+        let tag = n.name.as_str();
+        if HIDDEN_TAGS.contains(&tag) {
+            return;
+        }
+        if !n.rendered && tag != "body" && tag != "html" {
+            return;
+        }
+        if options.frames && matches!(tag, "iframe" | "frame") {
+            let mut desc = format!("{indent}[frame]");
+            if let Some(name) = self.attr(node, "title").or_else(|| self.attr(node, "name")) {
+                desc += &format!(" \"{name}\"");
+            }
+            let src = self.attr(node, "src").map_or("about:blank".to_string(), |src| self.resolve(node, src));
+            desc += &format!(" src={src}");
+            match n.content_document.and_then(|document| self.root(document)) {
+                Some(root) => {
+                    lines.push(desc);
+                    self.walk(root, depth + 1, options, lines);
+                }
+                None => lines.push(format!("{desc} (cross-origin)")),
+            }
+            return;
+        }
+
+        let role = self.attr(node, "role").unwrap_or(tag);
+        let label = self
+            .attr(node, "aria-label")
+            .map(String::from)
+            .or_else(|| self.label_for(node))
+            .or_else(|| self.attr(node, "alt").map(String::from))
+            .or_else(|| self.attr(node, "title").map(String::from))
+            .or_else(|| self.attr(node, "placeholder").map(String::from))
+            .unwrap_or_default();
+        let interactable = matches!(tag, "a" | "button" | "input" | "select" | "textarea");
+        let landmark = matches!(
+            tag,
+            "main" | "nav" | "header" | "footer" | "aside" | "section" | "article" | "form"
+        ) || self.attr(node, "role").is_some();
+
+        if interactable || landmark {
+            let mut desc = format!("{indent}[{role}]");
+            if !label.is_empty() {
+                desc += &format!(" \"{label}\"");
+            }
+            if let Some(href) = self.attr(node, "href").filter(|_| tag == "a") {
+                desc += &format!(" href={}", self.resolve(node, href));
+            }
+            let input_type = self.input_type(node);
+            if tag == "input" {
+                desc += &format!(" type={input_type}");
+                if let Some(name) = self.attr(node, "name") {
+                    desc += &format!(" name={name}");
+                }
+                let value = self.form_value(node);
+                if !value.is_empty() {
+                    let short: String = value.chars().take(options.value_limit.unwrap_or(usize::MAX)).collect();
+                    desc += &format!(" value=\"{short}\"");
+                }
+            }
+            if tag == "select" {
+                if let Some(name) = self.attr(node, "name") {
+                    desc += &format!(" name={name}");
+                }
+            }
+            if tag == "button" || (tag == "input" && matches!(input_type.as_str(), "submit" | "button")) {
+                let text = Some(self.text(node))
+                    .filter(|t| !t.is_empty())
+                    .unwrap_or_else(|| self.form_value(node));
+                if !text.is_empty() && label.is_empty() {
+                    desc += &format!(" \"{text}\"");
+                }
+            }
+            lines.push(desc);
+        }
+
+        let child_depth = if interactable || landmark { depth + 1 } else { depth };
+        for &child in &n.children {
+            self.walk(child, child_depth, options, lines);
+        }
+    }
+}
@@ -39,6 +39,18 @@ pub async fn apply_stealth(page: &CrPage) -> Result<()> {
     Ok(())
 }
 
+/// Apply stealth to a page that is already showing a document (e.g. a tab that
+/// was open before we attached): registers the scripts for future navigations
+/// and also runs them in the current document.
+pub async fn apply_stealth_to_existing(page: &CrPage) -> Result<()> {
+    apply_stealth(page).await?;
+    // Block scope keeps the script's top-level `const`s from clashing with page globals
+    page.evaluate(format!("{{\n{STEALTH_JS}\n}}"))
+        .await
+        .map_err(|e| Error::JsError(format!("Failed to run stealth scripts: {e}")))?;
+    Ok(())
+}
+
 /// All stealth evasion scripts combined into one JS string.
 static STEALTH_JS: &str = r#"
 // === navigator.webdriver ===
@@ -0,0 +1,73 @@
+use crate::error::{Error, Result};
+
+/// Ends CSS animations and transitions at their final state and turns off smooth
+/// scrolling and the blinking caret.
+pub(crate) const DISABLE_ANIMATIONS_CSS: &str = "*, *::before, *::after {
+    animation-duration: 0s !important;
+    animation-delay: 0s !important;
+    animation-iteration-count: 1 !important;
+    transition-duration: 0s !important;
+    transition-delay: 0s !important;
+    scroll-behavior: auto !important;
+    caret-color: transparent !important;
+}";
+
+/// Script that appends a `<style>` with `css` to the document. Also works as a
+/// new-document script: it waits for the root element so the style applies before
+/// first paint, then moves it after the page's own styles once the document is
+/// parsed, so it wins over rules of the same specificity.
+pub(crate) fn inject_js(css: &str) -> Result<String> {
+    let css_js = serde_json::to_string(css).map_err(|e| Error::JsError(e.to_string()))?;
+    Ok(format!(
+        r#"(() => {{
+    const add = () => {{
+        const style = document.createElement('style');
+        style.setAttribute('data-agentic-style', '');
+        style.textContent = {css_js};
+        const append = () => (document.head || document.documentElement).appendChild(style);
+        append();
+        if (document.readyState === 'loading') {{
+            document.addEventListener('DOMContentLoaded', append, {{ once: true }});
+        }}
+    }};
+    if (document.documentElement) return add();
+    const observer = new MutationObserver(() => {{
+        if (!document.documentElement) return;
+        observer.disconnect();
+        add();
+    }});
+    observer.observe(document, {{ childList: true }});
+}})()"#
+    ))
+}
+
+/// Draws a `color` outline box over each visible element matching `selector`, in
+/// a layer of its own that ignores the mouse. Returns the number of boxes drawn.
+pub(crate) const HIGHLIGHT_JS: &str = r#"(selector, color) => {
+    let layer = document.querySelector('[data-agentic-highlights]');
+    if (!layer) {
+        layer = document.createElement('div');
+        layer.setAttribute('data-agentic-highlights', '');
+        layer.style.cssText = 'position:absolute;top:0;left:0;width:0;height:0;'
+            + 'overflow:visible;pointer-events:none;z-index:2147483647';
+        document.documentElement.appendChild(layer);
+    }
+    let drawn = 0;
+    for (const el of document.querySelectorAll(selector)) {
+        const rect = el.getBoundingClientRect();
+        if (rect.width === 0 && rect.height === 0) continue;
+        const box = document.createElement('div');
+        box.style.cssText = 'position:absolute;box-sizing:border-box;pointer-events:none';
+        box.style.left = (rect.left + window.scrollX - 2) + 'px';
+        box.style.top = (rect.top + window.scrollY - 2) + 'px';
+        box.style.width = (rect.width + 4) + 'px';
+        box.style.height = (rect.height + 4) + 'px';
+        box.style.border = '3px solid ' + color;
+        layer.appendChild(box);
+        drawn++;
+    }
+    return drawn;
+}"#;
+
+pub(crate) const CLEAR_HIGHLIGHTS_JS: &str =
+    "document.querySelectorAll('[data-agentic-highlights]').forEach(el => el.remove())";
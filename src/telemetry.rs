@@ -0,0 +1,118 @@
+use std::time::Instant;
+
+#[cfg(feature = "telemetry")]
+use crate::error::Error;
+use crate::error::Result;
+
+/// Navigations started by `goto*`, `reload*` and history navigation, labeled
+/// `outcome="ok"` or `"error"`.
+pub const NAVIGATIONS: &str = "agentic_browser_navigations_total";
+/// Seconds each `Page::execute_action` took, labeled by `action`.
+pub const ACTION_DURATION: &str = "agentic_browser_action_duration_seconds";
+/// Actions that failed with `Error::Timeout`, labeled by `action`.
+pub const TIMEOUTS: &str = "agentic_browser_timeouts_total";
+/// Bytes of screenshots taken.
+pub const SCREENSHOT_BYTES: &str = "agentic_browser_screenshot_bytes_total";
+/// Tabs currently open in browsers launched by this process.
+pub const PAGES_OPEN: &str = "agentic_browser_pages_open";
+/// Calls to `AgenticBrowser::restart`.
+pub const CHROME_RESTARTS: &str = "agentic_browser_chrome_restarts_total";
+
+// Metrics are recorded through the `metrics` facade when the `telemetry` feature
+// is on, so any installed recorder (`serve_prometheus`, StatsD, ...) sees them;
+// without it these functions compile to nothing.
+
+/// Register the help text of every metric with the installed recorder. Call it
+/// after installing a recorder of your own; `serve_prometheus` does it itself.
+#[cfg(feature = "telemetry")]
+pub fn describe_metrics() {
+    use metrics::{describe_counter, describe_gauge, describe_histogram, Unit};
+
+    describe_counter!(NAVIGATIONS, "Page navigations by outcome");
+    describe_histogram!(ACTION_DURATION, Unit::Seconds, "Page::execute_action latency by action");
+    describe_counter!(TIMEOUTS, "Actions that timed out, by action");
+    describe_counter!(SCREENSHOT_BYTES, Unit::Bytes, "Bytes of screenshots taken");
+    describe_gauge!(PAGES_OPEN, "Open tabs");
+    describe_counter!(CHROME_RESTARTS, "Chrome restarts");
+}
+
+/// Install a Prometheus recorder and serve its metrics over HTTP on `addr`
+/// (any path, e.g. `http://0.0.0.0:9000/metrics`). Call once, from inside the
+/// tokio runtime; fails if another recorder is already installed.
+#[cfg(feature = "prometheus")]
+pub fn serve_prometheus(addr: std::net::SocketAddr) -> Result<()> {
+    metrics_exporter_prometheus::PrometheusBuilder::new()
+        .with_http_listener(addr)
+        .install()
+        .map_err(|e| Error::ConfigError(format!("Failed to start the Prometheus exporter: {e}")))?;
+    describe_metrics();
+    Ok(())
+}
+
+pub(crate) fn navigation<T>(result: &Result<T>) {
+    #[cfg(feature = "telemetry")]
+    metrics::counter!(NAVIGATIONS, "outcome" => if result.is_ok() { "ok" } else { "error" }).increment(1);
+    #[cfg(not(feature = "telemetry"))]
+    let _ = result;
+}
+
+pub(crate) fn action<T>(action: &'static str, started: Instant, result: &Result<T>) {
+    #[cfg(feature = "telemetry")]
+    {
+        metrics::histogram!(ACTION_DURATION, "action" => action).record(started.elapsed().as_secs_f64());
+        if let Err(Error::Timeout(_)) = result {
+            metrics::counter!(TIMEOUTS, "action" => action).increment(1);
+        }
+    }
+    #[cfg(not(feature = "telemetry"))]
+    let _ = (action, started, result);
+}
+
+/// Count the bytes of a screenshot on its way to the caller.
+pub(crate) fn screenshot(bytes: Vec<u8>) -> Vec<u8> {
+    #[cfg(feature = "telemetry")]
+    metrics::counter!(SCREENSHOT_BYTES).increment(bytes.len() as u64);
+    bytes
+}
+
+/// Keep `PAGES_OPEN` up to date from the browser's target events, including tabs
+/// opened by scripts. Tabs still open when the browser goes away are subtracted
+/// then.
+#[cfg(feature = "telemetry")]
+pub(crate) async fn watch_pages(browser: &chromiumoxide::browser::Browser) -> Result<()> {
+    use std::collections::HashSet;
+
+    use chromiumoxide::cdp::browser_protocol::target::{EventTargetCreated, EventTargetDestroyed};
+    use futures::StreamExt;
+
+    let listen_error = |e: chromiumoxide::error::CdpError| {
+        Error::LaunchError(format!("Failed to listen for browser events: {e}"))
+    };
+    let mut created = browser.event_listener::<EventTargetCreated>().await.map_err(listen_error)?;
+    let mut destroyed = browser.event_listener::<EventTargetDestroyed>().await.map_err(listen_error)?;
+    tokio::spawn(async move {
+        let mut open = HashSet::new();
+        loop {
+            tokio::select! {
+                Some(event) = created.next() => {
+                    if event.target_info.r#type == "page" && open.insert(event.target_info.target_id.inner().clone()) {
+                        metrics::gauge!(PAGES_OPEN).increment(1.0);
+                    }
+                }
+                Some(event) = destroyed.next() => {
+                    if open.remove(event.target_id.inner()) {
+                        metrics::gauge!(PAGES_OPEN).decrement(1.0);
+                    }
+                }
+                else => break,
+            }
+        }
+        metrics::gauge!(PAGES_OPEN).decrement(open.len() as f64);
+    });
+    Ok(())
+}
+
+pub(crate) fn chrome_restarted() {
+    #[cfg(feature = "telemetry")]
+    metrics::counter!(CHROME_RESTARTS).increment(1);
+}
@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::error::Result;
+
+/// A copy of example.com's page, so tests written against it run offline.
+const EXAMPLE_HTML: &str = r#"<!doctype html>
+<html>
+<head>
+    <title>Example Domain</title>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <style>
+    body { background-color: #f0f0f2; margin: 0; padding: 0; font-family: -apple-system, system-ui, sans-serif; }
+    div { width: 600px; margin: 5em auto; padding: 2em; background-color: #fdfdff; border-radius: 0.5em; }
+    a:link, a:visited { color: #38488f; text-decoration: none; }
+    </style>
+</head>
+<body>
+<div>
+    <h1>Example Domain</h1>
+    <p>This domain is for use in illustrative examples in documents. You may use this
+    domain in literature without prior coordination or asking for permission.</p>
+    <p><a href="https://www.iana.org/domains/example">More information...</a></p>
+</div>
+</body>
+</html>
+"#;
+
+const FORM_HTML: &str = r#"<!doctype html>
+<html>
+<head><title>Form</title><meta charset="utf-8"></head>
+<body>
+<h1>Sign up</h1>
+<form action="/echo" method="post">
+    <label for="name">Name</label> <input id="name" name="name" placeholder="Your name">
+    <label for="email">Email</label> <input id="email" name="email" type="email">
+    <label for="password">Password</label> <input id="password" name="password" type="password">
+    <label for="plan">Plan</label>
+    <select id="plan" name="plan">
+        <option value="free">Free</option>
+        <option value="pro">Pro</option>
+    </select>
+    <label><input type="checkbox" name="terms" value="yes"> I accept the terms</label>
+    <label for="comment">Comment</label> <textarea id="comment" name="comment"></textarea>
+    <button type="submit">Submit</button>
+</form>
+</body>
+</html>
+"#;
+
+const LATE_HTML: &str = r#"<!doctype html>
+<html>
+<head><title>Late content</title><meta charset="utf-8"></head>
+<body>
+<h1>Loading...</h1>
+<script>
+setTimeout(() => {
+    document.querySelector('h1').textContent = 'Loaded';
+    const p = document.createElement('p');
+    p.id = 'late';
+    p.textContent = 'Arrived after 500ms';
+    document.body.appendChild(p);
+}, 500);
+</script>
+</body>
+</html>
+"#;
+
+const STYLE_CSS: &str = "body { background: url(/pixel.png) repeat; }\n";
+
+/// A 1x1 green PNG.
+const PIXEL_PNG: &[u8] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52, 0x00, 0x00,
+    0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x02, 0x00, 0x00, 0x00, 0x90, 0x77, 0x53, 0xde, 0x00, 0x00, 0x00,
+    0x0c, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0xf8, 0xcf, 0xc0, 0x00, 0x00, 0x03, 0x01, 0x01, 0x00, 0xc9,
+    0xfe, 0x92, 0xef, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+];
+
+/// A response the server sends for a path.
+#[derive(Debug, Clone)]
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn new(content_type: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status: 200,
+            content_type,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    fn html(body: impl Into<Vec<u8>>) -> Self {
+        Self::new("text/html; charset=utf-8", body)
+    }
+}
+
+/// Local HTTP server with fixture pages, for tests that shouldn't depend on the
+/// network. Binds to a free port on 127.0.0.1 and stops when dropped.
+///
+/// Fixture pages:
+/// - `/`: a copy of example.com
+/// - `/form`: a sign-up form (text, email, password, select, checkbox,
+///   textarea) posting to `/echo`
+/// - `/echo`: the request's method, query, headers (names lowercased) and body,
+///   as JSON in `<pre id="echo">`
+/// - `/iframe`: `/form` in a same-origin iframe and `/` in a cross-origin one
+///   (through `localhost`)
+/// - `/late`: adds `<p id="late">` and changes the `<h1>` after 500ms
+/// - `/slow?ms=N`: example.com after an N ms delay (default 1000)
+/// - `/download`: a CSV file sent as an attachment
+/// - `/private-download`: `/download`, but only with a `session=ok` cookie
+///   (403 otherwise)
+/// - `/json`: `{"ok": true, "items": [1, 2, 3]}`
+/// - `/style.css`: a stylesheet tiling `/pixel.png` over the body
+/// - `/pixel.png`: a 1x1 PNG
+/// - `/cached.js`: `window.served = N;`, cacheable for an hour, with a new N
+///   every time it is actually fetched
+/// - `/status/N`: an empty page with status N
+///
+/// Add more with `add_page`. Anything else is a 404.
+pub struct TestServer {
+    addr: SocketAddr,
+    pages: Arc<Mutex<HashMap<String, Response>>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl TestServer {
+    pub async fn start() -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let pages = Arc::new(Mutex::new(HashMap::new()));
+        let shared = Arc::clone(&pages);
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let pages = Arc::clone(&shared);
+                tokio::spawn(async move {
+                    let _ = serve(stream, addr, &pages).await;
+                });
+            }
+        });
+        Ok(Self { addr, pages, task })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Absolute URL of `path` on this server, e.g. `server.url("/form")`.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    /// Serve `html` at `path` (exact match, without the query), replacing a
+    /// built-in page of the same path.
+    pub fn add_page(&self, path: &str, html: impl Into<String>) {
+        self.pages
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(path.to_string(), Response::html(html.into()));
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    headers: Vec<(String, String)>,
+    cookie: String,
+    body: Vec<u8>,
+}
+
+/// Read one request, answer it and close the connection.
+async fn serve(
+    stream: TcpStream,
+    addr: SocketAddr,
+    pages: &Mutex<HashMap<String, Response>>,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).await?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or("/");
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let (path, query) = (path.to_string(), query.to_string());
+
+    let mut content_length = 0;
+    let mut cookie = String::new();
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let (name, value) = (name.trim().to_ascii_lowercase(), value.trim().to_string());
+            if name == "content-length" {
+                content_length = value.parse().unwrap_or(0);
+            } else if name == "cookie" {
+                cookie = value.clone();
+            }
+            headers.push((name, value));
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+
+    let request = Request { method, path, query, headers, cookie, body };
+    let custom = pages.lock().unwrap_or_else(|e| e.into_inner()).get(&request.path).cloned();
+    let response = match custom {
+        Some(response) => response,
+        None => route(&request, addr).await,
+    };
+
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason(response.status),
+        response.content_type,
+        response.body.len(),
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+    let stream = reader.get_mut();
+    stream.write_all(head.as_bytes()).await?;
+    if request.method != "HEAD" {
+        stream.write_all(&response.body).await?;
+    }
+    stream.shutdown().await
+}
+
+async fn route(request: &Request, addr: SocketAddr) -> Response {
+    let param = |name: &str| {
+        request
+            .query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.to_string())
+    };
+    match request.path.as_str() {
+        "/" => Response::html(EXAMPLE_HTML),
+        "/form" => Response::html(FORM_HTML),
+        "/late" => Response::html(LATE_HTML),
+        "/echo" => {
+            let echo = serde_json::json!({
+                "method": request.method,
+                "query": request.query,
+                "headers": request.headers.iter().cloned().collect::<HashMap<_, _>>(),
+                "body": String::from_utf8_lossy(&request.body),
+            });
+            let json = echo.to_string().replace('&', "&amp;").replace('<', "&lt;");
+            Response::html(format!(
+                "<!doctype html><html><head><title>Echo</title></head><body><pre id=\"echo\">{json}</pre></body></html>"
+            ))
+        }
+        "/iframe" => Response::html(format!(
+            "<!doctype html><html><head><title>Frames</title></head><body>\
+             <h1>Frames</h1>\
+             <iframe id=\"same-origin\" src=\"/form\" width=\"600\" height=\"400\"></iframe>\
+             <iframe id=\"cross-origin\" src=\"http://localhost:{}/\" width=\"600\" height=\"400\"></iframe>\
+             </body></html>",
+            addr.port()
+        )),
+        "/slow" => {
+            let ms = param("ms").and_then(|ms| ms.parse().ok()).unwrap_or(1000);
+            tokio::time::sleep(Duration::from_millis(ms)).await;
+            Response::html(EXAMPLE_HTML)
+        }
+        "/private-download" if !request.cookie.split("; ").any(|c| c == "session=ok") => Response {
+            status: 403,
+            ..Response::html("<!doctype html><html><head><title>Forbidden</title></head><body></body></html>")
+        },
+        "/download" | "/private-download" => {
+            let mut response = Response::new("text/csv", "id,name\n1,Ada\n2,Grace\n");
+            response.headers.push((
+                "Content-Disposition".to_string(),
+                "attachment; filename=\"report.csv\"".to_string(),
+            ));
+            response
+        }
+        "/json" => Response::new("application/json", r#"{"ok": true, "items": [1, 2, 3]}"#),
+        "/style.css" => Response::new("text/css", STYLE_CSS),
+        "/pixel.png" => Response::new("image/png", PIXEL_PNG),
+        "/cached.js" => {
+            static SERVED: AtomicU64 = AtomicU64::new(0);
+            let served = SERVED.fetch_add(1, Ordering::Relaxed) + 1;
+            let mut response = Response::new("text/javascript", format!("window.served = {served};"));
+            response.headers.push(("Cache-Control".to_string(), "max-age=3600".to_string()));
+            response
+        }
+        path => match path.strip_prefix("/status/").and_then(|code| code.parse::<u16>().ok()) {
+            Some(status) if (200..600).contains(&status) => Response {
+                status,
+                ..Response::html(format!("<!doctype html><html><head><title>{status}</title></head><body></body></html>"))
+            },
+            _ => Response {
+                status: 404,
+                ..Response::html("<!doctype html><html><head><title>Not Found</title></head><body></body></html>")
+            },
+        },
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Status",
+    }
+}
@@ -0,0 +1,261 @@
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::error::{Error, Result};
+use crate::page::Page;
+
+/// A browser action exposed to an LLM as a callable tool.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ToolSchema {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// JSON Schema of the tool's arguments.
+    pub parameters: Value,
+}
+
+impl ToolSchema {
+    /// OpenAI function-calling format: `{"type": "function", "function": {...}}`.
+    pub fn openai(&self) -> Value {
+        json!({
+            "type": "function",
+            "function": {
+                "name": self.name,
+                "description": self.description,
+                "parameters": self.parameters,
+            }
+        })
+    }
+
+    /// Anthropic tool-use format: `{"name", "description", "input_schema"}`.
+    pub fn anthropic(&self) -> Value {
+        json!({
+            "name": self.name,
+            "description": self.description,
+            "input_schema": self.parameters,
+        })
+    }
+}
+
+fn object(properties: Value, required: &[&str]) -> Value {
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+fn selector_property() -> Value {
+    json!({ "type": "string", "description": "CSS selector of the element" })
+}
+
+/// Tool definitions for a curated set of page actions, dispatched by
+/// `execute_tool_call`.
+pub fn schemas() -> Vec<ToolSchema> {
+    vec![
+        ToolSchema {
+            name: "navigate",
+            description: "Open a URL in the current tab and wait for it to load.",
+            parameters: object(json!({ "url": { "type": "string" } }), &["url"]),
+        },
+        ToolSchema {
+            name: "go_back",
+            description: "Go back to the previous page in history.",
+            parameters: object(json!({}), &[]),
+        },
+        ToolSchema {
+            name: "read_page",
+            description: "Get the current URL, title and a compact accessibility tree of the page.",
+            parameters: object(json!({}), &[]),
+        },
+        ToolSchema {
+            name: "get_text",
+            description: "Get the visible text of an element.",
+            parameters: object(json!({ "selector": selector_property() }), &["selector"]),
+        },
+        ToolSchema {
+            name: "get_links",
+            description: "List the links on the page as text and URL.",
+            parameters: object(json!({}), &[]),
+        },
+        ToolSchema {
+            name: "click",
+            description: "Click an element, waiting for it to be visible and enabled.",
+            parameters: object(json!({ "selector": selector_property() }), &["selector"]),
+        },
+        ToolSchema {
+            name: "type",
+            description: "Replace the value of a text field, optionally pressing Enter afterwards.",
+            parameters: object(
+                json!({
+                    "selector": selector_property(),
+                    "text": { "type": "string" },
+                    "submit": { "type": "boolean", "description": "Press Enter after typing" },
+                }),
+                &["selector", "text"],
+            ),
+        },
+        ToolSchema {
+            name: "press_key",
+            description: "Press a key such as Enter, Tab or Escape.",
+            parameters: object(json!({ "key": { "type": "string" } }), &["key"]),
+        },
+        ToolSchema {
+            name: "select_option",
+            description: "Choose an option of a <select> element by its value.",
+            parameters: object(
+                json!({ "selector": selector_property(), "value": { "type": "string" } }),
+                &["selector", "value"],
+            ),
+        },
+        ToolSchema {
+            name: "scroll",
+            description: "Scroll the page up or down.",
+            parameters: object(
+                json!({
+                    "direction": { "type": "string", "enum": ["up", "down"] },
+                    "pixels": { "type": "integer", "minimum": 1, "description": "Default: 600" },
+                }),
+                &["direction"],
+            ),
+        },
+        ToolSchema {
+            name: "wait_for",
+            description: "Wait until an element matching the selector appears.",
+            parameters: object(json!({ "selector": selector_property() }), &["selector"]),
+        },
+    ]
+}
+
+/// `schemas()` in OpenAI function-calling format, ready for the `tools` field.
+pub fn openai_tools() -> Value {
+    Value::Array(schemas().iter().map(ToolSchema::openai).collect())
+}
+
+/// `schemas()` in Anthropic tool-use format, ready for the `tools` field.
+pub fn anthropic_tools() -> Value {
+    Value::Array(schemas().iter().map(ToolSchema::anthropic).collect())
+}
+
+#[derive(serde::Deserialize)]
+struct UrlArgs {
+    url: String,
+}
+
+#[derive(serde::Deserialize)]
+struct SelectorArgs {
+    selector: String,
+}
+
+#[derive(serde::Deserialize)]
+struct TypeArgs {
+    selector: String,
+    text: String,
+    #[serde(default)]
+    submit: bool,
+}
+
+#[derive(serde::Deserialize)]
+struct KeyArgs {
+    key: String,
+}
+
+#[derive(serde::Deserialize)]
+struct SelectArgs {
+    selector: String,
+    value: String,
+}
+
+#[derive(serde::Deserialize)]
+struct ScrollArgs {
+    direction: String,
+    #[serde(default)]
+    pixels: Option<u32>,
+}
+
+fn parse<T: DeserializeOwned>(name: &str, args: &Value) -> Result<T> {
+    serde_json::from_value(args.clone())
+        .map_err(|e| Error::ToolError(format!("{name}: invalid arguments: {e}")))
+}
+
+/// Run the tool call `name` with `args_json` (the arguments as a JSON string, as
+/// OpenAI sends them; use `input.to_string()` for Anthropic's `input` object) and
+/// return a short text result to send back to the model.
+pub async fn execute_tool_call(page: &Page, name: &str, args_json: &str) -> Result<String> {
+    let args: Value = if args_json.trim().is_empty() {
+        json!({})
+    } else {
+        serde_json::from_str(args_json)
+            .map_err(|e| Error::ToolError(format!("{name}: arguments are not JSON: {e}")))?
+    };
+
+    match name {
+        "navigate" => {
+            let a: UrlArgs = parse(name, &args)?;
+            page.goto(&a.url).await?;
+            Ok(format!("Navigated to {} ({})", page.url().await?, page.title().await?))
+        }
+        "go_back" => {
+            page.go_back().await?;
+            Ok("Went back".to_string())
+        }
+        "read_page" => {
+            let observation = page.observe().await?;
+            let tree = page.accessibility_tree().await?;
+            Ok(format!("URL: {}\nTitle: {}\n\n{tree}", observation.url, observation.title))
+        }
+        "get_text" => {
+            let a: SelectorArgs = parse(name, &args)?;
+            page.locator(&a.selector).inner_text().await
+        }
+        "get_links" => {
+            let links = page.get_links().await?;
+            Ok(links
+                .iter()
+                .map(|(text, href)| format!("{} -> {href}", text.trim()))
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        "click" => {
+            let a: SelectorArgs = parse(name, &args)?;
+            page.locator(&a.selector).click().await?;
+            Ok(format!("Clicked {}", a.selector))
+        }
+        "type" => {
+            let a: TypeArgs = parse(name, &args)?;
+            page.locator(&a.selector).fill(&a.text).await?;
+            if a.submit {
+                page.press_key("Enter").await?;
+            }
+            Ok(format!("Typed into {}", a.selector))
+        }
+        "press_key" => {
+            let a: KeyArgs = parse(name, &args)?;
+            page.press_key(&a.key).await?;
+            Ok(format!("Pressed {}", a.key))
+        }
+        "select_option" => {
+            let a: SelectArgs = parse(name, &args)?;
+            page.select_option(&a.selector, &a.value).await?;
+            Ok(format!("Selected {} in {}", a.value, a.selector))
+        }
+        "scroll" => {
+            let a: ScrollArgs = parse(name, &args)?;
+            let pixels = a.pixels.unwrap_or(600);
+            match a.direction.as_str() {
+                "up" => page.scroll_up(pixels).await?,
+                "down" => page.scroll_down(pixels).await?,
+                other => {
+                    return Err(Error::ToolError(format!("scroll: invalid direction {other:?}")));
+                }
+            }
+            Ok(format!("Scrolled {} {pixels}px", a.direction))
+        }
+        "wait_for" => {
+            let a: SelectorArgs = parse(name, &args)?;
+            page.wait_for_selector(&a.selector).await?;
+            Ok(format!("{} appeared", a.selector))
+        }
+        other => Err(Error::ToolError(format!("unknown tool {other:?}"))),
+    }
+}
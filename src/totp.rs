@@ -0,0 +1,160 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+
+use crate::error::{Error, Result};
+
+/// HMAC hash used by a `Totp`. Authenticator apps almost always use SHA-1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TotpAlgorithm {
+    #[default]
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+/// RFC 6238 time-based one-time password generator, as used by authenticator apps.
+#[derive(Clone)]
+pub struct Totp {
+    secret: Vec<u8>,
+    digits: u32,
+    period: u64,
+    algorithm: TotpAlgorithm,
+}
+
+impl std::fmt::Debug for Totp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Totp")
+            .field("digits", &self.digits)
+            .field("period", &self.period)
+            .field("algorithm", &self.algorithm)
+            .finish_non_exhaustive()
+    }
+}
+
+/// RFC 4648 base32, ignoring case, spaces, dashes and padding as shown by sites
+/// ("JBSW Y3DP EHPK 3PXP").
+fn decode_base32(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let (mut buffer, mut bits) = (0u64, 0u32);
+    for c in text.chars().filter(|c| !matches!(c, ' ' | '-' | '=')) {
+        let value = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u64 - 'A' as u64,
+            c @ '2'..='7' => c as u64 - '2' as u64 + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+fn hmac<M: Mac + KeyInit>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+impl Totp {
+    /// A 6-digit, 30-second, SHA-1 generator (the authenticator-app default) for a
+    /// base32 secret.
+    pub fn new(secret_base32: &str) -> Result<Self> {
+        let secret = decode_base32(secret_base32)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| Error::ConfigError("TOTP secret is not valid base32".to_string()))?;
+        Ok(Self {
+            secret,
+            digits: 6,
+            period: 30,
+            algorithm: TotpAlgorithm::Sha1,
+        })
+    }
+
+    /// Parse an `otpauth://totp/...?secret=...` URI, as encoded in enrollment QR codes.
+    pub fn from_uri(uri: &str) -> Result<Self> {
+        let invalid = |why: &str| Error::ConfigError(format!("Invalid otpauth URI: {why}"));
+        let rest = uri
+            .strip_prefix("otpauth://totp/")
+            .ok_or_else(|| invalid("expected otpauth://totp/"))?;
+        let query = rest.split_once('?').map(|(_, q)| q).unwrap_or_default();
+        let param = |name: &str| {
+            query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value)
+        };
+
+        let mut totp = Self::new(param("secret").ok_or_else(|| invalid("missing secret"))?)?;
+        if let Some(digits) = param("digits") {
+            totp = totp.digits(digits.parse().map_err(|_| invalid("bad digits"))?);
+        }
+        if let Some(period) = param("period") {
+            totp = totp.period(period.parse().map_err(|_| invalid("bad period"))?);
+        }
+        if let Some(algorithm) = param("algorithm") {
+            totp = totp.algorithm(match algorithm.to_ascii_uppercase().as_str() {
+                "SHA1" => TotpAlgorithm::Sha1,
+                "SHA256" => TotpAlgorithm::Sha256,
+                "SHA512" => TotpAlgorithm::Sha512,
+                _ => return Err(invalid("unsupported algorithm")),
+            });
+        }
+        Ok(totp)
+    }
+
+    /// Number of digits, 6 to 8.
+    pub fn digits(mut self, digits: u32) -> Self {
+        self.digits = digits.clamp(6, 8);
+        self
+    }
+
+    /// Seconds each code is valid for.
+    pub fn period(mut self, seconds: u64) -> Self {
+        self.period = seconds.max(1);
+        self
+    }
+
+    pub fn algorithm(mut self, algorithm: TotpAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// The code for a Unix timestamp (seconds).
+    pub fn generate(&self, unix_time: u64) -> String {
+        let counter = (unix_time / self.period).to_be_bytes();
+        let hash = match self.algorithm {
+            TotpAlgorithm::Sha1 => hmac::<Hmac<sha1::Sha1>>(&self.secret, &counter),
+            TotpAlgorithm::Sha256 => hmac::<Hmac<sha2::Sha256>>(&self.secret, &counter),
+            TotpAlgorithm::Sha512 => hmac::<Hmac<sha2::Sha512>>(&self.secret, &counter),
+        };
+        // Dynamic truncation (RFC 4226 section 5.3)
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let code = u32::from_be_bytes([hash[offset], hash[offset + 1], hash[offset + 2], hash[offset + 3]])
+            & 0x7fff_ffff;
+        format!("{:0width$}", code % 10u32.pow(self.digits), width = self.digits as usize)
+    }
+
+    /// The code for the current time.
+    pub fn now(&self) -> String {
+        self.generate(unix_now())
+    }
+
+    /// Seconds until the current code expires.
+    pub fn remaining(&self) -> u64 {
+        self.period - unix_now() % self.period
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
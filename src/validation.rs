@@ -0,0 +1,115 @@
+/// Where a validation message was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationSource {
+    /// The browser's HTML5 constraint validation (`required`, `pattern`,
+    /// `type=email`, ...), as `validationMessage`.
+    Constraint,
+    /// An element referenced by the field's `aria-errormessage`, or by its
+    /// `aria-describedby` when the field is `aria-invalid` or the element looks
+    /// like an error.
+    Aria,
+    /// An element that looks like an error (`.error`, `.invalid-feedback`,
+    /// `role="alert"`, ...) next to the field.
+    Nearby,
+    /// An error in the form that isn't tied to one field, such as a summary at
+    /// the top.
+    Form,
+}
+
+/// A validation message shown for a form, from `Page::collect_validation_errors`.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct ValidationError {
+    /// The field's `name`, else its `id`; empty for `ValidationSource::Form`.
+    pub field: String,
+    /// The field's label, if it has one.
+    pub label: String,
+    pub message: String,
+    pub source: ValidationSource,
+}
+
+/// `(formSelector) => ValidationError[] | null`, null when no form matches.
+/// Only visible messages are reported, each once.
+pub(crate) const VALIDATION_ERRORS_JS: &str = r#"(formSelector) => {
+    const form = document.querySelector(formSelector);
+    if (!form) return null;
+    const FIELDS = 'input, select, textarea';
+    const ERROR_CLASS = /(^|[\s_-])(error|errors|invalid)($|[\s_-])/i;
+    const visible = el => typeof el.checkVisibility === 'function'
+        ? el.checkVisibility({ checkOpacity: true, checkVisibilityCSS: true })
+        : el.offsetParent !== null;
+    const textOf = el => (el.innerText || el.textContent || '').trim().replace(/\s+/g, ' ');
+    const looksLikeError = el => el.getAttribute('role') === 'alert'
+        || el.getAttribute('aria-live') === 'assertive'
+        || ERROR_CLASS.test(el.getAttribute('class') || '')
+        || ERROR_CLASS.test(el.id || '');
+    const labelOf = el => {
+        if (el.labels && el.labels.length) return textOf(el.labels[0]);
+        return el.getAttribute('aria-label') || '';
+    };
+
+    const errors = [];
+    const reported = new Set();
+    const report = (field, node, source) => {
+        // Containers of fields (`.has-error` wrappers) aren't messages, and a
+        // message nested in one already reported is the same message
+        if (node.querySelector(FIELDS) || !visible(node)) return;
+        if (Array.from(reported).some(r => r.contains(node) || node.contains(r))) return;
+        const message = textOf(node);
+        if (!message) return;
+        reported.add(node);
+        errors.push({
+            field: field ? (field.name || field.id || '') : '',
+            label: field ? labelOf(field) : '',
+            message,
+            source,
+        });
+    };
+
+    const fields = Array.from(form.elements).filter(el => el.matches(FIELDS) && el.type !== 'hidden');
+    for (const field of fields) {
+        if (field.willValidate && !field.validity.valid && field.validationMessage) {
+            errors.push({
+                field: field.name || field.id || '',
+                label: labelOf(field),
+                message: field.validationMessage,
+                source: 'constraint',
+            });
+        }
+
+        const invalid = field.getAttribute('aria-invalid') === 'true';
+        const ids = (field.getAttribute('aria-errormessage') || '').split(/\s+/).filter(Boolean)
+            .map(id => [id, invalid])
+            .concat((field.getAttribute('aria-describedby') || '').split(/\s+/).filter(Boolean)
+                .map(id => [id, false]));
+        for (const [id, trusted] of ids) {
+            const node = document.getElementById(id);
+            if (node && (trusted || invalid || looksLikeError(node))) report(field, node, 'aria');
+        }
+
+        // The field's own wrapper: the largest ancestor inside the form with no
+        // other field in it (radio and checkbox groups count as one field)
+        let wrapper = null;
+        for (let el = field.parentElement; el && el !== form && form.contains(el); el = el.parentElement) {
+            const others = Array.from(el.querySelectorAll(FIELDS))
+                .filter(other => other !== field && !(field.name && other.name === field.name));
+            if (others.length) break;
+            wrapper = el;
+        }
+        const candidates = wrapper ? wrapper.querySelectorAll('*') : [];
+        for (const node of candidates) {
+            if (looksLikeError(node)) report(field, node, 'nearby');
+        }
+        // Messages right after the field, e.g. <input><span class="error">
+        let sibling = field.nextElementSibling;
+        while (sibling && !sibling.matches(FIELDS)) {
+            if (looksLikeError(sibling)) report(field, sibling, 'nearby');
+            sibling = sibling.nextElementSibling;
+        }
+    }
+
+    for (const node of form.querySelectorAll('*')) {
+        if (looksLikeError(node)) report(null, node, 'form');
+    }
+    return errors;
+}"#;
@@ -0,0 +1,165 @@
+use std::io::Cursor;
+
+use image::{ImageFormat, Rgba, RgbaImage};
+
+use crate::error::{Error, Result};
+
+/// Rectangle of a screenshot, in pixels, left out of a comparison (clocks, ads,
+/// carousels, ...).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Region {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && (x - self.x) < self.width
+            && (y - self.y) < self.height
+    }
+}
+
+/// Settings for `compare_screenshots_with`.
+#[derive(Debug, Clone)]
+pub struct DiffOptions {
+    /// Perceptual color difference, from 0 (exact) to 1 (anything goes), below
+    /// which two pixels count as the same. The default 0.1 ignores antialiasing
+    /// and compression noise.
+    pub tolerance: f64,
+    /// Regions ignored in both screenshots.
+    pub masks: Vec<Region>,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: 0.1,
+            masks: Vec::new(),
+        }
+    }
+}
+
+impl DiffOptions {
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn mask(mut self, region: Region) -> Self {
+        self.masks.push(region);
+        self
+    }
+}
+
+/// Result of comparing two screenshots.
+#[derive(Debug, Clone)]
+pub struct DiffResult {
+    /// Share of compared (unmasked) pixels that differ, from 0 to 1.
+    pub pixel_diff_ratio: f64,
+    pub diff_pixels: u64,
+    /// Size of the compared area: the larger of the two screenshots in each
+    /// direction. Pixels only one screenshot has count as different.
+    pub width: u32,
+    pub height: u32,
+    /// PNG of the first screenshot faded to gray, with differing pixels in red and
+    /// masked regions in blue.
+    pub diff_image: Vec<u8>,
+}
+
+impl DiffResult {
+    pub fn is_identical(&self) -> bool {
+        self.diff_pixels == 0
+    }
+}
+
+/// Compare two PNG or JPEG screenshots with the default `DiffOptions`.
+pub fn compare_screenshots(a: &[u8], b: &[u8]) -> Result<DiffResult> {
+    compare_screenshots_with(a, b, &DiffOptions::default())
+}
+
+/// Compare two PNG or JPEG screenshots pixel by pixel.
+pub fn compare_screenshots_with(a: &[u8], b: &[u8], options: &DiffOptions) -> Result<DiffResult> {
+    let decode = |bytes: &[u8]| {
+        image::load_from_memory(bytes)
+            .map(|image| image.to_rgba8())
+            .map_err(|e| Error::ScreenshotError(format!("Failed to decode screenshot: {e}")))
+    };
+    let (a, b) = (decode(a)?, decode(b)?);
+    let width = a.width().max(b.width());
+    let height = a.height().max(b.height());
+    let max_delta = MAX_YIQ_DELTA * options.tolerance * options.tolerance;
+
+    let mut diff = RgbaImage::new(width, height);
+    let (mut compared, mut diff_pixels) = (0u64, 0u64);
+    for y in 0..height {
+        for x in 0..width {
+            let pa = (x < a.width() && y < a.height()).then(|| *a.get_pixel(x, y));
+            let pb = (x < b.width() && y < b.height()).then(|| *b.get_pixel(x, y));
+            let out = if options.masks.iter().any(|m| m.contains(x, y)) {
+                Rgba([120, 160, 255, 255])
+            } else {
+                compared += 1;
+                let same = match (pa, pb) {
+                    (Some(pa), Some(pb)) => yiq_delta(pa, pb) <= max_delta,
+                    _ => false,
+                };
+                if same {
+                    faded(pa.unwrap_or(Rgba([255, 255, 255, 255])))
+                } else {
+                    diff_pixels += 1;
+                    Rgba([255, 0, 0, 255])
+                }
+            };
+            diff.put_pixel(x, y, out);
+        }
+    }
+
+    let mut diff_image = Vec::new();
+    diff.write_to(&mut Cursor::new(&mut diff_image), ImageFormat::Png)
+        .map_err(|e| Error::ScreenshotError(format!("Failed to encode diff image: {e}")))?;
+    Ok(DiffResult {
+        pixel_diff_ratio: if compared == 0 { 0.0 } else { diff_pixels as f64 / compared as f64 },
+        diff_pixels,
+        width,
+        height,
+        diff_image,
+    })
+}
+
+/// Largest possible `yiq_delta`, between black and white.
+const MAX_YIQ_DELTA: f64 = 35215.0;
+
+/// Squared color distance in YIQ space, weighted for how the eye perceives it
+/// (Kotsarenko & Ramos, as used by pixelmatch). Colors are blended onto white
+/// first, so transparency compares the way it renders.
+fn yiq_delta(a: Rgba<u8>, b: Rgba<u8>) -> f64 {
+    let blend = |p: Rgba<u8>| {
+        let alpha = p[3] as f64 / 255.0;
+        let c = |v: u8| 255.0 + (v as f64 - 255.0) * alpha;
+        (c(p[0]), c(p[1]), c(p[2]))
+    };
+    let (r1, g1, b1) = blend(a);
+    let (r2, g2, b2) = blend(b);
+    let y = |r: f64, g: f64, b: f64| r * 0.298_895_31 + g * 0.586_622_47 + b * 0.114_482_23;
+    let i = |r: f64, g: f64, b: f64| r * 0.595_977_99 - g * 0.274_176_8 - b * 0.321_801_19;
+    let q = |r: f64, g: f64, b: f64| r * 0.211_470_17 - g * 0.522_617_24 + b * 0.311_147_07;
+    let dy = y(r1, g1, b1) - y(r2, g2, b2);
+    let di = i(r1, g1, b1) - i(r2, g2, b2);
+    let dq = q(r1, g1, b1) - q(r2, g2, b2);
+    0.5053 * dy * dy + 0.299 * di * di + 0.1957 * dq * dq
+}
+
+/// Light gray version of a pixel, as the background of the diff image.
+fn faded(p: Rgba<u8>) -> Rgba<u8> {
+    let gray = (p[0] as u32 * 30 + p[1] as u32 * 59 + p[2] as u32 * 11) / 100;
+    let v = (255 - (255 - gray) / 4) as u8;
+    Rgba([v, v, v, 255])
+}
@@ -0,0 +1,171 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use chromiumoxide::cdp::js_protocol::heap_profiler::CollectGarbageParams;
+
+use crate::events::{BrowserEvent, SharedHandlers};
+use crate::intercept::Interceptor;
+use crate::metrics;
+
+/// What the memory watchdog does when a budget is exceeded. A
+/// `BrowserEvent::MemoryBudgetExceeded` is emitted in every case.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BudgetAction {
+    /// Only emit the event.
+    #[default]
+    EmitEvent,
+    /// Run a full garbage collection in the offending page (every page for the
+    /// RSS budget).
+    ForceGc,
+    /// Close the offending page (the page with the largest JS heap for the RSS
+    /// budget).
+    ClosePage,
+    /// Kill Chrome before the system runs out of memory. `is_alive()` turns false;
+    /// relaunch with `AgenticBrowser::restart`.
+    KillBrowser,
+}
+
+/// Memory limits checked periodically for a browser, set with
+/// `BrowserBuilder::memory_budget`.
+#[derive(Debug, Clone)]
+pub struct MemoryBudget {
+    /// Maximum JS heap in use per page, in bytes.
+    pub page_js_heap: Option<u64>,
+    /// Maximum resident memory of the whole Chrome process tree, in bytes (Linux).
+    pub process_rss: Option<u64>,
+    /// How often to check (default: 5s).
+    pub interval: Duration,
+    pub action: BudgetAction,
+}
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self {
+            page_js_heap: None,
+            process_rss: None,
+            interval: Duration::from_secs(5),
+            action: BudgetAction::EmitEvent,
+        }
+    }
+}
+
+type Pages = Mutex<HashMap<String, Arc<Interceptor>>>;
+
+/// Check `budget` every `budget.interval` against the pages in `pages` and the
+/// process tree of `process_id`. Stops once the browser (owner of `pages`) is dropped.
+pub(crate) fn spawn(
+    budget: MemoryBudget,
+    process_id: Option<u32>,
+    pages: Weak<Pages>,
+    handlers: Arc<SharedHandlers>,
+) {
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(budget.interval);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        // Pages whose Performance domain is already enabled
+        let mut enabled = HashSet::new();
+        loop {
+            ticks.tick().await;
+            let Some(pages) = pages.upgrade() else {
+                return;
+            };
+            let snapshot: Vec<(String, Arc<Interceptor>)> = pages
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .iter()
+                .map(|(id, i)| (id.clone(), Arc::clone(i)))
+                .collect();
+            enabled.retain(|id: &String| snapshot.iter().any(|(target_id, _)| target_id == id));
+
+            // Per-page JS heap
+            let mut heaps = Vec::with_capacity(snapshot.len());
+            for (target_id, interceptor) in &snapshot {
+                if !enabled.contains(target_id) {
+                    if metrics::enable_performance(interceptor.page()).await.is_err() {
+                        continue;
+                    }
+                    enabled.insert(target_id.clone());
+                }
+                let Ok(m) = metrics::read_page_metrics(interceptor.page()).await else {
+                    continue;
+                };
+                heaps.push((target_id.clone(), m.js_heap_used_size));
+                let Some(limit) = budget.page_js_heap.filter(|l| m.js_heap_used_size > *l) else {
+                    continue;
+                };
+                handlers.emit(BrowserEvent::MemoryBudgetExceeded {
+                    target_id: Some(target_id.clone()),
+                    used_bytes: m.js_heap_used_size,
+                    limit_bytes: limit,
+                    action: budget.action,
+                });
+                match budget.action {
+                    BudgetAction::EmitEvent => {}
+                    BudgetAction::ForceGc => {
+                        let _ = interceptor.page().execute(CollectGarbageParams::default()).await;
+                    }
+                    BudgetAction::ClosePage => close(&pages, target_id),
+                    BudgetAction::KillBrowser => {
+                        kill(process_id);
+                        return;
+                    }
+                }
+            }
+
+            // Whole process tree
+            let (Some(limit), Some(pid)) = (budget.process_rss, process_id) else {
+                continue;
+            };
+            let Ok(usage) = metrics::process_tree_memory(pid) else {
+                continue;
+            };
+            if usage.rss_bytes <= limit {
+                continue;
+            }
+            handlers.emit(BrowserEvent::MemoryBudgetExceeded {
+                target_id: None,
+                used_bytes: usage.rss_bytes,
+                limit_bytes: limit,
+                action: budget.action,
+            });
+            match budget.action {
+                BudgetAction::EmitEvent => {}
+                BudgetAction::ForceGc => {
+                    for (_, interceptor) in &snapshot {
+                        let _ = interceptor.page().execute(CollectGarbageParams::default()).await;
+                    }
+                }
+                BudgetAction::ClosePage => {
+                    if let Some((target_id, _)) = heaps.iter().max_by_key(|(_, heap)| *heap) {
+                        close(&pages, target_id);
+                    }
+                }
+                BudgetAction::KillBrowser => {
+                    kill(process_id);
+                    return;
+                }
+            }
+        }
+    });
+}
+
+/// Close a page and stop watching it.
+fn close(pages: &Pages, target_id: &str) {
+    let removed = pages.lock().unwrap_or_else(|e| e.into_inner()).remove(target_id);
+    if let Some(interceptor) = removed {
+        let page = interceptor.page().clone();
+        tokio::spawn(async move {
+            let _ = page.close().await;
+        });
+    }
+}
+
+/// Kill the Chrome browser process; its child processes exit with it.
+fn kill(process_id: Option<u32>) {
+    if let Some(pid) = process_id {
+        let _ = std::process::Command::new("kill")
+            .args(["-KILL", &pid.to_string()])
+            .status();
+    }
+}
@@ -0,0 +1,59 @@
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+
+/// A virtual X display (Xvfb) for running headful Chrome in containers and
+/// on servers without a screen. The Xvfb process is killed on drop.
+pub struct Xvfb {
+    child: Child,
+    display: String,
+}
+
+impl Xvfb {
+    /// Start Xvfb on the first free display number from `:99` upwards, with a
+    /// single screen of the given size, and wait until it accepts connections.
+    pub async fn start(width: u32, height: u32) -> Result<Self> {
+        let number = (99..200)
+            .find(|n| {
+                !Path::new(&format!("/tmp/.X11-unix/X{n}")).exists()
+                    && !Path::new(&format!("/tmp/.X{n}-lock")).exists()
+            })
+            .ok_or_else(|| Error::LaunchError("no free X display number for Xvfb".to_string()))?;
+        let display = format!(":{number}");
+
+        let child = Command::new("Xvfb")
+            .arg(&display)
+            .args(["-screen", "0", &format!("{width}x{height}x24"), "-nolisten", "tcp"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| Error::LaunchError(format!("failed to start Xvfb (install the xvfb package): {e}")))?;
+        let mut xvfb = Self { child, display };
+
+        let socket = format!("/tmp/.X11-unix/X{number}");
+        for _ in 0..50 {
+            if Path::new(&socket).exists() {
+                return Ok(xvfb);
+            }
+            if let Ok(Some(status)) = xvfb.child.try_wait() {
+                return Err(Error::LaunchError(format!("Xvfb exited during startup: {status}")));
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        Err(Error::LaunchError(format!("Xvfb did not open display {} within 5s", xvfb.display)))
+    }
+
+    /// The display name to use as `DISPLAY`, e.g. `":99"`.
+    pub fn display(&self) -> &str {
+        &self.display
+    }
+}
+
+impl Drop for Xvfb {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
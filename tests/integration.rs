@@ -1,7 +1,13 @@
-use agentic_browser::AgenticBrowser;
+use agentic_browser::{AgenticBrowser, TestServer};
+
+/// Local server with the fixture pages; `/` is a copy of example.com.
+async fn fixtures() -> TestServer {
+    TestServer::start().await.expect("Failed to start test server")
+}
 
 #[tokio::test]
 async fn test_launch_and_navigate() {
+    let server = fixtures().await;
     let browser = AgenticBrowser::builder()
         .headless(true)
         .build()
@@ -9,7 +15,7 @@ async fn test_launch_and_navigate() {
         .expect("Failed to launch browser");
 
     let page = browser
-        .new_page("https://example.com")
+        .new_page(&server.url("/"))
         .await
         .expect("Failed to open page");
 
@@ -22,6 +28,7 @@ async fn test_launch_and_navigate() {
 
 #[tokio::test]
 async fn test_screenshot() {
+    let server = fixtures().await;
     let browser = AgenticBrowser::builder()
         .headless(true)
         .build()
@@ -29,7 +36,7 @@ async fn test_screenshot() {
         .expect("Failed to launch browser");
 
     let page = browser
-        .new_page("https://example.com")
+        .new_page(&server.url("/"))
         .await
         .expect("Failed to open page");
 
@@ -40,6 +47,7 @@ async fn test_screenshot() {
 
 #[tokio::test]
 async fn test_text_content() {
+    let server = fixtures().await;
     let browser = AgenticBrowser::builder()
         .headless(true)
         .build()
@@ -47,7 +55,7 @@ async fn test_text_content() {
         .expect("Failed to launch browser");
 
     let page = browser
-        .new_page("https://example.com")
+        .new_page(&server.url("/"))
         .await
         .expect("Failed to open page");
 
@@ -57,6 +65,7 @@ async fn test_text_content() {
 
 #[tokio::test]
 async fn test_get_links() {
+    let server = fixtures().await;
     let browser = AgenticBrowser::builder()
         .headless(true)
         .build()
@@ -64,7 +73,7 @@ async fn test_get_links() {
         .expect("Failed to launch browser");
 
     let page = browser
-        .new_page("https://example.com")
+        .new_page(&server.url("/"))
         .await
         .expect("Failed to open page");
 
@@ -75,6 +84,7 @@ async fn test_get_links() {
 
 #[tokio::test]
 async fn test_wait_for_selector() {
+    let server = fixtures().await;
     let browser = AgenticBrowser::builder()
         .headless(true)
         .build()
@@ -82,7 +92,7 @@ async fn test_wait_for_selector() {
         .expect("Failed to launch browser");
 
     let page = browser
-        .new_page("https://example.com")
+        .new_page(&server.url("/"))
         .await
         .expect("Failed to open page");
 
@@ -94,6 +104,7 @@ async fn test_wait_for_selector() {
 
 #[tokio::test]
 async fn test_screenshot_jpeg() {
+    let server = fixtures().await;
     let browser = AgenticBrowser::builder()
         .headless(true)
         .build()
@@ -101,7 +112,7 @@ async fn test_screenshot_jpeg() {
         .expect("Failed to launch browser");
 
     let page = browser
-        .new_page("https://example.com")
+        .new_page(&server.url("/"))
         .await
         .expect("Failed to open page");
 
@@ -113,6 +124,7 @@ async fn test_screenshot_jpeg() {
 
 #[tokio::test]
 async fn test_query_selector_all_with_data() {
+    let server = fixtures().await;
     let browser = AgenticBrowser::builder()
         .headless(true)
         .build()
@@ -120,7 +132,7 @@ async fn test_query_selector_all_with_data() {
         .expect("Failed to launch browser");
 
     let page = browser
-        .new_page("https://example.com")
+        .new_page(&server.url("/"))
         .await
         .expect("Failed to open page");
 
@@ -132,6 +144,7 @@ async fn test_query_selector_all_with_data() {
 
 #[tokio::test]
 async fn test_goto_fast() {
+    let server = fixtures().await;
     let browser = AgenticBrowser::builder()
         .headless(true)
         .build()
@@ -143,7 +156,7 @@ async fn test_goto_fast() {
         .await
         .expect("Failed to open page");
 
-    page.goto_fast("https://example.com")
+    page.goto_fast(&server.url("/"))
         .await
         .expect("Failed to goto_fast");
 
@@ -153,6 +166,7 @@ async fn test_goto_fast() {
 
 #[tokio::test]
 async fn test_block_resources() {
+    let server = fixtures().await;
     let browser = AgenticBrowser::builder()
         .headless(true)
         .build()
@@ -170,10 +184,3471 @@ async fn test_block_resources() {
         .expect("Failed to block resources");
 
     // Navigate to a page — should still load HTML content
-    page.goto("https://example.com")
+    page.goto(&server.url("/"))
         .await
         .expect("Failed to navigate");
 
     let title = page.title().await.expect("Failed to get title");
     assert!(title.contains("Example"), "Title was: {title}");
 }
+
+#[tokio::test]
+async fn test_browser_request_rules_apply_to_every_page() {
+    let server = fixtures().await;
+    server.add_page("/scripted", r#"<script src="/cached.js"></script><script>window.inline = true;</script>"#);
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .block_resources(&["script"])
+        .extra_header("X-Agentic-Test", "on")
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    for _ in 0..2 {
+        let page = browser.new_page(&server.url("/scripted")).await.expect("Failed to open page");
+        let loaded: (bool, bool) = page
+            .evaluate_typed("[window.served !== undefined, window.inline === true]")
+            .await
+            .expect("evaluate failed");
+        assert_eq!(loaded, (false, true), "external scripts should be blocked, inline ones run");
+
+        page.goto(&server.url("/echo")).await.expect("Failed to navigate");
+        let echo: serde_json::Value = page
+            .evaluate_typed("JSON.parse(document.getElementById('echo').textContent)")
+            .await
+            .expect("Failed to read echo");
+        assert_eq!(echo["headers"]["x-agentic-test"], "on");
+    }
+}
+
+#[tokio::test]
+async fn test_feature_report() {
+    use agentic_browser::Feature;
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let report = browser.feature_report().await.expect("Failed to build feature report");
+    assert_eq!(report.features.len(), 5);
+    // Extensions are always disabled by the performance flags
+    let extensions = report.get(Feature::Extensions).expect("Missing extensions entry");
+    assert!(!extensions.available);
+    assert!(extensions.reason.is_some());
+}
+
+#[tokio::test]
+async fn test_get_by_role() {
+    use agentic_browser::Role;
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+
+    let heading = page
+        .get_by_role(Role::Heading, Some("example domain"))
+        .await
+        .expect("Failed to find heading");
+    let text = heading.inner_text().await.expect("Failed to get text");
+    assert_eq!(text, "Example Domain");
+
+    assert!(page.get_by_role(Role::Button, Some("does not exist")).await.is_err());
+}
+
+#[tokio::test]
+async fn test_locator_auto_wait() {
+    use std::time::Duration;
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+
+    // Element appears after a delay — the locator should wait for it
+    page.evaluate_void(
+        "setTimeout(() => { const b = document.createElement('button'); \
+         b.id = 'late'; b.textContent = 'Late'; document.body.appendChild(b); }, 300)",
+    )
+    .await
+    .expect("Failed to schedule element");
+
+    let text = page
+        .locator("#late")
+        .inner_text()
+        .await
+        .expect("Locator did not wait for element");
+    assert_eq!(text, "Late");
+
+    let missing = page
+        .locator("#never")
+        .timeout(Duration::from_millis(300))
+        .click()
+        .await;
+    assert!(matches!(missing, Err(agentic_browser::Error::Timeout(_))));
+
+    // An invalid selector fails right away instead of waiting out the timeout
+    let started = std::time::Instant::now();
+    let invalid = page.locator("button[").timeout(Duration::from_secs(5)).click().await;
+    assert!(matches!(invalid, Err(agentic_browser::Error::JsError(_))), "got {invalid:?}");
+    let waited = page
+        .wait_for_selector_with("button[", agentic_browser::WaitOptions::default().timeout(Duration::from_secs(5)))
+        .await;
+    assert!(matches!(waited, Err(agentic_browser::Error::JsError(_))));
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    // A navigation destroying the document mid-lookup is waited out, not reported
+    server.add_page(
+        "/next",
+        "<html><body><script>setTimeout(() => { const b = document.createElement('button'); \
+         b.id = 'next'; b.textContent = 'Next'; b.onclick = () => document.title = 'clicked'; \
+         document.body.appendChild(b); }, 200)</script></body></html>",
+    );
+    page.evaluate_void("setTimeout(() => location.href = '/next', 150)")
+        .await
+        .expect("Failed to schedule navigation");
+    page.locator("#next")
+        .timeout(Duration::from_secs(5))
+        .click()
+        .await
+        .expect("Locator did not wait out the navigation");
+    let title: String = page.evaluate_typed("document.title").await.expect("Failed to read title");
+    assert_eq!(title, "clicked");
+}
+
+#[tokio::test]
+async fn test_element_state_queries() {
+    let server = fixtures().await;
+    server.add_page(
+        "/states",
+        r#"<input id="text">
+        <input id="readonly" readonly>
+        <input id="hidden" style="display: none">
+        <input id="ghost" style="visibility: hidden">
+        <button id="disabled" disabled>Off</button>
+        <fieldset disabled><input id="fenced"></fieldset>
+        <span id="aria-off" role="button" aria-disabled="true">Off</span>
+        <input id="box" type="checkbox" checked>
+        <input id="unchecked" type="checkbox">
+        <div id="switch" role="switch" aria-checked="true"></div>
+        <div id="editor" contenteditable="true">Edit me</div>
+        <div id="below" style="margin-top: 3000px">Far down</div>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/states")).await.expect("Failed to open page");
+    let el = |selector: &'static str| {
+        let page = &page;
+        async move { page.find_element(selector).await.expect("Element not found") }
+    };
+
+    assert!(el("#text").await.is_visible().await.expect("is_visible failed"));
+    assert!(!el("#hidden").await.is_visible().await.expect("is_visible failed"));
+    assert!(!el("#ghost").await.is_visible().await.expect("is_visible failed"));
+
+    assert!(el("#text").await.is_enabled().await.expect("is_enabled failed"));
+    for selector in ["#disabled", "#fenced", "#aria-off"] {
+        assert!(!el(selector).await.is_enabled().await.expect("is_enabled failed"), "{selector}");
+    }
+
+    assert!(el("#box").await.is_checked().await.expect("is_checked failed"));
+    assert!(!el("#unchecked").await.is_checked().await.expect("is_checked failed"));
+    assert!(el("#switch").await.is_checked().await.expect("is_checked failed"));
+
+    assert!(el("#text").await.is_editable().await.expect("is_editable failed"));
+    assert!(el("#editor").await.is_editable().await.expect("is_editable failed"));
+    assert!(!el("#readonly").await.is_editable().await.expect("is_editable failed"));
+    assert!(!el("#fenced").await.is_editable().await.expect("is_editable failed"));
+
+    let below = el("#below").await;
+    assert!(el("#text").await.is_in_viewport().await.expect("is_in_viewport failed"));
+    assert!(!below.is_in_viewport().await.expect("is_in_viewport failed"));
+    below.scroll_into_view().await.expect("Failed to scroll");
+    assert!(below.is_in_viewport().await.expect("is_in_viewport failed"));
+}
+
+#[tokio::test]
+async fn test_element_get_property() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/form")).await.expect("Failed to open page");
+
+    let name = page.find_element("#name").await.expect("No name field");
+    name.fill("Ada").await.expect("Failed to fill");
+    // The property is the live value; the attribute is still the markup's
+    let value: String = name.get_property("value").await.expect("Failed to get value");
+    assert_eq!(value, "Ada");
+    assert_eq!(name.get_attribute("value").await.expect("Failed to get attribute"), None);
+
+    let checkbox = page.find_element("input[type=checkbox]").await.expect("No checkbox");
+    checkbox.click().await.expect("Failed to click");
+    assert!(checkbox.get_property::<bool>("checked").await.expect("Failed to get checked"));
+    let height: f64 = checkbox.get_property("offsetHeight").await.expect("Failed to get offsetHeight");
+    assert!(height > 0.0);
+    let missing: Option<String> = checkbox.get_property("noSuchProperty").await.expect("Failed to get property");
+    assert_eq!(missing, None);
+}
+
+#[tokio::test]
+async fn test_element_computed_style() {
+    let server = fixtures().await;
+    server.add_page(
+        "/styled",
+        r#"<style>.badge { display: inline-block; z-index: 5; position: relative; color: rgb(255, 0, 0); }</style>
+        <span id="badge" class="badge" style="visibility: hidden">New</span>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/styled")).await.expect("Failed to open page");
+
+    let badge = page.find_element("#badge").await.expect("No badge");
+    let style = badge
+        .computed_style(&["display", "z-index", "color", "visibility", "no-such-property"])
+        .await
+        .expect("Failed to get computed style");
+    assert_eq!(style["display"], "inline-block");
+    assert_eq!(style["z-index"], "5");
+    assert_eq!(style["color"], "rgb(255, 0, 0)");
+    assert_eq!(style["visibility"], "hidden");
+    assert_eq!(style["no-such-property"], "");
+}
+
+#[tokio::test]
+async fn test_element_traversal() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+
+    let h1 = page.find_element("h1").await.expect("Failed to find h1");
+    let parent = h1.parent().await.expect("Failed to get parent").expect("h1 has no parent");
+    let children = parent.children().await.expect("Failed to get children");
+    assert!(children.len() >= 2);
+
+    let next = h1.next_sibling().await.expect("Failed to get sibling").expect("h1 has no sibling");
+    let tag: String = next.get_property("tagName").await.expect("Failed to get tagName");
+    assert_eq!(tag, "P");
+
+    let body = h1.closest("body").await.expect("Failed to get closest");
+    assert!(body.is_some());
+    assert!(h1.closest("table").await.expect("Failed to get closest").is_none());
+
+    // Handles refer to the nodes themselves: they survive siblings shifting and
+    // detaching, and a detached element has no parent
+    page.evaluate_void("document.body.prepend(document.createElement('nav'))")
+        .await
+        .expect("Failed to insert element");
+    let tag: String = next.get_property("tagName").await.expect("Failed to get tagName");
+    assert_eq!(tag, "P");
+    page.evaluate_void("document.querySelector('h1').remove()")
+        .await
+        .expect("Failed to remove h1");
+    assert!(h1.parent().await.expect("Failed to get parent").is_none());
+    assert!(h1.closest("body").await.expect("Failed to get closest").is_none());
+}
+
+#[tokio::test]
+async fn test_select_by_label_multiple_and_options() {
+    let server = fixtures().await;
+    server.add_page(
+        "/toppings",
+        r#"<select id="size">
+            <option value="s">Small</option>
+            <option value="m">  Medium  </option>
+            <option value="l" disabled>Large</option>
+        </select>
+        <select id="toppings" multiple>
+            <option value="cheese" selected>Cheese</option>
+            <option value="ham">Ham</option>
+            <option value="olives">Olives</option>
+        </select>
+        <script>
+            window.changes = 0;
+            document.addEventListener('change', () => window.changes++);
+        </script>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/toppings")).await.expect("Failed to open page");
+
+    page.select_by_label("#size", "Medium").await.expect("Failed to select by label");
+    let size: String = page.evaluate_typed("document.getElementById('size').value").await.expect("evaluate failed");
+    assert_eq!(size, "m");
+    assert!(page.select_by_label("#size", "Huge").await.is_err());
+
+    page.select_multiple("#toppings", &["ham", "olives"]).await.expect("Failed to select several");
+    assert!(page.select_multiple("#size", &["s"]).await.is_err());
+    let changes: u32 = page.evaluate_typed("window.changes").await.expect("evaluate failed");
+    assert_eq!(changes, 2);
+
+    let toppings = page.find_element("#toppings").await.expect("No select").options().await.expect("No options");
+    let selected: Vec<_> = toppings.iter().filter(|o| o.selected).map(|o| o.value.as_str()).collect();
+    assert_eq!(selected, ["ham", "olives"]);
+    let sizes = page.find_element("#size").await.expect("No select").options().await.expect("No options");
+    let labels: Vec<_> = sizes.iter().map(|o| (o.label.as_str(), o.disabled)).collect();
+    assert_eq!(labels, [("Small", false), ("Medium", false), ("Large", true)]);
+    assert!(page.find_element("#toppings option").await.expect("No option").options().await.is_err());
+}
+
+#[tokio::test]
+async fn test_fill_replaces_values() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/form")).await.expect("Failed to open page");
+
+    page.type_text("#name", "Old").await.expect("type_text failed");
+    page.fill("#name", "Ada").await.expect("fill failed");
+    page.fill("#comment", "Hello").await.expect("fill failed");
+    page.fill("#plan", "pro").await.expect("fill failed");
+    let values: Vec<String> = page
+        .evaluate_typed("['#name', '#comment', '#plan'].map(s => document.querySelector(s).value)")
+        .await
+        .expect("evaluate failed");
+    assert_eq!(values, ["Ada", "Hello", "pro"]);
+}
+
+#[tokio::test]
+async fn test_evaluate_typed_and_args() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+
+    let sum: i64 = page.evaluate_typed("1 + 2").await.expect("Failed to evaluate");
+    assert_eq!(sum, 3);
+
+    let text: String = page
+        .evaluate_with_args("(sel) => document.querySelector(sel).innerText", ["h1"])
+        .await
+        .expect("Failed to evaluate with args");
+    assert_eq!(text, "Example Domain");
+
+    let quoted: String = page
+        .evaluate_with_args("(a, b) => a + b", ("it's ", "\"quoted\""))
+        .await
+        .expect("Failed to evaluate with args");
+    assert_eq!(quoted, "it's \"quoted\"");
+}
+
+#[tokio::test]
+async fn test_expose_function() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+
+    page.expose_function("rustAdd", |args| async move {
+        let sum: i64 = args.iter().filter_map(|v| v.as_i64()).sum();
+        Ok(serde_json::json!(sum))
+    })
+    .await
+    .expect("Failed to expose function");
+
+    let sum: i64 = page
+        .evaluate_typed("window.rustAdd(2, 3, 4)")
+        .await
+        .expect("Failed to call exposed function");
+    assert_eq!(sum, 9);
+}
+
+#[tokio::test]
+async fn test_evaluate_isolated_reuses_world() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+
+    page.evaluate_void("window.siteValue = 1").await.expect("evaluate failed");
+    let hidden: bool = page
+        .evaluate_isolated("window.siteValue === undefined && (window.worldValue = 2) === 2")
+        .await
+        .expect("evaluate_isolated failed");
+    assert!(hidden, "the isolated world doesn't see page globals");
+    let kept: Option<u32> = page.evaluate_isolated("window.worldValue ?? null").await.expect("evaluate_isolated failed");
+    assert_eq!(kept, Some(2), "the world is reused within a document");
+
+    page.goto(&server.url("/")).await.expect("Navigation failed");
+    let fresh: Option<u32> = page.evaluate_isolated("window.worldValue ?? null").await.expect("evaluate_isolated failed");
+    assert_eq!(fresh, None, "a navigation gets a new world");
+}
+
+#[tokio::test]
+async fn test_block_urls() {
+    let server = fixtures().await;
+    server.add_page("/scripted", r#"<script src="/cached.js"></script>"#);
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .block_urls(&["*/cached.js"])
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/scripted")).await.expect("Failed to open page");
+    let script_ran = "window.served !== undefined";
+    assert!(!page.evaluate_typed::<bool>(script_ran).await.expect("evaluate failed"));
+    let fetched = "fetch('/json').then(() => 'loaded', () => 'blocked')";
+    assert_eq!(page.evaluate_typed::<String>(fetched).await.expect("evaluate failed"), "loaded");
+
+    // Page patterns replace the browser's
+    page.block_urls(&["*/json"]).await.expect("Failed to block URLs");
+    assert_eq!(page.evaluate_typed::<String>(fetched).await.expect("evaluate failed"), "blocked");
+    page.reload().await.expect("Failed to reload");
+    assert!(page.evaluate_typed::<bool>(script_ran).await.expect("evaluate failed"));
+
+    // So does the ad list, which leaves first-party requests alone
+    page.block_ads().await.expect("Failed to block ads");
+    assert_eq!(page.evaluate_typed::<String>(fetched).await.expect("evaluate failed"), "loaded");
+}
+
+#[tokio::test]
+async fn test_navigation_policy() {
+    use agentic_browser::NavigationPolicy;
+
+    let policy = NavigationPolicy::allow_domains(&["example.com"]);
+    assert!(policy.is_allowed("https://www.example.com/path"));
+    assert!(policy.is_allowed("about:blank"));
+    assert!(!policy.is_allowed("https://example.com.evil.net/"));
+    assert!(!policy.is_allowed("file:///etc/passwd"));
+    assert!(!policy.is_allowed("data:text/html,<h1>hi</h1>"));
+    assert!(!policy.is_allowed("wss://example.com.evil.net/socket"));
+    assert!(policy.clone().allow_schemes(&["data"]).is_allowed("data:text/html,<h1>hi</h1>"));
+
+    let server = fixtures().await;
+    let policy = NavigationPolicy::allow_domains(&["127.0.0.1"]);
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .navigation_policy(policy)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Allowed domain should load");
+
+    let other_host = format!("http://localhost:{}/", server.addr().port());
+    assert!(page.goto(&other_host).await.is_err(), "Disallowed domain loaded");
+    assert!(page.goto("file:///etc/passwd").await.is_err(), "file: URL loaded");
+
+    // A popup is held to the policy from its first request
+    page.goto(&server.url("/")).await.expect("Allowed domain should load");
+    page.evaluate(&format!("window.open('{other_host}', '_blank')"))
+        .await
+        .expect("Failed to open popup");
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    for tab in browser.pages().await.expect("Failed to list pages") {
+        let html = tab.html().await.expect("Failed to get HTML");
+        let url = tab.url().await.expect("url failed");
+        assert!(url.contains("127.0.0.1") || !html.contains("Example Domain"), "Popup loaded {url}");
+    }
+}
+
+#[tokio::test]
+async fn test_custom_chrome_args() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .stealth(false)
+        .arg("--user-agent=AgenticTest/1.0")
+        .args(["js-flags=--expose-gc"])
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+
+    let user_agent: String = page.evaluate_typed("navigator.userAgent").await.expect("evaluate failed");
+    assert_eq!(user_agent, "AgenticTest/1.0");
+    let gc: bool = page.evaluate_typed("typeof gc === 'function'").await.expect("evaluate failed");
+    assert!(gc, "the switch without leading dashes should be passed too");
+}
+
+#[tokio::test]
+async fn test_config_from_file() {
+    use agentic_browser::BrowserConfig;
+
+    let server = fixtures().await;
+    let path = std::env::temp_dir().join("agentic_browser_test_config.toml");
+    std::fs::write(
+        &path,
+        "headless = true\nstealth = false\ntimeout_ms = 5000\nblock_resources = [\"image\"]\n",
+    )
+    .expect("Failed to write config file");
+
+    let config = BrowserConfig::from_file(&path).expect("Failed to load config");
+    assert!(!config.stealth);
+    assert_eq!(config.default_timeout, std::time::Duration::from_millis(5000));
+    assert_eq!(config.blocked_resources, vec!["image".to_string()]);
+
+    std::fs::write(&path, "headles = true\n").expect("Failed to write config file");
+    assert!(BrowserConfig::from_file(&path).is_err(), "Unknown key accepted");
+
+    // Proxy credentials from the environment complete a proxy set in the file,
+    // and are an error without one
+    let proxy_path = std::env::temp_dir().join("agentic_browser_test_proxy.toml");
+    std::fs::write(&proxy_path, "proxy = \"http://proxy.test:3128\"\nproxy_username = \"bob\"\n")
+        .expect("Failed to write config file");
+    std::env::set_var("AGENTIC_BROWSER_PROXY_PASSWORD", "hunter2");
+    let proxied = BrowserConfig::from_file(&proxy_path).and_then(|c| c.with_env());
+    let unproxied = BrowserConfig::from_env();
+    std::env::remove_var("AGENTIC_BROWSER_PROXY_PASSWORD");
+    let proxy = proxied.expect("Failed to load config").proxy.expect("No proxy");
+    assert_eq!(proxy.server, "http://proxy.test:3128");
+    assert_eq!(proxy.username.as_deref(), Some("bob"));
+    assert_eq!(proxy.password.as_deref(), Some("hunter2"));
+    assert!(matches!(unproxied, Err(agentic_browser::Error::ConfigError(_))));
+    let _ = std::fs::remove_file(&proxy_path);
+
+    let browser = AgenticBrowser::launch(config)
+        .await
+        .expect("Failed to launch browser");
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+    assert!(page.title().await.expect("Failed to get title").contains("Example"));
+}
+
+#[tokio::test]
+async fn test_detected_chrome_launches() {
+    use agentic_browser::detect;
+
+    let server = fixtures().await;
+    let found = detect::find_chrome().expect("No Chrome found");
+    assert!(found.is_file());
+    assert_eq!(detect::candidates().iter().find(|p| p.is_file()), Some(&found));
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .chrome_path(found.to_string_lossy())
+        .build()
+        .await
+        .expect("Failed to launch the detected browser");
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    assert_eq!(page.title().await.expect("Failed to get title"), "Example Domain");
+}
+
+#[tokio::test]
+async fn test_browser_health() {
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let version = browser.version().await.expect("Failed to get version");
+    assert!(version.product.contains("Chrome"), "Product was: {}", version.product);
+    assert!(version.major().is_some());
+    assert!(browser.is_alive().await);
+    assert!(browser.process_id().is_some());
+}
+
+#[tokio::test]
+async fn test_browser_events() {
+    use agentic_browser::{BrowserEvent, EventKind};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let created = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&created);
+    browser
+        .on(EventKind::TargetCreated, move |event| {
+            if let BrowserEvent::TargetCreated { target_type, .. } = event {
+                if target_type == "page" {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        })
+        .await
+        .expect("Failed to subscribe");
+
+    let _page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+    assert!(created.load(Ordering::SeqCst) >= 1, "No TargetCreated event received");
+}
+
+#[tokio::test]
+async fn test_download_started_events() {
+    use agentic_browser::{BrowserEvent, EventKind, ProfileManager};
+    use std::sync::{Arc, Mutex};
+
+    let server = fixtures().await;
+    let dir = std::env::temp_dir().join(format!("agentic_browser_download_events_{}", std::process::id()));
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .profiles(ProfileManager::new(&dir))
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let started = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&started);
+    browser
+        .on(EventKind::DownloadStarted, move |event| {
+            if let BrowserEvent::DownloadStarted { suggested_filename, .. } = event {
+                seen.lock().unwrap().push(suggested_filename);
+            }
+        })
+        .await
+        .expect("Failed to subscribe");
+
+    let start_download = "(() => { const a = document.createElement('a'); a.href = '/download'; \
+                          document.body.appendChild(a); a.click(); })()";
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    page.evaluate_void(start_download).await.expect("evaluate failed");
+    let context = browser.open_profile("downloads").await.expect("Failed to open profile");
+    let profile_page = context.new_page(&server.url("/")).await.expect("Failed to open page");
+    profile_page.evaluate_void(start_download).await.expect("evaluate failed");
+
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    assert_eq!(*started.lock().unwrap(), ["report.csv", "report.csv"], "both contexts report downloads");
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_pages_adopts_popups_with_stealth() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+    page.evaluate(&format!("window.open('{}', '_blank')", server.url("/")))
+        .await
+        .expect("Failed to open popup");
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+
+    let pages = browser.pages().await.expect("Failed to list pages");
+    assert!(pages.len() >= 2, "Popup not listed");
+    for page in &pages {
+        let webdriver: bool = page
+            .evaluate_typed("navigator.webdriver")
+            .await
+            .expect("Failed to read navigator.webdriver");
+        assert!(!webdriver, "Adopted page is missing stealth");
+    }
+}
+
+#[tokio::test]
+async fn test_page_reset_and_reuse() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser.new_blank_page().await.expect("Failed to open blank page");
+    page.goto(&server.url("/")).await.expect("Failed to navigate");
+    page.evaluate_void("localStorage.setItem('k', 'v')")
+        .await
+        .expect("Failed to set localStorage");
+
+    page.reset().await.expect("Failed to reset page");
+    assert_eq!(page.url().await.expect("Failed to get URL"), "about:blank");
+
+    page.goto(&server.url("/")).await.expect("Failed to navigate");
+    let stored: Option<String> = page
+        .evaluate_typed("localStorage.getItem('k')")
+        .await
+        .expect("Failed to read localStorage");
+    assert_eq!(stored, None, "localStorage survived reset");
+}
+
+#[tokio::test]
+async fn test_prewarmed_pages() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .prewarm_pages(2)
+        .extra_header("X-Agentic-Test", "prewarmed")
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let open_tabs = || async { browser.pages().await.expect("Failed to list pages").len() };
+    let at_launch = open_tabs().await;
+    assert!(at_launch >= 2);
+
+    // The first pages come from the pool, set up like any other
+    for _ in 0..2 {
+        let page = browser.new_page(&server.url("/echo")).await.expect("Failed to open page");
+        let echo: serde_json::Value = page
+            .evaluate_typed("JSON.parse(document.getElementById('echo').textContent)")
+            .await
+            .expect("Failed to read echo");
+        assert_eq!(echo["headers"]["x-agentic-test"], "prewarmed");
+        assert_eq!(open_tabs().await, at_launch);
+    }
+    // The pool is not refilled
+    browser.new_blank_page().await.expect("Failed to open page");
+    assert_eq!(open_tabs().await, at_launch + 1);
+}
+
+#[tokio::test]
+async fn test_observe() {
+    let server = fixtures().await;
+    server.add_page("/tall", r#"<title>Tall</title><div style="height: 5000px">Tall</div>"#);
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .viewport(1024, 768)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/tall")).await.expect("Failed to open page");
+    page.evaluate("window.scrollTo(0, 1200)").await.expect("Failed to scroll");
+
+    let observation = page.observe().await.expect("Failed to observe");
+    assert_eq!(observation.url, server.url("/tall"));
+    assert_eq!(observation.title, "Tall");
+    assert_eq!(observation.ready_state, "complete");
+    assert_eq!((observation.scroll_x, observation.scroll_y), (0.0, 1200.0));
+    assert_eq!((observation.viewport_width, observation.viewport_height), (1024, 768));
+}
+
+#[tokio::test]
+async fn test_dom_snapshot_extraction() {
+    use agentic_browser::ExtractionBackend;
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let mut page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+
+    let js_links = page.get_links().await.expect("Failed to get links via JS");
+    page.set_extraction_backend(ExtractionBackend::DomSnapshot);
+    let snapshot_links = page.get_links().await.expect("Failed to get links via snapshot");
+    assert_eq!(js_links, snapshot_links);
+
+    let tree = page.accessibility_tree().await.expect("Failed to get tree via snapshot");
+    assert!(tree.contains("Example Domain"), "Tree was: {tree}");
+}
+
+#[tokio::test]
+async fn test_page_fetch() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+
+    let response = page.fetch("/").await.expect("Failed to fetch");
+    assert!(response.ok(), "Status was {}", response.status);
+    assert!(response.body.contains("Example Domain"));
+    assert!(response.headers.contains_key("content-type"));
+}
+
+#[tokio::test]
+async fn test_capture_responses() {
+    use futures::StreamExt;
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    let mut responses = page.capture_responses("*/json*").await.expect("Failed to capture");
+
+    // Only matching XHR/fetch responses come through, in order
+    page.evaluate(
+        "fetch('/echo').then(() => fetch('/json?n=1')).then(() => new Promise(r => { \
+            const xhr = new XMLHttpRequest(); xhr.onload = r; xhr.open('GET', '/json?n=2'); xhr.send(); }))",
+    )
+    .await
+    .expect("Failed to fetch");
+    let mut captured = Vec::new();
+    for _ in 0..2 {
+        let response = tokio::time::timeout(std::time::Duration::from_secs(5), responses.next())
+            .await
+            .expect("No response captured")
+            .expect("Stream ended");
+        captured.push(response);
+    }
+    let urls: Vec<_> = captured.iter().map(|r| r.url.clone()).collect();
+    assert_eq!(urls, [server.url("/json?n=1"), server.url("/json?n=2")]);
+    assert_eq!(captured[0].status, 200);
+    assert_eq!(captured[0].mime_type, "application/json");
+    let json: serde_json::Value = captured[0].json().expect("Invalid JSON");
+    assert_eq!(json["items"], serde_json::json!([1, 2, 3]));
+}
+
+#[tokio::test]
+async fn test_memory_metrics() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+
+    let metrics = page.metrics().await.expect("Failed to get page metrics");
+    assert!(metrics.js_heap_used_size > 0);
+    assert!(metrics.nodes > 0);
+
+    if cfg!(target_os = "linux") {
+        let usage = browser.memory_usage().expect("Failed to get memory usage");
+        assert!(usage.process_count > 1, "Expected browser and renderer processes");
+        assert!(usage.rss_bytes > 0);
+    }
+}
+
+#[tokio::test]
+async fn test_memory_budget_closes_and_kills() {
+    use agentic_browser::{BudgetAction, EventKind, MemoryBudget};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let server = fixtures().await;
+    let budget = |action| MemoryBudget {
+        page_js_heap: Some(1),
+        interval: Duration::from_millis(200),
+        action,
+        ..Default::default()
+    };
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .memory_budget(budget(BudgetAction::ClosePage))
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let exceeded = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&exceeded);
+    browser
+        .on(EventKind::MemoryBudgetExceeded, move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .await
+        .expect("Failed to subscribe");
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert!(exceeded.load(Ordering::SeqCst) >= 1, "No MemoryBudgetExceeded event received");
+    assert!(page.title().await.is_err(), "The page over budget should be closed");
+    assert!(browser.is_alive().await);
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .memory_budget(budget(BudgetAction::KillBrowser))
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let _page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert!(!browser.is_alive().await, "KillBrowser should kill Chrome");
+}
+
+#[tokio::test]
+async fn test_performance_timings_and_waterfall() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+
+    let timings = page.performance_timings().await.expect("Failed to get timings");
+    assert!(timings.ttfb_ms.is_some());
+    assert!(timings.load_ms.is_some());
+
+    let waterfall = page.request_waterfall().await.expect("Failed to get waterfall");
+    let document = waterfall.first().expect("Waterfall is empty");
+    assert_eq!(document.resource_type, "navigation");
+    assert!(document.url.starts_with(&server.url("/")));
+}
+
+#[tokio::test]
+async fn test_record_and_replay_fixtures() {
+    let server = fixtures().await;
+    let dir = std::env::temp_dir().join(format!("agentic_browser_fixtures_test_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    {
+        let browser = AgenticBrowser::builder()
+            .headless(true)
+            .record_fixtures(&dir)
+            .build()
+            .await
+            .expect("Failed to launch recording browser");
+        browser
+            .new_page(&server.url("/"))
+            .await
+            .expect("Failed to record page");
+    }
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .replay_fixtures(&dir)
+        .build()
+        .await
+        .expect("Failed to launch replaying browser");
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to replay page");
+    let title = page.title().await.expect("Failed to get title");
+    assert_eq!(title, "Example Domain");
+
+    let missing = browser.new_page(&server.url("/not-recorded")).await;
+    assert!(missing.is_err(), "Unrecorded request should fail");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_background_error_policy() {
+    use agentic_browser::{BackgroundErrorPolicy, BrowserEvent, Error, EventKind};
+    use futures::StreamExt;
+
+    let server = fixtures().await;
+    let dir = std::env::temp_dir().join(format!("agentic_browser_background_errors_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    {
+        let browser = AgenticBrowser::builder()
+            .headless(true)
+            .record_fixtures(&dir)
+            .build()
+            .await
+            .expect("Failed to launch recording browser");
+        browser.new_page(&server.url("/")).await.expect("Failed to record page");
+    }
+    // Chrome rejects a fulfilled response with an invalid header name, so the
+    // interceptor's Fetch.fulfillRequest fails
+    let set_bad_header = |bad: bool| {
+        for entry in std::fs::read_dir(&dir).expect("No fixtures") {
+            let path = entry.expect("Unreadable fixture").path();
+            let text = std::fs::read_to_string(&path).expect("Unreadable fixture");
+            let mut fixture: serde_json::Value = serde_json::from_str(&text).expect("Invalid fixture");
+            let headers = fixture["headers"].as_array_mut().expect("No headers");
+            headers.retain(|h| h[0] != "bad header");
+            if bad {
+                headers.push(serde_json::json!(["bad header", "x"]));
+            }
+            std::fs::write(&path, fixture.to_string()).expect("Failed to write fixture");
+        }
+    };
+    set_bad_header(true);
+
+    // FailPage: the navigation fails with the error, the stream gets it too, and
+    // the page navigates normally once the cause is gone
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .replay_fixtures(&dir)
+        .background_error_policy(BackgroundErrorPolicy::FailPage)
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    let mut errors = page.background_errors();
+    let result = page.goto(&server.url("/")).await;
+    assert!(matches!(result, Err(Error::NavigationError(ref m)) if m.contains("Fetch.fulfillRequest")), "{result:?}");
+    let error = tokio::time::timeout(std::time::Duration::from_secs(5), errors.next())
+        .await
+        .expect("No background error")
+        .expect("Stream ended");
+    assert_eq!(error.command, "Fetch.fulfillRequest");
+    assert_eq!(error.url, server.url("/"));
+    set_bad_header(false);
+    page.goto(&server.url("/")).await.expect("Page stayed failed");
+    assert_eq!(page.title().await.expect("Failed to get title"), "Example Domain");
+
+    // Emit: handlers for EventKind::BackgroundError get it
+    set_bad_header(true);
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .replay_fixtures(&dir)
+        .background_error_policy(BackgroundErrorPolicy::Emit)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    browser
+        .on(EventKind::BackgroundError, move |event| {
+            if let BrowserEvent::BackgroundError(error) = event {
+                let _ = tx.send(error);
+            }
+        })
+        .await
+        .expect("Failed to subscribe");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    let _ = tokio::time::timeout(std::time::Duration::from_secs(3), page.goto(&server.url("/"))).await;
+    let error = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+        .await
+        .expect("No background error event")
+        .expect("Channel closed");
+    assert_eq!(error.command, "Fetch.fulfillRequest");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_action_recording_stops_cleanly() {
+    use agentic_browser::Action;
+
+    let server = fixtures().await;
+    server.add_page("/record", r#"<button id="go" onclick="this.textContent = 'Clicked'">Go</button>"#);
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/record")).await.expect("Failed to open page");
+    let installed = "typeof window[Symbol.for('agentic-browser.recorder')] === 'function'";
+
+    let recorder = page.start_action_recording().await.expect("Failed to start recording");
+    // The recorder leaves no named property on the page
+    let keys: Vec<String> = page
+        .evaluate_typed("Object.keys(window).filter(k => /agentic/i.test(k) && k !== '__agentic_record')")
+        .await
+        .expect("evaluate failed");
+    assert!(keys.is_empty(), "{keys:?}");
+    page.reload().await.expect("Failed to reload");
+    assert!(page.evaluate_typed::<bool>(installed).await.expect("evaluate failed"));
+    page.click("#go").await.expect("Failed to click");
+    let mut steps = Vec::new();
+    for _ in 0..20 {
+        steps = recorder.script().steps;
+        if steps.iter().any(|s| matches!(s, Action::Click { .. })) {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert!(steps.contains(&Action::Click { selector: "#go".to_string() }), "{steps:?}");
+
+    // Stopping removes the listeners and the script that re-installs them
+    recorder.stop();
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    assert!(!page.evaluate_typed::<bool>(installed).await.expect("evaluate failed"));
+    page.reload().await.expect("Failed to reload");
+    assert!(!page.evaluate_typed::<bool>(installed).await.expect("evaluate failed"));
+}
+
+#[tokio::test]
+async fn test_scenario_runner() {
+    use agentic_browser::Scenario;
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page("about:blank")
+        .await
+        .expect("Failed to open page");
+
+    let scenario = Scenario::from_json(
+        &r#"{
+            "variables": { "site": "SITE" },
+            "steps": [
+                { "navigate": "${site}" },
+                { "wait_for": "h1" },
+                { "assert": { "selector": "h1", "equals": "Example Domain" } },
+                { "extract": { "selector": "a", "as": "link", "attribute": "href" } }
+            ]
+        }"#
+        .replace("SITE", &server.url("/")),
+    )
+    .expect("Failed to parse scenario");
+
+    let report = scenario.run(&page).await.expect("Scenario failed");
+    assert_eq!(report.steps_run, 4);
+    assert!(report.extracted["link"].starts_with("http"));
+
+    let failing = Scenario::from_json(
+        r#"{ "steps": [ { "assert": { "selector": "h1", "contains": "Nope" } } ] }"#,
+    )
+    .expect("Failed to parse scenario");
+    let err = failing.run(&page).await.expect_err("Assertion should fail");
+    assert!(err.to_string().contains("step 1 (assert)"), "Error was: {err}");
+}
+
+#[tokio::test]
+async fn test_tool_calls() {
+    use agentic_browser::tools;
+
+    let server = fixtures().await;
+    let names: Vec<_> = tools::schemas().iter().map(|t| t.name).collect();
+    assert!(names.contains(&"navigate") && names.contains(&"click"));
+    assert_eq!(tools::openai_tools()[0]["type"], "function");
+    assert!(tools::anthropic_tools()[0]["input_schema"].is_object());
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page("about:blank")
+        .await
+        .expect("Failed to open page");
+
+    let args = serde_json::json!({ "url": server.url("/") }).to_string();
+    tools::execute_tool_call(&page, "navigate", &args)
+        .await
+        .expect("navigate failed");
+    let text = tools::execute_tool_call(&page, "get_text", r#"{"selector": "h1"}"#)
+        .await
+        .expect("get_text failed");
+    assert_eq!(text, "Example Domain");
+
+    let err = tools::execute_tool_call(&page, "fly", "{}").await;
+    assert!(err.is_err(), "Unknown tool should fail");
+}
+
+#[tokio::test]
+async fn test_execute_action() {
+    use agentic_browser::{Action, ActionResult};
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page("about:blank")
+        .await
+        .expect("Failed to open page");
+
+    let actions: Vec<Action> = serde_json::from_value(serde_json::json!([
+        {"action": "goto", "url": server.url("/")},
+        {"action": "wait", "selector": "h1"},
+        {"action": "extract", "selector": "h1"}
+    ]))
+    .expect("Failed to parse actions");
+
+    let mut results = Vec::new();
+    for action in &actions {
+        results.push(page.execute_action(action).await.expect("Action failed"));
+    }
+    assert_eq!(results[0], ActionResult::Done);
+    assert_eq!(results[2], ActionResult::Extracted("Example Domain".to_string()));
+}
+
+#[tokio::test]
+async fn test_agent_runner() {
+    use agentic_browser::{
+        Action, AgentDecision, AgentObservation, AgentRunner, LlmPolicy, StopReason,
+        TranscriptEntry,
+    };
+
+    /// Opens the given URL, then reports the title.
+    struct Scripted(String);
+
+    impl LlmPolicy for Scripted {
+        async fn decide(
+            &self,
+            observation: &AgentObservation,
+            history: &[TranscriptEntry],
+        ) -> agentic_browser::Result<AgentDecision> {
+            Ok(if history.is_empty() {
+                AgentDecision::Act {
+                    action: Action::Goto { url: self.0.clone() },
+                }
+            } else {
+                AgentDecision::Done { answer: observation.title.clone() }
+            })
+        }
+    }
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page("about:blank")
+        .await
+        .expect("Failed to open page");
+
+    let outcome = AgentRunner::new(Scripted(server.url("/")))
+        .max_steps(5)
+        .run(&page)
+        .await
+        .expect("Agent run failed");
+    assert_eq!(outcome.stop_reason, StopReason::Done("Example Domain".to_string()));
+    assert_eq!(outcome.transcript.len(), 1);
+    assert!(outcome.transcript[0].result.is_ok());
+}
+
+#[tokio::test]
+async fn test_describe_fits_budget() {
+    use agentic_browser::TokenEstimator;
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+
+    let full = page.describe(2000).await.expect("Failed to describe page");
+    assert!(full.contains("Title: Example Domain"));
+    assert!(full.contains("[h1] \"Example Domain\""));
+    assert!(full.contains("Content:"));
+
+    let estimator = TokenEstimator::default();
+    for budget in [10, 30, 60] {
+        let short = page.describe(budget).await.expect("Failed to describe page");
+        assert!(estimator.estimate(&short) <= budget, "{short:?} exceeds {budget} tokens");
+    }
+}
+
+#[tokio::test]
+async fn test_autofill() {
+    use agentic_browser::{FillProfile, ProfileField, SkipReason};
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page("about:blank")
+        .await
+        .expect("Failed to open page");
+
+    page.evaluate_void(
+        r#"document.body.innerHTML = `
+            <form>
+                <label for="fn">First name</label><input id="fn">
+                <label for="ln">Last name</label><input id="ln">
+                <input type="email" name="contact">
+                <input name="cc-number" placeholder="1234 5678 9012 3456">
+                <select name="country"><option value="">--</option><option value="GB">United Kingdom</option></select>
+                <input name="nickname">
+            </form>`"#,
+    )
+    .await
+    .expect("Failed to build form");
+
+    let profile = FillProfile {
+        full_name: Some("Ada Lovelace".to_string()),
+        email: Some("ada@example.com".to_string()),
+        country: Some("United Kingdom".to_string()),
+        ..Default::default()
+    };
+    let report = page.autofill(&profile).await.expect("Autofill failed");
+
+    let matched: Vec<ProfileField> = report.filled.iter().map(|f| f.matched).collect();
+    assert_eq!(
+        matched,
+        [ProfileField::FirstName, ProfileField::LastName, ProfileField::Email, ProfileField::Country]
+    );
+    let reasons: Vec<SkipReason> = report.skipped.iter().map(|s| s.reason).collect();
+    assert_eq!(reasons, [SkipReason::Sensitive, SkipReason::NoMatch]);
+
+    let values: Vec<String> = page
+        .evaluate_typed(
+            "['fn', 'ln'].map(id => document.getElementById(id).value)
+                .concat([document.querySelector('[name=contact]').value, document.querySelector('select').value])",
+        )
+        .await
+        .expect("Failed to read values");
+    assert_eq!(values, ["Ada", "Lovelace", "ada@example.com", "GB"]);
+}
+
+#[tokio::test]
+async fn test_multi_step_login() {
+    use agentic_browser::{LoginOutcome, LoginSpec};
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page("about:blank")
+        .await
+        .expect("Failed to open page");
+
+    // Username and password on separate screens, like X.com.
+    page.evaluate_void(
+        r#"(() => {
+            const passwordStep = () => {
+                document.body.innerHTML = `<form id="f"><input type="password" name="pw"><button>Log in</button></form>`;
+                document.getElementById('f').onsubmit = (e) => {
+                    e.preventDefault();
+                    document.body.innerHTML = e.target.pw.value === 'hunter2'
+                        ? '<h1>Welcome ' + window.user + '</h1>'
+                        : '<form><input type="password"></form><p role="alert">Wrong password</p>';
+                };
+            };
+            document.body.innerHTML = `<form id="f"><input name="username" autocomplete="username"><button>Next</button></form>`;
+            document.getElementById('f').onsubmit = (e) => {
+                e.preventDefault();
+                window.user = e.target.username.value;
+                setTimeout(passwordStep, 300);
+            };
+        })()"#,
+    )
+    .await
+    .expect("Failed to build login form");
+
+    let outcome = page
+        .login(&LoginSpec::new("bob", "hunter2"))
+        .await
+        .expect("Login failed");
+    assert_eq!(outcome, LoginOutcome::Success);
+    assert_eq!(page.text_content("h1").await.expect("No heading"), "Welcome bob");
+}
+
+#[cfg(feature = "totp")]
+#[tokio::test]
+async fn test_submit_otp() {
+    use agentic_browser::{LoginOutcome, Totp};
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page("about:blank")
+        .await
+        .expect("Failed to open page");
+
+    page.evaluate_void(
+        r#"(() => {
+            document.body.innerHTML = `<form id="f"><input name="code" autocomplete="one-time-code"><button>Verify</button></form>`;
+            document.getElementById('f').onsubmit = (e) => {
+                e.preventDefault();
+                window.submittedCode = e.target.code.value;
+                document.body.innerHTML = '<h1>Signed in</h1>';
+            };
+        })()"#,
+    )
+    .await
+    .expect("Failed to build OTP form");
+
+    let secret = "JBSWY3DPEHPK3PXP";
+    let outcome = page.submit_otp(None, secret).await.expect("OTP entry failed");
+    assert_eq!(outcome, LoginOutcome::Success);
+
+    let submitted: String = page
+        .evaluate_typed("window.submittedCode")
+        .await
+        .expect("No code submitted");
+    let totp = Totp::new(secret).expect("Invalid secret");
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("Clock before 1970")
+        .as_secs();
+    assert!(submitted == totp.generate(now) || submitted == totp.generate(now - 30));
+}
+
+#[tokio::test]
+async fn test_detect_blocking() {
+    use agentic_browser::Blocking;
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+    assert_eq!(page.detect_blocking().await.expect("Detection failed"), Blocking::None);
+
+    page.evaluate_void(
+        r#"document.body.innerHTML = '<div class="h-captcha" style="width:300px;height:80px"></div>'"#,
+    )
+    .await
+    .expect("Failed to add widget");
+    assert_eq!(page.detect_blocking().await.expect("Detection failed"), Blocking::Hcaptcha);
+
+    page.evaluate_void("document.title = 'Just a moment...'")
+        .await
+        .expect("Failed to set title");
+    assert_eq!(
+        page.detect_blocking().await.expect("Detection failed"),
+        Blocking::CloudflareChallenge
+    );
+}
+
+#[tokio::test]
+async fn test_dismiss_overlays() {
+    let server = fixtures().await;
+    server.add_page(
+        "/overlays",
+        r#"<html><body style="overflow: hidden">
+        <div id="app" style="position: fixed; inset: 0"><button>Menu</button><p>App content</p></div>
+        <div id="newsletter" class="modal" style="position: fixed; inset: 20%; background: white">
+            Subscribe to our newsletter <button aria-label="Close">x</button>
+        </div>
+        <div id="consent" style="position: fixed; bottom: 0; width: 100%">We use cookies <button>Close</button></div>
+        </body></html>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser.new_page(&server.url("/overlays")).await.expect("Failed to open page");
+    let dismissed = page.dismiss_overlays().await.expect("dismiss_overlays failed");
+    assert_eq!(dismissed, 1);
+
+    let state: (bool, bool, bool, String) = page
+        .evaluate_typed(
+            "[!!document.getElementById('newsletter'), \
+              getComputedStyle(document.getElementById('newsletter')).display !== 'none', \
+              getComputedStyle(document.getElementById('app')).display !== 'none' \
+                && getComputedStyle(document.getElementById('consent')).display !== 'none', \
+              getComputedStyle(document.body).overflow]",
+        )
+        .await
+        .expect("evaluate failed");
+    assert_eq!(state, (true, false, true, "auto".to_string()), "only the newsletter modal is hidden");
+}
+
+#[tokio::test]
+async fn test_save_mhtml() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page(&server.url("/"))
+        .await
+        .expect("Failed to open page");
+
+    let path = std::env::temp_dir().join("agentic_browser_test.mhtml");
+    page.save_mhtml(&path).await.expect("Failed to save MHTML");
+    let archive = std::fs::read_to_string(&path).expect("Failed to read archive");
+    let _ = std::fs::remove_file(&path);
+    assert!(archive.contains("multipart/related"));
+    assert!(archive.contains("Example Domain"));
+}
+
+#[tokio::test]
+async fn test_save_complete() {
+    let server = fixtures().await;
+    server.add_page(
+        "/gallery",
+        r#"<!doctype html><html><head><title>Gallery</title><link rel="stylesheet" href="/style.css"></head>
+        <body><img id="pic" src="/pixel.png"><a id="link" href="/form">Form</a>
+        <script>document.title = 'Gallery (scripted)';</script></body></html>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/gallery")).await.expect("Failed to open page");
+    let dir = std::env::temp_dir().join(format!("agentic_browser_save_complete_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let index = page.save_complete(&dir).await.expect("Failed to save page");
+    assert_eq!(index, dir.join("index.html"));
+    let html = std::fs::read_to_string(&index).expect("No index.html");
+    let resources: Vec<String> = std::fs::read_dir(dir.join("resources"))
+        .expect("No resource directory")
+        .map(|entry| entry.expect("Unreadable entry").file_name().to_string_lossy().into_owned())
+        .collect();
+    let png = resources.iter().find(|f| f.ends_with(".png")).expect("Image not saved");
+    let css = resources.iter().find(|f| f.ends_with(".css")).expect("Stylesheet not saved");
+
+    // The copy points at the local files, keeps the rendered DOM and drops scripts
+    assert!(html.contains(&format!("resources/{png}")), "{html}");
+    assert!(html.contains(&format!("resources/{css}")), "{html}");
+    assert!(html.contains(&server.url("/form")), "links should be absolute: {html}");
+    assert!(html.contains("Gallery (scripted)") && !html.contains("<script"), "{html}");
+    let stylesheet = std::fs::read_to_string(dir.join("resources").join(css)).expect("Unreadable stylesheet");
+    assert!(stylesheet.contains(png.as_str()) && !stylesheet.contains("/pixel.png"), "{stylesheet}");
+    let saved_png = std::fs::read(dir.join("resources").join(png)).expect("Unreadable image");
+    assert_eq!(&saved_png[1..4], b"PNG");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_rate_limiter_spacing() {
+    use agentic_browser::{RateLimit, RateLimiter};
+    use std::time::{Duration, Instant};
+
+    let limiter = RateLimiter::new().domain("example.com", RateLimit::every(Duration::from_millis(300)));
+
+    let start = Instant::now();
+    drop(limiter.acquire("https://example.com/a").await);
+    drop(limiter.acquire("https://www.example.com/b").await);
+    assert!(start.elapsed() >= Duration::from_millis(300), "subdomain shares the limit");
+
+    let start = Instant::now();
+    drop(limiter.acquire("https://example.org/").await);
+    drop(limiter.acquire("about:blank").await);
+    assert!(start.elapsed() < Duration::from_millis(100), "other domains are not limited");
+}
+
+#[tokio::test]
+async fn test_rate_limiter_relative_fetch() {
+    use agentic_browser::{RateLimit, RateLimiter};
+    use std::time::{Duration, Instant};
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .rate_limiter(RateLimiter::new().domain("127.0.0.1", RateLimit::every(Duration::from_millis(400))))
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+
+    // Relative URLs are limited under the page's host
+    let start = Instant::now();
+    page.fetch("/").await.expect("fetch failed");
+    page.fetch("/").await.expect("fetch failed");
+    assert!(start.elapsed() >= Duration::from_millis(400), "relative fetches share the host's limit");
+}
+
+#[tokio::test]
+async fn test_map_urls() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let urls = vec![server.url("/"), "not a url".to_string(), "about:blank".to_string()];
+    let results = browser
+        .map_urls(urls, 2, |page| async move { page.url().await })
+        .await;
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].url, server.url("/"));
+    assert!(results[0].result.as_ref().expect("Fixture page should load").contains("127.0.0.1"));
+    assert!(results[1].result.is_err());
+    assert_eq!(results[1].attempts, 2, "failed URL is retried once");
+    assert!(results[2].result.is_ok());
+}
+
+#[tokio::test]
+async fn test_set_zoom() {
+    use agentic_browser::RequestIdentity;
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .viewport(800, 600)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser.new_page("about:blank").await.expect("Failed to open page");
+    page.set_zoom(2.0).await.expect("set_zoom failed");
+
+    let viewport = page.viewport().await.expect("viewport failed");
+    assert_eq!(viewport.dpr, 2.0);
+    assert_eq!((viewport.width, viewport.height), (800.0, 600.0), "layout size is kept");
+    assert!(page.set_zoom(0.0).await.is_err());
+
+    // Zooming keeps a mobile identity's layout
+    let mut identity = RequestIdentity::new().next();
+    identity.mobile = true;
+    identity.viewport_width = 400;
+    page.set_identity(&identity).await.expect("set_identity failed");
+    let layout = page.viewport().await.expect("viewport failed").width;
+    page.set_zoom(2.0).await.expect("set_zoom failed");
+    let viewport = page.viewport().await.expect("viewport failed");
+    assert_eq!((viewport.width, viewport.dpr), (layout, 2.0));
+}
+
+#[tokio::test]
+async fn test_scroll_to_position() {
+    let server = fixtures().await;
+    server.add_page("/tall", "<html><body><div style='height: 5000px'>tall</div></body></html>");
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser.new_page(&server.url("/tall")).await.expect("Failed to open page");
+    page.scroll_to_position(0.0, 1200.0).await.expect("scroll_to_position failed");
+    let viewport = page.viewport().await.expect("viewport failed");
+    assert_eq!(viewport.scroll_y, 1200.0);
+
+    for (x, y) in [(0.0, f64::NAN), (f64::INFINITY, 0.0)] {
+        let err = page.scroll_to_position(x, y).await.expect_err("non-finite position should fail");
+        assert!(matches!(err, agentic_browser::Error::ConfigError(_)), "got {err:?}");
+    }
+    assert_eq!(page.viewport().await.expect("viewport failed").scroll_y, 1200.0);
+}
+
+#[tokio::test]
+async fn test_disable_animations() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser.new_page("about:blank").await.expect("Failed to open page");
+    page.disable_animations().await.expect("disable_animations failed");
+    page.add_style("body { margin: 7px; }").await.expect("add_style failed");
+    page.goto(&server.url("/")).await.expect("Navigation failed");
+
+    let styles: (String, String) = page
+        .evaluate_typed(
+            "[getComputedStyle(document.body).transitionDuration, getComputedStyle(document.body).margin]",
+        )
+        .await
+        .expect("evaluate failed");
+    assert_eq!(styles, ("0s".to_string(), "7px".to_string()), "styles survive navigation");
+}
+
+#[tokio::test]
+async fn test_highlight() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    page.highlight("h1", "red").await.expect("highlight failed");
+    assert!(page.highlight("#missing", "red").await.is_err());
+
+    let boxes: usize = page
+        .evaluate_typed("document.querySelectorAll('[data-agentic-highlights] > div').length")
+        .await
+        .expect("evaluate failed");
+    assert_eq!(boxes, 1);
+
+    page.clear_highlights().await.expect("clear_highlights failed");
+    let boxes: usize = page
+        .evaluate_typed("document.querySelectorAll('[data-agentic-highlights] > div').length")
+        .await
+        .expect("evaluate failed");
+    assert_eq!(boxes, 0);
+}
+
+#[tokio::test]
+async fn test_fake_media() {
+    use agentic_browser::FakeMedia;
+
+    let server = fixtures().await;
+    let missing = AgenticBrowser::builder()
+        .headless(true)
+        .fake_media(FakeMedia::new().video("/nonexistent/video.y4m"))
+        .build()
+        .await;
+    assert!(missing.is_err(), "missing media file is reported");
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .fake_media(FakeMedia::new())
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    let kinds: Vec<String> = page
+        .evaluate_typed(
+            "navigator.mediaDevices.getUserMedia({ video: true, audio: true })
+                .then(stream => stream.getTracks().map(t => t.kind).sort())",
+        )
+        .await
+        .expect("getUserMedia failed");
+    assert_eq!(kinds, ["audio", "video"]);
+}
+
+#[cfg(feature = "visual")]
+#[tokio::test]
+async fn test_compare_screenshots() {
+    use agentic_browser::{compare_screenshots, compare_screenshots_with, DiffOptions, Region};
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .viewport(800, 600)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    let before = page.screenshot().await.expect("Screenshot failed");
+    let same = compare_screenshots(&before, &page.screenshot().await.expect("Screenshot failed"))
+        .expect("compare failed");
+    assert!(same.is_identical());
+
+    page.evaluate("document.querySelector('h1').textContent = 'Something else'")
+        .await
+        .expect("evaluate failed");
+    let after = page.screenshot().await.expect("Screenshot failed");
+    let diff = compare_screenshots(&before, &after).expect("compare failed");
+    assert!(diff.pixel_diff_ratio > 0.0 && diff.pixel_diff_ratio < 0.5);
+    assert!(!diff.diff_image.is_empty());
+
+    let masked = compare_screenshots_with(&before, &after, &DiffOptions::default().mask(Region::new(0, 0, 800, 600)))
+        .expect("compare failed");
+    assert!(masked.is_identical());
+}
+
+#[tokio::test]
+async fn test_assertions() {
+    use agentic_browser::assertions::expect;
+    use std::time::Duration;
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    expect(&page).to_have_title("Example Domain").await.expect("title");
+    expect(&page).to_have_url_matching(&format!("{}*", server.url("/"))).await.expect("url");
+    expect(&page.locator("h1")).to_have_text("Example Domain").await.expect("h1 text");
+    expect(&page.locator("h1")).to_have_count(1).await.expect("h1 count");
+
+    // Auto-waits for content that appears later
+    page.evaluate(
+        "setTimeout(() => { const p = document.createElement('p'); p.id = 'late'; \
+         p.textContent = 'arrived'; document.body.appendChild(p); }, 500)",
+    )
+    .await
+    .expect("evaluate failed");
+    expect(&page.locator("#late")).to_be_visible().await.expect("late element");
+
+    let err = expect(&page)
+        .timeout(Duration::from_millis(300))
+        .to_have_title("Something else")
+        .await
+        .expect_err("wrong title should fail");
+    assert!(err.to_string().contains("Example Domain"), "error shows what was seen: {err}");
+}
+
+#[tokio::test]
+async fn test_fixture_server_pages() {
+    use agentic_browser::Blocking;
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser
+        .new_page(&server.url("/status/403"))
+        .await
+        .expect("Failed to open page");
+    assert_eq!(page.detect_blocking().await.expect("Detection failed"), Blocking::AccessDenied);
+
+    let json = page.fetch("/json").await.expect("Failed to fetch");
+    assert_eq!(json.json::<serde_json::Value>().expect("Invalid JSON")["items"][2], 3);
+
+    server.add_page("/custom", "<title>Custom</title><p>Hello</p>");
+    page.goto(&server.url("/custom")).await.expect("Failed to navigate");
+    assert_eq!(page.title().await.expect("Failed to get title"), "Custom");
+
+    page.goto(&server.url("/form")).await.expect("Failed to navigate");
+    page.type_text("#name", "Ada").await.expect("Failed to type");
+    page.click("button[type=submit]").await.expect("Failed to submit");
+    page.wait_for_selector("#echo").await.expect("No echo page");
+    let echo = page.text_content("#echo").await.expect("Failed to read echo");
+    assert!(echo.contains("name=Ada"), "Echo was: {echo}");
+}
+
+#[cfg(feature = "ffi")]
+#[test]
+fn test_ffi() {
+    use agentic_browser::ffi::*;
+    use std::ffi::{CStr, CString};
+
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to start runtime");
+    let server = runtime.block_on(fixtures());
+    let url = CString::new(server.url("/")).expect("URL has NUL");
+    let config = std::env::temp_dir().join("agentic_browser_ffi_test.toml");
+    std::fs::write(&config, "headless = true\ntimeout_ms = 2000\n").expect("Failed to write config file");
+    let config = CString::new(config.to_string_lossy().as_bytes()).expect("Path has NUL");
+
+    unsafe {
+        let browser = ab_browser_new(config.as_ptr());
+        assert!(!browser.is_null(), "Launch failed");
+        let page = ab_page_new(browser, url.as_ptr());
+        assert!(!page.is_null(), "Page failed");
+
+        let json = ab_page_snapshot(page);
+        assert!(!json.is_null());
+        let snapshot: serde_json::Value =
+            serde_json::from_str(CStr::from_ptr(json).to_str().expect("Not UTF-8")).expect("Not JSON");
+        ab_string_free(json);
+        assert_eq!(snapshot["observation"]["title"], "Example Domain");
+
+        let (mut data, mut len) = (std::ptr::null_mut(), 0);
+        assert_eq!(ab_page_screenshot(page, &mut data, &mut len), 0);
+        assert_eq!(std::slice::from_raw_parts(data, 4), &[0x89, 0x50, 0x4E, 0x47]);
+        ab_buffer_free(data, len);
+
+        let missing = CString::new("#missing").expect("Selector has NUL");
+        assert_eq!(ab_page_click(page, missing.as_ptr()), -1);
+        assert!(!ab_last_error().is_null());
+
+        ab_page_free(page);
+        ab_browser_free(browser);
+    }
+}
+
+#[cfg(feature = "prometheus")]
+#[tokio::test]
+async fn test_prometheus_metrics() {
+    use agentic_browser::telemetry;
+
+    let server = fixtures().await;
+    let addr = std::net::TcpListener::bind("127.0.0.1:0")
+        .and_then(|l| l.local_addr())
+        .expect("No free port");
+    telemetry::serve_prometheus(addr).expect("Failed to start exporter");
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    page.screenshot().await.expect("Screenshot failed");
+
+    page.goto(&format!("http://{addr}/metrics")).await.expect("Failed to open metrics");
+    let body = page.text_content("body").await.expect("Failed to read metrics");
+    assert!(body.contains(telemetry::NAVIGATIONS), "Metrics were: {body}");
+    assert!(body.contains(telemetry::SCREENSHOT_BYTES));
+    assert!(body.contains(telemetry::PAGES_OPEN));
+}
+
+#[cfg(feature = "cdp-log")]
+#[tokio::test]
+async fn test_cdp_log() {
+    let server = fixtures().await;
+    let path = std::env::temp_dir().join(format!("agentic_browser_cdp_test_{}.log", std::process::id()));
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .cdp_log(&path)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/")).await.expect("Failed to navigate");
+
+    let log = std::fs::read_to_string(&path).expect("No CDP log");
+    let _ = std::fs::remove_file(&path);
+    assert!(log.contains("Page.navigate"), "Log was: {log}");
+}
+
+#[tokio::test]
+async fn test_wait_for_selector_states() {
+    use agentic_browser::{ElementState, WaitOptions};
+    use std::time::Duration;
+
+    let server = fixtures().await;
+    server.add_page(
+        "/hidden",
+        r#"<button id="later" style="display:none">Go</button>
+        <p id="notice">Saving...</p>
+        <script>setTimeout(() => {
+            document.getElementById('later').style.display = '';
+            document.getElementById('notice').remove();
+        }, 300);</script>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/hidden")).await.expect("Failed to open page");
+
+    let attached = page
+        .wait_for_selector_with("#later", WaitOptions::new(ElementState::Attached))
+        .await
+        .expect("Failed to wait for attached");
+    assert!(attached.is_some());
+
+    let button = page.wait_for_selector("#later").await.expect("Button never became visible");
+    assert!(button.is_visible().await.expect("Failed to check visibility"));
+
+    let gone = page
+        .wait_for_selector_with("#notice", WaitOptions::new(ElementState::Hidden))
+        .await
+        .expect("Notice never went away");
+    assert!(gone.is_none());
+
+    let missing = page
+        .wait_for_selector_with("#missing", WaitOptions::default().timeout(Duration::from_millis(200)))
+        .await;
+    assert!(matches!(missing, Err(agentic_browser::Error::Timeout(_))));
+}
+
+#[tokio::test]
+async fn test_goto_with_wait_until() {
+    use agentic_browser::{GotoOptions, WaitUntil};
+
+    let server = fixtures().await;
+    server.add_page(
+        "/fetches",
+        r#"<h1>Loading</h1>
+        <script>window.addEventListener('load', () => setTimeout(async () => {
+            const data = await (await fetch('/json')).json();
+            document.querySelector('h1').textContent = 'Items: ' + data.items.length;
+        }, 100));</script>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+
+    page.goto_with(&server.url("/fetches"), GotoOptions::new(WaitUntil::NetworkIdle))
+        .await
+        .expect("Failed to navigate");
+    assert_eq!(page.text_content("h1").await.expect("No heading"), "Items: 3");
+
+    server.add_page(
+        "/ticking",
+        r#"<p id="tick">0</p>
+        <script>let n = 0; const t = setInterval(() => {
+            document.getElementById('tick').textContent = ++n;
+            if (n === 8) clearInterval(t);
+        }, 100);</script>"#,
+    );
+    page.goto_with(&server.url("/ticking"), GotoOptions::new(WaitUntil::Stable))
+        .await
+        .expect("Failed to navigate");
+    assert_eq!(page.text_content("#tick").await.expect("No counter"), "8");
+
+    page.goto_with(&server.url("/echo"), GotoOptions::default().referrer("https://referrer.test/"))
+        .await
+        .expect("Failed to navigate");
+    let referrer: String = page.evaluate_typed("document.referrer").await.expect("No referrer");
+    assert_eq!(referrer, "https://referrer.test/");
+
+    let slow = GotoOptions::default().timeout(std::time::Duration::from_millis(200));
+    let result = page.goto_with(&server.url("/slow?ms=2000"), slow).await;
+    assert!(matches!(result, Err(agentic_browser::Error::Timeout(_))));
+}
+
+#[tokio::test]
+async fn test_stop_after_cancelled_goto() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+
+    let cancelled = tokio::time::timeout(
+        std::time::Duration::from_millis(200),
+        page.goto(&server.url("/slow?ms=1500")),
+    )
+    .await;
+    assert!(cancelled.is_err());
+    // Dropping the goto stopped the load, so the slow page never commits
+    tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+    assert_eq!(page.url().await.expect("No URL"), "about:blank");
+
+    let started = std::time::Instant::now();
+    page.goto(&server.url("/echo")).await.expect("Failed to navigate");
+    assert!(started.elapsed() < std::time::Duration::from_secs(3));
+    assert!(page.url().await.expect("No URL").ends_with("/echo"));
+}
+
+#[tokio::test]
+async fn test_reload_hard_refetches_cached_resources() {
+    let server = fixtures().await;
+    server.add_page("/cache", r#"<script src="/cached.js"></script>"#);
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/cache")).await.expect("Failed to open page");
+    let served = || async { page.evaluate_typed::<u64>("window.served").await.expect("Script did not run") };
+
+    // A plain reload takes the script from the HTTP cache
+    let first = served().await;
+    page.reload().await.expect("Failed to reload");
+    assert_eq!(served().await, first);
+
+    page.reload_hard().await.expect("Failed to reload");
+    let refetched = served().await;
+    assert!(refetched > first);
+
+    page.reload().await.expect("Failed to reload");
+    assert_eq!(served().await, refetched);
+    page.clear_cache_and_reload().await.expect("Failed to reload");
+    assert!(served().await > refetched);
+}
+
+#[tokio::test]
+async fn test_history_navigation() {
+    let server = fixtures().await;
+    server.add_page("/first", "<title>First</title><h1>First</h1>");
+    server.add_page("/second", "<title>Second</title><h1>Second</h1>");
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/first")).await.expect("Failed to navigate");
+    page.goto(&server.url("/second")).await.expect("Failed to navigate");
+
+    page.go_back().await.expect("Failed to go back");
+    assert_eq!(page.text_content("h1").await.expect("No heading"), "First");
+    let history = page.history().await.expect("No history");
+    assert_eq!(history.current().expect("No current entry").title, "First");
+    assert!(history.can_go_forward());
+
+    page.go_forward().await.expect("Failed to go forward");
+    assert_eq!(page.text_content("h1").await.expect("No heading"), "Second");
+    assert!(page.go_forward().await.is_err());
+
+    let first = history
+        .entries
+        .iter()
+        .position(|entry| entry.url.ends_with("/first"))
+        .expect("No entry for /first");
+    page.navigate_to_entry(first).await.expect("Failed to navigate to entry");
+    assert!(page.url().await.expect("No URL").ends_with("/first"));
+}
+
+#[tokio::test]
+async fn test_accessibility_tree_frames_and_shadow_roots() {
+    use agentic_browser::{ExtractionBackend, TreeOptions};
+
+    let server = fixtures().await;
+    server.add_page(
+        "/widget",
+        r#"<h1>Shop</h1>
+        <iframe title="Checkout" srcdoc="<form><button>Pay now</button></form>"></iframe>
+        <login-box></login-box>
+        <script>customElements.define('login-box', class extends HTMLElement {
+            connectedCallback() {
+                this.attachShadow({ mode: 'open' }).innerHTML = '<button>Sign in</button>';
+            }
+        });</script>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let mut page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/widget")).await.expect("Failed to navigate");
+
+    for backend in [ExtractionBackend::Js, ExtractionBackend::DomSnapshot] {
+        page.set_extraction_backend(backend);
+        let main_only = page.accessibility_tree().await.expect("No tree");
+        assert!(!main_only.contains("Pay now") && !main_only.contains("Sign in"), "{main_only}");
+
+        let tree = page.accessibility_tree_with(TreeOptions::all()).await.expect("No tree");
+        assert!(tree.contains("[frame] \"Checkout\""), "{backend:?}: {tree}");
+        assert!(tree.contains("\"Pay now\""), "{backend:?}: {tree}");
+        assert!(tree.contains("\"Sign in\""), "{backend:?}: {tree}");
+    }
+}
+
+#[tokio::test]
+async fn test_form_fields_options_and_groups() {
+    use agentic_browser::ExtractionBackend;
+
+    let server = fixtures().await;
+    server.add_page(
+        "/order",
+        r#"<form id="order" action="/submit" method="POST">
+            <input name="email" type="email" required>
+            <select name="size"><option value="s">Small</option><option value="m" selected>Medium</option></select>
+            <input type="radio" name="ship" value="std" checked>
+            <input type="radio" name="ship" value="express">
+            <fieldset disabled><input name="coupon"></fieldset>
+        </form>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let mut page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/order")).await.expect("Failed to navigate");
+
+    for backend in [ExtractionBackend::Js, ExtractionBackend::DomSnapshot] {
+        page.set_extraction_backend(backend);
+        let fields = page.get_form_fields().await.expect("No form fields");
+        let field = |name: &str| fields.iter().find(|f| f.name == name).expect("Missing field").clone();
+
+        let email = field("email");
+        assert!(email.required && !email.disabled, "{backend:?}");
+        let form = email.form.expect("No owning form");
+        assert_eq!(form.id, "order");
+        assert_eq!(form.method, "post");
+        assert_eq!(form.action, server.url("/submit"));
+
+        let size = field("size");
+        let options: Vec<_> = size.options.iter().map(|o| (o.value.as_str(), o.selected)).collect();
+        assert_eq!(options, [("s", false), ("m", true)], "{backend:?}");
+
+        let ship = field("ship");
+        assert_eq!(ship.group, ["std", "express"], "{backend:?}");
+        assert!(ship.checked, "{backend:?}");
+        assert!(field("coupon").disabled, "{backend:?}");
+    }
+}
+
+#[tokio::test]
+async fn test_collect_validation_errors() {
+    use agentic_browser::ValidationSource;
+
+    let server = fixtures().await;
+    server.add_page(
+        "/signup",
+        r#"<form id="signup">
+            <div class="alert error">Please fix the errors below</div>
+            <div class="field has-error">
+                <label for="email">Email</label>
+                <input id="email" name="email" type="email" value="not-an-email">
+            </div>
+            <div class="field">
+                <label for="user">Username</label>
+                <input id="user" name="user" aria-invalid="true" aria-describedby="user-msg">
+                <p id="user-msg">That username is taken</p>
+            </div>
+            <input name="zip" value="123"><span class="error">Enter a 5-digit ZIP</span>
+            <span class="error" style="display: none">Hidden message</span>
+        </form>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/signup")).await.expect("Failed to navigate");
+
+    let errors = page.collect_validation_errors("#signup").await.expect("Failed to collect errors");
+    let find = |field: &str, source| errors.iter().find(|e| e.field == field && e.source == source);
+    let email = find("email", ValidationSource::Constraint).expect("No constraint error");
+    assert_eq!(email.label, "Email");
+    assert_eq!(find("user", ValidationSource::Aria).expect("No aria error").message, "That username is taken");
+    assert_eq!(find("zip", ValidationSource::Nearby).expect("No nearby error").message, "Enter a 5-digit ZIP");
+    assert!(find("", ValidationSource::Form).is_some_and(|e| e.message == "Please fix the errors below"));
+    assert!(errors.iter().all(|e| e.message != "Hidden message"));
+
+    assert!(page.collect_validation_errors("#missing").await.is_err());
+}
+
+#[tokio::test]
+async fn test_fill_form_safe_skips_honeypots() {
+    use agentic_browser::TrapReason;
+
+    let server = fixtures().await;
+    server.add_page(
+        "/contact",
+        r#"<form>
+            <input id="name">
+            <input id="email_confirm" style="position: absolute; left: -9999px">
+            <div style="display: none"><input id="company"></div>
+            <input id="honeypot" tabindex="-1" autocomplete="off">
+            <input id="no_hp" name="hp_number">
+            <input id="bot_check" name="bot_check">
+            <label>Leave this field empty <input id="extra"></label>
+        </form>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/contact")).await.expect("Failed to navigate");
+
+    let fields = ["#name", "#email_confirm", "#company", "#honeypot", "#no_hp", "#bot_check", "#extra"]
+        .map(|s| (s, "Ada"));
+    let report = page.fill_form_safe(&fields).await.expect("Failed to fill form");
+    // Phone fields named `hp_*` and a trap-like name without anything keeping the
+    // field out of reach are filled
+    assert_eq!(report.filled, ["#name", "#no_hp", "#bot_check"]);
+    let skipped: Vec<_> = report.skipped.iter().map(|s| (s.selector.as_str(), s.reason)).collect();
+    assert_eq!(
+        skipped,
+        [
+            ("#email_confirm", TrapReason::OffScreen),
+            ("#company", TrapReason::Hidden),
+            ("#honeypot", TrapReason::TrapName),
+            ("#extra", TrapReason::TrapName),
+        ]
+    );
+    let untouched: String = page
+        .evaluate_typed("['email_confirm', 'company', 'honeypot', 'extra'].map(id => document.getElementById(id).value).join('')")
+        .await
+        .expect("Failed to read values");
+    assert!(untouched.is_empty());
+}
+
+#[tokio::test]
+async fn test_tab_to_and_focused_element() {
+    use agentic_browser::{By, Role};
+
+    let server = fixtures().await;
+    server.add_page(
+        "/keyboard",
+        r#"<input id="first"><a href="/echo">Help</a><input id="second"><button>Send</button>
+        <script>
+            window.mutations = 0;
+            new MutationObserver(records => window.mutations += records.length)
+                .observe(document, { attributes: true, subtree: true });
+        </script>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/keyboard")).await.expect("Failed to navigate");
+    assert!(page.focused_element().await.expect("Failed to read focus").is_none());
+
+    page.tab_to(By::Css("#second".into())).await.expect("Failed to tab to input");
+    let focused: String = page.evaluate_typed("document.activeElement.id").await.expect("No focus");
+    assert_eq!(focused, "second");
+
+    let button = page
+        .tab_to(By::Role { role: Role::Button, name: Some("send".into()) })
+        .await
+        .expect("Failed to tab to button");
+    assert_eq!(button.inner_text().await.expect("No text"), "Send");
+    assert!(page.focused_element().await.expect("Failed to read focus").is_some());
+
+    assert!(page.tab_to(By::Css("#missing".into())).await.is_err());
+
+    // Finding elements leaves no trace in the page
+    let children = page.find_element("body").await.expect("No body").children().await.expect("No children");
+    assert_eq!(children.len(), 5);
+    let mutations: u64 = page.evaluate_typed("window.mutations").await.expect("evaluate failed");
+    assert_eq!(mutations, 0);
+}
+
+#[tokio::test]
+async fn test_hover_and_wait() {
+    let server = fixtures().await;
+    server.add_page(
+        "/menu",
+        r#"<nav id="products" style="display: inline-block; padding: 20px">Products
+            <ul id="submenu" hidden><li><a href="/echo">Laptops</a></li></ul>
+        </nav>
+        <script>
+            const nav = document.getElementById('products');
+            let timer;
+            nav.addEventListener('mouseenter', () => {
+                timer = setTimeout(() => document.getElementById('submenu').hidden = false, 400);
+            });
+            nav.addEventListener('mouseleave', () => clearTimeout(timer));
+        </script>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/menu")).await.expect("Failed to navigate");
+
+    let submenu = page.hover_and_wait("#products", "#submenu").await.expect("Menu did not open");
+    assert_eq!(submenu.inner_text().await.expect("No text"), "Laptops");
+    page.click("#submenu a").await.expect("Failed to click menu item");
+}
+
+#[tokio::test]
+async fn test_drag_slider() {
+    let server = fixtures().await;
+    server.add_page(
+        "/slider",
+        r#"<div id="track" style="position: relative; width: 400px; height: 40px; margin: 40px; background: #eee">
+            <div id="handle" style="position: absolute; left: 0; width: 40px; height: 40px; background: #333"></div>
+            <div id="slot" style="position: absolute; left: 250px; width: 40px; height: 40px"></div>
+        </div>
+        <script>
+            const handle = document.getElementById('handle');
+            window.moves = 0;
+            let start = null;
+            handle.addEventListener('mousedown', e => start = e.clientX);
+            document.addEventListener('mousemove', e => {
+                if (start === null) return;
+                window.moves++;
+                handle.style.left = (e.clientX - start) + 'px';
+            });
+            document.addEventListener('mouseup', e => {
+                window.released = e.clientX - start;
+                start = null;
+            });
+        </script>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/slider")).await.expect("Failed to navigate");
+
+    page.drag_slider("#handle", 120.0).await.expect("Failed to drag");
+    let released: f64 = page.evaluate_typed("window.released").await.expect("Not released");
+    assert!((released - 120.0).abs() <= 1.0, "released at {released}");
+    let moves: u32 = page.evaluate_typed("window.moves").await.expect("No moves");
+    assert!(moves >= 20, "only {moves} moves");
+
+    page.evaluate_typed::<serde_json::Value>("document.getElementById('handle').style.left = '0px'")
+        .await
+        .expect("Failed to reset");
+    page.drag_slider("#handle", "#slot").await.expect("Failed to drag onto slot");
+    let released: f64 = page.evaluate_typed("window.released").await.expect("Not released");
+    assert!((released - 250.0).abs() <= 1.0, "released at {released}");
+}
+
+#[tokio::test]
+async fn test_element_scoped_extraction() {
+    let server = fixtures().await;
+    server.add_page(
+        "/cards",
+        r#"<div class="card"><h2>Lamp</h2><span class="price" data-currency="EUR">20</span><a href="/lamp">Details</a></div>
+        <div class="card"><h2>Desk</h2><span class="price" data-currency="USD">150</span><a href="/desk">Details</a>
+            <button>Add to cart</button></div>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/cards")).await.expect("Failed to navigate");
+
+    let cards = page.find_elements(".card").await.expect("No cards");
+    assert_eq!(cards.len(), 2);
+    let desk = &cards[1];
+
+    let prices = desk.query_all_with_data(".price", &["data-currency"]).await.expect("No prices");
+    assert_eq!(prices.len(), 1);
+    assert_eq!(prices[0].text, "150");
+    assert_eq!(prices[0].attributes["data-currency"], "USD");
+
+    let links = desk.get_links().await.expect("No links");
+    assert_eq!(links, [("Details".to_string(), server.url("/desk"))]);
+
+    let tree = desk.accessibility_subtree().await.expect("No tree");
+    assert!(tree.contains("[button] \"Add to cart\""), "{tree}");
+    assert!(!tree.contains("Lamp"), "{tree}");
+}
+
+#[tokio::test]
+async fn test_query_stream_chunks() {
+    use futures::TryStreamExt;
+
+    let server = fixtures().await;
+    let rows: String = (0..250).map(|i| format!("<li data-id=\"{i}\">Row {i}</li>")).collect();
+    server.add_page("/rows", format!("<ul>{rows}</ul>"));
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/rows")).await.expect("Failed to navigate");
+
+    let mut stream = page.query_stream("li", &["data-id"], 100);
+    let first = stream.try_next().await.expect("Failed to stream rows").expect("No rows");
+    assert_eq!(first.text, "Row 0");
+    // Matches are fixed by the first chunk; rows added later don't shift the rest
+    page.evaluate_void("document.querySelector('ul').prepend(document.createElement('li'))")
+        .await
+        .expect("evaluate failed");
+    let rest: Vec<_> = stream.try_collect().await.expect("Failed to stream rows");
+    assert_eq!(rest.len(), 249);
+    assert_eq!(rest[248].text, "Row 249");
+    assert_eq!(rest[99].attributes["data-id"], "100");
+
+    let invalid = page.query_stream("li[", &[], 10).try_collect::<Vec<_>>().await;
+    assert!(invalid.is_err(), "an invalid selector fails the stream");
+
+    let none: Vec<_> = page.query_stream(".missing", &[], 10).try_collect().await.expect("Failed to stream");
+    assert!(none.is_empty());
+}
+
+#[tokio::test]
+async fn test_full_text_extraction() {
+    use agentic_browser::{ExtractOptions, TreeOptions};
+
+    let server = fixtures().await;
+    let body = "Lorem ipsum dolor sit amet. ".repeat(40);
+    server.add_page("/article", format!("<article><p>{body}</p></article>"));
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/article")).await.expect("Failed to navigate");
+
+    let cut = page.query_selector_all_with_data("p", &[]).await.expect("Failed to query");
+    assert_eq!(cut[0].text.len(), 500);
+    let full = page
+        .query_selector_all_with("p", &[], ExtractOptions::full_text())
+        .await
+        .expect("Failed to query");
+    assert_eq!(full[0].text, body.trim());
+
+    let tree = page.accessibility_tree().await.expect("Failed to get tree");
+    assert!(!tree.contains("Lorem"));
+    let tree = page
+        .accessibility_tree_with(TreeOptions::default().full_text())
+        .await
+        .expect("Failed to get tree");
+    assert!(tree.contains(body.trim()));
+}
+
+#[tokio::test]
+async fn test_interactive_elements() {
+    let server = fixtures().await;
+    server.add_page(
+        "/shop",
+        r#"<h1>Shop</h1>
+        <p>Plain text is not listed</p>
+        <a href="/cart">Cart</a>
+        <label for="q">Search</label><input id="q">
+        <button disabled>Buy</button>
+        <div role="button" style="display: none">Hidden</div>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/shop")).await.expect("Failed to navigate");
+
+    let elements = page.interactive_elements().await.expect("Failed to list elements");
+    assert_eq!(elements.len(), 4);
+    let link = &elements[0];
+    assert_eq!((link.role.as_str(), link.text.as_str()), ("link", "Cart"));
+    assert!(link.visible && link.enabled && link.bounds.width > 0.0);
+    assert_eq!((elements[1].role.as_str(), elements[1].text.as_str()), ("textbox", "Search"));
+    assert_eq!(elements[1].selector, "#q");
+    assert!(!elements[2].enabled);
+    assert!(!elements[3].visible);
+
+    page.click(&elements[0].selector).await.expect("Failed to click by selector");
+}
+
+#[tokio::test]
+async fn test_page_regions() {
+    use agentic_browser::RegionKind;
+
+    let server = fixtures().await;
+    server.add_page(
+        "/news",
+        r#"<header><a href="/">Daily News</a><nav aria-label="Sections"><a href="/world">World</a></nav></header>
+        <div class="content">
+            <h1>Big story</h1>
+            <p>Something happened today, and it was quite remarkable.</p>
+            <p>Witnesses described it at length.</p>
+        </div>
+        <aside><h2>Trending</h2></aside>
+        <footer>Copyright</footer>
+        <div id="cookie-consent" style="position: fixed; bottom: 0; left: 0; right: 0">
+            We use cookies. <button>Accept</button>
+        </div>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/news")).await.expect("Failed to navigate");
+
+    let regions = page.regions().await.expect("Failed to get regions");
+    let kinds: Vec<_> = regions.iter().map(|r| r.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            RegionKind::Header,
+            RegionKind::Nav,
+            RegionKind::Main,
+            RegionKind::Sidebar,
+            RegionKind::Footer,
+            RegionKind::CookieBanner,
+        ]
+    );
+    assert_eq!(regions[1].label, "Sections");
+    let main = &regions[2];
+    assert_eq!(main.label, "Big story");
+    assert!(main.text.starts_with("Big story"));
+    let main_el = page.find_element(&main.selector).await.expect("Failed to find main");
+    assert!(main_el.inner_text().await.expect("Failed to read text").contains("Witnesses"));
+}
+
+#[cfg(feature = "ocr")]
+#[tokio::test]
+async fn test_ocr_canvas_text() {
+    use agentic_browser::BoundingBox;
+
+    let server = fixtures().await;
+    server.add_page(
+        "/chart",
+        r#"<canvas id="chart" width="400" height="100" style="position: absolute; left: 50px; top: 50px"></canvas>
+        <script>
+            const ctx = document.getElementById('chart').getContext('2d');
+            ctx.fillStyle = 'white';
+            ctx.fillRect(0, 0, 400, 100);
+            ctx.fillStyle = 'black';
+            ctx.font = '32px sans-serif';
+            ctx.fillText('Revenue 4200', 20, 60);
+        </script>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .viewport(800, 600)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/chart")).await.expect("Failed to navigate");
+
+    let lines = page.ocr(None).await.expect("OCR failed");
+    let line = lines.iter().find(|l| l.text.contains("Revenue")).expect("Text not recognized");
+    assert!(line.text.contains("4200"));
+    let (x, y) = line.bounds.center();
+    assert!((50.0..450.0).contains(&x) && (50.0..150.0).contains(&y));
+
+    let region = BoundingBox { x: 50.0, y: 50.0, width: 400.0, height: 100.0 };
+    let lines = page.ocr(Some(region)).await.expect("OCR failed");
+    assert!(lines.iter().any(|l| l.text.contains("Revenue")));
+}
+
+#[tokio::test]
+async fn test_screenshot_within() {
+    let server = fixtures().await;
+    // Noise compresses badly as PNG
+    let cells: String = (0..2000)
+        .map(|i| format!("<i style=\"background: hsl({}, 80%, {}%)\"></i>", i * 37 % 360, 30 + i * 13 % 50))
+        .collect();
+    server.add_page(
+        "/noise",
+        format!("<style>i {{ display: inline-block; width: 16px; height: 16px }}</style>{cells}"),
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .viewport(800, 600)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/noise")).await.expect("Failed to navigate");
+
+    let roomy = page.screenshot_within(50_000_000).await.expect("Screenshot failed");
+    assert_eq!(roomy.mime_type, "image/png");
+    assert_eq!(roomy.scale, 1.0);
+
+    let shot = page.screenshot_within(40_000).await.expect("Screenshot failed");
+    assert!(shot.data.len() <= 40_000);
+    assert_eq!(shot.mime_type, "image/jpeg");
+    assert!(shot.data.starts_with(&[0xff, 0xd8]));
+
+    assert!(page.screenshot_within(100).await.is_err());
+}
+
+#[cfg(feature = "image")]
+#[tokio::test]
+async fn test_screenshot_image_pipeline() {
+    let server = fixtures().await;
+    server.add_page(
+        "/box",
+        r#"<div id="box" style="position: absolute; left: 100px; top: 80px; width: 200px; height: 100px; background: rgb(0, 128, 255)"></div>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .viewport(800, 600)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/box")).await.expect("Failed to navigate");
+
+    let bounds = page
+        .find_element("#box")
+        .await
+        .expect("Failed to find box")
+        .bounding_box()
+        .await
+        .expect("Failed to get bounds");
+    assert_eq!((bounds.x, bounds.y, bounds.width, bounds.height), (100.0, 80.0, 200.0, 100.0));
+
+    let shot = page.screenshot_image().await.expect("Screenshot failed");
+    assert_eq!((shot.width(), shot.height()), (800, 600));
+    let cropped = shot.clone().crop(bounds);
+    assert_eq!((cropped.width(), cropped.height()), (200, 100));
+    // Boxes still refer to the viewport after cropping
+    let inner = agentic_browser::BoundingBox { x: 150.0, y: 100.0, width: 50.0, height: 50.0 };
+    let png = cropped.annotate(inner, "1", [255, 0, 0]).grayscale().to_png().expect("Encode failed");
+    assert!(png.starts_with(b"\x89PNG"));
+
+    let small = shot.resize(400, 400);
+    assert_eq!((small.width(), small.height()), (400, 300));
+    assert!(small.to_jpeg(70).expect("Encode failed").starts_with(&[0xff, 0xd8]));
+}
+
+#[tokio::test]
+async fn test_observe_visual() {
+    let server = fixtures().await;
+    server.add_page(
+        "/menu",
+        r#"<button id="a">First</button>
+        <a href="/b">Second</a>
+        <style>@keyframes slide { to { transform: translateX(300px) } }</style>
+        <button style="animation: slide 0.2s linear infinite alternate">Moving</button>
+        <button style="display: none">Hidden</button>
+        <button style="position: absolute; top: 5000px">Far below</button>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .viewport(800, 600)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    page.goto(&server.url("/menu")).await.expect("Failed to navigate");
+
+    let observation = page.observe_visual().await.expect("Failed to observe");
+    // The CSS animation is paused for the capture, so the boxes agree
+    assert!(observation.consistent);
+    assert!(observation.screenshot.starts_with(b"\x89PNG"));
+    let texts: Vec<_> = observation.elements.iter().map(|e| e.text.as_str()).collect();
+    assert_eq!(texts, ["First", "Second", "Moving"]);
+    assert_eq!(observation.viewport.width, 800.0);
+    page.click(&observation.elements[0].selector).await.expect("Failed to click label 0");
+}
+
+#[tokio::test]
+async fn test_bring_to_front() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let first = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    let mut second = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+
+    first.bring_to_front().await.expect("Failed to bring to front");
+    assert!(first.is_active().await.expect("Failed to check"));
+    // Screenshots leave the active tab alone by default
+    let png = second.screenshot().await.expect("Screenshot failed");
+    assert!(png.starts_with(b"\x89PNG"));
+    assert!(first.is_active().await.expect("Failed to check"));
+    // Unless asked to bring the tab behind forward first
+    second.set_screenshot_background(true);
+    second.screenshot().await.expect("Screenshot failed");
+    assert!(second.is_active().await.expect("Failed to check"));
+}
+
+#[tokio::test]
+async fn test_keep_active() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    page.keep_active(true).await.expect("Failed to keep active");
+
+    let state_js = "[document.visibilityState, document.hidden, document.hasFocus()]";
+    // Behind another tab, it still looks visible and focused to the site
+    let other = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    other.bring_to_front().await.expect("Failed to bring to front");
+    let state: (String, bool, bool) = page.evaluate_typed(state_js).await.expect("evaluate failed");
+    assert_eq!(state, ("visible".to_string(), false, true));
+    // Also on the next document
+    page.reload().await.expect("Failed to reload");
+    let state: (String, bool, bool) = page.evaluate_typed(state_js).await.expect("evaluate failed");
+    assert_eq!(state.0, "visible");
+
+    page.keep_active(false).await.expect("Failed to release");
+    page.reload().await.expect("Failed to reload");
+    let spoofed: bool = page
+        .evaluate_typed("Symbol.for('agentic.keepVisible') in document")
+        .await
+        .expect("evaluate failed");
+    assert!(!spoofed);
+}
+
+#[tokio::test]
+async fn test_window_bounds_need_a_window() {
+    use agentic_browser::{Error, HeadlessMode, WindowBounds, WindowState};
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    let bounds = browser.set_window_bounds(&page, WindowBounds::new(0, 0, 800, 600)).await;
+    assert!(matches!(bounds, Err(Error::ConfigError(_))), "{bounds:?}");
+    let state = browser.set_window_state(&page, WindowState::Maximized).await;
+    assert!(matches!(state, Err(Error::ConfigError(_))), "{state:?}");
+
+    // Rejected before Chrome starts, so no display is needed
+    let minimized = AgenticBrowser::builder()
+        .headless_mode(HeadlessMode::False)
+        .window_state(WindowState::Minimized)
+        .build()
+        .await;
+    assert!(matches!(minimized, Err(Error::ConfigError(_))));
+}
+
+#[tokio::test]
+#[ignore = "needs a display or Xvfb"]
+async fn test_window_bounds_headful() {
+    use agentic_browser::{HeadlessMode, WindowBounds, WindowState};
+
+    let browser = AgenticBrowser::builder()
+        .headless_mode(HeadlessMode::False)
+        .xvfb(true)
+        .window_position(40, 30)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_blank_page().await.expect("Failed to open page");
+    let position = || async {
+        page.evaluate_typed::<(i32, i32, u32, u32)>("[screenX, screenY, outerWidth, outerHeight]")
+            .await
+            .expect("evaluate failed")
+    };
+    let (x, y, _, _) = position().await;
+    assert_eq!((x, y), (40, 30));
+
+    browser
+        .set_window_bounds(&page, WindowBounds::new(100, 50, 800, 600))
+        .await
+        .expect("Failed to set bounds");
+    assert_eq!(position().await, (100, 50, 800, 600));
+
+    for state in [WindowState::Maximized, WindowState::Fullscreen, WindowState::Minimized, WindowState::Normal] {
+        browser.set_window_state(&page, state).await.expect("Failed to set state");
+    }
+    assert_eq!(position().await, (100, 50, 800, 600));
+}
+
+#[tokio::test]
+async fn test_host_rules() {
+    let server = fixtures().await;
+    server.add_page("/mapped", "<html><head><title>Mapped</title></head><body>local</body></html>");
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .host_rules(&[("shop.example.com", "127.0.0.1")])
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let url = format!("http://shop.example.com:{}/mapped", server.addr().port());
+    let page = browser.new_page(&url).await.expect("Failed to open mapped host");
+    assert_eq!(page.title().await.expect("Failed to get title"), "Mapped");
+}
+
+#[tokio::test]
+async fn test_secure_dns() {
+    use agentic_browser::{Error, SecureDns};
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .secure_dns(SecureDns::automatic().template("https://dns.example/dns-query"))
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    // IP addresses need no lookup, so the unreachable provider is never asked
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    assert!(page.url().await.expect("Failed to get url").starts_with("http://127.0.0.1"));
+
+    let result = AgenticBrowser::builder()
+        .headless(true)
+        .secure_dns(SecureDns::secure("dns.example"))
+        .build()
+        .await;
+    assert!(matches!(result, Err(Error::ConfigError(_))));
+}
+
+#[tokio::test]
+async fn test_proxy_bypass() {
+    use agentic_browser::ProxyConfig;
+
+    let server = fixtures().await;
+    server.add_page("/health", "<html><head><title>OK</title></head><body>ok</body></html>");
+    // Nothing listens on the proxy port; only bypassed hosts can load
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .proxy_config(ProxyConfig::new("http://127.0.0.1:9").bypass(&["*.internal.test"]))
+        .host_rules(&[("*.internal.test", "127.0.0.1")])
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let url = format!("http://health.internal.test:{}/health", server.addr().port());
+    let page = browser.new_page(&url).await.expect("Failed to open bypassed host");
+    assert_eq!(page.title().await.expect("Failed to get title"), "OK");
+}
+
+#[tokio::test]
+async fn test_request_identity() {
+    use agentic_browser::{PlatformProfile, RequestIdentity};
+
+    let server = fixtures().await;
+    let pool = RequestIdentity::new()
+        .platforms(vec![PlatformProfile::windows()])
+        .languages(&["de-DE,de;q=0.9,en;q=0.8"])
+        .viewport_jitter(0);
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .request_identity(pool)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+
+    let (user_agent, platform, language): (String, String, String) = page
+        .evaluate_typed("[navigator.userAgent, navigator.platform, navigator.language]")
+        .await
+        .expect("evaluate failed");
+    assert!(user_agent.contains("Windows NT 10.0"), "{user_agent}");
+    assert!(!user_agent.contains("Headless"));
+    assert_eq!(platform, "Win32");
+    assert_eq!(language, "de-DE");
+    let viewport = page.viewport().await.expect("Failed to read viewport");
+    let sizes = PlatformProfile::windows().viewports;
+    assert!(sizes.contains(&(viewport.width as u32, viewport.height as u32)));
+    let screen: (u32, u32) = page.evaluate_typed("[screen.width, screen.height]").await.expect("evaluate failed");
+    assert_eq!(screen, (viewport.width as u32, viewport.height as u32));
+
+    // A second identity replaces the first, on reloads too
+    let identity = RequestIdentity::new().platforms(vec![PlatformProfile::linux()]).next();
+    page.set_identity(&identity).await.expect("set_identity failed");
+    page.set_identity(&identity).await.expect("set_identity failed");
+    page.reload().await.expect("reload failed");
+    let platform: String = page.evaluate_typed("navigator.platform").await.expect("evaluate failed");
+    assert_eq!(platform, identity.platform);
+    let screen: (u32, u32) = page.evaluate_typed("[screen.width, screen.height]").await.expect("evaluate failed");
+    assert_eq!(screen, (identity.viewport_width, identity.viewport_height));
+}
+
+#[tokio::test]
+async fn test_profiles() {
+    use agentic_browser::{Profile, ProfileManager, ProxyConfig, RequestIdentity};
+
+    let server = fixtures().await;
+    let dir = std::env::temp_dir().join("agentic_browser_profiles_test");
+    let _ = std::fs::remove_dir_all(&dir);
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .profiles(ProfileManager::new(&dir))
+        .build()
+        .await
+        .expect("Failed to launch browser");
+
+    let context = browser.open_profile("acct-42").await.expect("Failed to open profile");
+    let identity = context.profile().identity;
+    let page = context.new_page(&server.url("/")).await.expect("Failed to open page");
+    page.evaluate_void("document.cookie = 'session=abc; max-age=3600'; localStorage.setItem('cart', '3')")
+        .await
+        .expect("evaluate failed");
+    let user_agent: String = page.evaluate_typed("navigator.userAgent").await.expect("evaluate failed");
+    assert_eq!(user_agent, identity.user_agent);
+
+    // A popup of a profile page presents the profile too, and its storage is saved
+    let popup_url = server.url("/").replace("127.0.0.1", "localhost");
+    page.evaluate(&format!("window.open('{popup_url}', '_blank')"))
+        .await
+        .expect("Failed to open popup");
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    let mut popup = None;
+    for tab in browser.pages().await.expect("Failed to list pages") {
+        if tab.url().await.expect("url failed").contains("localhost") {
+            popup = Some(tab);
+        }
+    }
+    let popup = popup.expect("Popup not listed");
+    popup.reload().await.expect("reload failed");
+    let user_agent: String = popup.evaluate_typed("navigator.userAgent").await.expect("evaluate failed");
+    assert_eq!(user_agent, identity.user_agent);
+    popup.evaluate_void("localStorage.setItem('seen', '1')").await.expect("evaluate failed");
+    context.close().await.expect("Failed to close profile");
+
+    // Another profile shares nothing
+    let other = browser.open_profile("acct-43").await.expect("Failed to open profile");
+    let page = other.new_page(&server.url("/")).await.expect("Failed to open page");
+    let cookie: String = page.evaluate_typed("document.cookie").await.expect("evaluate failed");
+    assert_eq!(cookie, "");
+    other.close().await.expect("Failed to close profile");
+
+    // Reopened, the first one has its cookies, storage and identity back
+    let context = browser.open_profile("acct-42").await.expect("Failed to reopen profile");
+    assert_eq!(context.profile().identity, identity);
+    let page = context.new_page(&server.url("/")).await.expect("Failed to open page");
+    let state: (String, Option<String>) = page
+        .evaluate_typed("[document.cookie, localStorage.getItem('cart')]")
+        .await
+        .expect("evaluate failed");
+    assert_eq!(state, ("session=abc".to_string(), Some("3".to_string())));
+    let origin = popup_url.trim_end_matches('/');
+    assert_eq!(context.profile().local_storage[origin].get("seen").map(String::as_str), Some("1"));
+    context.close().await.expect("Failed to close profile");
+
+    let manager = ProfileManager::new(&dir);
+    assert_eq!(manager.list().expect("Failed to list"), vec!["acct-42", "acct-43"]);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(dir.join("acct-42.json")).expect("profile file").permissions().mode();
+        assert_eq!(mode & 0o777, 0o600, "profiles hold session cookies");
+    }
+
+    // A profile proxy gets only its own credentials, so half of them is an error
+    let mut proxy = ProxyConfig::new("http://127.0.0.1:9");
+    proxy.username = Some("user".to_string());
+    let profile = Profile::new("proxied", RequestIdentity::new().next()).proxy(proxy);
+    manager.save(&profile).expect("Failed to save");
+    let opened = browser.open_profile("proxied").await;
+    assert!(matches!(opened, Err(agentic_browser::Error::ConfigError(_))));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_profile_export_import() {
+    use agentic_browser::{Profile, ProfileManager, RequestIdentity};
+
+    let server = fixtures().await;
+    let base = std::env::temp_dir().join("agentic_browser_bundle_test");
+    let _ = std::fs::remove_dir_all(&base);
+    let source = ProfileManager::new(base.join("a"));
+    let identity = RequestIdentity::new().timezones(&["Asia/Tokyo"]).next();
+    source.save(&Profile::new("travel", identity.clone())).expect("Failed to save");
+
+    let bundle = source.export_profile("travel").expect("Failed to export");
+    let target = ProfileManager::new(base.join("b"));
+    let imported = target.import_profile(&bundle).expect("Failed to import");
+    assert_eq!(imported.identity, identity);
+    assert_eq!(target.list().expect("Failed to list"), vec!["travel"]);
+
+    let newer = bundle.replacen("\"version\": 1", "\"version\": 99", 1);
+    assert!(target.import_profile(&newer).is_err());
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .profiles(target)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let context = browser.open_profile("travel").await.expect("Failed to open profile");
+    let page = context.new_page(&server.url("/")).await.expect("Failed to open page");
+    let timezone: String = page
+        .evaluate_typed("Intl.DateTimeFormat().resolvedOptions().timeZone")
+        .await
+        .expect("evaluate failed");
+    assert_eq!(timezone, "Asia/Tokyo");
+    context.close().await.expect("Failed to close profile");
+    let _ = std::fs::remove_dir_all(&base);
+}
+
+#[tokio::test]
+async fn test_redaction() {
+    use agentic_browser::{Action, Redaction};
+
+    let server = fixtures().await;
+    server.add_page(
+        "/signin",
+        r#"<html><body>
+            <input id="user"><input id="pass" type="password" style="filter: grayscale(1)">
+            <input id="code" autocomplete="one-time-code"><span class="iban">DE89 3704 0044</span>
+        </body></html>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .redaction(Redaction::new().selector(".iban").mask("***"))
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/signin")).await.expect("Failed to open page");
+
+    let typed = |selector: &str| Action::Type { selector: selector.to_string(), text: "hunter2".to_string() };
+    assert_eq!(page.redact_action(&typed("#user")).await, typed("#user"));
+    for selector in ["#pass", "#code", ".iban"] {
+        let Action::Type { text, .. } = page.redact_action(&typed(selector)).await else {
+            panic!("not a type action");
+        };
+        assert_eq!(text, "***", "{selector}");
+    }
+
+    // Log the fields' filters each time they change: blurred for the capture, then restored
+    page.evaluate_void(
+        r#"window.filters = [];
+        const read = () => document.getElementById('pass').style.filter + '|' + document.querySelector('.iban').style.filter;
+        new MutationObserver(() => window.filters.push(read()))
+            .observe(document.body, { attributes: true, subtree: true, attributeFilter: ['style'] })"#,
+    )
+    .await
+    .expect("evaluate failed");
+    let png = page.screenshot_redacted().await.expect("Failed to take screenshot");
+    assert_eq!(&png[1..4], b"PNG");
+    let filters: Vec<String> = page.evaluate_typed("window.filters").await.expect("evaluate failed");
+    assert_eq!(filters, ["blur(12px)|blur(12px)", "grayscale(1)|"]);
+
+    // Fields in same-origin frames and open shadow roots are blurred as well, and
+    // frames of other origins whole
+    server.add_page(
+        "/checkout",
+        format!(
+            r#"<html><body>
+                <iframe id="card" srcdoc="<input id='cvc' autocomplete='cc-csc'>"></iframe>
+                <iframe id="hosted" src="{}"></iframe>
+                <div id="host"></div>
+                <script>document.getElementById('host').attachShadow({{ mode: 'open' }})
+                    .innerHTML = '<input id="pin" type="password">';</script>
+            </body></html>"#,
+            server.url("/").replace("127.0.0.1", "localhost")
+        ),
+    );
+    page.goto(&server.url("/checkout")).await.expect("Failed to navigate");
+    page.evaluate_void(
+        r#"window.blurred = new Set();
+        const cvc = document.getElementById('card').contentDocument.getElementById('cvc');
+        const pin = document.getElementById('host').shadowRoot.getElementById('pin');
+        const hosted = document.getElementById('hosted');
+        const observer = new MutationObserver(records => records.forEach(r => {
+            if (r.target.style.filter.startsWith('blur')) window.blurred.add(r.target.id);
+        }));
+        for (const el of [cvc, pin, hosted]) observer.observe(el, { attributes: true, attributeFilter: ['style'] });"#,
+    )
+    .await
+    .expect("evaluate failed");
+    page.screenshot_redacted().await.expect("Failed to take screenshot");
+    let blurred: Vec<String> = page
+        .evaluate_typed("[...window.blurred].sort()")
+        .await
+        .expect("evaluate failed");
+    assert_eq!(blurred, ["cvc", "hosted", "pin"]);
+
+    assert_eq!(page.redact_action(&typed("#missing")).await, Action::Type {
+        selector: "#missing".to_string(),
+        text: "***".to_string(),
+    });
+
+    // Recordings mask password fields even without a configured redaction
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/signin")).await.expect("Failed to open page");
+    let recorder = page.start_action_recording().await.expect("Failed to start recording");
+    for (selector, text) in [("#user", "alice"), ("#pass", "hunter2")] {
+        page.type_text(selector, text).await.expect("Failed to type");
+        page.press_key("Enter").await.expect("Failed to press Enter");
+    }
+    let mut typed_steps = Vec::new();
+    for _ in 0..20 {
+        typed_steps = recorder
+            .script()
+            .steps
+            .into_iter()
+            .filter_map(|step| match step {
+                Action::Type { selector, text } => Some((selector, text)),
+                _ => None,
+            })
+            .collect();
+        if typed_steps.len() == 2 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_eq!(
+        typed_steps,
+        [("#user".to_string(), "alice".to_string()), ("#pass".to_string(), "[REDACTED]".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn test_read_only() {
+    use agentic_browser::{Action, Error, ReadOnly};
+
+    let server = fixtures().await;
+    server.add_page(
+        "/form",
+        r#"<html><body>
+            <form method="post" action="/submitted"><input id="q"><button id="go">Send</button></form>
+            <button id="menu" type="button" onclick="document.title = 'Menu'">Menu</button>
+        </body></html>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .read_only(ReadOnly::new().confirm(|action| matches!(action, Action::Type { .. })))
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/form")).await.expect("Failed to open page");
+
+    let click = |selector: &str| Action::Click { selector: selector.to_string() };
+    page.execute_action(&click("#menu")).await.expect("Plain click was rejected");
+    assert_eq!(page.title().await.expect("Failed to get title"), "Menu");
+    let typed = Action::Type { selector: "#q".to_string(), text: "hello".to_string() };
+    page.execute_action(&typed).await.expect("Confirmed typing was rejected");
+    assert!(matches!(page.execute_action(&click("#go")).await, Err(Error::ActionRejected(_))));
+    let press = Action::Press { key: "Enter".to_string() };
+    assert!(matches!(page.execute_action(&press).await, Err(Error::ActionRejected(_))));
+
+    // Writes are blocked in the network, whatever starts them
+    let post = "fetch('/submitted', { method: 'POST' }).then(() => 'sent', () => 'blocked')";
+    let status: String = page.evaluate_typed(post).await.expect("evaluate failed");
+    assert_eq!(status, "blocked");
+
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .read_only(ReadOnly::new().allow_writes())
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/form")).await.expect("Failed to open page");
+    let status: String = page.evaluate_typed(post).await.expect("evaluate failed");
+    assert_eq!(status, "sent");
+    assert!(matches!(page.execute_action(&click("#go")).await, Err(Error::ActionRejected(_))));
+}
+
+#[tokio::test]
+async fn test_agent_approval() {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use agentic_browser::{
+        Action, AgentDecision, AgentObservation, AgentRunner, Approval, ApprovalPolicy,
+        ApprovalRequest, LlmPolicy, StopReason, TranscriptEntry,
+    };
+
+    /// Clicks "More", "Delete account" and a missing button, then reports the title.
+    struct Clicker;
+
+    impl LlmPolicy for Clicker {
+        async fn decide(
+            &self,
+            observation: &AgentObservation,
+            history: &[TranscriptEntry],
+        ) -> agentic_browser::Result<AgentDecision> {
+            let click = |selector: &str| AgentDecision::Act {
+                action: Action::Click { selector: selector.to_string() },
+            };
+            Ok(match history.len() {
+                0 => click("#more"),
+                1 => click("#delete"),
+                2 => click("#gone"),
+                _ => AgentDecision::Done { answer: observation.title.clone() },
+            })
+        }
+    }
+
+    /// Denies everything it is asked about, remembering the requests.
+    #[derive(Default)]
+    struct Deny(Mutex<Vec<ApprovalRequest>>);
+
+    impl ApprovalPolicy for &Deny {
+        async fn review(
+            &self,
+            request: &ApprovalRequest,
+            _: &[TranscriptEntry],
+        ) -> agentic_browser::Result<Approval> {
+            self.0.lock().unwrap().push(request.clone());
+            Ok(Approval::Deny("not today".to_string()))
+        }
+    }
+
+    let server = fixtures().await;
+    server.add_page(
+        "/account",
+        r#"<html><head><title>Account</title></head><body>
+            <button id="more" onclick="document.title = 'More'">More</button>
+            <form onsubmit="document.title = 'Deleted'; return false"><button id="delete">Delete account</button></form>
+        </body></html>"#,
+    );
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .timeout(Duration::from_secs(1))
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/account")).await.expect("Failed to open page");
+
+    let approver = Deny::default();
+    let outcome = AgentRunner::new(Clicker)
+        .approval(&approver)
+        .run(&page)
+        .await
+        .expect("Agent run failed");
+    assert_eq!(outcome.stop_reason, StopReason::Done("More".to_string()));
+    assert!(outcome.transcript[0].result.is_ok());
+    let denied = outcome.transcript[1].result.as_ref().expect_err("Delete was not denied");
+    assert!(denied.contains("not today"), "{denied}");
+
+    let requests = approver.0.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].step, 2);
+    assert!(requests[0].reason.contains("\"delete\""), "{}", requests[0].reason);
+    assert_eq!(&requests[0].screenshot[1..4], b"PNG");
+    // What can't be assessed is asked about, not let through
+    assert_eq!(requests[1].step, 3);
+    assert!(requests[1].reason.starts_with("could not assess"), "{}", requests[1].reason);
+}
+
+#[tokio::test]
+async fn test_download_url() {
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+
+    let path = std::env::temp_dir().join("agentic_browser_download_test.csv");
+    let _ = std::fs::remove_file(&path);
+    let mut updates = Vec::new();
+    let bytes = page
+        .download_url_with("/download", &path, |p| updates.push(p))
+        .await
+        .expect("Download failed");
+    let csv = std::fs::read_to_string(&path).expect("Failed to read download");
+    assert_eq!(csv, "id,name\n1,Ada\n2,Grace\n");
+    assert_eq!(bytes, csv.len() as u64);
+    let last = updates.last().expect("No progress reported");
+    assert_eq!((last.received, last.total), (bytes, Some(bytes)));
+
+    let missing = std::env::temp_dir().join("agentic_browser_download_missing.csv");
+    assert!(page.download_url("/status/404", &missing).await.is_err());
+    assert!(!missing.exists());
+
+    // The download carries the page's cookies
+    let _ = std::fs::remove_file(&path);
+    assert!(page.download_url("/private-download", &path).await.is_err(), "no cookie, no file");
+    assert!(!path.exists());
+    page.evaluate_void("document.cookie = 'session=ok; path=/'").await.expect("evaluate failed");
+    page.download_url("/private-download", &path).await.expect("Download with cookie failed");
+    assert_eq!(std::fs::read_to_string(&path).expect("Failed to read download"), csv);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_fetch_form_and_multipart() {
+    use agentic_browser::{FetchRequest, FormPart};
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+    let echoed = |html: &str| -> serde_json::Value {
+        let json = html
+            .split("<pre id=\"echo\">")
+            .nth(1)
+            .and_then(|s| s.split("</pre>").next())
+            .expect("No echo");
+        serde_json::from_str(&json.replace("&lt;", "<").replace("&amp;", "&")).expect("Invalid echo")
+    };
+
+    let form = FetchRequest::post_form("/echo", &[("q", "rust & chrome"), ("page", "2")]);
+    let echo = echoed(&page.fetch(form).await.expect("Form POST failed").body);
+    assert_eq!(echo["method"], "POST");
+    assert_eq!(echo["body"], "q=rust+%26+chrome&page=2");
+
+    let raw = FetchRequest::post_bytes("/echo", b"\x00binary".to_vec(), "application/octet-stream");
+    let echo = echoed(&page.fetch(raw).await.expect("Raw POST failed").body);
+    assert_eq!(echo["body"], "\u{0}binary");
+
+    let path = std::env::temp_dir().join("agentic_browser_upload_test.txt");
+    std::fs::write(&path, "uploaded contents").expect("Failed to write file");
+    // The file inputs never enter the document, even briefly
+    page.evaluate_void(
+        "window.__added = 0; new MutationObserver(r => window.__added += r.length) \
+         .observe(document, { childList: true, subtree: true })",
+    )
+    .await
+    .expect("Failed to observe mutations");
+    let multipart = FetchRequest::post_multipart(
+        "/echo",
+        vec![FormPart::text("title", "Q3"), FormPart::file("report", &path).filename("q3.txt")],
+    );
+    let echo = echoed(&page.fetch(multipart).await.expect("Multipart POST failed").body);
+    let body = echo["body"].as_str().expect("No body");
+    assert!(body.contains("name=\"title\"\r\n\r\nQ3"), "{body}");
+    assert!(body.contains("name=\"report\"; filename=\"q3.txt\""), "{body}");
+    assert!(body.contains("uploaded contents"), "{body}");
+    let added: usize = page.evaluate_typed("window.__added").await.expect("evaluate failed");
+    assert_eq!(added, 0);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_capture_graphql() {
+    use std::time::Duration;
+
+    use futures::StreamExt;
+    use serde_json::json;
+
+    let server = fixtures().await;
+    let browser = AgenticBrowser::builder()
+        .headless(true)
+        .build()
+        .await
+        .expect("Failed to launch browser");
+    let page = browser.new_page(&server.url("/")).await.expect("Failed to open page");
+
+    let mut operations = page.capture_graphql().await.expect("Failed to start capture");
+    page.evaluate_void(
+        "const post = body => fetch('/json', { method: 'POST', headers: { 'Content-Type': 'application/json', \
+         'Authorization': 'Bearer t0ken' }, body: JSON.stringify(body) });
+         post({ query: 'shoes' }).then(() => post({ operationName: 'Items', \
+         query: 'query Items($first: Int) { items(first: $first) }', variables: { first: 2, sort: 'new' } }));",
+    )
+    .await
+    .expect("evaluate failed");
+
+    let operation = tokio::time::timeout(Duration::from_secs(10), operations.next())
+        .await
+        .expect("No GraphQL operation captured")
+        .expect("Capture ended");
+    assert!(operation.endpoint.ends_with("/json"));
+    assert_eq!(operation.operation_name.as_deref(), Some("Items"));
+    assert_eq!(operation.variables, json!({ "first": 2, "sort": "new" }));
+    assert_eq!(operation.response["items"], json!([1, 2, 3]));
+
+    let mut replay = operation.with_variables(json!({ "first": 10 }));
+    replay.endpoint = server.url("/echo");
+    let echoed = page.fetch(replay.to_request()).await.expect("Replay failed").body;
+    assert!(echoed.contains(r#"\"variables\":{\"first\":10,\"sort\":\"new\"}"#), "{echoed}");
+    assert!(echoed.contains(r#"\"operationName\":\"Items\""#), "{echoed}");
+}